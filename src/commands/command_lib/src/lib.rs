@@ -84,8 +84,9 @@ pub fn get_parts(parts: &mut SplitAsciiWhitespace, is_value: bool) -> (String, S
 /// Retrieves the value from a string, considering quotation marks.
 ///
 /// This function takes a string and a mutable reference to a SplitAsciiWhitespace iterator.
-/// If the string starts with a quotation mark, it continues to concatenate the parts from the iterator to the string until it finds a part that ends with a quotation mark.
-/// After that, it trims the quotation marks from the start and end of the string.
+/// If the string starts with a quotation mark, it continues to concatenate the parts from the iterator to the string until it finds a part that ends with an unescaped quotation mark.
+/// Inside a quoted value `\"` stands for a quotation mark and `\\` for a backslash, see [`quote_value`].
+/// An unquoted value only has the quotation marks at its start and end trimmed.
 ///
 /// # Arguments
 ///
@@ -96,18 +97,87 @@ pub fn get_parts(parts: &mut SplitAsciiWhitespace, is_value: bool) -> (String, S
 ///
 /// * A string that has been processed to include parts within quotation marks and has had its quotation marks removed.
 pub fn get_value(value: String, parts: &mut SplitAsciiWhitespace) -> String {
+    if !value.starts_with('"') {
+        return value.trim_matches('"').to_string();
+    }
     let mut value = value;
-    if is_with_quotation_marks(value.as_str()) {
-        while let Some(part) = parts.next() {
-            value.push_str(" ");
-            value.push_str(part);
-            if part.ends_with('"') {
-                break;
+    while !is_closed_quote(&value) {
+        match parts.next() {
+            Some(part) => {
+                value.push(' ');
+                value.push_str(part);
             }
+            None => break,
+        }
+    }
+    let inner = &value[1..];
+    let inner = if is_closed_quote(&value) {
+        &inner[..inner.len() - 1]
+    } else {
+        inner
+    };
+    let mut result = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(escaped @ ('"' | '\\')) => result.push(escaped),
+                Some(other) => {
+                    result.push('\\');
+                    result.push(other);
+                }
+                None => result.push('\\'),
+            },
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+// Whether a value starting with a quotation mark ends with its closing one, not an escaped one.
+fn is_closed_quote(value: &str) -> bool {
+    if value.len() < 2 || !value.ends_with('"') {
+        return false;
+    }
+    let backslashes = value[1..value.len() - 1]
+        .chars()
+        .rev()
+        .take_while(|c| *c == '\\')
+        .count();
+    backslashes % 2 == 0
+}
+
+/// Quotes a value so that [`get_value`] reads it back as a single value.
+///
+/// Values that are empty, contain whitespace or start or end with a quotation mark are put in
+/// quotation marks, with their quotation marks and backslashes escaped. Other values are
+/// returned unchanged.
+///
+/// # Arguments
+///
+/// * `value` - The value to be quoted.
+///
+/// # Returns
+///
+/// * A string that is read back as `value`.
+pub fn quote_value(value: &str) -> String {
+    if !value.is_empty()
+        && !value.contains(char::is_whitespace)
+        && !value.starts_with('"')
+        && !value.ends_with('"')
+    {
+        return value.to_string();
+    }
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        if c == '"' || c == '\\' {
+            quoted.push('\\');
         }
+        quoted.push(c);
     }
-    value = value.trim_matches('"').to_string();
-    value
+    quoted.push('"');
+    quoted
 }
 
 /// Returns a substring from a given string, starting and ending at the specified indices.
@@ -195,3 +265,104 @@ pub fn fn_lcs(str1: &str, str2: &str) -> (String, usize) {
 
     (lcs.chars().rev().collect::<String>(), dp[m][n])
 }
+
+/// Checks whether a string matches a glob-style pattern.
+///
+/// This function implements the same glob syntax as Redis, so it can be shared by every command taking a pattern.
+/// `*` matches any sequence of characters, `?` matches one character, `[abc]` matches one of the listed characters,
+/// `[a-z]` matches a range, `[^a]` negates a class and `\` escapes the next character, inside or outside a class.
+/// An unterminated class is closed at the end of the pattern and a trailing `\` matches itself.
+///
+/// # Arguments
+///
+/// * `pattern` - The glob pattern.
+/// * `str` - The string to be checked.
+///
+/// # Returns
+///
+/// * A boolean value indicating whether the whole string matches the pattern.
+pub fn glob_match(pattern: &str, str: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let str: Vec<char> = str.chars().collect();
+    let (mut p, mut s) = (0, 0);
+    // pattern index after the last `*` and the string index it is currently matched up to
+    let mut star: Option<(usize, usize)> = None;
+
+    loop {
+        if p < pattern.len() {
+            if pattern[p] == '*' {
+                while p < pattern.len() && pattern[p] == '*' {
+                    p += 1;
+                }
+                star = Some((p, s));
+                continue;
+            }
+            if s < str.len() {
+                if let Some(next) = glob_match_char(&pattern, p, str[s]) {
+                    p = next;
+                    s += 1;
+                    continue;
+                }
+            }
+        } else if s == str.len() {
+            return true;
+        }
+
+        // mismatch, let the last `*` swallow one more character
+        match star {
+            Some((star_p, star_s)) if star_s < str.len() => {
+                star = Some((star_p, star_s + 1));
+                p = star_p;
+                s = star_s + 1;
+            }
+            _ => return false,
+        }
+    }
+}
+
+// Matches one character against the pattern token at `p`, returning the index of the next token.
+fn glob_match_char(pattern: &[char], p: usize, c: char) -> Option<usize> {
+    match pattern[p] {
+        '?' => Some(p + 1),
+        '\\' if p + 1 < pattern.len() => (pattern[p + 1] == c).then_some(p + 2),
+        '[' => {
+            let mut i = p + 1;
+            let negate = i < pattern.len() && pattern[i] == '^';
+            if negate {
+                i += 1;
+            }
+            let mut matched = false;
+            while i < pattern.len() && pattern[i] != ']' {
+                if pattern[i] == '\\' && i + 1 < pattern.len() {
+                    i += 1;
+                    matched |= pattern[i] == c;
+                } else if i + 2 < pattern.len() && pattern[i + 1] == '-' {
+                    let (start, end) = if pattern[i] <= pattern[i + 2] {
+                        (pattern[i], pattern[i + 2])
+                    } else {
+                        (pattern[i + 2], pattern[i])
+                    };
+                    matched |= start <= c && c <= end;
+                    i += 2;
+                } else {
+                    matched |= pattern[i] == c;
+                }
+                i += 1;
+            }
+            (matched != negate).then_some((i + 1).min(pattern.len()))
+        }
+        other => (other == c).then_some(p + 1),
+    }
+}
+
+/// Returns the position of an item in the order of the SCAN family of commands.
+///
+/// 64-bit FNV-1a, stable across runs and platforms so cursors stay valid.
+pub fn scan_hash(item: &str) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in item.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
@@ -1,10 +1,20 @@
 use std::str::SplitAsciiWhitespace;
 
-use rus_key_trait::command_trait::Command;
-use rus_key_db::db::{Db, DataType};
+use rus_key_trait::command_trait::{Arity, Command};
+use rus_key_db::db::Db;
+use rus_key_db::notify::parse_flags;
+use rus_key_db::output_buffer::OutputBufferLimits;
 
 const CANNOT_MODIFY: [&str; 2] = ["port", "host"];
 
+// the option whose value is case sensitive
+const NOTIFY_KEYSPACE_EVENTS: &str = "notify-keyspace-events";
+// the option whose value is several arguments
+const CLIENT_OUTPUT_BUFFER_LIMIT: &str = "client-output-buffer-limit";
+
+const INVALID_NOTIFY_FLAGS: &str = "ERR Invalid event class character. Use 'Ag$lshzxeKEtdn'.";
+const INVALID_OUTPUT_BUFFER_LIMIT: &str = "ERR Invalid client-output-buffer-limit, use class hard soft soft_seconds with class normal, replica or pubsub";
+
 fn get_next_arg(parts: &mut SplitAsciiWhitespace) -> Result<String, &'static str> {
     match parts.next() {
         Some(arg) => Ok(arg.to_lowercase()),
@@ -67,10 +77,7 @@ impl ConfigCommand {
 
     fn get(&self, parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
         let value = get_next_arg(parts)?;
-        let btree_map = match db.get("ruskey_config") {
-            Some(DataType::ZSet(btree_map)) => btree_map,
-            _ => return Err("No ruskey_config key"),
-        };
+        let btree_map = db.server().config();
         let result = if value == "*" {
             btree_map
                 .iter()
@@ -89,21 +96,32 @@ impl ConfigCommand {
 
     fn set(&self, parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
         let field = get_next_arg(parts)?;
-        let value = get_next_arg(parts)?;
-        let mut btree_map = match db.get("ruskey_config") {
-            Some(DataType::ZSet(btree_map)) => btree_map.clone(),
-            _ => return Err("No such key or wrong data type"),
+        let value = match field.as_str() {
+            NOTIFY_KEYSPACE_EVENTS => {
+                // the event classes are case sensitive, and no class at all is an empty value
+                let value = parts.next().unwrap_or_default();
+                let value = value.trim_matches('"').to_string();
+                parse_flags(&value).ok_or(INVALID_NOTIFY_FLAGS)?;
+                value
+            }
+            CLIENT_OUTPUT_BUFFER_LIMIT => {
+                // like `pubsub 32mb 8mb 60`, for one class or more
+                let value = parts.collect::<Vec<_>>().join(" ").to_lowercase();
+                OutputBufferLimits::default()
+                    .parse(&value)
+                    .ok_or(INVALID_OUTPUT_BUFFER_LIMIT)?;
+                value
+            }
+            _ => get_next_arg(parts)?,
         };
-        let keys: Vec<&String> = btree_map.keys().collect();
-        if !keys.contains(&&field) {
+        if db.server().config_get(&field).is_none() {
             return Err("No such key or wrong data type");
         }
         // check cannot modify
         if CANNOT_MODIFY.contains(&field.as_str()) {
             return Err("Cannot modify");
         }
-        btree_map.insert(field.to_string(), value.to_string());
-        db.set("ruskey_config".to_string(), DataType::ZSet(btree_map));
+        db.server().config_set(&field, &value);
         Ok("OK".to_string())
     }
 }
@@ -121,4 +139,8 @@ impl Command for ConfigCommand {
             _ => Err("Config Invalid command!"),
         }
     }
+
+    fn arity(&self) -> Arity {
+        Arity::new(1)
+    }
 }
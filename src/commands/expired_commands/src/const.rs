@@ -1 +0,0 @@
-pub const EXPIRED: &str = "expired";
@@ -1,6 +1,5 @@
-use rus_key_db::db::{DataType, Db};
-use crate::r#const::EXPIRED;
-use crate::utils::get_expired_map;
+use rus_key_db::db::Db;
+use rus_key_db::notify::NOTIFY_GENERIC;
 
 pub fn del_key_expired(key: Option<&str>, db: &mut Db) -> String {
     let key = match key {
@@ -8,14 +7,10 @@ pub fn del_key_expired(key: Option<&str>, db: &mut Db) -> String {
         None => return "0".to_string(),
     };
 
-    let mut expired_map = get_expired_map(db);
-
-    if !expired_map.contains_key(key) {
+    if !db.persist(key) {
         return "0".to_string();
     }
-
-    expired_map.remove(key);
-    db.set(EXPIRED.to_string(), DataType::HashMap(expired_map));
+    db.notify(NOTIFY_GENERIC, "persist", key);
 
     "1".to_string()
 }
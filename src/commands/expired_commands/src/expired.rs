@@ -1,37 +1,11 @@
 use std::str::SplitAsciiWhitespace;
-use crate::utils::{get_current_time, get_expired_map};
 use crate::handle_expired::handle_expired;
 use crate::del_key_expired::del_key_expired;
 use crate::handle_ttl::handle_ttl;
 
-use rus_key_trait::command_trait::Command;
+use rus_key_trait::command_trait::{Arity, Command};
 use rus_key_db::db::Db;
 
-pub fn get_key_expired(key: Option<&str>, db: &mut Db) -> String {
-    let key = match key {
-        Some(key) => key,
-        None => return "No such key".to_string(),
-    };
-
-    if !db.check_expired(key) {
-        return "No such key".to_string();
-    }
-
-    let expired_map = get_expired_map(db);
-    let current_time = get_current_time();
-    let expired_time = match expired_map.get(key) {
-        Some(expired_time) => match expired_time.parse::<i64>() {
-            Ok(n) if n > current_time => n.to_string(),
-            _ => {
-                db.delete(key);
-                return "nil".to_string();
-            }
-        },
-        None => "".to_string(),
-    };
-    expired_time
-}
-
 pub struct ExpiredCommand {
     command: String,
 }
@@ -49,24 +23,10 @@ impl Command for ExpiredCommand {
         db: &mut Db,
     ) -> Result<String, &'static str> {
         match self.command.as_str() {
-            "expired" => {
-                let key = parts.next();
-                let value = parts.next();
-                let expired = handle_expired(key, value, "", db);
-                expired
-            }
-            "expireat" => {
-                let key = parts.next();
-                let value = parts.next();
-                let expireat = handle_expired(key, value, "at", db);
-                expireat
-            }
-            "pexpireat" => {
-                let key = parts.next();
-                let value = parts.next();
-                let pexpireat = handle_expired(key, value, "p", db);
-                pexpireat
-            }
+            "expire" => handle_expired(parts, "", db),
+            "pexpire" => handle_expired(parts, "p", db),
+            "expireat" => handle_expired(parts, "at", db),
+            "pexpireat" => handle_expired(parts, "pat", db),
             "ttl" => {
                 let key = parts.next();
                 let ttl = handle_ttl(key, "", db);
@@ -77,6 +37,16 @@ impl Command for ExpiredCommand {
                 let ttl = handle_ttl(key, "p", db);
                 Ok(ttl.to_string())
             }
+            "expiretime" => {
+                let key = parts.next();
+                let ttl = handle_ttl(key, "at", db);
+                Ok(ttl.to_string())
+            }
+            "pexpiretime" => {
+                let key = parts.next();
+                let ttl = handle_ttl(key, "pat", db);
+                Ok(ttl.to_string())
+            }
             "persist" => {
                 let key = parts.next();
                 let persist = del_key_expired(key, db);
@@ -85,4 +55,11 @@ impl Command for ExpiredCommand {
             _ => Err("ExpiredCommand Error: Command not found"),
         }
     }
+
+    fn arity(&self) -> Arity {
+        match self.command.as_str() {
+            "expire" | "pexpire" | "expireat" | "pexpireat" => Arity::with_integers(2, &[1]),
+            _ => Arity::new(1),
+        }
+    }
 }
@@ -1,47 +1,98 @@
-use rus_key_db::db::{DataType, Db};
-use crate::r#const::EXPIRED;
-use crate::utils::{get_current_time, get_expired_map, splice_time};
+use std::str::SplitAsciiWhitespace;
 
+use rus_key_db::db::Db;
+use rus_key_db::notify::NOTIFY_GENERIC;
+use crate::utils::{get_current_time, splice_time};
+
+const WRONG_ARGS: &str = "ERR wrong number of arguments for command";
+const NOT_INTEGER: &str = "ERR value is not an integer or out of range";
+const INVALID_EXPIRE: &str = "ERR invalid expire time in command";
+const NX_CONFLICT: &str = "ERR NX and XX, GT or LT options at the same time are not compatible";
+const GT_LT_CONFLICT: &str = "ERR GT and LT options at the same time are not compatible";
+const UNSUPPORTED_OPTION: &str = "ERR Unsupported option";
+
+#[derive(Default)]
+struct ExpireFlags {
+    nx: bool,
+    xx: bool,
+    gt: bool,
+    lt: bool,
+}
+
+fn parse_flags(parts: &mut SplitAsciiWhitespace) -> Result<ExpireFlags, &'static str> {
+    let mut flags = ExpireFlags::default();
+    for flag in parts {
+        match flag.to_lowercase().as_str() {
+            "nx" => flags.nx = true,
+            "xx" => flags.xx = true,
+            "gt" => flags.gt = true,
+            "lt" => flags.lt = true,
+            _ => return Err(UNSUPPORTED_OPTION),
+        }
+    }
+    if flags.nx && (flags.xx || flags.gt || flags.lt) {
+        return Err(NX_CONFLICT);
+    }
+    if flags.gt && flags.lt {
+        return Err(GT_LT_CONFLICT);
+    }
+    Ok(flags)
+}
+
+// A key without TTL counts as an infinite one, so GT never applies to it and LT always does.
+fn flags_allow(flags: &ExpireFlags, current: Option<i64>, deadline: i64) -> bool {
+    match current {
+        Some(current) => {
+            !flags.nx && (!flags.gt || deadline > current) && (!flags.lt || deadline < current)
+        }
+        None => !flags.xx && !flags.gt,
+    }
+}
+
+/// `EXPIRE`, `PEXPIRE`, `EXPIREAT` and `PEXPIREAT` with the `NX`, `XX`, `GT` and `LT` flags.
+///
+/// `type_str` is `""` for seconds, `"p"` for milliseconds, `"at"` for a unix time in seconds
+/// and `"pat"` for one in milliseconds. A deadline already in the past deletes the key.
+///
+/// # Returns
+///
+/// * `1` if the timeout was set or the key deleted, `0` if the key does not exist or a flag
+///   condition was not met.
 pub fn handle_expired(
-    key: Option<&str>,
-    value: Option<&str>,
+    parts: &mut SplitAsciiWhitespace,
     type_str: &str,
     db: &mut Db,
 ) -> Result<String, &'static str> {
-    let key = match key {
-        Some(key) => key,
-        None => return Err("No such key"),
-    };
-
-    if !db.check_expired(&key) {
-        return Err("No such key");
-    }
+    let key = parts.next().ok_or(WRONG_ARGS)?;
+    let value = parts
+        .next()
+        .ok_or(WRONG_ARGS)?
+        .parse::<i64>()
+        .map_err(|_| NOT_INTEGER)?;
+    let flags = parse_flags(parts)?;
 
-    let (flag_number, multiplier) = match type_str {
-        "" => (0, 1),
-        "at" => (get_current_time() / 1000, 1000),
-        "p" => (get_current_time(), 1),
+    let deadline = match type_str {
+        "" => value.checked_mul(1000).map(splice_time),
+        "p" => Some(splice_time(value)),
+        "at" => value.checked_mul(1000),
+        "pat" => Some(value),
         _ => return Err("Invalid type"),
     };
+    let deadline = deadline.ok_or(INVALID_EXPIRE)?;
 
-    let value = match value {
-        Some(v) => match v.parse::<i64>() {
-            Ok(n) if n > flag_number => n * multiplier,
-            _ => return Err("Invalid value"),
-        },
-        None => return Err("Invalid value"),
-    };
-
-    let mut expired_map = get_expired_map(db);
+    if !db.check_expired(key) {
+        return Ok("0".to_string());
+    }
+    if !flags_allow(&flags, db.expire_time(key), deadline) {
+        return Ok("0".to_string());
+    }
 
-    let expired_time = if type_str == "" {
-        splice_time(value * 1000)
+    if deadline <= get_current_time() {
+        db.delete(key);
+        db.notify(NOTIFY_GENERIC, "del", key);
     } else {
-        value
-    };
-
-    expired_map.insert(key.to_string(), expired_time.to_string());
-    db.set(EXPIRED.to_string(), DataType::HashMap(expired_map));
-
-    Ok("OK".to_string())
+        db.expire_at(key, deadline);
+        db.notify(NOTIFY_GENERIC, "expire", key);
+    }
+    Ok("1".to_string())
 }
@@ -1,6 +1,15 @@
 use rus_key_db::db::Db;
-use crate::utils::{get_current_time, get_expired_map};
+use crate::utils::get_current_time;
 
+/// `TTL`, `PTTL`, `EXPIRETIME` and `PEXPIRETIME`.
+///
+/// `type_str` is `""` for the remaining seconds, `"p"` for the remaining milliseconds,
+/// `"at"` for the deadline in seconds and `"pat"` for the deadline in milliseconds.
+/// Seconds are rounded to the nearest one.
+///
+/// # Returns
+///
+/// * `-2` if the key does not exist, `-1` if it has no TTL.
 pub fn handle_ttl(key: Option<&str>, type_str: &str, db: &mut Db) -> i64 {
     let key = match key {
         Some(key) => key,
@@ -11,23 +20,15 @@ pub fn handle_ttl(key: Option<&str>, type_str: &str, db: &mut Db) -> i64 {
         return -2;
     }
 
-    let expired_map = get_expired_map(db);
-
-    let current_time = get_current_time();
-    let expired_time = match expired_map.get(key) {
-        Some(expired_time) => match expired_time.parse::<i64>() {
-            Ok(n) if n > current_time => n,
-            _ => {
-                db.delete(key);
-                return -2;
-            }
-        },
+    let expired_time = match db.expire_time(key) {
+        Some(expired_time) => expired_time,
         None => return -1,
     };
-    let multiplier = match type_str {
-        "" => 1,
-        "p" => 1000,
-        _ => 1,
-    };
-    ((expired_time - current_time) / 1000) * multiplier
+    let remaining = (expired_time - get_current_time()).max(0);
+    match type_str {
+        "p" => remaining,
+        "at" => (expired_time + 500) / 1000,
+        "pat" => expired_time,
+        _ => (remaining + 500) / 1000,
+    }
 }
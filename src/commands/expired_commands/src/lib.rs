@@ -1,5 +1,4 @@
 pub mod expired;
-mod r#const;
 mod handle_expired;
 mod utils;
 mod del_key_expired;
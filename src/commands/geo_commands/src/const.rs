@@ -0,0 +1,56 @@
+pub const EMPTY: &str = "nil";
+
+pub const WRONG_TYPE: &str =
+    "There is no such key, the key is expired, or the data type is incorrect";
+
+pub const INVALID_LON_LAT: &str = "ERR invalid longitude,latitude pair";
+
+pub const INVALID_UNIT: &str = "ERR unsupported unit provided. please use M, KM, FT, MI";
+
+pub const SYNTAX_ERROR: &str = "ERR syntax error";
+
+pub const WRONG_ARGS: &str = "ERR wrong number of arguments for command";
+
+// Limits from EPSG:900913 / EPSG:3785 / OSGEO:41001, the same ones Redis uses
+pub const GEO_LAT_MIN: f64 = -85.05112878;
+pub const GEO_LAT_MAX: f64 = 85.05112878;
+pub const GEO_LONG_MIN: f64 = -180.0;
+pub const GEO_LONG_MAX: f64 = 180.0;
+
+// 26 bits per coordinate, 52 bits for the interleaved score
+pub const GEO_STEP: u32 = 26;
+
+// Earth's quadratic mean radius for WGS-84
+pub const EARTH_RADIUS_IN_METERS: f64 = 6372797.560856;
+
+pub const GEO_ALPHABET: &[u8] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+pub enum Origin {
+    Member(String),
+    LonLat(f64, f64),
+}
+
+pub enum Shape {
+    Radius(f64),
+    Box(f64, f64),
+}
+
+pub enum Sort {
+    None,
+    Asc,
+    Desc,
+}
+
+pub struct SearchArgs {
+    pub origin: Option<Origin>,
+    pub shape: Option<Shape>,
+    // conversion factor from meters to the requested unit
+    pub unit: f64,
+    pub sort: Sort,
+    pub count: Option<usize>,
+    pub any: bool,
+    pub with_coord: bool,
+    pub with_dist: bool,
+    pub with_hash: bool,
+    pub store_dist: bool,
+}
@@ -0,0 +1,47 @@
+use crate::geo_add::geo_add;
+use crate::geo_dist::geo_dist;
+use crate::geo_hash::geo_hash;
+use crate::geo_pos::geo_pos;
+use crate::geo_search::{geo_search, geo_search_store};
+use rus_key_db::db::Db;
+use rus_key_trait::command_trait::{Arity, Command};
+use std::str::SplitAsciiWhitespace;
+
+pub struct GeoCommand {
+    command: String,
+}
+
+impl GeoCommand {
+    pub fn new(command: String) -> GeoCommand {
+        GeoCommand { command }
+    }
+}
+
+impl Command for GeoCommand {
+    fn execute(
+        &self,
+        parts: &mut SplitAsciiWhitespace,
+        db: &mut Db,
+    ) -> Result<String, &'static str> {
+        match self.command.as_str() {
+            "geoadd" => geo_add(parts, db),
+            "geopos" => geo_pos(parts, db),
+            "geodist" => geo_dist(parts, db),
+            "geohash" => geo_hash(parts, db),
+            "geosearch" => geo_search(parts, db),
+            "geosearchstore" => geo_search_store(parts, db),
+            _ => Err("GeoCommand Error: Command not found"),
+        }
+    }
+
+    fn arity(&self) -> Arity {
+        match self.command.as_str() {
+            "geoadd" => Arity::new(4),
+            "geodist" => Arity::new(3),
+            // FROMMEMBER member BYRADIUS radius unit, and the destination
+            "geosearch" => Arity::new(6),
+            "geosearchstore" => Arity::new(7),
+            _ => Arity::new(1),
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use crate::geohash::{encode, is_valid_lon_lat};
+use crate::r#const::{INVALID_LON_LAT, SYNTAX_ERROR, WRONG_ARGS};
+use crate::utils::get_geo_set;
+use rus_key_db::db::{DataType, Db};
+use rus_key_db::notify::NOTIFY_ZSET;
+use std::collections::BTreeMap;
+use std::str::SplitAsciiWhitespace;
+
+/// Adds members with their positions to a geo key.
+///
+/// Syntax: `GEOADD key [NX | XX] [CH] longitude latitude member [longitude latitude member ...]`
+///
+/// Each position is stored as a 52 bit geohash score in the sorted set held at `key`.
+/// `NX` only adds new members, `XX` only updates existing ones and `CH` counts updated
+/// members as well as added ones.
+///
+/// # Returns
+///
+/// * The number of members added (or changed with `CH`), or an error message.
+pub fn geo_add(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let key = match parts.next() {
+        Some(key) => key,
+        None => return Err(WRONG_ARGS),
+    };
+
+    let (mut nx, mut xx, mut ch) = (false, false, false);
+    let mut args: Vec<&str> = parts.collect();
+    // leading flags
+    while let Some(arg) = args.first() {
+        match arg.to_lowercase().as_str() {
+            "nx" => nx = true,
+            "xx" => xx = true,
+            "ch" => ch = true,
+            _ => break,
+        }
+        args.remove(0);
+    }
+    if nx && xx {
+        return Err("ERR XX and NX options at the same time are not compatible");
+    }
+    if args.is_empty() || !args.len().is_multiple_of(3) {
+        return Err(SYNTAX_ERROR);
+    }
+
+    // validate everything before touching the set
+    let mut members: Vec<(String, u64)> = Vec::with_capacity(args.len() / 3);
+    for triple in args.chunks(3) {
+        let lon = triple[0].parse::<f64>().map_err(|_| INVALID_LON_LAT)?;
+        let lat = triple[1].parse::<f64>().map_err(|_| INVALID_LON_LAT)?;
+        if !is_valid_lon_lat(lon, lat) {
+            return Err(INVALID_LON_LAT);
+        }
+        members.push((triple[2].to_string(), encode(lon, lat)));
+    }
+
+    let mut set = get_geo_set(key, db)?.unwrap_or_else(BTreeMap::new);
+    let mut count = 0;
+    for (member, score) in members {
+        let score = score.to_string();
+        match set.get(&member) {
+            Some(old) => {
+                if nx {
+                    continue;
+                }
+                if *old != score && ch {
+                    count += 1;
+                }
+            }
+            None => {
+                if xx {
+                    continue;
+                }
+                count += 1;
+            }
+        }
+        set.insert(member, score);
+    }
+    db.set(key.to_string(), DataType::ZSet(set));
+    db.notify(NOTIFY_ZSET, "zadd", key);
+    Ok(count.to_string())
+}
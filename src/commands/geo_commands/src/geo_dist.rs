@@ -0,0 +1,38 @@
+use crate::geohash::{distance, parse_unit};
+use crate::r#const::{EMPTY, INVALID_UNIT, SYNTAX_ERROR, WRONG_ARGS};
+use crate::utils::{get_geo_set, member_position};
+use rus_key_db::db::Db;
+use std::str::SplitAsciiWhitespace;
+
+/// Returns the distance between two members of a geo key.
+///
+/// Syntax: `GEODIST key member1 member2 [M | KM | FT | MI]`
+///
+/// # Returns
+///
+/// * The distance in the requested unit (meters by default) with four decimals,
+///   or `nil` if either member does not exist.
+pub fn geo_dist(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let (key, member1, member2) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(key), Some(member1), Some(member2)) => (key, member1, member2),
+        _ => return Err(WRONG_ARGS),
+    };
+    let unit = match parts.next() {
+        Some(unit) => parse_unit(unit).ok_or(INVALID_UNIT)?,
+        None => 1.0,
+    };
+    if parts.next().is_some() {
+        return Err(SYNTAX_ERROR);
+    }
+
+    let set = get_geo_set(key, db)?.unwrap_or_default();
+    match (
+        member_position(&set, member1),
+        member_position(&set, member2),
+    ) {
+        (Some((lon1, lat1)), Some((lon2, lat2))) => {
+            Ok(format!("{:.4}", distance(lon1, lat1, lon2, lat2) / unit))
+        }
+        _ => Ok(EMPTY.to_string()),
+    }
+}
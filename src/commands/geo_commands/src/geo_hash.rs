@@ -0,0 +1,28 @@
+use crate::geohash::to_geohash_string;
+use crate::r#const::{EMPTY, WRONG_ARGS};
+use crate::utils::{get_geo_set, member_position};
+use rus_key_db::db::Db;
+use std::str::SplitAsciiWhitespace;
+
+/// Returns the standard geohash strings of members of a geo key.
+///
+/// Syntax: `GEOHASH key member [member ...]`
+///
+/// # Returns
+///
+/// * The 11 character geohash of each member separated by spaces, `nil` for missing members.
+pub fn geo_hash(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let key = match parts.next() {
+        Some(key) => key,
+        None => return Err(WRONG_ARGS),
+    };
+    let set = get_geo_set(key, db)?.unwrap_or_default();
+    let result = parts
+        .map(|member| match member_position(&set, member) {
+            Some((lon, lat)) => to_geohash_string(lon, lat),
+            None => EMPTY.to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join(" ");
+    Ok(result)
+}
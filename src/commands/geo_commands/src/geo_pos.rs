@@ -0,0 +1,27 @@
+use crate::r#const::{EMPTY, WRONG_ARGS};
+use crate::utils::{format_coord, get_geo_set, member_position};
+use rus_key_db::db::Db;
+use std::str::SplitAsciiWhitespace;
+
+/// Returns the positions of members of a geo key.
+///
+/// Syntax: `GEOPOS key member [member ...]`
+///
+/// # Returns
+///
+/// * One `member: longitude latitude` entry per requested member, `member: nil` for missing members.
+pub fn geo_pos(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let key = match parts.next() {
+        Some(key) => key,
+        None => return Err(WRONG_ARGS),
+    };
+    let set = get_geo_set(key, db)?.unwrap_or_default();
+    let result = parts
+        .map(|member| match member_position(&set, member) {
+            Some((lon, lat)) => format!("{}: {}", member, format_coord(lon, lat)),
+            None => format!("{}: {}", member, EMPTY),
+        })
+        .collect::<Vec<String>>()
+        .join(" ");
+    Ok(result)
+}
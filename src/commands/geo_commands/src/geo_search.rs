@@ -0,0 +1,284 @@
+use crate::geohash::{decode, distance, is_valid_lon_lat, parse_unit};
+use crate::r#const::{
+    Origin, SearchArgs, Shape, Sort, INVALID_LON_LAT, INVALID_UNIT, SYNTAX_ERROR, WRONG_ARGS,
+};
+use crate::utils::{format_coord, get_geo_set, member_position};
+use rus_key_db::db::{DataType, Db};
+use rus_key_db::notify::{NOTIFY_GENERIC, NOTIFY_ZSET};
+use std::collections::BTreeMap;
+use std::str::SplitAsciiWhitespace;
+
+struct Match {
+    member: String,
+    dist: f64,
+    score: u64,
+    lon: f64,
+    lat: f64,
+}
+
+fn next_f64(parts: &mut SplitAsciiWhitespace, error: &'static str) -> Result<f64, &'static str> {
+    match parts.next() {
+        Some(value) => value.parse::<f64>().map_err(|_| error),
+        None => Err(SYNTAX_ERROR),
+    }
+}
+
+fn next_unit(parts: &mut SplitAsciiWhitespace) -> Result<f64, &'static str> {
+    match parts.next() {
+        Some(unit) => parse_unit(unit).ok_or(INVALID_UNIT),
+        None => Err(SYNTAX_ERROR),
+    }
+}
+
+/// Parses the options shared by `GEOSEARCH` and `GEOSEARCHSTORE`.
+fn parse_search_args(
+    parts: &mut SplitAsciiWhitespace,
+    is_store: bool,
+) -> Result<SearchArgs, &'static str> {
+    let mut args = SearchArgs {
+        origin: None,
+        shape: None,
+        unit: 1.0,
+        sort: Sort::None,
+        count: None,
+        any: false,
+        with_coord: false,
+        with_dist: false,
+        with_hash: false,
+        store_dist: false,
+    };
+
+    while let Some(arg) = parts.next() {
+        match arg.to_lowercase().as_str() {
+            "frommember" => {
+                if args.origin.is_some() {
+                    return Err(SYNTAX_ERROR);
+                }
+                let member = parts.next().ok_or(SYNTAX_ERROR)?;
+                args.origin = Some(Origin::Member(member.to_string()));
+            }
+            "fromlonlat" => {
+                if args.origin.is_some() {
+                    return Err(SYNTAX_ERROR);
+                }
+                let lon = next_f64(parts, INVALID_LON_LAT)?;
+                let lat = next_f64(parts, INVALID_LON_LAT)?;
+                if !is_valid_lon_lat(lon, lat) {
+                    return Err(INVALID_LON_LAT);
+                }
+                args.origin = Some(Origin::LonLat(lon, lat));
+            }
+            "byradius" => {
+                if args.shape.is_some() {
+                    return Err(SYNTAX_ERROR);
+                }
+                let radius = next_f64(parts, "ERR need numeric radius")?;
+                if radius < 0.0 {
+                    return Err("ERR radius cannot be negative");
+                }
+                args.unit = next_unit(parts)?;
+                args.shape = Some(Shape::Radius(radius * args.unit));
+            }
+            "bybox" => {
+                if args.shape.is_some() {
+                    return Err(SYNTAX_ERROR);
+                }
+                let width = next_f64(parts, "ERR need numeric width")?;
+                let height = next_f64(parts, "ERR need numeric height")?;
+                if width < 0.0 || height < 0.0 {
+                    return Err("ERR height or width cannot be negative");
+                }
+                args.unit = next_unit(parts)?;
+                args.shape = Some(Shape::Box(width * args.unit, height * args.unit));
+            }
+            "asc" => args.sort = Sort::Asc,
+            "desc" => args.sort = Sort::Desc,
+            "count" => {
+                let count = match parts.next() {
+                    Some(count) => count.parse::<i64>().map_err(|_| SYNTAX_ERROR)?,
+                    None => return Err(SYNTAX_ERROR),
+                };
+                if count <= 0 {
+                    return Err("ERR COUNT must be > 0");
+                }
+                args.count = Some(count as usize);
+            }
+            "any" => args.any = true,
+            "withcoord" if !is_store => args.with_coord = true,
+            "withdist" if !is_store => args.with_dist = true,
+            "withhash" if !is_store => args.with_hash = true,
+            "storedist" if is_store => args.store_dist = true,
+            _ => return Err(SYNTAX_ERROR),
+        }
+    }
+
+    if args.origin.is_none() {
+        return Err("ERR exactly one of FROMMEMBER or FROMLONLAT can be specified");
+    }
+    if args.shape.is_none() {
+        return Err("ERR exactly one of BYRADIUS and BYBOX can be specified");
+    }
+    if args.any && args.count.is_none() {
+        return Err("ERR the ANY argument requires COUNT argument");
+    }
+    Ok(args)
+}
+
+/// Checks whether a point lies inside the search shape centered at the origin.
+///
+/// For boxes the point is projected onto the origin's meridian and parallel, so the
+/// width and height are measured along the surface like Redis does.
+fn distance_if_in_shape(shape: &Shape, origin: (f64, f64), point: (f64, f64)) -> Option<f64> {
+    let dist = distance(origin.0, origin.1, point.0, point.1);
+    match shape {
+        Shape::Radius(radius) => (dist <= *radius).then_some(dist),
+        Shape::Box(width, height) => {
+            let lat_distance = distance(point.0, point.1, point.0, origin.1);
+            let lon_distance = distance(point.0, point.1, origin.0, point.1);
+            (lat_distance <= height / 2.0 && lon_distance <= width / 2.0).then_some(dist)
+        }
+    }
+}
+
+/// Runs a search against the set held at `key`.
+fn search(key: &str, args: &SearchArgs, db: &mut Db) -> Result<Vec<Match>, &'static str> {
+    let set = match get_geo_set(key, db)? {
+        Some(set) => set,
+        None => return Ok(Vec::new()),
+    };
+    let origin = match args.origin.as_ref() {
+        Some(Origin::Member(member)) => {
+            member_position(&set, member).ok_or("ERR could not decode requested zset member")?
+        }
+        Some(Origin::LonLat(lon, lat)) => (*lon, *lat),
+        None => return Err(SYNTAX_ERROR),
+    };
+    let shape = match args.shape.as_ref() {
+        Some(shape) => shape,
+        None => return Err(SYNTAX_ERROR),
+    };
+
+    let mut matches = Vec::new();
+    for (member, score) in set.iter() {
+        let score = match score.parse::<u64>() {
+            Ok(score) => score,
+            Err(_) => continue,
+        };
+        let (lon, lat) = decode(score);
+        if let Some(dist) = distance_if_in_shape(shape, origin, (lon, lat)) {
+            matches.push(Match {
+                member: member.clone(),
+                dist,
+                score,
+                lon,
+                lat,
+            });
+            // ANY returns as soon as enough matches are found
+            if args.any && Some(matches.len()) == args.count {
+                break;
+            }
+        }
+    }
+
+    match args.sort {
+        Sort::Asc => matches.sort_by(|a, b| a.dist.total_cmp(&b.dist)),
+        Sort::Desc => matches.sort_by(|a, b| b.dist.total_cmp(&a.dist)),
+        // a COUNT without ANY keeps the closest members
+        Sort::None if args.count.is_some() && !args.any => {
+            matches.sort_by(|a, b| a.dist.total_cmp(&b.dist))
+        }
+        Sort::None => {}
+    }
+    if let Some(count) = args.count {
+        matches.truncate(count);
+    }
+    Ok(matches)
+}
+
+/// Queries a geo key for members inside a circle or a box.
+///
+/// Syntax: `GEOSEARCH key <FROMMEMBER member | FROMLONLAT longitude latitude>
+/// <BYRADIUS radius unit | BYBOX width height unit> [ASC | DESC] [COUNT count [ANY]]
+/// [WITHCOORD] [WITHDIST] [WITHHASH]`
+///
+/// # Returns
+///
+/// * The matching members separated by spaces. When any `WITH*` option is given each entry is
+///   `member: [distance] [hash] [longitude latitude]`.
+pub fn geo_search(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let key = match parts.next() {
+        Some(key) => key,
+        None => return Err(WRONG_ARGS),
+    };
+    let args = parse_search_args(parts, false)?;
+    let matches = search(key, &args, db)?;
+
+    let with_any = args.with_coord || args.with_dist || args.with_hash;
+    let result = matches
+        .iter()
+        .map(|m| {
+            if !with_any {
+                return m.member.clone();
+            }
+            let mut fields = Vec::new();
+            if args.with_dist {
+                fields.push(format!("{:.4}", m.dist / args.unit));
+            }
+            if args.with_hash {
+                fields.push(m.score.to_string());
+            }
+            if args.with_coord {
+                fields.push(format_coord(m.lon, m.lat));
+            }
+            format!("{}: {}", m.member, fields.join(" "))
+        })
+        .collect::<Vec<String>>()
+        .join(" ");
+    Ok(result)
+}
+
+/// Stores the result of a geo search in another key.
+///
+/// Syntax: `GEOSEARCHSTORE destination source <FROMMEMBER ... | FROMLONLAT ...>
+/// <BYRADIUS ... | BYBOX ...> [ASC | DESC] [COUNT count [ANY]] [STOREDIST]`
+///
+/// The destination is overwritten with a sorted set holding the geohash scores of the
+/// matches, or their distances in the requested unit with `STOREDIST`.
+/// An empty result deletes the destination.
+///
+/// # Returns
+///
+/// * The number of members stored.
+pub fn geo_search_store(
+    parts: &mut SplitAsciiWhitespace,
+    db: &mut Db,
+) -> Result<String, &'static str> {
+    let (destination, source) = match (parts.next(), parts.next()) {
+        (Some(destination), Some(source)) => (destination, source),
+        _ => return Err(WRONG_ARGS),
+    };
+    let args = parse_search_args(parts, true)?;
+    let matches = search(source, &args, db)?;
+
+    if matches.is_empty() {
+        if db.delete(destination) {
+            db.notify(NOTIFY_GENERIC, "del", destination);
+        }
+        return Ok("0".to_string());
+    }
+    let set: BTreeMap<String, String> = matches
+        .iter()
+        .map(|m| {
+            let score = if args.store_dist {
+                (m.dist / args.unit).to_string()
+            } else {
+                m.score.to_string()
+            };
+            (m.member.clone(), score)
+        })
+        .collect();
+    let count = set.len();
+    db.set(destination.to_string(), DataType::ZSet(set));
+    db.notify(NOTIFY_ZSET, "geosearchstore", destination);
+    Ok(count.to_string())
+}
@@ -0,0 +1,118 @@
+use crate::r#const::{
+    EARTH_RADIUS_IN_METERS, GEO_ALPHABET, GEO_LAT_MAX, GEO_LAT_MIN, GEO_LONG_MAX, GEO_LONG_MIN,
+    GEO_STEP,
+};
+
+/// Checks whether a longitude/latitude pair can be indexed.
+pub fn is_valid_lon_lat(lon: f64, lat: f64) -> bool {
+    (GEO_LONG_MIN..=GEO_LONG_MAX).contains(&lon) && (GEO_LAT_MIN..=GEO_LAT_MAX).contains(&lat)
+}
+
+/// Spreads the low 32 bits of `x` so that they occupy the even bit positions.
+fn spread(x: u32) -> u64 {
+    let mut result = 0u64;
+    for i in 0..32 {
+        result |= (((x >> i) & 1) as u64) << (2 * i);
+    }
+    result
+}
+
+/// Collects the even bit positions of `x` back into a 32 bit integer.
+fn squash(x: u64) -> u32 {
+    let mut result = 0u32;
+    for i in 0..32 {
+        result |= (((x >> (2 * i)) & 1) as u32) << i;
+    }
+    result
+}
+
+fn encode_with_range(lon: f64, lat: f64, lat_range: (f64, f64), lon_range: (f64, f64)) -> u64 {
+    let scale = (1u64 << GEO_STEP) as f64;
+    let lat_offset = ((lat - lat_range.0) / (lat_range.1 - lat_range.0) * scale) as u32;
+    let lon_offset = ((lon - lon_range.0) / (lon_range.1 - lon_range.0) * scale) as u32;
+    // latitude takes the even bits, longitude the odd ones
+    spread(lat_offset) | (spread(lon_offset) << 1)
+}
+
+/// Encodes a coordinate into the 52 bit interleaved score stored in the sorted set.
+///
+/// # Arguments
+///
+/// * `lon` - The longitude, already validated by `is_valid_lon_lat`.
+/// * `lat` - The latitude, already validated by `is_valid_lon_lat`.
+///
+/// # Returns
+///
+/// * The geohash score.
+pub fn encode(lon: f64, lat: f64) -> u64 {
+    encode_with_range(
+        lon,
+        lat,
+        (GEO_LAT_MIN, GEO_LAT_MAX),
+        (GEO_LONG_MIN, GEO_LONG_MAX),
+    )
+}
+
+/// Decodes a geohash score into the coordinate at the center of its cell.
+///
+/// # Arguments
+///
+/// * `bits` - The geohash score.
+///
+/// # Returns
+///
+/// * A `(longitude, latitude)` tuple.
+pub fn decode(bits: u64) -> (f64, f64) {
+    let scale = (1u64 << GEO_STEP) as f64;
+    let lat_cell = squash(bits) as f64;
+    let lon_cell = squash(bits >> 1) as f64;
+
+    let lat_min = GEO_LAT_MIN + (lat_cell / scale) * (GEO_LAT_MAX - GEO_LAT_MIN);
+    let lat_max = GEO_LAT_MIN + ((lat_cell + 1.0) / scale) * (GEO_LAT_MAX - GEO_LAT_MIN);
+    let lon_min = GEO_LONG_MIN + (lon_cell / scale) * (GEO_LONG_MAX - GEO_LONG_MIN);
+    let lon_max = GEO_LONG_MIN + ((lon_cell + 1.0) / scale) * (GEO_LONG_MAX - GEO_LONG_MIN);
+
+    let lon = ((lon_min + lon_max) / 2.0).clamp(GEO_LONG_MIN, GEO_LONG_MAX);
+    let lat = ((lat_min + lat_max) / 2.0).clamp(GEO_LAT_MIN, GEO_LAT_MAX);
+    (lon, lat)
+}
+
+/// Returns the standard 11 character geohash string of a coordinate.
+///
+/// The stored score uses the Mercator latitude limits, so the coordinate is re-encoded
+/// against the full `-90..90` range to stay compatible with other geohash implementations.
+pub fn to_geohash_string(lon: f64, lat: f64) -> String {
+    let bits = encode_with_range(lon, lat, (-90.0, 90.0), (-180.0, 180.0));
+    (0..11)
+        .map(|i| {
+            let idx = if i == 10 {
+                // only 52 bits are available, the last character is padding
+                0
+            } else {
+                (bits >> (52 - (i + 1) * 5)) & 0x1f
+            };
+            GEO_ALPHABET[idx as usize] as char
+        })
+        .collect()
+}
+
+/// Returns the haversine distance in meters between two coordinates.
+pub fn distance(lon1: f64, lat1: f64, lon2: f64, lat2: f64) -> f64 {
+    let lat1r = lat1.to_radians();
+    let lat2r = lat2.to_radians();
+    let u = ((lat2r - lat1r) / 2.0).sin();
+    let v = ((lon2 - lon1).to_radians() / 2.0).sin();
+    let a = u * u + lat1r.cos() * lat2r.cos() * v * v;
+    2.0 * EARTH_RADIUS_IN_METERS * a.sqrt().asin()
+}
+
+/// Parses a distance unit into its size in meters.
+pub fn parse_unit(unit: &str) -> Option<f64> {
+    match unit.to_lowercase().as_str() {
+        "m" => Some(1.0),
+        "km" => Some(1000.0),
+        "ft" => Some(0.3048),
+        "mi" => Some(1609.34),
+        _ => None,
+    }
+}
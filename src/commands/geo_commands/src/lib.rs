@@ -0,0 +1,9 @@
+mod r#const;
+pub mod geo;
+mod geo_add;
+mod geo_dist;
+mod geo_hash;
+mod geo_pos;
+mod geo_search;
+mod geohash;
+mod utils;
@@ -0,0 +1,40 @@
+use crate::geohash::decode;
+use crate::r#const::WRONG_TYPE;
+use rus_key_db::db::{DataType, Db};
+use std::collections::BTreeMap;
+
+/// Retrieves the sorted set backing a geo key.
+///
+/// Expired keys are removed and reported as missing.
+///
+/// # Arguments
+///
+/// * `key` - The geo key.
+/// * `db` - A mutable reference to the `Db` object that represents the database.
+///
+/// # Returns
+///
+/// * `Ok(Some(set))` with a copy of the members and their scores, `Ok(None)` if the key does not exist,
+///   or an error if the key holds another data type.
+pub fn get_geo_set(
+    key: &str,
+    db: &mut Db,
+) -> Result<Option<BTreeMap<String, String>>, &'static str> {
+    match db.get(key) {
+        Some(DataType::ZSet(set)) => Ok(Some(set.clone())),
+        Some(_) => Err(WRONG_TYPE),
+        None => Ok(None),
+    }
+}
+
+/// Decodes the position of a member from its stored score.
+pub fn member_position(set: &BTreeMap<String, String>, member: &str) -> Option<(f64, f64)> {
+    set.get(member)
+        .and_then(|score| score.parse::<u64>().ok())
+        .map(decode)
+}
+
+/// Formats a coordinate with the precision the geohash can actually represent.
+pub fn format_coord(lon: f64, lat: f64) -> String {
+    format!("{:.6} {:.6}", lon, lat)
+}
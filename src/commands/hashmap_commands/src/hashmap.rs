@@ -1,8 +1,9 @@
 use std::str::SplitAsciiWhitespace;
-use rus_key_trait::command_trait::Command;
+use rus_key_trait::command_trait::{Arity, Command};
 use rus_key_db::db::Db;
 use crate::hgetall::hgetall;
 use crate::hmset::hmset;
+use crate::hset::hset;
 
 pub struct HashMapCommand {
     command: String,
@@ -22,8 +23,16 @@ impl Command for HashMapCommand {
     ) -> Result<String, &'static str> {
         match self.command.as_str() {
             "hmset" => hmset(parts, db),
+            "hset" => hset(parts, db),
             "hgetall" => hgetall(parts, db),
             _ => Err("HashMapCommand Error: Command not found"),
         }
     }
+
+    fn arity(&self) -> Arity {
+        match self.command.as_str() {
+            "hmset" | "hset" => Arity::new(3),
+            _ => Arity::new(1),
+        }
+    }
 }
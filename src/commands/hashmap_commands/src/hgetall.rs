@@ -1,5 +1,4 @@
 use std::str::SplitAsciiWhitespace;
-use expired_commands::expired::get_key_expired;
 use rus_key_db::db::{DataType, Db};
 
 pub fn hgetall(
@@ -10,16 +9,8 @@ pub fn hgetall(
         Some(key) => key,
         None => return Err("Key not specified"),
     };
-    // check expired
-    let expired = get_key_expired(Some(key), db);
-    if !expired.is_empty() && expired != "nil" {
-        return Err("There is no such key, the key is expired, or the data type is incorrect");
-    }
-    if expired == "nil" {
-        return Err("nil");
-    }
     match db.get(key) {
-        Some(DataType::ZSet(btree_map)) => {
+        Some(DataType::HashMap(btree_map)) => {
             let mut result = String::new();
             for (field, value) in btree_map {
                 result.push_str(&format!("{}: {} ", field, value));
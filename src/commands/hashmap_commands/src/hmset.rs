@@ -1,6 +1,7 @@
 use std::collections::BTreeMap;
 use std::str::SplitAsciiWhitespace;
 use rus_key_db::db::{DataType, Db};
+use rus_key_db::notify::NOTIFY_HASH;
 
 pub fn hmset(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
     let key = match parts.next() {
@@ -15,6 +16,7 @@ pub fn hmset(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'
         };
         btree_map.insert(field.to_string(), value.to_string());
     }
-    db.set(key.to_string(), DataType::ZSet(btree_map));
+    db.set(key.to_string(), DataType::HashMap(btree_map));
+    db.notify(NOTIFY_HASH, "hset", key);
     Ok("OK".to_string())
 }
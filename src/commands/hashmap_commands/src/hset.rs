@@ -0,0 +1,39 @@
+use std::collections::BTreeMap;
+use std::str::SplitAsciiWhitespace;
+use rus_key_db::db::{DataType, Db};
+use rus_key_db::notify::NOTIFY_HASH;
+
+pub fn hset(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let key = match parts.next() {
+        Some(key) => key,
+        None => return Err("Key not specified"),
+    };
+    let mut fields = Vec::new();
+    while let Some(field) = parts.next() {
+        let value = match parts.next() {
+            Some(value) => value,
+            None => return Err("Value not specified"),
+        };
+        fields.push((field, value));
+    }
+    if fields.is_empty() {
+        return Err("Field not specified");
+    }
+
+    let mut btree_map = match db.get(key) {
+        Some(DataType::HashMap(btree_map)) => btree_map.clone(),
+        Some(_) => {
+            return Err("There is no such key, the key is expired, or the data type is incorrect")
+        }
+        None => BTreeMap::new(),
+    };
+    let mut added = 0;
+    for (field, value) in fields {
+        if btree_map.insert(field.to_string(), value.to_string()).is_none() {
+            added += 1;
+        }
+    }
+    db.set(key.to_string(), DataType::HashMap(btree_map));
+    db.notify(NOTIFY_HASH, "hset", key);
+    Ok(added.to_string())
+}
@@ -1,3 +1,4 @@
 pub mod hashmap;
 mod hgetall;
 mod hmset;
+mod hset;
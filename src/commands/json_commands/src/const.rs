@@ -0,0 +1,21 @@
+pub const EMPTY: &str = "nil";
+
+pub const WRONG_TYPE: &str =
+    "There is no such key, the key is expired, or the data type is incorrect";
+
+pub const WRONG_ARGS: &str = "ERR wrong number of arguments for command";
+
+pub const INVALID_PATH: &str = "ERR invalid path";
+
+pub const INVALID_JSON: &str = "ERR invalid JSON value";
+
+pub const PATH_NOT_EXISTS: &str = "ERR path does not exist";
+
+pub const WRONG_PATH_TYPE: &str = "ERR wrong type of path value";
+
+pub const KEY_NOT_EXISTS: &str = "ERR could not perform this operation on a key that doesn't exist";
+
+pub const ROOT_ONLY: &str = "ERR new objects must be created at the root";
+
+// default path of the commands where the path is optional
+pub const ROOT: &str = ".";
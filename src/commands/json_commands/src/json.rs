@@ -0,0 +1,51 @@
+use crate::json_arr::{json_arr_append, json_arr_len, json_arr_pop};
+use crate::json_del::json_del;
+use crate::json_get::json_get;
+use crate::json_num_incr_by::json_num_incr_by;
+use crate::json_obj_keys::json_obj_keys;
+use crate::json_set::json_set;
+use crate::json_str_append::json_str_append;
+use crate::json_type::json_type;
+use rus_key_db::db::Db;
+use rus_key_trait::command_trait::{Arity, Command};
+use std::str::SplitAsciiWhitespace;
+
+pub struct JsonCommand {
+    command: String,
+}
+
+impl JsonCommand {
+    pub fn new(command: String) -> JsonCommand {
+        JsonCommand { command }
+    }
+}
+
+impl Command for JsonCommand {
+    fn execute(
+        &self,
+        parts: &mut SplitAsciiWhitespace,
+        db: &mut Db,
+    ) -> Result<String, &'static str> {
+        match self.command.as_str() {
+            "json.set" => json_set(parts, db),
+            "json.get" => json_get(parts, db),
+            "json.del" => json_del(parts, db),
+            "json.type" => json_type(parts, db),
+            "json.numincrby" => json_num_incr_by(parts, db),
+            "json.strappend" => json_str_append(parts, db),
+            "json.arrappend" => json_arr_append(parts, db),
+            "json.arrpop" => json_arr_pop(parts, db),
+            "json.arrlen" => json_arr_len(parts, db),
+            "json.objkeys" => json_obj_keys(parts, db),
+            _ => Err("JsonCommand Error: Command not found"),
+        }
+    }
+
+    fn arity(&self) -> Arity {
+        match self.command.as_str() {
+            "json.set" | "json.numincrby" | "json.arrappend" => Arity::new(3),
+            "json.strappend" => Arity::new(2),
+            _ => Arity::new(1),
+        }
+    }
+}
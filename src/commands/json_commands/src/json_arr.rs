@@ -0,0 +1,107 @@
+use crate::r#const::{EMPTY, KEY_NOT_EXISTS, ROOT, WRONG_ARGS, WRONG_PATH_TYPE};
+use crate::utils::{parse_values, reply, reply_value, update_json};
+use rus_key_db::db::Db;
+use serde_json::Value;
+use std::str::SplitAsciiWhitespace;
+
+/// Appends values to the arrays matched by a path.
+///
+/// Syntax: `JSON.ARRAPPEND key path value [value ...]`
+///
+/// # Returns
+///
+/// * The new length for a legacy path, an array of new lengths (null for non-arrays) for JSONPath.
+pub fn json_arr_append(
+    parts: &mut SplitAsciiWhitespace,
+    db: &mut Db,
+) -> Result<String, &'static str> {
+    let (key, path) = match (parts.next(), parts.next()) {
+        (Some(key), Some(path)) => (key, path),
+        _ => return Err(WRONG_ARGS),
+    };
+    let args: Vec<&str> = parts.collect();
+    let items = parse_values(&args)?;
+    if items.is_empty() {
+        return Err(WRONG_ARGS);
+    }
+
+    let (path, values) = update_json(
+        key,
+        path,
+        Some("json.arrappend"),
+        db,
+        |target| match target {
+            Value::Array(array) => {
+                array.extend(items.iter().cloned());
+                Ok(Value::from(array.len()))
+            }
+            _ => Err(WRONG_PATH_TYPE),
+        },
+    )?;
+    reply(&path, values)
+}
+
+/// Removes and returns an element from the arrays matched by a path.
+///
+/// Syntax: `JSON.ARRPOP key [path [index]]`
+///
+/// The index defaults to `-1` (the last element) and is clamped to the array bounds.
+///
+/// # Returns
+///
+/// * The popped JSON value for a legacy path, an array of popped values for JSONPath.
+///   Empty arrays pop `nil`.
+pub fn json_arr_pop(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let key = match parts.next() {
+        Some(key) => key,
+        None => return Err(WRONG_ARGS),
+    };
+    let path = parts.next().unwrap_or(ROOT);
+    let index = match parts.next() {
+        Some(index) => index
+            .parse::<i64>()
+            .map_err(|_| "ERR index is not an integer")?,
+        None => -1,
+    };
+
+    let (path, values) = update_json(key, path, Some("json.arrpop"), db, |target| match target {
+        Value::Array(array) if array.is_empty() => Ok(Value::Null),
+        Value::Array(array) => {
+            let len = array.len() as i64;
+            let index = if index < 0 { len + index } else { index };
+            let index = index.clamp(0, len - 1) as usize;
+            Ok(array.remove(index))
+        }
+        _ => Err(WRONG_PATH_TYPE),
+    })?;
+    if path.legacy {
+        return Ok(values.first().map(reply_value).unwrap_or(EMPTY.to_string()));
+    }
+    reply(&path, values)
+}
+
+/// Returns the length of the arrays matched by a path.
+///
+/// Syntax: `JSON.ARRLEN key [path]`
+///
+/// # Returns
+///
+/// * The length for a legacy path, an array of lengths (null for non-arrays) for JSONPath,
+///   `nil` if the key does not exist.
+pub fn json_arr_len(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let key = match parts.next() {
+        Some(key) => key,
+        None => return Err(WRONG_ARGS),
+    };
+    let path = parts.next().unwrap_or(ROOT);
+
+    let result = update_json(key, path, None, db, |target| match target {
+        Value::Array(array) => Ok(Value::from(array.len())),
+        _ => Err(WRONG_PATH_TYPE),
+    });
+    match result {
+        Ok((path, values)) => reply(&path, values),
+        Err(KEY_NOT_EXISTS) => Ok(EMPTY.to_string()),
+        Err(e) => Err(e),
+    }
+}
@@ -0,0 +1,67 @@
+use crate::path::{find, get_mut, parse, Loc};
+use crate::r#const::{ROOT, WRONG_ARGS};
+use crate::utils::get_json;
+use rus_key_db::db::{DataType, Db};
+use rus_key_db::notify::NOTIFY_MODULE;
+use serde_json::Value;
+use std::str::SplitAsciiWhitespace;
+
+/// Deletes the values matched by a path.
+///
+/// Syntax: `JSON.DEL key [path]`
+///
+/// Deleting the root deletes the key.
+///
+/// # Returns
+///
+/// * The number of values deleted.
+pub fn json_del(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let key = match parts.next() {
+        Some(key) => key,
+        None => return Err(WRONG_ARGS),
+    };
+    let path = parse(parts.next().unwrap_or(ROOT))?;
+
+    let mut document = match get_json(key, db)? {
+        Some(document) => document,
+        None => return Ok("0".to_string()),
+    };
+    let mut locations = find(&document, &path.segments);
+    if locations.iter().any(|locs| locs.is_empty()) {
+        db.delete(key);
+        db.notify(NOTIFY_MODULE, "json.del", key);
+        return Ok("1".to_string());
+    }
+
+    // children and higher array indexes go first so the remaining locations stay valid
+    locations.sort();
+    locations.reverse();
+    let mut count = 0;
+    for mut locs in locations {
+        let last = match locs.pop() {
+            Some(last) => last,
+            None => continue,
+        };
+        let removed = match (get_mut(&mut document, &locs), last) {
+            (Some(Value::Object(map)), Loc::Key(key)) => {
+                // retain keeps the member order, remove would swap the last member in
+                let len = map.len();
+                map.retain(|name, _| *name != key);
+                map.len() < len
+            }
+            (Some(Value::Array(array)), Loc::Index(index)) if index < array.len() => {
+                array.remove(index);
+                true
+            }
+            _ => false,
+        };
+        if removed {
+            count += 1;
+        }
+    }
+    if count > 0 {
+        db.set(key.to_string(), DataType::Json(document));
+        db.notify(NOTIFY_MODULE, "json.del", key);
+    }
+    Ok(count.to_string())
+}
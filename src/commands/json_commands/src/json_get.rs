@@ -0,0 +1,57 @@
+use crate::path::{find, get, parse};
+use crate::r#const::{EMPTY, PATH_NOT_EXISTS, ROOT, WRONG_ARGS};
+use crate::utils::get_json;
+use rus_key_db::db::Db;
+use serde_json::{Map, Value};
+use std::str::SplitAsciiWhitespace;
+
+/// Resolves one path for `JSON.GET`.
+fn get_path(document: &Value, path: &str) -> Result<Value, &'static str> {
+    let path = parse(path)?;
+    let mut values = find(document, &path.segments)
+        .iter()
+        .filter_map(|locs| get(document, locs).cloned())
+        .collect::<Vec<Value>>();
+    if path.legacy {
+        if values.is_empty() {
+            return Err(PATH_NOT_EXISTS);
+        }
+        Ok(values.swap_remove(0))
+    } else {
+        Ok(Value::Array(values))
+    }
+}
+
+/// Returns the serialized JSON matched by one or more paths.
+///
+/// Syntax: `JSON.GET key [path [path ...]]`
+///
+/// # Returns
+///
+/// * Without a path the whole document. With one path the matched value (legacy path) or an
+///   array of matches (JSONPath). With several paths an object keyed by path.
+///   `nil` if the key does not exist.
+pub fn json_get(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let key = match parts.next() {
+        Some(key) => key,
+        None => return Err(WRONG_ARGS),
+    };
+    let mut paths: Vec<&str> = parts.collect();
+    if paths.is_empty() {
+        paths.push(ROOT);
+    }
+
+    let document = match get_json(key, db)? {
+        Some(document) => document,
+        None => return Ok(EMPTY.to_string()),
+    };
+
+    if paths.len() == 1 {
+        return Ok(get_path(&document, paths[0])?.to_string());
+    }
+    let mut result = Map::new();
+    for path in paths {
+        result.insert(path.to_string(), get_path(&document, path)?);
+    }
+    Ok(Value::Object(result).to_string())
+}
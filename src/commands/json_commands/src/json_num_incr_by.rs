@@ -0,0 +1,51 @@
+use crate::r#const::{INVALID_JSON, WRONG_ARGS};
+use crate::utils::{reply, update_json};
+use rus_key_db::db::Db;
+use serde_json::Value;
+use std::str::SplitAsciiWhitespace;
+
+const NOT_A_NUMBER: &str = "ERR value is not a number";
+
+/// Adds two JSON numbers, staying an integer while both sides are integers and it fits.
+fn add(current: &Value, increment: &Value) -> Result<Value, &'static str> {
+    if let (Some(a), Some(b)) = (current.as_i64(), increment.as_i64()) {
+        if let Some(sum) = a.checked_add(b) {
+            return Ok(Value::from(sum));
+        }
+    }
+    match (current.as_f64(), increment.as_f64()) {
+        (Some(a), Some(b)) if (a + b).is_finite() => Ok(Value::from(a + b)),
+        (Some(_), Some(_)) => Err("ERR result is not a finite number"),
+        _ => Err(NOT_A_NUMBER),
+    }
+}
+
+/// Increments the numbers matched by a path.
+///
+/// Syntax: `JSON.NUMINCRBY key path value`
+///
+/// # Returns
+///
+/// * The new value for a legacy path, an array of new values (null for non-numbers) for JSONPath.
+pub fn json_num_incr_by(
+    parts: &mut SplitAsciiWhitespace,
+    db: &mut Db,
+) -> Result<String, &'static str> {
+    let (key, path, increment) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(key), Some(path), Some(increment)) => (key, path, increment),
+        _ => return Err(WRONG_ARGS),
+    };
+    let increment = match serde_json::from_str::<Value>(increment) {
+        Ok(increment) if increment.is_number() => increment,
+        _ => return Err(INVALID_JSON),
+    };
+
+    let (path, values) = update_json(key, path, Some("json.numincrby"), db, |target| {
+        if !target.is_number() {
+            return Err(NOT_A_NUMBER);
+        }
+        *target = add(target, &increment)?;
+        Ok(target.clone())
+    })?;
+    reply(&path, values)
+}
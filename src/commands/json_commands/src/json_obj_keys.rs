@@ -0,0 +1,34 @@
+use crate::r#const::{EMPTY, KEY_NOT_EXISTS, ROOT, WRONG_ARGS, WRONG_PATH_TYPE};
+use crate::utils::{reply, update_json};
+use rus_key_db::db::Db;
+use serde_json::Value;
+use std::str::SplitAsciiWhitespace;
+
+/// Returns the member names of the objects matched by a path.
+///
+/// Syntax: `JSON.OBJKEYS key [path]`
+///
+/// # Returns
+///
+/// * An array of names for a legacy path, an array of such arrays (null for non-objects) for
+///   JSONPath, `nil` if the key does not exist.
+pub fn json_obj_keys(
+    parts: &mut SplitAsciiWhitespace,
+    db: &mut Db,
+) -> Result<String, &'static str> {
+    let key = match parts.next() {
+        Some(key) => key,
+        None => return Err(WRONG_ARGS),
+    };
+    let path = parts.next().unwrap_or(ROOT);
+
+    let result = update_json(key, path, None, db, |target| match target {
+        Value::Object(map) => Ok(Value::from(map.keys().cloned().collect::<Vec<String>>())),
+        _ => Err(WRONG_PATH_TYPE),
+    });
+    match result {
+        Ok((path, values)) => reply(&path, values),
+        Err(KEY_NOT_EXISTS) => Ok(EMPTY.to_string()),
+        Err(e) => Err(e),
+    }
+}
@@ -0,0 +1,100 @@
+use crate::path::{find, get_mut, parse, Segment};
+use crate::r#const::{EMPTY, INVALID_JSON, PATH_NOT_EXISTS, ROOT_ONLY, WRONG_ARGS};
+use crate::utils::{get_json, parse_values};
+use rus_key_db::db::{DataType, Db};
+use rus_key_db::notify::NOTIFY_MODULE;
+use serde_json::Value;
+use std::str::SplitAsciiWhitespace;
+
+/// Sets a JSON value at a path.
+///
+/// Syntax: `JSON.SET key path value [NX | XX]`
+///
+/// A new key can only be created at the root. When the path does not match anything but its
+/// parent is an object, the last member is added to that object.
+///
+/// # Returns
+///
+/// * `OK`, `nil` if the `NX` or `XX` condition was not met, or an error message.
+pub fn json_set(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let (key, path) = match (parts.next(), parts.next()) {
+        (Some(key), Some(path)) => (key, path),
+        _ => return Err(WRONG_ARGS),
+    };
+    let mut args: Vec<&str> = parts.collect();
+    let (mut nx, mut xx) = (false, false);
+    match args.last().map(|arg| arg.to_lowercase()) {
+        Some(flag) if flag == "nx" => nx = true,
+        Some(flag) if flag == "xx" => xx = true,
+        _ => {}
+    }
+    if nx || xx {
+        args.pop();
+    }
+    let value = match parse_values(&args)?.as_slice() {
+        [value] => value.clone(),
+        [] => return Err(WRONG_ARGS),
+        _ => return Err(INVALID_JSON),
+    };
+    let path = parse(path)?;
+
+    let mut document = match get_json(key, db)? {
+        Some(document) => document,
+        None => {
+            if !path.segments.is_empty() {
+                return Err(ROOT_ONLY);
+            }
+            if xx {
+                return Ok(EMPTY.to_string());
+            }
+            db.set(key.to_string(), DataType::Json(value));
+            db.notify(NOTIFY_MODULE, "json.set", key);
+            return Ok("OK".to_string());
+        }
+    };
+
+    let locations = find(&document, &path.segments);
+    if !locations.is_empty() {
+        if nx {
+            return Ok(EMPTY.to_string());
+        }
+        for locs in locations.iter() {
+            if let Some(target) = get_mut(&mut document, locs) {
+                *target = value.clone();
+            }
+            if path.legacy {
+                break;
+            }
+        }
+    } else {
+        if xx {
+            return Ok(EMPTY.to_string());
+        }
+        // only a missing object member can be created
+        let (last, parent) = match path.segments.split_last() {
+            Some((Segment::Key(name), parent)) => (name.clone(), parent),
+            _ => return Ok(EMPTY.to_string()),
+        };
+        let parents = find(&document, parent);
+        if parents.is_empty() {
+            return if path.legacy {
+                Err(PATH_NOT_EXISTS)
+            } else {
+                Ok(EMPTY.to_string())
+            };
+        }
+        let mut created = false;
+        for locs in parents.iter() {
+            if let Some(Value::Object(map)) = get_mut(&mut document, locs) {
+                map.insert(last.clone(), value.clone());
+                created = true;
+            }
+        }
+        if !created {
+            return Ok(EMPTY.to_string());
+        }
+    }
+    db.set(key.to_string(), DataType::Json(document));
+    db.notify(NOTIFY_MODULE, "json.set", key);
+    Ok("OK".to_string())
+}
@@ -0,0 +1,53 @@
+use crate::r#const::{INVALID_JSON, ROOT, WRONG_ARGS, WRONG_PATH_TYPE};
+use crate::utils::{parse_values, reply, update_json};
+use rus_key_db::db::Db;
+use serde_json::Value;
+use std::str::SplitAsciiWhitespace;
+
+/// Appends a string to the strings matched by a path.
+///
+/// Syntax: `JSON.STRAPPEND key [path] value`
+///
+/// The value must be a JSON string, e.g. `'"suffix"'`.
+///
+/// # Returns
+///
+/// * The new length for a legacy path, an array of new lengths (null for non-strings) for JSONPath.
+pub fn json_str_append(
+    parts: &mut SplitAsciiWhitespace,
+    db: &mut Db,
+) -> Result<String, &'static str> {
+    let key = match parts.next() {
+        Some(key) => key,
+        None => return Err(WRONG_ARGS),
+    };
+    let args: Vec<&str> = parts.collect();
+    // the path is optional, and the value itself may contain spaces
+    let (path, value) = match parse_values(&args).ok().as_deref() {
+        Some([value]) => (ROOT, value.clone()),
+        _ if args.len() >= 2 => match parse_values(&args[1..])?.as_slice() {
+            [value] => (args[0], value.clone()),
+            _ => return Err(INVALID_JSON),
+        },
+        _ => return Err(WRONG_ARGS),
+    };
+    let suffix = match value {
+        Value::String(suffix) => suffix,
+        _ => return Err(INVALID_JSON),
+    };
+
+    let (path, values) = update_json(
+        key,
+        path,
+        Some("json.strappend"),
+        db,
+        |target| match target {
+            Value::String(s) => {
+                s.push_str(&suffix);
+                Ok(Value::from(s.len()))
+            }
+            _ => Err(WRONG_PATH_TYPE),
+        },
+    )?;
+    reply(&path, values)
+}
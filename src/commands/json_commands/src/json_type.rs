@@ -0,0 +1,48 @@
+use crate::path::{find, get, parse};
+use crate::r#const::{EMPTY, ROOT, WRONG_ARGS};
+use crate::utils::get_json;
+use rus_key_db::db::Db;
+use serde_json::Value;
+use std::str::SplitAsciiWhitespace;
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Returns the type of the values matched by a path.
+///
+/// Syntax: `JSON.TYPE key [path]`
+///
+/// # Returns
+///
+/// * The type name for a legacy path, an array of type names for JSONPath,
+///   `nil` if the key or the legacy path does not exist.
+pub fn json_type(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let key = match parts.next() {
+        Some(key) => key,
+        None => return Err(WRONG_ARGS),
+    };
+    let path = parse(parts.next().unwrap_or(ROOT))?;
+    let document = match get_json(key, db)? {
+        Some(document) => document,
+        None => return Ok(EMPTY.to_string()),
+    };
+
+    let types = find(&document, &path.segments)
+        .iter()
+        .filter_map(|locs| get(&document, locs))
+        .map(|value| type_name(value))
+        .collect::<Vec<&str>>();
+    if path.legacy {
+        return Ok(types.first().unwrap_or(&EMPTY).to_string());
+    }
+    Ok(Value::from(types).to_string())
+}
@@ -0,0 +1,12 @@
+mod r#const;
+pub mod json;
+mod json_arr;
+mod json_del;
+mod json_get;
+mod json_num_incr_by;
+mod json_obj_keys;
+mod json_set;
+mod json_str_append;
+mod json_type;
+mod path;
+mod utils;
@@ -0,0 +1,268 @@
+use crate::r#const::INVALID_PATH;
+use serde_json::Value;
+
+/// A single step of a parsed path.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment {
+    Key(String),
+    Index(i64),
+    Wildcard,
+    // `..key`
+    Descendant(String),
+    // `..*`
+    DescendantWildcard,
+}
+
+/// A concrete location inside a document, produced by resolving a path.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Loc {
+    Key(String),
+    Index(usize),
+}
+
+/// A parsed path.
+///
+/// Paths starting with `$` are JSONPath and may match any number of values.
+/// Everything else is a legacy path (`.`, `.a.b`, `a[0]`) which addresses a single value.
+#[derive(Debug, Clone)]
+pub struct JsonPath {
+    pub segments: Vec<Segment>,
+    pub legacy: bool,
+}
+
+fn read_name(chars: &[char], start: usize) -> (String, usize) {
+    let mut end = start;
+    while end < chars.len() && chars[end] != '.' && chars[end] != '[' {
+        end += 1;
+    }
+    (chars[start..end].iter().collect(), end)
+}
+
+/// Parses the content of a bracket starting right after `[`.
+fn read_bracket(chars: &[char], start: usize) -> Result<(Segment, usize), &'static str> {
+    let mut i = start;
+    let segment = match chars.get(i) {
+        Some('*') => {
+            i += 1;
+            Segment::Wildcard
+        }
+        Some(quote) if *quote == '\'' || *quote == '"' => {
+            let quote = *quote;
+            i += 1;
+            let name_start = i;
+            while i < chars.len() && chars[i] != quote {
+                i += 1;
+            }
+            if i >= chars.len() {
+                return Err(INVALID_PATH);
+            }
+            let name: String = chars[name_start..i].iter().collect();
+            i += 1;
+            Segment::Key(name)
+        }
+        Some(_) => {
+            let index_start = i;
+            while i < chars.len() && chars[i] != ']' {
+                i += 1;
+            }
+            let index: String = chars[index_start..i].iter().collect();
+            Segment::Index(index.trim().parse::<i64>().map_err(|_| INVALID_PATH)?)
+        }
+        None => return Err(INVALID_PATH),
+    };
+    if chars.get(i) != Some(&']') {
+        return Err(INVALID_PATH);
+    }
+    Ok((segment, i + 1))
+}
+
+/// Parses a JSONPath or legacy path.
+///
+/// The supported subset is member access (`.name`, `['name']`), array indexes
+/// (`[0]`, `[-1]`), wildcards (`.*`, `[*]`) and recursive descent (`..name`, `..*`).
+///
+/// # Arguments
+///
+/// * `path` - The path as given on the command line.
+///
+/// # Returns
+///
+/// * The parsed path, or an error message if the path cannot be parsed.
+pub fn parse(path: &str) -> Result<JsonPath, &'static str> {
+    let (legacy, rest) = match path.strip_prefix('$') {
+        Some(rest) => (false, rest.to_string()),
+        None if path == "." => (true, String::new()),
+        None if path.starts_with('.') || path.starts_with('[') => (true, path.to_string()),
+        None => (true, format!(".{}", path)),
+    };
+
+    let chars: Vec<char> = rest.chars().collect();
+    let mut segments = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '.' if chars.get(i + 1) == Some(&'.') => {
+                i += 2;
+                if chars.get(i) == Some(&'*') {
+                    segments.push(Segment::DescendantWildcard);
+                    i += 1;
+                } else {
+                    let (name, end) = read_name(&chars, i);
+                    if name.is_empty() {
+                        return Err(INVALID_PATH);
+                    }
+                    segments.push(Segment::Descendant(name));
+                    i = end;
+                }
+            }
+            '.' => {
+                i += 1;
+                if chars.get(i) == Some(&'*') {
+                    segments.push(Segment::Wildcard);
+                    i += 1;
+                } else {
+                    let (name, end) = read_name(&chars, i);
+                    if name.is_empty() {
+                        return Err(INVALID_PATH);
+                    }
+                    segments.push(Segment::Key(name));
+                    i = end;
+                }
+            }
+            '[' => {
+                let (segment, end) = read_bracket(&chars, i + 1)?;
+                segments.push(segment);
+                i = end;
+            }
+            _ => return Err(INVALID_PATH),
+        }
+    }
+    Ok(JsonPath { segments, legacy })
+}
+
+fn push(locs: &[Loc], loc: Loc) -> Vec<Loc> {
+    let mut locs = locs.to_vec();
+    locs.push(loc);
+    locs
+}
+
+fn collect_descendants<'a>(
+    value: &'a Value,
+    locs: Vec<Loc>,
+    name: Option<&str>,
+    out: &mut Vec<(Vec<Loc>, &'a Value)>,
+) {
+    match value {
+        Value::Object(map) => {
+            if let Some(name) = name {
+                if let Some(child) = map.get(name) {
+                    out.push((push(&locs, Loc::Key(name.to_string())), child));
+                }
+            }
+            for (key, child) in map {
+                let child_locs = push(&locs, Loc::Key(key.clone()));
+                if name.is_none() {
+                    out.push((child_locs.clone(), child));
+                }
+                collect_descendants(child, child_locs, name, out);
+            }
+        }
+        Value::Array(array) => {
+            for (index, child) in array.iter().enumerate() {
+                let child_locs = push(&locs, Loc::Index(index));
+                if name.is_none() {
+                    out.push((child_locs.clone(), child));
+                }
+                collect_descendants(child, child_locs, name, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn step<'a>(
+    value: &'a Value,
+    locs: &[Loc],
+    segment: &Segment,
+    out: &mut Vec<(Vec<Loc>, &'a Value)>,
+) {
+    match segment {
+        Segment::Key(name) => {
+            if let Some(child) = value.as_object().and_then(|map| map.get(name)) {
+                out.push((push(locs, Loc::Key(name.clone())), child));
+            }
+        }
+        Segment::Index(index) => {
+            if let Some(array) = value.as_array() {
+                let len = array.len() as i64;
+                let index = if *index < 0 { len + index } else { *index };
+                if (0..len).contains(&index) {
+                    out.push((
+                        push(locs, Loc::Index(index as usize)),
+                        &array[index as usize],
+                    ));
+                }
+            }
+        }
+        Segment::Wildcard => match value {
+            Value::Object(map) => {
+                for (key, child) in map {
+                    out.push((push(locs, Loc::Key(key.clone())), child));
+                }
+            }
+            Value::Array(array) => {
+                for (index, child) in array.iter().enumerate() {
+                    out.push((push(locs, Loc::Index(index)), child));
+                }
+            }
+            _ => {}
+        },
+        Segment::Descendant(name) => collect_descendants(value, locs.to_vec(), Some(name), out),
+        Segment::DescendantWildcard => collect_descendants(value, locs.to_vec(), None, out),
+    }
+}
+
+/// Resolves the segments of a path against a document.
+///
+/// # Arguments
+///
+/// * `root` - The document.
+/// * `segments` - The parsed path segments.
+///
+/// # Returns
+///
+/// * The locations of every matching value, in document order.
+pub fn find(root: &Value, segments: &[Segment]) -> Vec<Vec<Loc>> {
+    let mut current: Vec<(Vec<Loc>, &Value)> = vec![(Vec::new(), root)];
+    for segment in segments {
+        let mut next = Vec::new();
+        for (locs, value) in current.iter() {
+            step(value, locs, segment, &mut next);
+        }
+        current = next;
+    }
+    let mut result: Vec<Vec<Loc>> = Vec::with_capacity(current.len());
+    for (locs, _) in current {
+        // recursive descent can reach the same value twice
+        if !result.contains(&locs) {
+            result.push(locs);
+        }
+    }
+    result
+}
+
+/// Returns the value at a concrete location.
+pub fn get<'a>(root: &'a Value, locs: &[Loc]) -> Option<&'a Value> {
+    locs.iter().try_fold(root, |value, loc| match loc {
+        Loc::Key(key) => value.get(key.as_str()),
+        Loc::Index(index) => value.get(*index),
+    })
+}
+
+/// Returns a mutable reference to the value at a concrete location.
+pub fn get_mut<'a>(root: &'a mut Value, locs: &[Loc]) -> Option<&'a mut Value> {
+    locs.iter().try_fold(root, |value, loc| match loc {
+        Loc::Key(key) => value.get_mut(key.as_str()),
+        Loc::Index(index) => value.get_mut(*index),
+    })
+}
@@ -0,0 +1,116 @@
+use crate::path::{find, get_mut, parse, JsonPath};
+use crate::r#const::{EMPTY, INVALID_JSON, KEY_NOT_EXISTS, PATH_NOT_EXISTS, WRONG_TYPE};
+use rus_key_db::db::{DataType, Db};
+use rus_key_db::notify::NOTIFY_MODULE;
+use serde_json::{Deserializer, Value};
+
+/// Retrieves a copy of the document stored at `key`.
+///
+/// Expired keys are removed and reported as missing.
+///
+/// # Returns
+///
+/// * `Ok(Some(document))`, `Ok(None)` if the key does not exist, or an error if the key holds
+///   another data type.
+pub fn get_json(key: &str, db: &mut Db) -> Result<Option<Value>, &'static str> {
+    match db.get(key) {
+        Some(DataType::Json(value)) => Ok(Some(value.clone())),
+        Some(_) => Err(WRONG_TYPE),
+        None => Ok(None),
+    }
+}
+
+/// Parses a sequence of JSON values separated by whitespace.
+///
+/// The command line is split on whitespace before it reaches the command, so values are
+/// re-joined and streamed through the JSON parser to keep strings and objects with spaces intact.
+pub fn parse_values(args: &[&str]) -> Result<Vec<Value>, &'static str> {
+    let joined = args.join(" ");
+    Deserializer::from_str(&joined)
+        .into_iter::<Value>()
+        .collect::<Result<Vec<Value>, _>>()
+        .map_err(|_| INVALID_JSON)
+}
+
+/// Renders a single value as a reply, `nil` for JSON null.
+pub fn reply_value(value: &Value) -> String {
+    match value {
+        Value::Null => EMPTY.to_string(),
+        value => value.to_string(),
+    }
+}
+
+/// Renders the values matched by a path.
+///
+/// Legacy paths reply with the first value only, JSONPath replies with a JSON array.
+pub fn reply(path: &JsonPath, values: Vec<Value>) -> Result<String, &'static str> {
+    if path.legacy {
+        match values.first() {
+            Some(value) => Ok(reply_value(value)),
+            None => Err(PATH_NOT_EXISTS),
+        }
+    } else {
+        Ok(Value::Array(values).to_string())
+    }
+}
+
+/// Applies an operation to every value matched by a path of the document stored at `key`.
+///
+/// For JSONPath a value the operation rejects is reported as null, for legacy paths the
+/// error is returned. A writing operation names its keyspace event, the document is then
+/// written back and the event published if the operation succeeded on any value.
+///
+/// # Arguments
+///
+/// * `key` - The key holding the document.
+/// * `path` - The path as given on the command line.
+/// * `event` - The keyspace event of the operation if it modifies the document.
+/// * `db` - A mutable reference to the `Db` object that represents the database.
+/// * `op` - The operation, returning the per-value reply.
+///
+/// # Returns
+///
+/// * The parsed path together with the per-value replies.
+pub fn update_json<F>(
+    key: &str,
+    path: &str,
+    event: Option<&str>,
+    db: &mut Db,
+    mut op: F,
+) -> Result<(JsonPath, Vec<Value>), &'static str>
+where
+    F: FnMut(&mut Value) -> Result<Value, &'static str>,
+{
+    let mut document = get_json(key, db)?.ok_or(KEY_NOT_EXISTS)?;
+    let path = parse(path)?;
+    let locations = find(&document, &path.segments);
+    if path.legacy && locations.is_empty() {
+        return Err(PATH_NOT_EXISTS);
+    }
+
+    let mut values = Vec::with_capacity(locations.len());
+    let mut modified = false;
+    for locs in locations.iter() {
+        let target = match get_mut(&mut document, locs) {
+            Some(target) => target,
+            None => continue,
+        };
+        match op(target) {
+            Ok(value) => {
+                values.push(value);
+                modified = true;
+            }
+            Err(e) if path.legacy => return Err(e),
+            Err(_) => values.push(Value::Null),
+        }
+        if path.legacy {
+            break;
+        }
+    }
+
+    if let Some(event) = event.filter(|_| modified) {
+        db.set(key.to_string(), DataType::Json(document));
+        db.notify(NOTIFY_MODULE, event, key);
+    }
+    Ok((path, values))
+}
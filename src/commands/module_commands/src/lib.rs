@@ -0,0 +1,3 @@
+pub mod module;
+pub mod registry;
+pub mod wasm;
@@ -0,0 +1,58 @@
+use std::collections::HashSet;
+use std::str::SplitAsciiWhitespace;
+
+use rus_key_command_lib::get_value;
+use rus_key_db::db::Db;
+use rus_key_trait::command_trait::{Arity, Command};
+
+use crate::registry;
+use crate::wasm::module_fuel;
+
+const WRONG_ARGS: &str = "ERR wrong number of arguments for command";
+const UNKNOWN_SUBCOMMAND: &str = "ERR unknown subcommand";
+
+/// `MODULE LOAD path | UNLOAD name | LIST`
+///
+/// Loads WebAssembly modules adding commands to the server, see `WasmModule` for what a module
+/// may do. The commands of a module are available from the next command, and a module is named
+/// after its file, `path/to/counter.wasm` is `counter`.
+///
+/// Since a module may write anything, its commands are refused with an OOM error once
+/// eviction cannot bring the databases under `maxmemory`, like the built in writes.
+pub struct ModuleCommand {
+    builtin: &'static HashSet<String>,
+}
+
+impl ModuleCommand {
+    /// `builtin` are the names a module cannot register.
+    pub fn new(builtin: &'static HashSet<String>) -> ModuleCommand {
+        ModuleCommand { builtin }
+    }
+}
+
+impl Command for ModuleCommand {
+    fn execute(
+        &self,
+        parts: &mut SplitAsciiWhitespace,
+        db: &mut Db,
+    ) -> Result<String, &'static str> {
+        let subcommand = parts.next().ok_or(WRONG_ARGS)?.to_lowercase();
+        match subcommand.as_str() {
+            "load" => {
+                let path = get_value(parts.next().ok_or(WRONG_ARGS)?.to_string(), parts);
+                registry::load(db.server(), &path, self.builtin, module_fuel(db))
+            }
+            "unload" => registry::unload(db.server(), parts.next().ok_or(WRONG_ARGS)?),
+            "list" => Ok(registry::list(db.server())
+                .into_iter()
+                .map(|(name, path, commands)| format!("{} {} {}", name, path, commands.join(" ")))
+                .collect::<Vec<String>>()
+                .join("\n")),
+            _ => Err(UNKNOWN_SUBCOMMAND),
+        }
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::new(1)
+    }
+}
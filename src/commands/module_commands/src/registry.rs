@@ -0,0 +1,93 @@
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Arc;
+
+use rus_key_db::server::Server;
+
+use crate::wasm::{WasmCommand, WasmModule};
+
+const CANNOT_READ: &str = "ERR Error loading the module: cannot read the file";
+const ALREADY_LOADED: &str =
+    "ERR Error loading the module: a module with that name is already loaded";
+const NAME_IN_USE: &str = "ERR Error loading the module: command name already in use";
+const NO_SUCH_MODULE: &str = "ERR Error unloading module: no such module with that name";
+
+// the modules loaded in a server, in name order
+fn modules(server: &Server) -> Vec<Arc<WasmModule>> {
+    server
+        .modules
+        .read()
+        .values()
+        .filter_map(|module| Arc::clone(module).downcast::<WasmModule>().ok())
+        .collect()
+}
+
+/// Loads the module at `path` in a server, named after its file, unless one of its commands
+/// is built in or registered by another module.
+pub fn load(
+    server: &Server,
+    path: &str,
+    builtin: &HashSet<String>,
+    fuel: u64,
+) -> Result<String, &'static str> {
+    let name = Path::new(path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_lowercase())
+        .ok_or(CANNOT_READ)?;
+    let bytes = std::fs::read(path).map_err(|_| CANNOT_READ)?;
+    let module = WasmModule::load(&name, path, &bytes, fuel)?;
+
+    let mut modules = server.modules.write();
+    if modules.contains_key(&name) {
+        return Err(ALREADY_LOADED);
+    }
+    let in_use = module.commands.iter().any(|command| {
+        builtin.contains(command)
+            || modules
+                .values()
+                .filter_map(|loaded| loaded.downcast_ref::<WasmModule>())
+                .any(|loaded| loaded.commands.contains(command))
+    });
+    if in_use {
+        return Err(NAME_IN_USE);
+    }
+    modules.insert(name, Arc::new(module));
+    Ok("OK".to_string())
+}
+
+/// Unloads a module, its commands are gone from the next command factory.
+pub fn unload(server: &Server, name: &str) -> Result<String, &'static str> {
+    match server.modules.write().remove(&name.to_lowercase()) {
+        Some(_) => Ok("OK".to_string()),
+        None => Err(NO_SUCH_MODULE),
+    }
+}
+
+/// Returns the loaded modules, each with its path and commands.
+pub fn list(server: &Server) -> Vec<(String, String, Vec<String>)> {
+    modules(server)
+        .iter()
+        .map(|module| {
+            (
+                module.name.clone(),
+                module.path.clone(),
+                module.commands.clone(),
+            )
+        })
+        .collect()
+}
+
+/// Returns the commands of the modules loaded in a server, by name.
+pub fn commands(server: &Server) -> Vec<(String, WasmCommand)> {
+    modules(server)
+        .iter()
+        .flat_map(|module| {
+            module.commands.iter().map(|command| {
+                (
+                    command.clone(),
+                    WasmCommand::new(command.clone(), Arc::clone(module)),
+                )
+            })
+        })
+        .collect()
+}
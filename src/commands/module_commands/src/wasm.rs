@@ -0,0 +1,309 @@
+use std::str::SplitAsciiWhitespace;
+use std::sync::Arc;
+
+use rus_key_db::db::{DataType, Db};
+use rus_key_db::notify::{NOTIFY_GENERIC, NOTIFY_STRING};
+use rus_key_trait::command_trait::{Arity, Command};
+use wasmi::core::TrapCode;
+use wasmi::{
+    Caller, Config, Engine, Error, Extern, Instance, Linker, Memory, Module, Store, StoreLimits,
+    StoreLimitsBuilder,
+};
+
+const INVALID_MODULE: &str = "ERR Error loading the module: not a valid WebAssembly module";
+const UNKNOWN_IMPORT: &str =
+    "ERR Error loading the module: it imports something the host API does not provide";
+const MISSING_EXPORT: &str =
+    "ERR Error loading the module: it must export memory, ruskey_init, ruskey_alloc and ruskey_command";
+const NO_COMMANDS: &str = "ERR Error loading the module: it registers no command";
+const OUT_OF_FUEL: &str = "ERR Module command ran out of fuel";
+const TRAPPED: &str = "ERR Module command trapped";
+const FAILED: &str = "ERR Module command failed";
+
+const MODULE_FUEL: &str = "module-fuel";
+const DEFAULT_MODULE_FUEL: u64 = 10_000_000;
+// the size the linear memory of a module may grow to
+const MAX_MEMORY: usize = 64 * 1024 * 1024;
+// the import module of the host API
+const HOST: &str = "ruskey";
+
+/// Returns the fuel a module may burn per call, from `module-fuel`.
+pub fn module_fuel(db: &Db) -> u64 {
+    db.server()
+        .config_get(MODULE_FUEL)
+        .and_then(|fuel| fuel.parse().ok())
+        .filter(|&fuel| fuel > 0)
+        .unwrap_or(DEFAULT_MODULE_FUEL)
+}
+
+// the state of one call into a module, the database is only given to commands
+struct Host<'a> {
+    db: Option<&'a mut Db>,
+    registered: Vec<String>,
+    reply: Option<String>,
+    limits: StoreLimits,
+}
+
+/// A compiled WebAssembly module and the commands it registered.
+///
+/// Modules only see the host API imported from `ruskey`, all taking pointers and lengths into
+/// the memory of the module:
+///
+/// * `register(name_ptr, name_len)` adds a command, from `ruskey_init` only.
+/// * `get(key_ptr, key_len, out_ptr, out_cap) -> i32` copies up to `out_cap` bytes of the value
+///   and returns its length, -1 if the key does not exist, -2 if it does not hold a string.
+/// * `set(key_ptr, key_len, value_ptr, value_len)` sets a string value, like SET.
+/// * `del(key_ptr, key_len) -> i32` returns 1 if the key was removed.
+/// * `reply(ptr, len)` sets the reply of the command.
+///
+/// A module exports its `memory`, `ruskey_init()`, `ruskey_alloc(len) -> ptr` and
+/// `ruskey_command(ptr, len) -> i32`, called with the command line, name first, and returning
+/// 0 on success. Every call runs in a new instance with `module-fuel` fuel, so nothing is kept
+/// between calls outside of the database and a call that runs too long traps.
+pub struct WasmModule {
+    pub name: String,
+    pub path: String,
+    pub commands: Vec<String>,
+    engine: Engine,
+    module: Module,
+}
+
+impl WasmModule {
+    /// Compiles a module and runs its `ruskey_init` to get its commands.
+    pub fn load(
+        name: &str,
+        path: &str,
+        bytes: &[u8],
+        fuel: u64,
+    ) -> Result<WasmModule, &'static str> {
+        let mut config = Config::default();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config);
+        let module = Module::new(&engine, bytes).map_err(|_| INVALID_MODULE)?;
+        let mut wasm = WasmModule {
+            name: name.to_string(),
+            path: path.to_string(),
+            commands: Vec::new(),
+            engine,
+            module,
+        };
+        let mut store = wasm.store(None, fuel);
+        let instance = wasm.instantiate(&mut store)?;
+        for export in ["ruskey_alloc", "ruskey_command"] {
+            instance.get_func(&store, export).ok_or(MISSING_EXPORT)?;
+        }
+        instance
+            .get_typed_func::<(), ()>(&store, "ruskey_init")
+            .map_err(|_| MISSING_EXPORT)?
+            .call(&mut store, ())
+            .map_err(trap_error)?;
+        let mut commands = store.into_data().registered;
+        commands.sort();
+        commands.dedup();
+        if commands.is_empty() {
+            return Err(NO_COMMANDS);
+        }
+        wasm.commands = commands;
+        Ok(wasm)
+    }
+
+    /// Runs a command of the module, `line` is the command name and its arguments.
+    pub fn call(&self, line: &str, db: &mut Db) -> Result<String, &'static str> {
+        let fuel = module_fuel(db);
+        let mut store = self.store(Some(db), fuel);
+        let instance = self.instantiate(&mut store)?;
+        let memory = instance
+            .get_memory(&store, "memory")
+            .ok_or(MISSING_EXPORT)?;
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&store, "ruskey_alloc")
+            .map_err(|_| MISSING_EXPORT)?;
+        let command = instance
+            .get_typed_func::<(i32, i32), i32>(&store, "ruskey_command")
+            .map_err(|_| MISSING_EXPORT)?;
+
+        let len = i32::try_from(line.len()).map_err(|_| FAILED)?;
+        let ptr = alloc.call(&mut store, len).map_err(trap_error)?;
+        memory
+            .write(&mut store, ptr as u32 as usize, line.as_bytes())
+            .map_err(|_| TRAPPED)?;
+        let status = command.call(&mut store, (ptr, len)).map_err(trap_error)?;
+        if status != 0 {
+            return Err(FAILED);
+        }
+        Ok(store.into_data().reply.unwrap_or_else(|| "OK".to_string()))
+    }
+
+    fn store<'a>(&self, db: Option<&'a mut Db>, fuel: u64) -> Store<Host<'a>> {
+        let host = Host {
+            db,
+            registered: Vec::new(),
+            reply: None,
+            limits: StoreLimitsBuilder::new().memory_size(MAX_MEMORY).build(),
+        };
+        let mut store = Store::new(&self.engine, host);
+        store.limiter(|host| &mut host.limits);
+        // fuel metering is enabled on the engine
+        let _ = store.set_fuel(fuel);
+        store
+    }
+
+    fn instantiate(&self, store: &mut Store<Host<'_>>) -> Result<Instance, &'static str> {
+        let mut linker = Linker::new(&self.engine);
+        link_host(&mut linker).map_err(|_| UNKNOWN_IMPORT)?;
+        linker
+            .instantiate(&mut *store, &self.module)
+            .map_err(|_| UNKNOWN_IMPORT)?
+            .start(store)
+            .map_err(trap_error)
+    }
+}
+
+fn trap_error(error: Error) -> &'static str {
+    match error.as_trap_code() {
+        Some(TrapCode::OutOfFuel) => OUT_OF_FUEL,
+        _ => TRAPPED,
+    }
+}
+
+fn memory(caller: &Caller<'_, Host<'_>>) -> Result<Memory, Error> {
+    caller
+        .get_export("memory")
+        .and_then(Extern::into_memory)
+        .ok_or_else(|| Error::new(MISSING_EXPORT))
+}
+
+// reads a string of the module, out of bounds or invalid UTF-8 traps
+fn read_string(caller: &Caller<'_, Host<'_>>, ptr: i32, len: i32) -> Result<String, Error> {
+    let memory = memory(caller)?;
+    let start = ptr as u32 as usize;
+    let bytes = memory
+        .data(caller)
+        .get(start..start.saturating_add(len as u32 as usize))
+        .ok_or_else(|| Error::new("out of bounds memory access"))?;
+    String::from_utf8(bytes.to_vec()).map_err(|_| Error::new("string is not UTF-8"))
+}
+
+fn db<'c>(caller: &'c mut Caller<'_, Host<'_>>) -> Result<&'c mut Db, Error> {
+    caller
+        .data_mut()
+        .db
+        .as_deref_mut()
+        .ok_or_else(|| Error::new("keys are only accessed from commands"))
+}
+
+fn link_host(linker: &mut Linker<Host<'_>>) -> Result<(), wasmi::errors::LinkerError> {
+    linker.func_wrap(
+        HOST,
+        "register",
+        |mut caller: Caller<'_, Host<'_>>, ptr: i32, len: i32| -> Result<(), Error> {
+            let name = read_string(&caller, ptr, len)?.to_lowercase();
+            let host = caller.data_mut();
+            if host.db.is_some() || name.is_empty() || name.contains(char::is_whitespace) {
+                return Err(Error::new("commands are registered from ruskey_init"));
+            }
+            host.registered.push(name);
+            Ok(())
+        },
+    )?;
+    linker.func_wrap(
+        HOST,
+        "get",
+        |mut caller: Caller<'_, Host<'_>>,
+         key_ptr: i32,
+         key_len: i32,
+         out_ptr: i32,
+         out_cap: i32|
+         -> Result<i32, Error> {
+            let key = read_string(&caller, key_ptr, key_len)?;
+            let value = match db(&mut caller)?.get(&key) {
+                Some(DataType::String(value)) => value.clone(),
+                Some(_) => return Ok(-2),
+                None => return Ok(-1),
+            };
+            let copied = value.len().min(out_cap.max(0) as usize);
+            memory(&caller)?
+                .write(
+                    &mut caller,
+                    out_ptr as u32 as usize,
+                    &value.as_bytes()[..copied],
+                )
+                .map_err(|_| Error::new("out of bounds memory access"))?;
+            Ok(value.len() as i32)
+        },
+    )?;
+    linker.func_wrap(
+        HOST,
+        "set",
+        |mut caller: Caller<'_, Host<'_>>,
+         key_ptr: i32,
+         key_len: i32,
+         value_ptr: i32,
+         value_len: i32|
+         -> Result<(), Error> {
+            let key = read_string(&caller, key_ptr, key_len)?;
+            let value = read_string(&caller, value_ptr, value_len)?;
+            let db = db(&mut caller)?;
+            db.set(key.clone(), DataType::String(value));
+            db.persist(&key);
+            db.notify(NOTIFY_STRING, "set", &key);
+            Ok(())
+        },
+    )?;
+    linker.func_wrap(
+        HOST,
+        "del",
+        |mut caller: Caller<'_, Host<'_>>, ptr: i32, len: i32| -> Result<i32, Error> {
+            let key = read_string(&caller, ptr, len)?;
+            let db = db(&mut caller)?;
+            if !db.delete(&key) {
+                return Ok(0);
+            }
+            db.notify(NOTIFY_GENERIC, "del", &key);
+            Ok(1)
+        },
+    )?;
+    linker.func_wrap(
+        HOST,
+        "reply",
+        |mut caller: Caller<'_, Host<'_>>, ptr: i32, len: i32| -> Result<(), Error> {
+            let reply = read_string(&caller, ptr, len)?;
+            caller.data_mut().reply = Some(reply);
+            Ok(())
+        },
+    )?;
+    Ok(())
+}
+
+/// A command registered by a module, it keeps the module loaded until the factory holding it
+/// is dropped.
+pub struct WasmCommand {
+    name: String,
+    module: Arc<WasmModule>,
+}
+
+impl WasmCommand {
+    pub fn new(name: String, module: Arc<WasmModule>) -> WasmCommand {
+        WasmCommand { name, module }
+    }
+}
+
+impl Command for WasmCommand {
+    fn execute(
+        &self,
+        parts: &mut SplitAsciiWhitespace,
+        db: &mut Db,
+    ) -> Result<String, &'static str> {
+        let mut line = self.name.clone();
+        for part in parts {
+            line.push(' ');
+            line.push_str(part);
+        }
+        self.module.call(&line, db)
+    }
+
+    fn arity(&self) -> Arity {
+        // a module parses its own arguments
+        Arity::new(0)
+    }
+}
@@ -1,4 +1,4 @@
-use rus_key_trait::command_trait::Command;
+use rus_key_trait::command_trait::{Arity, Command};
 use rus_key_db::db::Db;
 use std::str::SplitAsciiWhitespace;
 
@@ -16,4 +16,8 @@ impl Command for PingCommand {
             None => Ok("PONG".to_string()),
         }
     }
+
+    fn arity(&self) -> Arity {
+        Arity::new(0)
+    }
 }
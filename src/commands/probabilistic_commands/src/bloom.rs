@@ -0,0 +1,175 @@
+use crate::r#const::{
+    BAD_CAPACITY, BAD_EXPANSION, BF_DEFAULT_CAPACITY, BF_DEFAULT_ERROR_RATE, BF_DEFAULT_EXPANSION,
+    ITEM_EXISTS, NOT_FOUND, SYNTAX_ERROR, WRONG_ARGS, WRONG_TYPE,
+};
+use crate::utils::{check_key, format_flags, next_number};
+use rus_key_db::bloom::BloomFilter;
+use rus_key_db::db::{DataType, Db};
+use rus_key_db::notify::NOTIFY_MODULE;
+use std::str::SplitAsciiWhitespace;
+
+fn get_filter<'a>(key: &str, db: &'a mut Db) -> Result<Option<&'a mut BloomFilter>, &'static str> {
+    if !check_key(key, db) {
+        return Ok(None);
+    }
+    match db.get_mut(key) {
+        Some(DataType::Bloom(filter)) => Ok(Some(filter)),
+        Some(_) => Err(WRONG_TYPE),
+        None => Ok(None),
+    }
+}
+
+/// Creates an empty bloom filter.
+///
+/// Syntax: `BF.RESERVE key error_rate capacity [EXPANSION expansion] [NONSCALING]`
+///
+/// # Returns
+///
+/// * `OK`, or an error message if the key exists or an argument is invalid.
+pub fn bf_reserve(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let key = parts.next().ok_or(WRONG_ARGS)?;
+    let error_rate: f64 = next_number(parts, "ERR bad error rate")?;
+    if !(error_rate > 0.0 && error_rate < 1.0) {
+        return Err("ERR (0 < error rate range < 1)");
+    }
+    let capacity: u64 = next_number(parts, BAD_CAPACITY)?;
+    if capacity == 0 {
+        return Err(BAD_CAPACITY);
+    }
+
+    let mut expansion = BF_DEFAULT_EXPANSION;
+    let mut non_scaling = false;
+    while let Some(arg) = parts.next() {
+        match arg.to_lowercase().as_str() {
+            "expansion" => {
+                expansion = next_number(parts, BAD_EXPANSION)?;
+                if expansion == 0 {
+                    return Err(BAD_EXPANSION);
+                }
+            }
+            "nonscaling" => non_scaling = true,
+            _ => return Err(SYNTAX_ERROR),
+        }
+    }
+    if non_scaling {
+        expansion = 0;
+    }
+
+    if get_filter(key, db)?.is_some() {
+        return Err(ITEM_EXISTS);
+    }
+    db.set(
+        key.to_string(),
+        DataType::Bloom(BloomFilter::new(error_rate, capacity, expansion)),
+    );
+    db.notify(NOTIFY_MODULE, "bf.reserve", key);
+    Ok("OK".to_string())
+}
+
+/// Adds one or more items to a bloom filter, creating it with default settings if needed.
+///
+/// Syntax: `BF.ADD key item` / `BF.MADD key item [item ...]`
+///
+/// # Returns
+///
+/// * `1` for each item that was added, `0` for each item that may have existed.
+pub fn bf_add(
+    parts: &mut SplitAsciiWhitespace,
+    db: &mut Db,
+    multi: bool,
+) -> Result<String, &'static str> {
+    let key = parts.next().ok_or(WRONG_ARGS)?;
+    let items: Vec<&str> = parts.collect();
+    if items.is_empty() || (!multi && items.len() > 1) {
+        return Err(WRONG_ARGS);
+    }
+
+    let created = get_filter(key, db)?.is_none();
+    if created {
+        db.set(
+            key.to_string(),
+            DataType::Bloom(BloomFilter::new(
+                BF_DEFAULT_ERROR_RATE,
+                BF_DEFAULT_CAPACITY,
+                BF_DEFAULT_EXPANSION,
+            )),
+        );
+    }
+    let filter = get_filter(key, db)?.ok_or(NOT_FOUND)?;
+    let mut added = Vec::with_capacity(items.len());
+    for item in items {
+        added.push(filter.add(item)?);
+    }
+    if created || added.contains(&true) {
+        let event = if multi { "bf.madd" } else { "bf.add" };
+        db.notify(NOTIFY_MODULE, event, key);
+    }
+    Ok(format_flags(added))
+}
+
+/// Checks whether one or more items may exist in a bloom filter.
+///
+/// Syntax: `BF.EXISTS key item` / `BF.MEXISTS key item [item ...]`
+///
+/// # Returns
+///
+/// * `1` for each item that may exist, `0` for each item that certainly does not.
+pub fn bf_exists(
+    parts: &mut SplitAsciiWhitespace,
+    db: &mut Db,
+    multi: bool,
+) -> Result<String, &'static str> {
+    let key = parts.next().ok_or(WRONG_ARGS)?;
+    let items: Vec<&str> = parts.collect();
+    if items.is_empty() || (!multi && items.len() > 1) {
+        return Err(WRONG_ARGS);
+    }
+
+    let filter = get_filter(key, db)?;
+    let exists = items
+        .iter()
+        .map(|item| filter.as_ref().is_some_and(|filter| filter.exists(item)))
+        .collect();
+    Ok(format_flags(exists))
+}
+
+/// Returns information about a bloom filter.
+///
+/// Syntax: `BF.INFO key [CAPACITY | SIZE | FILTERS | ITEMS | EXPANSION]`
+///
+/// # Returns
+///
+/// * All fields as `name: value` pairs, or the single requested value.
+pub fn bf_info(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let key = parts.next().ok_or(WRONG_ARGS)?;
+    let field = parts.next().map(|field| field.to_lowercase());
+    let filter = get_filter(key, db)?.ok_or(NOT_FOUND)?;
+
+    let expansion = match filter.expansion() {
+        0 => "nil".to_string(),
+        expansion => expansion.to_string(),
+    };
+    let fields = [
+        ("capacity", "Capacity", filter.capacity().to_string()),
+        ("size", "Size", filter.size().to_string()),
+        ("filters", "Number of filters", filter.filters().to_string()),
+        (
+            "items",
+            "Number of items inserted",
+            filter.items().to_string(),
+        ),
+        ("expansion", "Expansion rate", expansion),
+    ];
+    match field {
+        Some(field) => fields
+            .iter()
+            .find(|(name, _, _)| *name == field)
+            .map(|(_, _, value)| value.clone())
+            .ok_or(SYNTAX_ERROR),
+        None => Ok(fields
+            .iter()
+            .map(|(_, title, value)| format!("{}: {}", title, value))
+            .collect::<Vec<String>>()
+            .join(" ")),
+    }
+}
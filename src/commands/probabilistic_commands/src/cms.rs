@@ -0,0 +1,215 @@
+use crate::r#const::{
+    CMS_BAD_INCREMENT, CMS_KEY_EXISTS, CMS_KEY_NOT_FOUND, SYNTAX_ERROR, WRONG_ARGS, WRONG_TYPE,
+};
+use crate::utils::{check_key, next_number};
+use rus_key_db::cms::CountMinSketch;
+use rus_key_db::db::{DataType, Db};
+use rus_key_db::notify::NOTIFY_MODULE;
+use std::str::SplitAsciiWhitespace;
+
+fn get_sketch<'a>(
+    key: &str,
+    db: &'a mut Db,
+) -> Result<Option<&'a mut CountMinSketch>, &'static str> {
+    if !check_key(key, db) {
+        return Ok(None);
+    }
+    match db.get_mut(key) {
+        Some(DataType::CountMinSketch(sketch)) => Ok(Some(sketch)),
+        Some(_) => Err(WRONG_TYPE),
+        None => Ok(None),
+    }
+}
+
+fn create_sketch(
+    key: &str,
+    sketch: CountMinSketch,
+    event: &str,
+    db: &mut Db,
+) -> Result<String, &'static str> {
+    if get_sketch(key, db)?.is_some() {
+        return Err(CMS_KEY_EXISTS);
+    }
+    db.set(key.to_string(), DataType::CountMinSketch(sketch));
+    db.notify(NOTIFY_MODULE, event, key);
+    Ok("OK".to_string())
+}
+
+/// Creates a count-min sketch with the given dimensions.
+///
+/// Syntax: `CMS.INITBYDIM key width depth`
+///
+/// # Returns
+///
+/// * `OK`, or an error message if the key exists or a dimension is invalid.
+pub fn cms_init_by_dim(
+    parts: &mut SplitAsciiWhitespace,
+    db: &mut Db,
+) -> Result<String, &'static str> {
+    let key = parts.next().ok_or(WRONG_ARGS)?;
+    let width: usize = next_number(parts, "ERR CMS: invalid width")?;
+    let depth: usize = next_number(parts, "ERR CMS: invalid depth")?;
+    if parts.next().is_some() {
+        return Err(WRONG_ARGS);
+    }
+    if width == 0 || depth == 0 {
+        return Err("ERR CMS: width and depth must be positive");
+    }
+    create_sketch(key, CountMinSketch::new(width, depth), "cms.initbydim", db)
+}
+
+/// Creates a count-min sketch sized for an error bound.
+///
+/// Syntax: `CMS.INITBYPROB key error probability`
+///
+/// `error` is the over-estimation as a fraction of the total count, `probability` the
+/// desired probability of exceeding it; both must be between 0 and 1.
+///
+/// # Returns
+///
+/// * `OK`, or an error message if the key exists or an argument is invalid.
+pub fn cms_init_by_prob(
+    parts: &mut SplitAsciiWhitespace,
+    db: &mut Db,
+) -> Result<String, &'static str> {
+    let key = parts.next().ok_or(WRONG_ARGS)?;
+    let error: f64 = next_number(parts, "ERR CMS: invalid overestimation value")?;
+    let probability: f64 = next_number(parts, "ERR CMS: invalid prob value")?;
+    if parts.next().is_some() {
+        return Err(WRONG_ARGS);
+    }
+    if !(error > 0.0 && error < 1.0) {
+        return Err("ERR CMS: invalid overestimation value");
+    }
+    if !(probability > 0.0 && probability < 1.0) {
+        return Err("ERR CMS: invalid prob value");
+    }
+    create_sketch(
+        key,
+        CountMinSketch::with_probability(error, probability),
+        "cms.initbyprob",
+        db,
+    )
+}
+
+/// Increases the count of one or more items.
+///
+/// Syntax: `CMS.INCRBY key item increment [item increment ...]`
+///
+/// # Returns
+///
+/// * The estimated count of each item after the increment, separated by spaces.
+pub fn cms_incr_by(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let key = parts.next().ok_or(WRONG_ARGS)?;
+    let mut increments = Vec::new();
+    while let Some(item) = parts.next() {
+        let increment: u64 = next_number(parts, CMS_BAD_INCREMENT)?;
+        increments.push((item, increment));
+    }
+    if increments.is_empty() {
+        return Err(WRONG_ARGS);
+    }
+
+    let sketch = get_sketch(key, db)?.ok_or(CMS_KEY_NOT_FOUND)?;
+    let counts = increments
+        .iter()
+        .map(|(item, increment)| sketch.incr_by(item, *increment).to_string())
+        .collect::<Vec<String>>()
+        .join(" ");
+    db.notify(NOTIFY_MODULE, "cms.incrby", key);
+    Ok(counts)
+}
+
+/// Returns the estimated count of one or more items.
+///
+/// Syntax: `CMS.QUERY key item [item ...]`
+///
+/// # Returns
+///
+/// * The estimated count of each item, separated by spaces.
+pub fn cms_query(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let key = parts.next().ok_or(WRONG_ARGS)?;
+    let items: Vec<&str> = parts.collect();
+    if items.is_empty() {
+        return Err(WRONG_ARGS);
+    }
+
+    let sketch = get_sketch(key, db)?.ok_or(CMS_KEY_NOT_FOUND)?;
+    Ok(items
+        .iter()
+        .map(|item| sketch.query(item).to_string())
+        .collect::<Vec<String>>()
+        .join(" "))
+}
+
+/// Merges several sketches into a destination sketch.
+///
+/// Syntax: `CMS.MERGE destination numKeys source [source ...] [WEIGHTS weight [weight ...]]`
+///
+/// The destination must already exist and every sketch must have the same dimensions.
+/// Its counters are replaced by the weighted sum of the sources, so it can be one of them.
+///
+/// # Returns
+///
+/// * `OK`, or an error message if a key is missing or the dimensions differ.
+pub fn cms_merge(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let destination = parts.next().ok_or(WRONG_ARGS)?;
+    let num_keys: usize = next_number(parts, "ERR CMS: invalid numkeys")?;
+    if num_keys == 0 {
+        return Err("ERR CMS: invalid numkeys");
+    }
+    let sources: Vec<&str> = parts.by_ref().take(num_keys).collect();
+    if sources.len() != num_keys {
+        return Err(WRONG_ARGS);
+    }
+    let weights: Vec<u64> = match parts.next() {
+        Some(arg) if arg.eq_ignore_ascii_case("weights") => {
+            let weights = parts
+                .map(|weight| {
+                    weight
+                        .parse::<u64>()
+                        .map_err(|_| "ERR CMS: invalid weight value")
+                })
+                .collect::<Result<Vec<u64>, &'static str>>()?;
+            if weights.len() != num_keys {
+                return Err(WRONG_ARGS);
+            }
+            weights
+        }
+        Some(_) => return Err(SYNTAX_ERROR),
+        None => vec![1; num_keys],
+    };
+
+    let mut sketches = Vec::with_capacity(num_keys);
+    for source in sources.iter() {
+        let sketch = get_sketch(source, db)?.ok_or(CMS_KEY_NOT_FOUND)?;
+        sketches.push(sketch.clone());
+    }
+    let weighted: Vec<(&CountMinSketch, u64)> = sketches.iter().zip(weights).collect();
+    let target = get_sketch(destination, db)?.ok_or(CMS_KEY_NOT_FOUND)?;
+    target.merge(&weighted)?;
+    db.notify(NOTIFY_MODULE, "cms.merge", destination);
+    Ok("OK".to_string())
+}
+
+/// Returns information about a count-min sketch.
+///
+/// Syntax: `CMS.INFO key`
+///
+/// # Returns
+///
+/// * All fields as `name: value` pairs.
+pub fn cms_info(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let key = parts.next().ok_or(WRONG_ARGS)?;
+    let sketch = get_sketch(key, db)?.ok_or(CMS_KEY_NOT_FOUND)?;
+    let fields = [
+        ("width", sketch.width().to_string()),
+        ("depth", sketch.depth().to_string()),
+        ("count", sketch.count().to_string()),
+    ];
+    Ok(fields
+        .iter()
+        .map(|(title, value)| format!("{}: {}", title, value))
+        .collect::<Vec<String>>()
+        .join(" "))
+}
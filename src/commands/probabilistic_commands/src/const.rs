@@ -0,0 +1,36 @@
+pub const WRONG_TYPE: &str =
+    "There is no such key, the key is expired, or the data type is incorrect";
+
+pub const WRONG_ARGS: &str = "ERR wrong number of arguments for command";
+
+pub const SYNTAX_ERROR: &str = "ERR syntax error";
+
+pub const ITEM_EXISTS: &str = "ERR item exists";
+
+pub const NOT_FOUND: &str = "ERR not found";
+
+pub const BAD_CAPACITY: &str = "ERR bad capacity";
+
+pub const BAD_EXPANSION: &str = "ERR bad expansion";
+
+// defaults used when a filter is created implicitly by an add
+pub const BF_DEFAULT_ERROR_RATE: f64 = 0.01;
+pub const BF_DEFAULT_CAPACITY: u64 = 100;
+pub const BF_DEFAULT_EXPANSION: u32 = 2;
+
+pub const CF_DEFAULT_CAPACITY: u64 = 1024;
+pub const CF_DEFAULT_BUCKET_SIZE: usize = 2;
+pub const CF_DEFAULT_MAX_ITERATIONS: u32 = 20;
+pub const CF_DEFAULT_EXPANSION: u32 = 1;
+
+pub const CMS_KEY_NOT_FOUND: &str = "ERR CMS: key does not exist";
+pub const CMS_KEY_EXISTS: &str = "ERR CMS: key already exists";
+pub const CMS_BAD_INCREMENT: &str = "ERR CMS: Cannot parse number";
+
+pub const TOPK_KEY_NOT_FOUND: &str = "ERR TopK: key does not exist";
+pub const TOPK_KEY_EXISTS: &str = "ERR TopK: key already exists";
+
+// defaults used when `TOPK.RESERVE` only gets `topk`
+pub const TOPK_DEFAULT_WIDTH: usize = 8;
+pub const TOPK_DEFAULT_DEPTH: usize = 7;
+pub const TOPK_DEFAULT_DECAY: f64 = 0.9;
@@ -0,0 +1,210 @@
+use crate::r#const::{
+    BAD_CAPACITY, BAD_EXPANSION, CF_DEFAULT_BUCKET_SIZE, CF_DEFAULT_CAPACITY, CF_DEFAULT_EXPANSION,
+    CF_DEFAULT_MAX_ITERATIONS, ITEM_EXISTS, NOT_FOUND, SYNTAX_ERROR, WRONG_ARGS, WRONG_TYPE,
+};
+use crate::utils::{check_key, format_flags, next_number};
+use rus_key_db::cuckoo::CuckooFilter;
+use rus_key_db::db::{DataType, Db};
+use rus_key_db::notify::NOTIFY_MODULE;
+use std::str::SplitAsciiWhitespace;
+
+fn get_filter<'a>(key: &str, db: &'a mut Db) -> Result<Option<&'a mut CuckooFilter>, &'static str> {
+    if !check_key(key, db) {
+        return Ok(None);
+    }
+    match db.get_mut(key) {
+        Some(DataType::Cuckoo(filter)) => Ok(Some(filter)),
+        Some(_) => Err(WRONG_TYPE),
+        None => Ok(None),
+    }
+}
+
+fn get_or_create_filter<'a>(
+    key: &str,
+    db: &'a mut Db,
+) -> Result<&'a mut CuckooFilter, &'static str> {
+    if get_filter(key, db)?.is_none() {
+        db.set(
+            key.to_string(),
+            DataType::Cuckoo(CuckooFilter::new(
+                CF_DEFAULT_CAPACITY,
+                CF_DEFAULT_BUCKET_SIZE,
+                CF_DEFAULT_MAX_ITERATIONS,
+                CF_DEFAULT_EXPANSION,
+            )),
+        );
+    }
+    get_filter(key, db)?.ok_or(NOT_FOUND)
+}
+
+fn key_and_item<'a>(
+    parts: &mut SplitAsciiWhitespace<'a>,
+) -> Result<(&'a str, &'a str), &'static str> {
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(key), Some(item), None) => Ok((key, item)),
+        _ => Err(WRONG_ARGS),
+    }
+}
+
+/// Creates an empty cuckoo filter.
+///
+/// Syntax: `CF.RESERVE key capacity [BUCKETSIZE bucketsize] [MAXITERATIONS maxiterations]
+/// [EXPANSION expansion]`
+///
+/// An expansion of 0 creates a filter that never grows.
+///
+/// # Returns
+///
+/// * `OK`, or an error message if the key exists or an argument is invalid.
+pub fn cf_reserve(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let key = parts.next().ok_or(WRONG_ARGS)?;
+    let capacity: u64 = next_number(parts, BAD_CAPACITY)?;
+    if capacity == 0 {
+        return Err(BAD_CAPACITY);
+    }
+
+    let mut bucket_size = CF_DEFAULT_BUCKET_SIZE;
+    let mut max_iterations = CF_DEFAULT_MAX_ITERATIONS;
+    let mut expansion = CF_DEFAULT_EXPANSION;
+    while let Some(arg) = parts.next() {
+        match arg.to_lowercase().as_str() {
+            "bucketsize" => {
+                bucket_size = next_number(parts, "ERR bad bucket size")?;
+                if !(1..=255).contains(&bucket_size) {
+                    return Err("ERR bad bucket size");
+                }
+            }
+            "maxiterations" => {
+                max_iterations = next_number(parts, "ERR bad max iterations")?;
+                if max_iterations == 0 {
+                    return Err("ERR bad max iterations");
+                }
+            }
+            "expansion" => expansion = next_number(parts, BAD_EXPANSION)?,
+            _ => return Err(SYNTAX_ERROR),
+        }
+    }
+
+    if get_filter(key, db)?.is_some() {
+        return Err(ITEM_EXISTS);
+    }
+    db.set(
+        key.to_string(),
+        DataType::Cuckoo(CuckooFilter::new(
+            capacity,
+            bucket_size,
+            max_iterations,
+            expansion,
+        )),
+    );
+    db.notify(NOTIFY_MODULE, "cf.reserve", key);
+    Ok("OK".to_string())
+}
+
+/// Adds an item to a cuckoo filter, creating it with default settings if needed.
+///
+/// Syntax: `CF.ADD key item` / `CF.ADDNX key item`
+///
+/// `CF.ADD` allows the same item to be added several times, `CF.ADDNX` only adds items
+/// that do not exist yet.
+///
+/// # Returns
+///
+/// * `1` if the item was added, `0` if `CF.ADDNX` found it.
+pub fn cf_add(
+    parts: &mut SplitAsciiWhitespace,
+    db: &mut Db,
+    nx: bool,
+) -> Result<String, &'static str> {
+    let (key, item) = key_and_item(parts)?;
+    let filter = get_or_create_filter(key, db)?;
+    if nx && filter.exists(item) {
+        return Ok("0".to_string());
+    }
+    filter.add(item)?;
+    db.notify(NOTIFY_MODULE, if nx { "cf.addnx" } else { "cf.add" }, key);
+    Ok("1".to_string())
+}
+
+/// Checks whether one or more items may exist in a cuckoo filter.
+///
+/// Syntax: `CF.EXISTS key item` / `CF.MEXISTS key item [item ...]`
+///
+/// # Returns
+///
+/// * `1` for each item that may exist, `0` for each item that certainly does not.
+pub fn cf_exists(
+    parts: &mut SplitAsciiWhitespace,
+    db: &mut Db,
+    multi: bool,
+) -> Result<String, &'static str> {
+    let key = parts.next().ok_or(WRONG_ARGS)?;
+    let items: Vec<&str> = parts.collect();
+    if items.is_empty() || (!multi && items.len() > 1) {
+        return Err(WRONG_ARGS);
+    }
+
+    let filter = get_filter(key, db)?;
+    let exists = items
+        .iter()
+        .map(|item| filter.as_ref().is_some_and(|filter| filter.exists(item)))
+        .collect();
+    Ok(format_flags(exists))
+}
+
+/// Deletes one occurrence of an item from a cuckoo filter.
+///
+/// Syntax: `CF.DEL key item`
+///
+/// # Returns
+///
+/// * `1` if the item was deleted, `0` if it was not found.
+pub fn cf_del(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let (key, item) = key_and_item(parts)?;
+    let filter = get_filter(key, db)?.ok_or(NOT_FOUND)?;
+    if !filter.delete(item) {
+        return Ok("0".to_string());
+    }
+    db.notify(NOTIFY_MODULE, "cf.del", key);
+    Ok("1".to_string())
+}
+
+/// Returns the number of times an item may have been added to a cuckoo filter.
+///
+/// Syntax: `CF.COUNT key item`
+///
+/// # Returns
+///
+/// * The count, which can be higher than the real one when fingerprints collide.
+pub fn cf_count(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let (key, item) = key_and_item(parts)?;
+    let count = get_filter(key, db)?.map_or(0, |filter| filter.count(item));
+    Ok(count.to_string())
+}
+
+/// Returns information about a cuckoo filter.
+///
+/// Syntax: `CF.INFO key`
+///
+/// # Returns
+///
+/// * All fields as `name: value` pairs.
+pub fn cf_info(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let key = parts.next().ok_or(WRONG_ARGS)?;
+    let filter = get_filter(key, db)?.ok_or(NOT_FOUND)?;
+    let fields = [
+        ("Size", filter.size().to_string()),
+        ("Number of buckets", filter.buckets().to_string()),
+        ("Number of filters", filter.filters().to_string()),
+        ("Number of items inserted", filter.items().to_string()),
+        ("Number of items deleted", filter.deletes().to_string()),
+        ("Bucket size", filter.bucket_size().to_string()),
+        ("Expansion rate", filter.expansion().to_string()),
+        ("Max iterations", filter.max_iterations().to_string()),
+    ];
+    Ok(fields
+        .iter()
+        .map(|(title, value)| format!("{}: {}", title, value))
+        .collect::<Vec<String>>()
+        .join(" "))
+}
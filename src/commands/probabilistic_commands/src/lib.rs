@@ -0,0 +1,7 @@
+mod bloom;
+mod cms;
+mod r#const;
+mod cuckoo;
+pub mod probabilistic;
+mod topk;
+mod utils;
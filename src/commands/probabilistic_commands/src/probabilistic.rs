@@ -0,0 +1,71 @@
+use crate::bloom::{bf_add, bf_exists, bf_info, bf_reserve};
+use crate::cms::{cms_incr_by, cms_info, cms_init_by_dim, cms_init_by_prob, cms_merge, cms_query};
+use crate::cuckoo::{cf_add, cf_count, cf_del, cf_exists, cf_info, cf_reserve};
+use crate::topk::{topk_add, topk_incr_by, topk_info, topk_list, topk_query, topk_reserve};
+use rus_key_db::db::Db;
+use rus_key_trait::command_trait::{Arity, Command};
+use std::str::SplitAsciiWhitespace;
+
+pub struct ProbabilisticCommand {
+    command: String,
+}
+
+impl ProbabilisticCommand {
+    pub fn new(command: String) -> ProbabilisticCommand {
+        ProbabilisticCommand { command }
+    }
+}
+
+impl Command for ProbabilisticCommand {
+    fn execute(
+        &self,
+        parts: &mut SplitAsciiWhitespace,
+        db: &mut Db,
+    ) -> Result<String, &'static str> {
+        match self.command.as_str() {
+            // Bloom filter
+            "bf.reserve" => bf_reserve(parts, db),
+            "bf.add" => bf_add(parts, db, false),
+            "bf.madd" => bf_add(parts, db, true),
+            "bf.exists" => bf_exists(parts, db, false),
+            "bf.mexists" => bf_exists(parts, db, true),
+            "bf.info" => bf_info(parts, db),
+            // Cuckoo filter
+            "cf.reserve" => cf_reserve(parts, db),
+            "cf.add" => cf_add(parts, db, false),
+            "cf.addnx" => cf_add(parts, db, true),
+            "cf.exists" => cf_exists(parts, db, false),
+            "cf.mexists" => cf_exists(parts, db, true),
+            "cf.del" => cf_del(parts, db),
+            "cf.count" => cf_count(parts, db),
+            "cf.info" => cf_info(parts, db),
+            // Count-min sketch
+            "cms.initbydim" => cms_init_by_dim(parts, db),
+            "cms.initbyprob" => cms_init_by_prob(parts, db),
+            "cms.incrby" => cms_incr_by(parts, db),
+            "cms.query" => cms_query(parts, db),
+            "cms.merge" => cms_merge(parts, db),
+            "cms.info" => cms_info(parts, db),
+            // Top-k
+            "topk.reserve" => topk_reserve(parts, db),
+            "topk.add" => topk_add(parts, db),
+            "topk.incrby" => topk_incr_by(parts, db),
+            "topk.query" => topk_query(parts, db),
+            "topk.list" => topk_list(parts, db),
+            "topk.info" => topk_info(parts, db),
+            _ => Err("ProbabilisticCommand Error: Command not found"),
+        }
+    }
+
+    fn arity(&self) -> Arity {
+        match self.command.as_str() {
+            "bf.reserve" => Arity::with_integers(3, &[2]),
+            "cf.reserve" | "topk.reserve" => Arity::with_integers(2, &[1]),
+            "cms.initbydim" => Arity::with_integers(3, &[1, 2]),
+            "cms.merge" => Arity::with_integers(3, &[1]),
+            "cms.initbyprob" | "cms.incrby" | "topk.incrby" => Arity::new(3),
+            "bf.info" | "cf.info" | "cms.info" | "topk.list" | "topk.info" => Arity::new(1),
+            _ => Arity::new(2),
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use crate::r#const::{
+    SYNTAX_ERROR, TOPK_DEFAULT_DECAY, TOPK_DEFAULT_DEPTH, TOPK_DEFAULT_WIDTH, TOPK_KEY_EXISTS,
+    TOPK_KEY_NOT_FOUND, WRONG_ARGS, WRONG_TYPE,
+};
+use crate::utils::{check_key, format_flags, next_number};
+use rus_key_db::db::{DataType, Db};
+use rus_key_db::notify::NOTIFY_MODULE;
+use rus_key_db::topk::TopK;
+use std::str::SplitAsciiWhitespace;
+
+fn get_topk<'a>(key: &str, db: &'a mut Db) -> Result<Option<&'a mut TopK>, &'static str> {
+    if !check_key(key, db) {
+        return Ok(None);
+    }
+    match db.get_mut(key) {
+        Some(DataType::TopK(topk)) => Ok(Some(topk)),
+        Some(_) => Err(WRONG_TYPE),
+        None => Ok(None),
+    }
+}
+
+fn format_expelled(expelled: Vec<Option<String>>) -> String {
+    expelled
+        .into_iter()
+        .map(|item| item.unwrap_or_else(|| "nil".to_string()))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Creates an empty top-k list.
+///
+/// Syntax: `TOPK.RESERVE key topk [width depth decay]`
+///
+/// # Returns
+///
+/// * `OK`, or an error message if the key exists or an argument is invalid.
+pub fn topk_reserve(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let key = parts.next().ok_or(WRONG_ARGS)?;
+    let k: usize = next_number(parts, "ERR TopK: invalid k")?;
+    if k == 0 {
+        return Err("ERR TopK: invalid k");
+    }
+    let (width, depth, decay) = match parts.next() {
+        Some(width) => {
+            let width = width
+                .parse::<usize>()
+                .map_err(|_| "ERR TopK: invalid width")?;
+            let depth: usize = next_number(parts, "ERR TopK: invalid depth")?;
+            let decay: f64 = next_number(parts, "ERR TopK: invalid decay value")?;
+            if parts.next().is_some() {
+                return Err(SYNTAX_ERROR);
+            }
+            (width, depth, decay)
+        }
+        None => (TOPK_DEFAULT_WIDTH, TOPK_DEFAULT_DEPTH, TOPK_DEFAULT_DECAY),
+    };
+    if width == 0 {
+        return Err("ERR TopK: invalid width");
+    }
+    if depth == 0 {
+        return Err("ERR TopK: invalid depth");
+    }
+    if !(decay > 0.0 && decay <= 1.0) {
+        return Err("ERR TopK: invalid decay value. must be '<= 1' & '> 0'");
+    }
+
+    if get_topk(key, db)?.is_some() {
+        return Err(TOPK_KEY_EXISTS);
+    }
+    db.set(
+        key.to_string(),
+        DataType::TopK(TopK::new(k, width, depth, decay)),
+    );
+    db.notify(NOTIFY_MODULE, "topk.reserve", key);
+    Ok("OK".to_string())
+}
+
+/// Adds one or more items to a top-k list.
+///
+/// Syntax: `TOPK.ADD key item [item ...]`
+///
+/// # Returns
+///
+/// * For each item, the item it expelled from the list or `nil`, separated by spaces.
+pub fn topk_add(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let key = parts.next().ok_or(WRONG_ARGS)?;
+    let items: Vec<&str> = parts.collect();
+    if items.is_empty() {
+        return Err(WRONG_ARGS);
+    }
+
+    let topk = get_topk(key, db)?.ok_or(TOPK_KEY_NOT_FOUND)?;
+    let expelled = items.iter().map(|item| topk.incr_by(item, 1)).collect();
+    db.notify(NOTIFY_MODULE, "topk.add", key);
+    Ok(format_expelled(expelled))
+}
+
+/// Increases the count of one or more items in a top-k list.
+///
+/// Syntax: `TOPK.INCRBY key item increment [item increment ...]`
+///
+/// # Returns
+///
+/// * For each item, the item it expelled from the list or `nil`, separated by spaces.
+pub fn topk_incr_by(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let key = parts.next().ok_or(WRONG_ARGS)?;
+    let mut increments = Vec::new();
+    while let Some(item) = parts.next() {
+        let increment: u64 = next_number(parts, "ERR TopK: Cannot parse number")?;
+        if increment > 100_000 {
+            return Err("ERR TopK: increment must be an integer between 1 and 100000");
+        }
+        increments.push((item, increment));
+    }
+    if increments.is_empty() {
+        return Err(WRONG_ARGS);
+    }
+
+    let topk = get_topk(key, db)?.ok_or(TOPK_KEY_NOT_FOUND)?;
+    let expelled = increments
+        .iter()
+        .map(|(item, increment)| topk.incr_by(item, *increment))
+        .collect();
+    db.notify(NOTIFY_MODULE, "topk.incrby", key);
+    Ok(format_expelled(expelled))
+}
+
+/// Checks whether one or more items are in a top-k list.
+///
+/// Syntax: `TOPK.QUERY key item [item ...]`
+///
+/// # Returns
+///
+/// * `1` for each item in the list, `0` otherwise.
+pub fn topk_query(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let key = parts.next().ok_or(WRONG_ARGS)?;
+    let items: Vec<&str> = parts.collect();
+    if items.is_empty() {
+        return Err(WRONG_ARGS);
+    }
+
+    let topk = get_topk(key, db)?.ok_or(TOPK_KEY_NOT_FOUND)?;
+    Ok(format_flags(
+        items.iter().map(|item| topk.query(item)).collect(),
+    ))
+}
+
+/// Returns the items of a top-k list, highest count first.
+///
+/// Syntax: `TOPK.LIST key [WITHCOUNT]`
+///
+/// # Returns
+///
+/// * The items separated by spaces, or `item: count` pairs with `WITHCOUNT`.
+pub fn topk_list(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let key = parts.next().ok_or(WRONG_ARGS)?;
+    let with_count = match parts.next() {
+        Some(arg) if arg.eq_ignore_ascii_case("withcount") => true,
+        Some(_) => return Err(SYNTAX_ERROR),
+        None => false,
+    };
+
+    let topk = get_topk(key, db)?.ok_or(TOPK_KEY_NOT_FOUND)?;
+    Ok(topk
+        .list()
+        .iter()
+        .map(|(item, count)| {
+            if with_count {
+                format!("{}: {}", item, count)
+            } else {
+                item.clone()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" "))
+}
+
+/// Returns information about a top-k list.
+///
+/// Syntax: `TOPK.INFO key`
+///
+/// # Returns
+///
+/// * All fields as `name: value` pairs.
+pub fn topk_info(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let key = parts.next().ok_or(WRONG_ARGS)?;
+    let topk = get_topk(key, db)?.ok_or(TOPK_KEY_NOT_FOUND)?;
+    let fields = [
+        ("k", topk.k().to_string()),
+        ("width", topk.width().to_string()),
+        ("depth", topk.depth().to_string()),
+        ("decay", topk.decay().to_string()),
+    ];
+    Ok(fields
+        .iter()
+        .map(|(title, value)| format!("{}: {}", title, value))
+        .collect::<Vec<String>>()
+        .join(" "))
+}
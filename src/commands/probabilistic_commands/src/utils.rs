@@ -0,0 +1,31 @@
+use rus_key_db::db::Db;
+use std::str::SplitAsciiWhitespace;
+
+/// Removes `key` if it is expired.
+///
+/// # Returns
+///
+/// * `true` if the key exists and is not expired.
+pub fn check_key(key: &str, db: &mut Db) -> bool {
+    db.check_expired(key)
+}
+
+/// Parses the next argument as a number.
+pub fn next_number<T: std::str::FromStr>(
+    parts: &mut SplitAsciiWhitespace,
+    error: &'static str,
+) -> Result<T, &'static str> {
+    match parts.next() {
+        Some(value) => value.parse::<T>().map_err(|_| error),
+        None => Err(error),
+    }
+}
+
+/// Formats a list of flags as `1`/`0` separated by spaces.
+pub fn format_flags(flags: Vec<bool>) -> String {
+    flags
+        .iter()
+        .map(|flag| if *flag { "1" } else { "0" })
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
@@ -0,0 +1,18 @@
+pub const WRONG_ARGS: &str = "ERR wrong number of arguments for command";
+
+pub const SYNTAX_ERROR: &str = "ERR syntax error";
+
+pub const INDEX_EXISTS: &str = "ERR Index already exists";
+
+pub const UNKNOWN_INDEX: &str = "ERR Unknown Index name";
+
+pub const UNKNOWN_FIELD: &str = "ERR Unknown field";
+
+pub const INVALID_QUERY: &str = "ERR Syntax error in query";
+
+pub const INVALID_RANGE: &str = "ERR Bad lower range or upper range";
+
+pub const INVALID_LIMIT: &str = "ERR LIMIT requires two non negative integers";
+
+// the default page size of FT.SEARCH
+pub const DEFAULT_LIMIT: usize = 10;
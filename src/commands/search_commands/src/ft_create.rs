@@ -0,0 +1,63 @@
+use crate::r#const::{INDEX_EXISTS, SYNTAX_ERROR, WRONG_ARGS};
+use rus_key_db::db::Db;
+use rus_key_db::search::{FieldType, SearchIndex};
+use std::str::SplitAsciiWhitespace;
+
+/// Creates a secondary index over hashes.
+///
+/// Syntax: `FT.CREATE index [ON HASH] [PREFIX count prefix [prefix ...]]
+/// SCHEMA field TEXT|NUMERIC|TAG [SORTABLE] [field ...]`
+///
+/// Without `PREFIX` every hash is indexed. Existing hashes are indexed right away and
+/// the index follows every later change to a matching key.
+///
+/// # Returns
+///
+/// * `OK`, or an error message if the index exists or the schema is invalid.
+pub fn ft_create(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let name = parts.next().ok_or(WRONG_ARGS)?;
+    let mut prefixes = Vec::new();
+    let mut schema: Vec<(String, FieldType)> = Vec::new();
+    while let Some(arg) = parts.next() {
+        match arg.to_lowercase().as_str() {
+            "on" => match parts.next() {
+                Some(target) if target.eq_ignore_ascii_case("hash") => {}
+                _ => return Err("ERR only HASH indexes are supported"),
+            },
+            "prefix" => {
+                let count = parts
+                    .next()
+                    .and_then(|count| count.parse::<usize>().ok())
+                    .ok_or(SYNTAX_ERROR)?;
+                for _ in 0..count {
+                    prefixes.push(parts.next().ok_or(SYNTAX_ERROR)?.to_string());
+                }
+            }
+            "schema" => {
+                while let Some(field) = parts.next() {
+                    if field.eq_ignore_ascii_case("sortable") && !schema.is_empty() {
+                        // every field can be sorted on
+                        continue;
+                    }
+                    let field_type = parts
+                        .next()
+                        .and_then(FieldType::parse)
+                        .ok_or("ERR Invalid field type")?;
+                    if schema.iter().any(|(name, _)| name == field) {
+                        return Err("ERR Duplicate field in schema");
+                    }
+                    schema.push((field.to_string(), field_type));
+                }
+            }
+            _ => return Err(SYNTAX_ERROR),
+        }
+    }
+    if schema.is_empty() {
+        return Err("ERR Fields arguments are missing");
+    }
+
+    if !db.create_index(SearchIndex::new(name.to_string(), prefixes, schema)) {
+        return Err(INDEX_EXISTS);
+    }
+    Ok("OK".to_string())
+}
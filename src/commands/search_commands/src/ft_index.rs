@@ -0,0 +1,61 @@
+use crate::r#const::{UNKNOWN_INDEX, WRONG_ARGS};
+use rus_key_db::db::Db;
+use std::str::SplitAsciiWhitespace;
+
+/// Deletes an index, the indexed hashes are kept.
+///
+/// Syntax: `FT.DROPINDEX index`
+///
+/// # Returns
+///
+/// * `OK`, or an error message if the index does not exist.
+pub fn ft_drop_index(
+    parts: &mut SplitAsciiWhitespace,
+    db: &mut Db,
+) -> Result<String, &'static str> {
+    let name = parts.next().ok_or(WRONG_ARGS)?;
+    if !db.drop_index(name) {
+        return Err(UNKNOWN_INDEX);
+    }
+    Ok("OK".to_string())
+}
+
+/// Returns information about an index.
+///
+/// Syntax: `FT.INFO index`
+///
+/// # Returns
+///
+/// * All fields as `name: value` pairs, lists are separated by commas.
+pub fn ft_info(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let name = parts.next().ok_or(WRONG_ARGS)?;
+    let index = db.index(name).ok_or(UNKNOWN_INDEX)?;
+    let attributes = index
+        .schema
+        .iter()
+        .map(|(field, field_type)| format!("{} {}", field, field_type.name()))
+        .collect::<Vec<String>>()
+        .join(",");
+    let fields = [
+        ("index_name", index.name.clone()),
+        ("prefixes", index.prefixes.join(",")),
+        ("attributes", attributes),
+        ("num_docs", index.num_docs().to_string()),
+    ];
+    Ok(fields
+        .iter()
+        .map(|(title, value)| format!("{}: {}", title, value))
+        .collect::<Vec<String>>()
+        .join(" "))
+}
+
+/// Returns the names of all indexes.
+///
+/// Syntax: `FT._LIST`
+///
+/// # Returns
+///
+/// * The names separated by spaces.
+pub fn ft_list(_parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    Ok(db.index_names().join(" "))
+}
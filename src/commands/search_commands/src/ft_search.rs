@@ -0,0 +1,130 @@
+use crate::query::{execute, parse};
+use crate::r#const::{
+    DEFAULT_LIMIT, INVALID_LIMIT, SYNTAX_ERROR, UNKNOWN_FIELD, UNKNOWN_INDEX, WRONG_ARGS,
+};
+use rus_key_db::db::{DataType, Db};
+use rus_key_db::search::FieldType;
+use std::cmp::Ordering;
+use std::str::SplitAsciiWhitespace;
+
+// keywords ending the query
+const OPTIONS: [&str; 3] = ["nocontent", "sortby", "limit"];
+
+fn parse_limit(value: Option<&str>) -> Result<usize, &'static str> {
+    value
+        .and_then(|value| value.parse::<usize>().ok())
+        .ok_or(INVALID_LIMIT)
+}
+
+/// Searches an index.
+///
+/// Syntax: `FT.SEARCH index query [NOCONTENT] [SORTBY field [ASC | DESC]]
+/// [LIMIT offset num]`
+///
+/// The query runs until the first option, see `query::parse` for its syntax. Results are
+/// ordered by key unless `SORTBY` is given; numeric fields sort by value, documents
+/// without the field come last. `LIMIT` defaults to the first 10 results.
+///
+/// # Returns
+///
+/// * The total number of matches followed by the page of results, each as
+///   `key: [field: value ...]`, or only the keys with `NOCONTENT`.
+pub fn ft_search(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let name = parts.next().ok_or(WRONG_ARGS)?;
+    let args: Vec<&str> = parts.collect();
+    let query_end = args
+        .iter()
+        .position(|arg| OPTIONS.contains(&arg.to_lowercase().as_str()))
+        .unwrap_or(args.len());
+    if query_end == 0 {
+        return Err(WRONG_ARGS);
+    }
+    let query = args[..query_end].join(" ");
+
+    let mut no_content = false;
+    let mut sort_by: Option<(String, bool)> = None;
+    let mut offset = 0;
+    let mut limit = DEFAULT_LIMIT;
+    let mut options = args[query_end..].iter();
+    while let Some(option) = options.next() {
+        match option.to_lowercase().as_str() {
+            "nocontent" => no_content = true,
+            "sortby" => {
+                let field = options.next().ok_or(SYNTAX_ERROR)?;
+                let mut descending = false;
+                let mut peek = options.clone();
+                match peek.next().map(|order| order.to_lowercase()).as_deref() {
+                    Some("asc") => {
+                        options = peek;
+                    }
+                    Some("desc") => {
+                        descending = true;
+                        options = peek;
+                    }
+                    _ => {}
+                }
+                sort_by = Some((field.to_string(), descending));
+            }
+            "limit" => {
+                offset = parse_limit(options.next().copied())?;
+                limit = parse_limit(options.next().copied())?;
+            }
+            _ => return Err(SYNTAX_ERROR),
+        }
+    }
+
+    let index = db.index(name).ok_or(UNKNOWN_INDEX)?;
+    let clauses = parse(&query, index)?;
+    let sort_type = match sort_by.as_ref() {
+        Some((field, _)) => Some(index.field_type(field).ok_or(UNKNOWN_FIELD)?),
+        None => None,
+    };
+    let mut keys: Vec<String> = execute(&clauses, index).into_iter().collect();
+
+    if let (Some((field, descending)), Some(sort_type)) = (sort_by.as_ref(), sort_type) {
+        let value = |key: &String| index.value(key, field).map(|value| value.to_string());
+        let mut sorted: Vec<(Option<String>, String)> =
+            keys.into_iter().map(|key| (value(&key), key)).collect();
+        sorted.sort_by(|(a, a_key), (b, b_key)| {
+            let ordering = match (a, b) {
+                (Some(a), Some(b)) => {
+                    let ordering = match (sort_type, a.parse::<f64>(), b.parse::<f64>()) {
+                        (FieldType::Numeric, Ok(a), Ok(b)) => a.total_cmp(&b),
+                        _ => a.cmp(b),
+                    };
+                    if *descending {
+                        ordering.reverse()
+                    } else {
+                        ordering
+                    }
+                }
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            };
+            ordering.then_with(|| a_key.cmp(b_key))
+        });
+        keys = sorted.into_iter().map(|(_, key)| key).collect();
+    }
+
+    // expired keys are deleted here, which also drops them from the index
+    keys.retain(|key| db.check_expired(key));
+
+    let mut result = vec![keys.len().to_string()];
+    for key in keys.iter().skip(offset).take(limit) {
+        if no_content {
+            result.push(key.clone());
+            continue;
+        }
+        let content = match db.get(key) {
+            Some(DataType::HashMap(hash)) => hash
+                .iter()
+                .map(|(field, value)| format!("{}: {}", field, value))
+                .collect::<Vec<String>>()
+                .join(" "),
+            _ => String::new(),
+        };
+        result.push(format!("{}: [{}]", key, content));
+    }
+    Ok(result.join(" "))
+}
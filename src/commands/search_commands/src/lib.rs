@@ -0,0 +1,6 @@
+mod r#const;
+mod ft_create;
+mod ft_index;
+mod ft_search;
+mod query;
+pub mod search;
@@ -0,0 +1,180 @@
+use crate::r#const::{INVALID_QUERY, INVALID_RANGE, UNKNOWN_FIELD};
+use rus_key_db::search::{split_tags, tokenize, FieldType, SearchIndex};
+use std::collections::BTreeSet;
+
+/// One condition of a query.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Clause {
+    // `*`
+    All,
+    // `term`, `prefix*`, `@field:term`
+    Term {
+        field: Option<String>,
+        term: String,
+        prefix: bool,
+    },
+    // `@field:{a|b}`
+    Tag {
+        field: String,
+        tags: Vec<String>,
+    },
+    // `@field:[min max]`, `(` makes a bound exclusive
+    Range {
+        field: String,
+        min: (f64, bool),
+        max: (f64, bool),
+    },
+}
+
+fn parse_bound(bound: &str) -> Result<(f64, bool), &'static str> {
+    let (bound, exclusive) = match bound.strip_prefix('(') {
+        Some(bound) => (bound, true),
+        None => (bound, false),
+    };
+    let value = match bound.to_lowercase().as_str() {
+        "-inf" => f64::NEG_INFINITY,
+        "inf" | "+inf" => f64::INFINITY,
+        _ => bound.parse::<f64>().map_err(|_| INVALID_RANGE)?,
+    };
+    Ok((value, exclusive))
+}
+
+/// Reads until `close`, returning the content and the position after it.
+fn read_until(chars: &[char], start: usize, close: char) -> Result<(String, usize), &'static str> {
+    let end = chars[start..]
+        .iter()
+        .position(|c| *c == close)
+        .map(|offset| start + offset)
+        .ok_or(INVALID_QUERY)?;
+    Ok((chars[start..end].iter().collect(), end + 1))
+}
+
+fn read_word(chars: &[char], start: usize) -> (String, usize) {
+    let end = chars[start..]
+        .iter()
+        .position(|c| c.is_whitespace())
+        .map_or(chars.len(), |offset| start + offset);
+    (chars[start..end].iter().collect(), end)
+}
+
+fn term_clauses(field: Option<&str>, word: &str) -> Vec<Clause> {
+    let (word, prefix) = match word.strip_suffix('*') {
+        Some(word) => (word, true),
+        None => (word, false),
+    };
+    let terms = tokenize(word);
+    let last = terms.len().saturating_sub(1);
+    terms
+        .into_iter()
+        .enumerate()
+        .map(|(i, term)| Clause::Term {
+            field: field.map(|field| field.to_string()),
+            term,
+            prefix: prefix && i == last,
+        })
+        .collect()
+}
+
+/// Parses a query into clauses that must all match, each possibly negated with `-`.
+///
+/// The supported syntax is `*`, bare terms matching any text field, `@field:term` for
+/// one text field, a trailing `*` for prefix matches, `@field:{tag1|tag2}` for tag fields
+/// and `@field:[min max]` for numeric fields, where `(` makes a bound exclusive and
+/// `-inf`/`+inf` are open bounds.
+///
+/// # Returns
+///
+/// * `(negated, clause)` pairs, or an error message if the query is invalid or uses a
+///   field that is not in the schema with the matching type.
+pub fn parse(query: &str, index: &SearchIndex) -> Result<Vec<(bool, Clause)>, &'static str> {
+    let chars: Vec<char> = query.chars().collect();
+    let mut clauses = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i].is_whitespace() {
+            i += 1;
+            continue;
+        }
+        let negate = chars[i] == '-';
+        if negate {
+            i += 1;
+        }
+        if chars.get(i) != Some(&'@') {
+            let (word, end) = read_word(&chars, i);
+            i = end;
+            if word == "*" {
+                clauses.push((negate, Clause::All));
+                continue;
+            }
+            let terms = term_clauses(None, &word);
+            if terms.is_empty() {
+                return Err(INVALID_QUERY);
+            }
+            clauses.extend(terms.into_iter().map(|clause| (negate, clause)));
+            continue;
+        }
+
+        let (field, end) = read_until(&chars, i + 1, ':')?;
+        i = end;
+        let field_type = index.field_type(&field).ok_or(UNKNOWN_FIELD)?;
+        match (chars.get(i), field_type) {
+            (Some('['), FieldType::Numeric) => {
+                let (range, end) = read_until(&chars, i + 1, ']')?;
+                i = end;
+                let bounds: Vec<&str> = range.split_whitespace().collect();
+                if bounds.len() != 2 {
+                    return Err(INVALID_RANGE);
+                }
+                let clause = Clause::Range {
+                    field,
+                    min: parse_bound(bounds[0])?,
+                    max: parse_bound(bounds[1])?,
+                };
+                clauses.push((negate, clause));
+            }
+            (Some('{'), FieldType::Tag) => {
+                let (tags, end) = read_until(&chars, i + 1, '}')?;
+                i = end;
+                let tags = tags.split('|').flat_map(split_tags).collect();
+                clauses.push((negate, Clause::Tag { field, tags }));
+            }
+            (Some(c), FieldType::Text) if !c.is_whitespace() => {
+                let (word, end) = read_word(&chars, i);
+                i = end;
+                let terms = term_clauses(Some(&field), &word);
+                if terms.is_empty() {
+                    return Err(INVALID_QUERY);
+                }
+                clauses.extend(terms.into_iter().map(|clause| (negate, clause)));
+            }
+            _ => return Err(INVALID_QUERY),
+        }
+    }
+    if clauses.is_empty() {
+        return Err(INVALID_QUERY);
+    }
+    Ok(clauses)
+}
+
+/// Returns the keys of the documents matching every clause.
+pub fn execute(clauses: &[(bool, Clause)], index: &SearchIndex) -> BTreeSet<String> {
+    let mut result = index.all();
+    for (negate, clause) in clauses {
+        let matched = match clause {
+            Clause::All => index.all(),
+            Clause::Term {
+                field,
+                term,
+                prefix,
+            } => index.term(field.as_deref(), term, *prefix),
+            Clause::Tag { field, tags } => index.tag(field, tags),
+            Clause::Range { field, min, max } => index.range(field, *min, *max),
+        };
+        if *negate {
+            result.retain(|key| !matched.contains(key));
+        } else {
+            result.retain(|key| matched.contains(key));
+        }
+    }
+    result
+}
@@ -0,0 +1,41 @@
+use crate::ft_create::ft_create;
+use crate::ft_index::{ft_drop_index, ft_info, ft_list};
+use crate::ft_search::ft_search;
+use rus_key_db::db::Db;
+use rus_key_trait::command_trait::{Arity, Command};
+use std::str::SplitAsciiWhitespace;
+
+pub struct SearchCommand {
+    command: String,
+}
+
+impl SearchCommand {
+    pub fn new(command: String) -> SearchCommand {
+        SearchCommand { command }
+    }
+}
+
+impl Command for SearchCommand {
+    fn execute(
+        &self,
+        parts: &mut SplitAsciiWhitespace,
+        db: &mut Db,
+    ) -> Result<String, &'static str> {
+        match self.command.as_str() {
+            "ft.create" => ft_create(parts, db),
+            "ft.search" => ft_search(parts, db),
+            "ft.dropindex" => ft_drop_index(parts, db),
+            "ft.info" => ft_info(parts, db),
+            "ft._list" => ft_list(parts, db),
+            _ => Err("SearchCommand Error: Command not found"),
+        }
+    }
+
+    fn arity(&self) -> Arity {
+        match self.command.as_str() {
+            "ft._list" => Arity::new(0),
+            "ft.search" => Arity::new(2),
+            _ => Arity::new(1),
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use std::str::SplitAsciiWhitespace;
 use rus_key_db::db::{DataType, Db};
+use rus_key_db::notify::NOTIFY_STRING;
 use crate::get::get;
 use crate::r#const::EMPTY;
 use rus_key_command_lib::get_parts;
@@ -38,6 +39,7 @@ pub fn append(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> String {
     };
 
     let len = new_value.len();
-    db.set(key, DataType::String(new_value));
+    db.set(key.clone(), DataType::String(new_value));
+    db.notify(NOTIFY_STRING, "append", &key);
     len.to_string()
 }
@@ -1,5 +1,4 @@
 use std::str::SplitAsciiWhitespace;
-use expired_commands::expired::get_key_expired;
 use rus_key_db::db::{DataType, Db};
 use rus_key_command_lib::get_parts;
 use crate::r#const::EMPTY;
@@ -33,11 +32,6 @@ pub fn get(
     if !db.check_expired(&key) {
         return EMPTY.to_string();
     }
-    let expired = get_key_expired(Some(&key), db);
-
-    if expired == EMPTY {
-        return EMPTY.to_string();
-    }
     match db.get(&key) {
         Some(DataType::String(value)) => value.clone(),
         _ => "There is no such key, the key is expired, or the data type is incorrect"
@@ -1,5 +1,6 @@
 use std::str::SplitAsciiWhitespace;
 use rus_key_db::db::Db;
+use rus_key_db::notify::NOTIFY_GENERIC;
 use crate::get::get;
 use crate::r#const::EMPTY;
 use rus_key_command_lib::get_parts;
@@ -25,5 +26,6 @@ pub fn get_del(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> String {
         return EMPTY.to_string();
     }
     db.delete(&key);
+    db.notify(NOTIFY_GENERIC, "del", &key);
     value
 }
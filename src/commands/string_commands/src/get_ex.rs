@@ -8,8 +8,10 @@ use rus_key_command_lib::get_parts;
 
 pub fn get_ex(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> String {
     let (key, _) = get_parts(parts, false);
-    // ex px command
-    let expired_command = ExpiredCommand::new("expired".to_string());
+    // ex command
+    let expired_command = ExpiredCommand::new("expire".to_string());
+    // px command
+    let pexpired_command = ExpiredCommand::new("pexpire".to_string());
     // exat command
     let expired_at_command = ExpiredCommand::new("expireat".to_string());
     // pxat command
@@ -45,7 +47,7 @@ pub fn get_ex(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> String {
                 expired_count += 1;
                 if let Some(milliseconds_str) = parts.next() {
                     let milliseconds = milliseconds_str.parse::<i64>().unwrap();
-                    extra_args.px = Some((milliseconds + 999) / 1000);
+                    extra_args.px = Some(milliseconds);
                 } else {
                     error_str = Some(SetError::InvalidExpiredTime);
                 }
@@ -76,6 +78,12 @@ pub fn get_ex(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> String {
         }
     }
 
+    // expired times must be positive
+    let expired_times = [extra_args.ex, extra_args.px, extra_args.exat, extra_args.pxat];
+    if expired_times.iter().flatten().any(|&time| time <= 0) {
+        return "Set Error: Invalid expired time".to_string();
+    }
+
     return if !key.is_empty() {
         let value = get(false, parts, &key, db);
         if value == EMPTY {
@@ -89,17 +97,17 @@ pub fn get_ex(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> String {
                 &expired_command,
                 &format!("{} {}", key, extra_args.ex.unwrap_or(0)),
             );
-            if result != "OK" {
+            if result != "1" {
                 error_str = Some(SetError::InvalidExpiredTime);
             }
         }
         if extra_args.px.is_some() {
             let result = general_command(
                 db,
-                &expired_command,
+                &pexpired_command,
                 &format!("{} {}", key, extra_args.px.unwrap_or(0)),
             );
-            if result != "OK" {
+            if result != "1" {
                 error_str = Some(SetError::InvalidExpiredTime);
             }
         }
@@ -109,7 +117,7 @@ pub fn get_ex(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> String {
                 &expired_at_command,
                 &format!("{} {}", key, extra_args.exat.unwrap_or(0)),
             );
-            if result != "OK" {
+            if result != "1" {
                 error_str = Some(SetError::InvalidExpiredTime);
             }
         }
@@ -119,7 +127,7 @@ pub fn get_ex(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> String {
                 &pexpired_at_command,
                 &format!("{} {}", key, extra_args.pxat.unwrap_or(0)),
             );
-            if result != "OK" {
+            if result != "1" {
                 error_str = Some(SetError::InvalidExpiredTime);
             }
         }
@@ -1,5 +1,6 @@
 use std::str::SplitAsciiWhitespace;
 use rus_key_db::db::{DataType, Db};
+use rus_key_db::notify::NOTIFY_STRING;
 use crate::get::get;
 use crate::r#const::EMPTY;
 use rus_key_command_lib::get_parts;
@@ -27,6 +28,7 @@ pub fn get_set(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> String {
     let old_value = get(false, parts, &key, db);
     // Set the new value in the database
     db.set(key.to_string(), DataType::String(value.to_string()));
+    db.notify(NOTIFY_STRING, "set", &key);
     // If the old value was `EMPTY`, return `EMPTY`. Otherwise, return the old value.
     if old_value == EMPTY {
         EMPTY.to_string()
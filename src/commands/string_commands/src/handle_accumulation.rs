@@ -1,5 +1,6 @@
 use std::str::SplitAsciiWhitespace;
 use rus_key_db::db::{DataType, Db};
+use rus_key_db::notify::NOTIFY_STRING;
 use crate::get::get;
 use crate::r#const::{Accumulation, EMPTY};
 use rus_key_command_lib::{get_parts, is_integer};
@@ -77,5 +78,6 @@ pub fn handle_accumulation(
     };
 
     db.set(key.to_string(), DataType::String(new_value.to_string()));
+    db.notify(NOTIFY_STRING, "incrby", &key);
     new_value.to_string()
 }
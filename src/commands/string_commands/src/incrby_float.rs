@@ -3,6 +3,7 @@ use bigdecimal::BigDecimal;
 use std::str::FromStr;
 
 use rus_key_db::db::{DataType, Db};
+use rus_key_db::notify::NOTIFY_STRING;
 use crate::get::get;
 use crate::r#const::{EMPTY, MIN_VALUE, MAX_VALUE};
 use rus_key_command_lib::{get_parts, is_number};
@@ -60,6 +61,7 @@ pub fn incrby_float(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> String {
     };
     // Set the new value in the database
     db.set(key.to_string(), DataType::String(new_value.to_string()));
+    db.notify(NOTIFY_STRING, "incrbyfloat", &key);
     // Return the new value as a string
     new_value.to_string()
 }
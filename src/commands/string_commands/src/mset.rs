@@ -1,5 +1,6 @@
 use std::str::SplitAsciiWhitespace;
 use rus_key_db::db::{DataType, Db};
+use rus_key_db::notify::NOTIFY_STRING;
 use rus_key_command_lib::get_value;
 
 /// Sets the values of multiple keys in the database.
@@ -28,6 +29,7 @@ pub fn mset(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> String {
     // Iterate over the vector of key-value pairs and set each value in the database
     for (key, value) in key_value_vec {
         db.set(key.to_string(), DataType::String(value.to_string()));
+        db.notify(NOTIFY_STRING, "set", &key);
     }
     // Return "OK" to indicate that the operation was successful
     "OK".to_string()
@@ -1,6 +1,7 @@
 use std::str::SplitAsciiWhitespace;
-use expired_commands::expired::{ExpiredCommand, get_key_expired};
+use expired_commands::expired::ExpiredCommand;
 use rus_key_db::db::{DataType, Db};
+use rus_key_db::notify::NOTIFY_STRING;
 use rus_key_command_lib::get_parts;
 use crate::utils::general_command;
 use crate::get::get;
@@ -22,21 +23,21 @@ use crate::r#const::{ExtraArgs, SetError};
 ///
 /// If the key is not empty, the function retrieves the old value of the key from the database. If the old value is not empty and `extra_args.get` is `Some(true)`, it sets `return_value` to the old value.
 ///
-/// The function then sets the value in the database. If `extra_args.ex`, `extra_args.px`, `extra_args.exat`, or `extra_args.pxat` is `Some`, it calls the `general_command` function to handle the corresponding command. If the result is not "OK", it sets `error_str` to `Some(SetError::InvalidExpiredTime)`.
+/// The function then sets the value in the database. If `extra_args.ex`, `extra_args.px`, `extra_args.exat`, or `extra_args.pxat` is `Some`, it calls the `general_command` function to handle the corresponding command. If the result is not "1", it sets `error_str` to `Some(SetError::InvalidExpiredTime)`.
 ///
 /// If the key is not expired and no expired time argument is provided, and `extra_args.keepttl` is `None`, the function sets the expired time to nil.
 ///
 /// Finally, the function checks if `error_str` is `Some`. If it is, it returns an error message. Otherwise, it returns `return_value`.
 pub fn set(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> String {
     let (key, value) = get_parts(parts, true);
-    // ex px command
-    let expired_command = ExpiredCommand::new("expired".to_string());
+    // ex command
+    let expired_command = ExpiredCommand::new("expire".to_string());
+    // px command
+    let pexpired_command = ExpiredCommand::new("pexpire".to_string());
     // exat command
     let expired_at_command = ExpiredCommand::new("expireat".to_string());
     // pxat command
     let pexpired_at_command = ExpiredCommand::new("pexpireat".to_string());
-    // persist command
-    let persist_command = ExpiredCommand::new("persist".to_string());
     // extra object
     let mut extra_args = ExtraArgs {
         ex: None,
@@ -72,7 +73,7 @@ pub fn set(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> String {
                 expired_count += 1;
                 if let Some(milliseconds_str) = parts.next() {
                     let milliseconds = milliseconds_str.parse::<i64>().unwrap();
-                    extra_args.px = Some((milliseconds + 999) / 1000);
+                    extra_args.px = Some(milliseconds);
                 } else {
                     error_str = Some(SetError::InvalidExpiredTime);
                 }
@@ -111,6 +112,12 @@ pub fn set(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> String {
         return "Set Error: Invalid expired time in set".to_string();
     }
 
+    // expired times must be positive
+    let expired_times = [extra_args.ex, extra_args.px, extra_args.exat, extra_args.pxat];
+    if expired_times.iter().flatten().any(|&time| time <= 0) {
+        return "Set Error: Invalid expired time".to_string();
+    }
+
     // nx and xx cannot exist simultaneously
     if extra_args.nx.is_some() && extra_args.xx.is_some() {
         return "Set Error: nx and xx cannot exist simultaneously".to_string();
@@ -133,6 +140,7 @@ pub fn set(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> String {
             return_value = old_value;
         }
         db.set(key.to_string(), DataType::String(value.to_string()));
+        db.notify(NOTIFY_STRING, "set", &key);
         // hangle extra arg
         if extra_args.ex.is_some() {
             let result = general_command(
@@ -140,17 +148,17 @@ pub fn set(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> String {
                 &expired_command,
                 &format!("{} {}", key, extra_args.ex.unwrap_or(0)),
             );
-            if result != "OK" {
+            if result != "1" {
                 error_str = Some(SetError::InvalidExpiredTime);
             }
         }
         if extra_args.px.is_some() {
             let result = general_command(
                 db,
-                &expired_command,
+                &pexpired_command,
                 &format!("{} {}", key, extra_args.px.unwrap_or(0)),
             );
-            if result != "OK" {
+            if result != "1" {
                 error_str = Some(SetError::InvalidExpiredTime);
             }
         }
@@ -160,7 +168,7 @@ pub fn set(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> String {
                 &expired_at_command,
                 &format!("{} {}", key, extra_args.exat.unwrap_or(0)),
             );
-            if result != "OK" {
+            if result != "1" {
                 error_str = Some(SetError::InvalidExpiredTime);
             }
         }
@@ -170,14 +178,14 @@ pub fn set(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> String {
                 &pexpired_at_command,
                 &format!("{} {}", key, extra_args.pxat.unwrap_or(0)),
             );
-            if result != "OK" {
+            if result != "1" {
                 error_str = Some(SetError::InvalidExpiredTime);
             }
         }
-        let key_expired = get_key_expired(Some(&key), db);
         // if key not expired and not expired time arg, set expired time to nil
-        if !key_expired.is_empty() && expired_count == 0 && extra_args.keepttl.is_none() {
-            general_command(db, &persist_command, &key);
+        if db.expire_time(&key).is_some() && expired_count == 0 && extra_args.keepttl.is_none() {
+            // not through PERSIST, which would send its own keyspace event
+            db.persist(&key);
         }
     } else {
         error_str = Some(SetError::KeyOfValueNotSpecified);
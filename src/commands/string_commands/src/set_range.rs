@@ -1,5 +1,6 @@
 use std::str::SplitAsciiWhitespace;
 use rus_key_db::db::{DataType, Db};
+use rus_key_db::notify::NOTIFY_STRING;
 use crate::get::get;
 use crate::r#const::EMPTY;
 use rus_key_command_lib::get_parts;
@@ -70,6 +71,7 @@ pub fn set_range(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> String {
 
     // Set the new value in the database
     db.set(key.to_string(), DataType::String(value_with_capacity));
+    db.notify(NOTIFY_STRING, "setrange", &key);
 
     // Return the length of the new value as a string
     len.to_string()
@@ -14,7 +14,7 @@ use crate::set::set;
 use crate::set_range::set_range;
 use crate::str_len::str_len;
 use rus_key_db::db::Db;
-use rus_key_trait::command_trait::Command;
+use rus_key_trait::command_trait::{Arity, Command};
 use std::str::SplitAsciiWhitespace;
 
 pub struct StringCommand {
@@ -54,4 +54,14 @@ impl Command for StringCommand {
             _ => Err("StringCommand Error: Command not found"),
         }
     }
+
+    fn arity(&self) -> Arity {
+        match self.command.as_str() {
+            "decrby" | "incrby" => Arity::with_integers(2, &[1]),
+            "getrange" => Arity::with_integers(3, &[1, 2]),
+            "setrange" => Arity::with_integers(3, &[1]),
+            "append" | "getset" | "incrbyfloat" | "set" | "mset" | "lcs" => Arity::new(2),
+            _ => Arity::new(1),
+        }
+    }
 }
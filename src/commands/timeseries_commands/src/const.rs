@@ -0,0 +1,28 @@
+pub const WRONG_TYPE: &str =
+    "There is no such key, the key is expired, or the data type is incorrect";
+
+pub const WRONG_ARGS: &str = "ERR wrong number of arguments for command";
+
+pub const KEY_NOT_EXISTS: &str = "ERR TSDB: the key does not exist";
+
+pub const KEY_EXISTS: &str = "ERR TSDB: key already exists";
+
+pub const INVALID_TIMESTAMP: &str = "ERR TSDB: invalid timestamp";
+
+pub const INVALID_VALUE: &str = "ERR TSDB: invalid value";
+
+pub const INVALID_RETENTION: &str = "ERR TSDB: invalid RETENTION value";
+
+pub const INVALID_DUPLICATE_POLICY: &str = "ERR TSDB: Unknown DUPLICATE_POLICY";
+
+pub const INVALID_AGGREGATION: &str = "ERR TSDB: Unknown aggregation type";
+
+pub const INVALID_BUCKET_DURATION: &str = "ERR TSDB: bucketDuration must be greater than zero";
+
+pub const INVALID_COUNT: &str = "ERR TSDB: Couldn't parse COUNT";
+
+pub const INVALID_LABELS: &str = "ERR TSDB: Invalid labels";
+
+pub const INVALID_FILTER: &str = "ERR TSDB: failed parsing labels";
+
+pub const SYNTAX_ERROR: &str = "ERR syntax error";
@@ -0,0 +1,10 @@
+mod r#const;
+pub mod timeseries;
+mod ts_add;
+mod ts_create;
+mod ts_get;
+mod ts_incr_by;
+mod ts_info;
+mod ts_range;
+mod ts_rule;
+mod utils;
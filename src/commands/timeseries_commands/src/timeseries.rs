@@ -0,0 +1,55 @@
+use crate::ts_add::{ts_add, ts_madd};
+use crate::ts_create::ts_create;
+use crate::ts_get::ts_get;
+use crate::ts_incr_by::ts_incr_by;
+use crate::ts_info::ts_info;
+use crate::ts_range::{ts_mrange, ts_range};
+use crate::ts_rule::{ts_create_rule, ts_delete_rule};
+use rus_key_db::db::Db;
+use rus_key_trait::command_trait::{Arity, Command};
+use std::str::SplitAsciiWhitespace;
+
+pub struct TimeSeriesCommand {
+    command: String,
+}
+
+impl TimeSeriesCommand {
+    pub fn new(command: String) -> TimeSeriesCommand {
+        TimeSeriesCommand { command }
+    }
+}
+
+impl Command for TimeSeriesCommand {
+    fn execute(
+        &self,
+        parts: &mut SplitAsciiWhitespace,
+        db: &mut Db,
+    ) -> Result<String, &'static str> {
+        match self.command.as_str() {
+            "ts.create" => ts_create(parts, db),
+            "ts.add" => ts_add(parts, db),
+            "ts.madd" => ts_madd(parts, db),
+            "ts.incrby" => ts_incr_by(parts, db),
+            "ts.get" => ts_get(parts, db),
+            "ts.range" => ts_range(parts, db, false),
+            "ts.revrange" => ts_range(parts, db, true),
+            "ts.mrange" => ts_mrange(parts, db, false),
+            "ts.mrevrange" => ts_mrange(parts, db, true),
+            "ts.createrule" => ts_create_rule(parts, db),
+            "ts.deleterule" => ts_delete_rule(parts, db),
+            "ts.info" => ts_info(parts, db),
+            _ => Err("TimeSeriesCommand Error: Command not found"),
+        }
+    }
+
+    fn arity(&self) -> Arity {
+        match self.command.as_str() {
+            "ts.add" | "ts.madd" | "ts.range" | "ts.revrange" => Arity::new(3),
+            "ts.incrby" | "ts.deleterule" => Arity::new(2),
+            // FILTER and a label
+            "ts.mrange" | "ts.mrevrange" => Arity::new(4),
+            "ts.createrule" => Arity::with_integers(5, &[4]),
+            _ => Arity::new(1),
+        }
+    }
+}
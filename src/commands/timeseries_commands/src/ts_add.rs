@@ -0,0 +1,71 @@
+use crate::r#const::{INVALID_VALUE, WRONG_ARGS};
+use crate::utils::{add_sample, get_series, parse_series_options, parse_timestamp};
+use rus_key_db::db::{DataType, Db};
+use rus_key_db::timeseries::{DuplicatePolicy, TimeSeries};
+use std::str::SplitAsciiWhitespace;
+
+/// Appends a sample to a time series, creating the series if needed.
+///
+/// Syntax: `TS.ADD key timestamp value [RETENTION retentionPeriod]
+/// [DUPLICATE_POLICY policy] [ON_DUPLICATE policy] [LABELS label value ...]`
+///
+/// The timestamp is in milliseconds, `*` uses the current time. `RETENTION`,
+/// `DUPLICATE_POLICY` and `LABELS` only apply when the series is created, `ON_DUPLICATE`
+/// overrides the duplicate policy for this sample.
+///
+/// # Returns
+///
+/// * The timestamp of the sample, or an error message if it was rejected.
+pub fn ts_add(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let (key, timestamp, value) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(key), Some(timestamp), Some(value)) => (key, timestamp, value),
+        _ => return Err(WRONG_ARGS),
+    };
+    let timestamp = parse_timestamp(timestamp)?;
+    let value = value.parse::<f64>().map_err(|_| INVALID_VALUE)?;
+    let options = parse_series_options(
+        parts,
+        &["retention", "duplicate_policy", "on_duplicate", "labels"],
+    )?;
+
+    if get_series(key, db)?.is_none() {
+        db.set(
+            key.to_string(),
+            DataType::TimeSeries(TimeSeries::new(
+                options.retention.unwrap_or(0),
+                options.duplicate_policy.unwrap_or(DuplicatePolicy::Block),
+                options.labels.unwrap_or_default(),
+            )),
+        );
+    }
+    let timestamp = add_sample(key, timestamp, value, options.on_duplicate, "ts.add", db)?;
+    Ok(timestamp.to_string())
+}
+
+/// Appends samples to one or more existing time series.
+///
+/// Syntax: `TS.MADD key timestamp value [key timestamp value ...]`
+///
+/// # Returns
+///
+/// * For each sample its timestamp, or the error message that rejected it, separated by
+///   spaces.
+pub fn ts_madd(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let args: Vec<&str> = parts.collect();
+    if args.is_empty() || !args.len().is_multiple_of(3) {
+        return Err(WRONG_ARGS);
+    }
+
+    let mut result = Vec::with_capacity(args.len() / 3);
+    for sample in args.chunks(3) {
+        let added = parse_timestamp(sample[1]).and_then(|timestamp| {
+            let value = sample[2].parse::<f64>().map_err(|_| INVALID_VALUE)?;
+            add_sample(sample[0], timestamp, value, None, "ts.madd", db)
+        });
+        result.push(match added {
+            Ok(timestamp) => timestamp.to_string(),
+            Err(e) => e.to_string(),
+        });
+    }
+    Ok(result.join(" "))
+}
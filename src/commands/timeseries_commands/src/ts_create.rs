@@ -0,0 +1,35 @@
+use crate::r#const::{KEY_EXISTS, WRONG_ARGS};
+use crate::utils::{get_series, parse_series_options};
+use rus_key_db::db::{DataType, Db};
+use rus_key_db::notify::NOTIFY_MODULE;
+use rus_key_db::timeseries::{DuplicatePolicy, TimeSeries};
+use std::str::SplitAsciiWhitespace;
+
+/// Creates an empty time series.
+///
+/// Syntax: `TS.CREATE key [RETENTION retentionPeriod] [DUPLICATE_POLICY policy]
+/// [LABELS label value ...]`
+///
+/// The retention is in milliseconds, 0 keeps every sample. The duplicate policy is one of
+/// `BLOCK` (the default), `FIRST`, `LAST`, `MIN`, `MAX` or `SUM`.
+///
+/// # Returns
+///
+/// * `OK`, or an error message if the key exists or an option is invalid.
+pub fn ts_create(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let key = parts.next().ok_or(WRONG_ARGS)?;
+    let options = parse_series_options(parts, &["retention", "duplicate_policy", "labels"])?;
+    if get_series(key, db)?.is_some() {
+        return Err(KEY_EXISTS);
+    }
+    db.set(
+        key.to_string(),
+        DataType::TimeSeries(TimeSeries::new(
+            options.retention.unwrap_or(0),
+            options.duplicate_policy.unwrap_or(DuplicatePolicy::Block),
+            options.labels.unwrap_or_default(),
+        )),
+    );
+    db.notify(NOTIFY_MODULE, "ts.create", key);
+    Ok("OK".to_string())
+}
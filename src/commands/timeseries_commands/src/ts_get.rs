@@ -0,0 +1,22 @@
+use crate::r#const::{KEY_NOT_EXISTS, WRONG_ARGS};
+use crate::utils::{format_samples, get_series};
+use rus_key_db::db::Db;
+use std::str::SplitAsciiWhitespace;
+
+/// Returns the newest sample of a time series.
+///
+/// Syntax: `TS.GET key`
+///
+/// # Returns
+///
+/// * The sample as `timestamp: value`, or an empty string if the series has no samples.
+pub fn ts_get(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let key = match (parts.next(), parts.next()) {
+        (Some(key), None) => key,
+        _ => return Err(WRONG_ARGS),
+    };
+    let series = get_series(key, db)?.ok_or(KEY_NOT_EXISTS)?;
+    Ok(format_samples(
+        &series.last().into_iter().collect::<Vec<_>>(),
+    ))
+}
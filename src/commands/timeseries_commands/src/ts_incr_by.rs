@@ -0,0 +1,63 @@
+use crate::r#const::{INVALID_VALUE, WRONG_ARGS};
+use crate::utils::{add_sample, get_series, parse_series_options};
+use rus_key_db::db::{DataType, Db};
+use rus_key_db::timeseries::{DuplicatePolicy, TimeSeries};
+use std::str::SplitAsciiWhitespace;
+
+/// Adds a sample holding the newest value of a series increased by `value`, creating the
+/// series if needed.
+///
+/// Syntax: `TS.INCRBY key value [TIMESTAMP timestamp] [RETENTION retentionPeriod]
+/// [DUPLICATE_POLICY policy] [LABELS label value ...]`
+///
+/// The timestamp defaults to the current time and cannot be older than the newest sample.
+/// A sample at the same timestamp as the newest one is replaced.
+///
+/// # Returns
+///
+/// * The timestamp of the sample, or an error message if it was rejected.
+pub fn ts_incr_by(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let (key, increment) = match (parts.next(), parts.next()) {
+        (Some(key), Some(increment)) => (key, increment),
+        _ => return Err(WRONG_ARGS),
+    };
+    let increment = increment.parse::<f64>().map_err(|_| INVALID_VALUE)?;
+    let options = parse_series_options(
+        parts,
+        &["timestamp", "retention", "duplicate_policy", "labels"],
+    )?;
+    let timestamp = options
+        .timestamp
+        .unwrap_or_else(|| chrono::Utc::now().timestamp_millis() as u64);
+
+    let last = match get_series(key, db)? {
+        Some(series) => series.last(),
+        None => {
+            db.set(
+                key.to_string(),
+                DataType::TimeSeries(TimeSeries::new(
+                    options.retention.unwrap_or(0),
+                    options.duplicate_policy.unwrap_or(DuplicatePolicy::Block),
+                    options.labels.unwrap_or_default(),
+                )),
+            );
+            None
+        }
+    };
+    let value = match last {
+        Some((last_timestamp, _)) if timestamp < last_timestamp => return Err(
+            "ERR TSDB: timestamp must be equal to or higher than the maximum existing timestamp",
+        ),
+        Some((_, last_value)) => last_value + increment,
+        None => increment,
+    };
+    let timestamp = add_sample(
+        key,
+        timestamp,
+        value,
+        Some(DuplicatePolicy::Last),
+        "ts.incrby",
+        db,
+    )?;
+    Ok(timestamp.to_string())
+}
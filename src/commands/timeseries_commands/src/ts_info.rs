@@ -0,0 +1,61 @@
+use crate::r#const::{KEY_NOT_EXISTS, WRONG_ARGS};
+use crate::utils::get_series;
+use rus_key_db::db::Db;
+use std::str::SplitAsciiWhitespace;
+
+/// Returns information about a time series.
+///
+/// Syntax: `TS.INFO key`
+///
+/// # Returns
+///
+/// * All fields as `name: value` pairs. Labels are `label=value` and rules
+///   `destKey:aggregator:bucketDuration`, separated by commas.
+pub fn ts_info(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let key = parts.next().ok_or(WRONG_ARGS)?;
+    let series = get_series(key, db)?.ok_or(KEY_NOT_EXISTS)?;
+    let (first, last) = match (series.range(0, u64::MAX).first(), series.last()) {
+        (Some(first), Some(last)) => (first.0.to_string(), last.0.to_string()),
+        _ => ("0".to_string(), "0".to_string()),
+    };
+    let labels = series
+        .labels
+        .iter()
+        .map(|(label, value)| format!("{}={}", label, value))
+        .collect::<Vec<String>>()
+        .join(",");
+    let rules = series
+        .rules
+        .iter()
+        .map(|rule| {
+            format!(
+                "{}:{}:{}",
+                rule.destination,
+                rule.aggregation.name(),
+                rule.bucket_duration
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(",");
+    let fields = [
+        ("totalSamples", series.len().to_string()),
+        ("firstTimestamp", first),
+        ("lastTimestamp", last),
+        ("retentionTime", series.retention.to_string()),
+        (
+            "duplicatePolicy",
+            series.duplicate_policy.name().to_string(),
+        ),
+        ("labels", labels),
+        (
+            "sourceKey",
+            series.source.clone().unwrap_or_else(|| "nil".to_string()),
+        ),
+        ("rules", rules),
+    ];
+    Ok(fields
+        .iter()
+        .map(|(title, value)| format!("{}: {}", title, value))
+        .collect::<Vec<String>>()
+        .join(" "))
+}
@@ -0,0 +1,172 @@
+use crate::r#const::{INVALID_FILTER, KEY_NOT_EXISTS, SYNTAX_ERROR, WRONG_ARGS};
+use crate::utils::{
+    format_samples, get_series, parse_aggregation, parse_count, parse_range_timestamp,
+};
+use rus_key_db::db::{DataType, Db};
+use rus_key_db::timeseries::{aggregate, Aggregation, TimeSeries};
+use std::str::SplitAsciiWhitespace;
+
+struct RangeArgs {
+    from: u64,
+    to: u64,
+    count: Option<usize>,
+    aggregation: Option<(Aggregation, u64)>,
+    filters: Vec<Filter>,
+}
+
+/// A label matcher of `TS.MRANGE`.
+///
+/// `label=value` and `label=(a,b)` match one of the values, `label!=...` the opposite.
+/// An empty value list matches series without the label.
+struct Filter {
+    label: String,
+    values: Vec<String>,
+    negate: bool,
+}
+
+impl Filter {
+    fn parse(filter: &str) -> Result<Filter, &'static str> {
+        let (label, value, negate) = match filter.split_once("!=") {
+            Some((label, value)) => (label, value, true),
+            None => match filter.split_once('=') {
+                Some((label, value)) => (label, value, false),
+                None => return Err(INVALID_FILTER),
+            },
+        };
+        if label.is_empty() {
+            return Err(INVALID_FILTER);
+        }
+        let values = match value.strip_prefix('(').and_then(|v| v.strip_suffix(')')) {
+            Some(list) => list.split(',').map(|v| v.to_string()).collect(),
+            None if value.is_empty() => Vec::new(),
+            None => vec![value.to_string()],
+        };
+        Ok(Filter {
+            label: label.to_string(),
+            values,
+            negate,
+        })
+    }
+
+    fn matches(&self, series: &TimeSeries) -> bool {
+        let found = match series.label(&self.label) {
+            Some(_) if self.values.is_empty() => false,
+            Some(value) => self.values.iter().any(|v| v == value),
+            None => self.values.is_empty(),
+        };
+        found != self.negate
+    }
+}
+
+fn parse_range_args(
+    parts: &mut SplitAsciiWhitespace,
+    with_filter: bool,
+) -> Result<RangeArgs, &'static str> {
+    let (from, to) = match (parts.next(), parts.next()) {
+        (Some(from), Some(to)) => (parse_range_timestamp(from)?, parse_range_timestamp(to)?),
+        _ => return Err(WRONG_ARGS),
+    };
+    let mut args = RangeArgs {
+        from,
+        to,
+        count: None,
+        aggregation: None,
+        filters: Vec::new(),
+    };
+    while let Some(arg) = parts.next() {
+        match arg.to_lowercase().as_str() {
+            "count" => args.count = Some(parse_count(parts)?),
+            "aggregation" => args.aggregation = Some(parse_aggregation(parts)?),
+            "filter" if with_filter => {
+                for filter in parts.by_ref() {
+                    args.filters.push(Filter::parse(filter)?);
+                }
+            }
+            _ => return Err(SYNTAX_ERROR),
+        }
+    }
+    if with_filter && args.filters.is_empty() {
+        return Err(INVALID_FILTER);
+    }
+    Ok(args)
+}
+
+fn query(series: &TimeSeries, args: &RangeArgs, reverse: bool) -> Vec<(u64, f64)> {
+    let mut samples = series.range(args.from, args.to);
+    if let Some((aggregation, bucket_duration)) = args.aggregation {
+        samples = aggregate(&samples, aggregation, bucket_duration);
+    }
+    if reverse {
+        samples.reverse();
+    }
+    if let Some(count) = args.count {
+        samples.truncate(count);
+    }
+    samples
+}
+
+/// Queries a range of samples from a time series.
+///
+/// Syntax: `TS.RANGE key fromTimestamp toTimestamp [COUNT count]
+/// [AGGREGATION aggregator bucketDuration]` / `TS.REVRANGE ...`
+///
+/// Both timestamps are inclusive, `-` and `+` stand for the oldest and newest sample.
+/// With `AGGREGATION` the samples are grouped into buckets aligned to multiples of
+/// `bucketDuration` and each bucket is reduced with `avg`, `sum`, `min`, `max`, `count`
+/// or `last`. `COUNT` limits the number of returned samples.
+///
+/// # Returns
+///
+/// * The samples as `timestamp: value` pairs, oldest first or newest first for
+///   `TS.REVRANGE`.
+pub fn ts_range(
+    parts: &mut SplitAsciiWhitespace,
+    db: &mut Db,
+    reverse: bool,
+) -> Result<String, &'static str> {
+    let key = parts.next().ok_or(WRONG_ARGS)?;
+    let args = parse_range_args(parts, false)?;
+    let series = get_series(key, db)?.ok_or(KEY_NOT_EXISTS)?;
+    Ok(format_samples(&query(series, &args, reverse)))
+}
+
+/// Queries a range of samples from every time series matching label filters.
+///
+/// Syntax: `TS.MRANGE fromTimestamp toTimestamp [COUNT count]
+/// [AGGREGATION aggregator bucketDuration] FILTER filter ...` / `TS.MREVRANGE ...`
+///
+/// Filters are `label=value`, `label!=value`, `label=(value1,value2)`,
+/// `label!=(value1,value2)`, `label=` (the label is missing) and `label!=` (the label is
+/// present). A series must match all of them.
+///
+/// # Returns
+///
+/// * One `key: [timestamp: value ...]` entry per matching series, ordered by key.
+pub fn ts_mrange(
+    parts: &mut SplitAsciiWhitespace,
+    db: &mut Db,
+    reverse: bool,
+) -> Result<String, &'static str> {
+    let args = parse_range_args(parts, true)?;
+
+    let mut keys: Vec<String> = db
+        .keys()
+        .into_iter()
+        .filter(|key| matches!(db.get(key), Some(DataType::TimeSeries(_))))
+        .collect();
+    keys.sort();
+
+    let mut result = Vec::new();
+    for key in keys {
+        // skips expired keys
+        let series = match get_series(&key, db)? {
+            Some(series) => series,
+            None => continue,
+        };
+        if args.filters.iter().all(|filter| filter.matches(series)) {
+            let samples = query(series, &args, reverse);
+            result.push(format!("{}: [{}]", key, format_samples(&samples)));
+        }
+    }
+    Ok(result.join(" "))
+}
@@ -0,0 +1,91 @@
+use crate::r#const::{KEY_NOT_EXISTS, SYNTAX_ERROR, WRONG_ARGS};
+use crate::utils::{get_series, parse_aggregation};
+use rus_key_db::db::Db;
+use rus_key_db::notify::NOTIFY_MODULE;
+use rus_key_db::timeseries::CompactionRule;
+use std::str::SplitAsciiWhitespace;
+
+/// Creates a compaction rule that downsamples a series into another one.
+///
+/// Syntax: `TS.CREATERULE sourceKey destKey AGGREGATION aggregator bucketDuration`
+///
+/// Both series must exist. Every time a sample opens a new bucket in the source, the
+/// previous bucket is aggregated and written to the destination; samples added to an
+/// older bucket rewrite its aggregate. A destination cannot have rules of its own and
+/// accepts a single source.
+///
+/// # Returns
+///
+/// * `OK`, or an error message if a key is missing or the rule is not allowed.
+pub fn ts_create_rule(
+    parts: &mut SplitAsciiWhitespace,
+    db: &mut Db,
+) -> Result<String, &'static str> {
+    let (source, destination) = match (parts.next(), parts.next()) {
+        (Some(source), Some(destination)) => (source, destination),
+        _ => return Err(WRONG_ARGS),
+    };
+    match parts.next() {
+        Some(arg) if arg.eq_ignore_ascii_case("aggregation") => {}
+        _ => return Err(SYNTAX_ERROR),
+    }
+    let (aggregation, bucket_duration) = parse_aggregation(parts)?;
+    if parts.next().is_some() {
+        return Err(SYNTAX_ERROR);
+    }
+    if source == destination {
+        return Err("ERR TSDB: the source key and destination key should be different");
+    }
+
+    let target = get_series(destination, db)?.ok_or(KEY_NOT_EXISTS)?;
+    if target.source.is_some() {
+        return Err("ERR TSDB: the destination key already has a src rule");
+    }
+    if !target.rules.is_empty() {
+        return Err("ERR TSDB: the destination key already has a dst rule");
+    }
+    let series = get_series(source, db)?.ok_or(KEY_NOT_EXISTS)?;
+    if series.source.is_some() {
+        return Err("ERR TSDB: the source key already has a source rule");
+    }
+    series.rules.push(CompactionRule::new(
+        destination.to_string(),
+        aggregation,
+        bucket_duration,
+    ));
+    if let Some(target) = get_series(destination, db)? {
+        target.source = Some(source.to_string());
+    }
+    db.notify(NOTIFY_MODULE, "ts.createrule", source);
+    db.notify(NOTIFY_MODULE, "ts.createrule", destination);
+    Ok("OK".to_string())
+}
+
+/// Deletes a compaction rule.
+///
+/// Syntax: `TS.DELETERULE sourceKey destKey`
+///
+/// # Returns
+///
+/// * `OK`, or an error message if the rule does not exist.
+pub fn ts_delete_rule(
+    parts: &mut SplitAsciiWhitespace,
+    db: &mut Db,
+) -> Result<String, &'static str> {
+    let (source, destination) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(source), Some(destination), None) => (source, destination),
+        _ => return Err(WRONG_ARGS),
+    };
+    let series = get_series(source, db)?.ok_or(KEY_NOT_EXISTS)?;
+    let count = series.rules.len();
+    series.rules.retain(|rule| rule.destination != destination);
+    if series.rules.len() == count {
+        return Err("ERR TSDB: compaction rule does not exist");
+    }
+    db.notify(NOTIFY_MODULE, "ts.deleterule", source);
+    if let Some(target) = get_series(destination, db)? {
+        target.source = None;
+        db.notify(NOTIFY_MODULE, "ts.deleterule", destination);
+    }
+    Ok("OK".to_string())
+}
@@ -0,0 +1,168 @@
+use crate::r#const::{
+    INVALID_AGGREGATION, INVALID_BUCKET_DURATION, INVALID_COUNT, INVALID_DUPLICATE_POLICY,
+    INVALID_LABELS, INVALID_RETENTION, INVALID_TIMESTAMP, KEY_NOT_EXISTS, SYNTAX_ERROR, WRONG_TYPE,
+};
+use rus_key_db::db::{DataType, Db};
+use rus_key_db::notify::NOTIFY_MODULE;
+use rus_key_db::timeseries::{Aggregation, DuplicatePolicy, TimeSeries};
+use std::str::SplitAsciiWhitespace;
+
+/// Returns the series held at `key`, removing it first if it is expired.
+pub fn get_series<'a>(
+    key: &str,
+    db: &'a mut Db,
+) -> Result<Option<&'a mut TimeSeries>, &'static str> {
+    match db.get_mut(key) {
+        Some(DataType::TimeSeries(series)) => Ok(Some(series)),
+        Some(_) => Err(WRONG_TYPE),
+        None => Ok(None),
+    }
+}
+
+/// Parses a timestamp in milliseconds, `*` meaning the current time.
+pub fn parse_timestamp(timestamp: &str) -> Result<u64, &'static str> {
+    if timestamp == "*" {
+        return Ok(chrono::Utc::now().timestamp_millis() as u64);
+    }
+    timestamp.parse::<u64>().map_err(|_| INVALID_TIMESTAMP)
+}
+
+/// Parses a range boundary, `-` and `+` meaning the oldest and newest possible timestamps.
+pub fn parse_range_timestamp(timestamp: &str) -> Result<u64, &'static str> {
+    match timestamp {
+        "-" => Ok(0),
+        "+" => Ok(u64::MAX),
+        _ => parse_timestamp(timestamp),
+    }
+}
+
+/// The options shared by `TS.CREATE`, `TS.ADD` and `TS.INCRBY`.
+pub struct SeriesOptions {
+    pub retention: Option<u64>,
+    pub duplicate_policy: Option<DuplicatePolicy>,
+    pub on_duplicate: Option<DuplicatePolicy>,
+    pub labels: Option<Vec<(String, String)>>,
+    pub timestamp: Option<u64>,
+}
+
+/// Parses series options until the end of the command.
+///
+/// `LABELS` consumes every remaining argument as label/value pairs, so it must come last.
+///
+/// # Arguments
+///
+/// * `parts` - The remaining arguments.
+/// * `allowed` - The lowercase option names accepted by the command.
+pub fn parse_series_options(
+    parts: &mut SplitAsciiWhitespace,
+    allowed: &[&str],
+) -> Result<SeriesOptions, &'static str> {
+    let mut options = SeriesOptions {
+        retention: None,
+        duplicate_policy: None,
+        on_duplicate: None,
+        labels: None,
+        timestamp: None,
+    };
+    while let Some(arg) = parts.next() {
+        let name = arg.to_lowercase();
+        if !allowed.contains(&name.as_str()) {
+            return Err(SYNTAX_ERROR);
+        }
+        match name.as_str() {
+            "retention" => {
+                let retention = parts.next().ok_or(INVALID_RETENTION)?;
+                options.retention = Some(retention.parse::<u64>().map_err(|_| INVALID_RETENTION)?);
+            }
+            "duplicate_policy" | "on_duplicate" => {
+                let policy = parts
+                    .next()
+                    .and_then(DuplicatePolicy::parse)
+                    .ok_or(INVALID_DUPLICATE_POLICY)?;
+                if name == "duplicate_policy" {
+                    options.duplicate_policy = Some(policy);
+                } else {
+                    options.on_duplicate = Some(policy);
+                }
+            }
+            "labels" => {
+                let rest: Vec<&str> = parts.by_ref().collect();
+                if !rest.len().is_multiple_of(2) {
+                    return Err(INVALID_LABELS);
+                }
+                options.labels = Some(
+                    rest.chunks(2)
+                        .map(|pair| (pair[0].to_string(), pair[1].to_string()))
+                        .collect(),
+                );
+            }
+            "timestamp" => {
+                let timestamp = parts.next().ok_or(INVALID_TIMESTAMP)?;
+                options.timestamp = Some(parse_timestamp(timestamp)?);
+            }
+            _ => return Err(SYNTAX_ERROR),
+        }
+    }
+    Ok(options)
+}
+
+/// Parses `AGGREGATION aggregator bucketDuration` after the keyword.
+pub fn parse_aggregation(
+    parts: &mut SplitAsciiWhitespace,
+) -> Result<(Aggregation, u64), &'static str> {
+    let aggregation = parts
+        .next()
+        .and_then(Aggregation::parse)
+        .ok_or(INVALID_AGGREGATION)?;
+    let bucket_duration = parts
+        .next()
+        .and_then(|duration| duration.parse::<u64>().ok())
+        .ok_or(INVALID_BUCKET_DURATION)?;
+    if bucket_duration == 0 {
+        return Err(INVALID_BUCKET_DURATION);
+    }
+    Ok((aggregation, bucket_duration))
+}
+
+/// Parses `COUNT count` after the keyword.
+pub fn parse_count(parts: &mut SplitAsciiWhitespace) -> Result<usize, &'static str> {
+    parts
+        .next()
+        .and_then(|count| count.parse::<usize>().ok())
+        .ok_or(INVALID_COUNT)
+}
+
+/// Adds a sample to an existing series and writes the compacted samples it produces to
+/// the destinations of its rules. An added sample publishes `event` for the series.
+///
+/// # Returns
+///
+/// * The timestamp of the sample, or an error message if the sample was rejected.
+pub fn add_sample(
+    key: &str,
+    timestamp: u64,
+    value: f64,
+    policy: Option<DuplicatePolicy>,
+    event: &str,
+    db: &mut Db,
+) -> Result<u64, &'static str> {
+    let series = get_series(key, db)?.ok_or(KEY_NOT_EXISTS)?;
+    let compacted = series.add(timestamp, value, policy)?;
+    db.notify(NOTIFY_MODULE, event, key);
+    for (destination, bucket, value) in compacted {
+        // a destination deleted since the rule was created is skipped
+        if let Ok(Some(destination)) = get_series(&destination, db) {
+            let _ = destination.add(bucket, value, Some(DuplicatePolicy::Last));
+        }
+    }
+    Ok(timestamp)
+}
+
+/// Formats samples as `timestamp: value` pairs separated by spaces.
+pub fn format_samples(samples: &[(u64, f64)]) -> String {
+    samples
+        .iter()
+        .map(|(timestamp, value)| format!("{}: {}", timestamp, value))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
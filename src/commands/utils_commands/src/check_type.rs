@@ -10,10 +10,17 @@ pub fn check_type(key: Option<&str>, db: &mut Db) -> String {
     }
     match db.get(key) {
         Some(DataType::String(_)) => "string".to_string(),
-        // Some(DataType::List(_)) => "list".to_string(),
+        Some(DataType::List(_)) => "list".to_string(),
         // Some(DataType::Set(_)) => "set".to_string(),
         Some(DataType::HashMap(_)) => "hash".to_string(),
         Some(DataType::ZSet(_)) => "zset".to_string(),
+        Some(DataType::Json(_)) => "ReJSON-RL".to_string(),
+        Some(DataType::Bloom(_)) => "MBbloom--".to_string(),
+        Some(DataType::Cuckoo(_)) => "MBbloomCF".to_string(),
+        Some(DataType::CountMinSketch(_)) => "CMSk-TYPE".to_string(),
+        Some(DataType::TopK(_)) => "TopK-TYPE".to_string(),
+        Some(DataType::TimeSeries(_)) => "TSDB-TYPE".to_string(),
+        Some(DataType::VectorSet(_)) => "vectorset".to_string(),
         None => "none".to_string(),
     }
 }
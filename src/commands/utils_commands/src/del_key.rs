@@ -1,12 +1,30 @@
 use std::str::SplitAsciiWhitespace;
 use rus_key_db::db::Db;
+use rus_key_db::notify::NOTIFY_GENERIC;
 
 pub fn del_key(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> String {
     let mut count = 0;
     while let Some(key) = parts.next() {
         if db.delete(key) {
+            db.notify(NOTIFY_GENERIC, "del", key);
             count += 1;
         }
     }
     count.to_string()
 }
+
+/// `UNLINK key [key ...]`
+///
+/// Removes the keys like `DEL` and frees the large values on a background thread.
+pub fn unlink(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> String {
+    parts
+        .filter(|key| {
+            let unlinked = db.unlink(key);
+            if unlinked {
+                db.notify(NOTIFY_GENERIC, "del", key);
+            }
+            unlinked
+        })
+        .count()
+        .to_string()
+}
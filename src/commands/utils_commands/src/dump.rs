@@ -0,0 +1,70 @@
+use std::str::SplitAsciiWhitespace;
+
+use rus_key_db::db::{now_millis, Db};
+use rus_key_db::dump::{dump as dump_value, from_hex, restore as restore_value, to_hex};
+use rus_key_db::notify::NOTIFY_GENERIC;
+
+const WRONG_ARGS: &str = "ERR wrong number of arguments for command";
+const SYNTAX_ERROR: &str = "ERR syntax error";
+const NOT_INTEGER: &str = "ERR value is not an integer or out of range";
+const INVALID_TTL: &str = "ERR Invalid TTL value, must be >= 0";
+const BUSY_KEY: &str = "BUSYKEY Target key name already exists.";
+
+/// `DUMP key`
+///
+/// Replies the value and the TTL of the key serialized as hex, or `nil` for a missing key.
+pub fn dump(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let key = parts.next().ok_or(WRONG_ARGS)?;
+    if parts.next().is_some() {
+        return Err(WRONG_ARGS);
+    }
+    let result = match db.peek(key) {
+        Some(entry) => to_hex(&dump_value(entry.value(), db.expire_time(key))),
+        None => "nil".to_string(),
+    };
+    Ok(result)
+}
+
+/// `RESTORE key ttl payload [REPLACE] [ABSTTL]`
+///
+/// Creates a key from a `DUMP` payload. `ttl` is in milliseconds, a unix time with ABSTTL,
+/// and 0 keeps the TTL stored in the payload. A deadline already in the past creates nothing.
+pub fn restore(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let key = parts.next().ok_or(WRONG_ARGS)?;
+    let ttl = parts.next().ok_or(WRONG_ARGS)?;
+    let payload = parts.next().ok_or(WRONG_ARGS)?;
+    let mut replace = false;
+    let mut absolute = false;
+    for option in parts {
+        match option.to_lowercase().as_str() {
+            "replace" => replace = true,
+            "absttl" => absolute = true,
+            _ => return Err(SYNTAX_ERROR),
+        }
+    }
+    let ttl = ttl.parse::<i64>().map_err(|_| NOT_INTEGER)?;
+    if ttl < 0 {
+        return Err(INVALID_TTL);
+    }
+    let (value, stored_deadline) = restore_value(&from_hex(payload)?)?;
+    if !replace && db.check_expired(key) {
+        return Err(BUSY_KEY);
+    }
+
+    let now = now_millis();
+    let deadline = match ttl {
+        0 => stored_deadline,
+        ttl if absolute => Some(ttl),
+        ttl => Some(now.saturating_add(ttl)),
+    };
+    db.delete(key);
+    if deadline.is_some_and(|deadline| deadline <= now) {
+        return Ok("OK".to_string());
+    }
+    db.set(key.to_string(), value);
+    if let Some(deadline) = deadline {
+        db.expire_at(key, deadline);
+    }
+    db.notify(NOTIFY_GENERIC, "restore", key);
+    Ok("OK".to_string())
+}
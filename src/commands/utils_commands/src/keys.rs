@@ -0,0 +1,17 @@
+use rus_key_command_lib::glob_match;
+use rus_key_db::db::Db;
+
+pub fn keys(pattern: Option<&str>, db: &mut Db) -> Result<String, &'static str> {
+    let pattern = match pattern {
+        Some(pattern) => pattern,
+        None => return Err("ERR wrong number of arguments for command"),
+    };
+
+    let mut keys: Vec<String> = db
+        .keys()
+        .into_iter()
+        .filter(|key| glob_match(pattern, key))
+        .collect();
+    keys.sort();
+    Ok(keys.join(" "))
+}
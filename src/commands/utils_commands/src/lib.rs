@@ -4,3 +4,9 @@ mod rename;
 mod randomkey;
 mod check_type;
 mod del_key;
+mod keys;
+mod scan;
+mod object;
+mod dump;
+mod sort;
+mod touch;
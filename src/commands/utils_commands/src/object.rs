@@ -0,0 +1,73 @@
+use std::str::SplitAsciiWhitespace;
+
+use rus_key_db::db::{now_millis, DataType, Db};
+
+const WRONG_ARGS: &str = "ERR wrong number of arguments for command";
+const UNKNOWN_SUBCOMMAND: &str = "ERR unknown subcommand";
+
+// collections up to this size are reported as compact, like the Redis listpack limits
+const LISTPACK_MAX_ENTRIES: usize = 128;
+const LISTPACK_MAX_VALUE: usize = 64;
+// longest string stored in the same allocation as its object in Redis
+const EMBSTR_MAX_LEN: usize = 44;
+
+fn is_listpack<'a, I: ExactSizeIterator<Item = (&'a String, &'a String)>>(mut entries: I) -> bool {
+    entries.len() <= LISTPACK_MAX_ENTRIES
+        && entries.all(|(field, value)| {
+            field.len() <= LISTPACK_MAX_VALUE && value.len() <= LISTPACK_MAX_VALUE
+        })
+}
+
+// the Redis name of the internal representation, module types have none
+fn encoding(value: &DataType) -> &'static str {
+    match value {
+        DataType::String(value) if value.parse::<i64>().is_ok() => "int",
+        DataType::String(value) if value.len() <= EMBSTR_MAX_LEN => "embstr",
+        DataType::String(_) => "raw",
+        DataType::List(list)
+            if list.len() <= LISTPACK_MAX_ENTRIES
+                && list.iter().all(|value| value.len() <= LISTPACK_MAX_VALUE) =>
+        {
+            "listpack"
+        }
+        DataType::List(_) => "quicklist",
+        DataType::HashMap(map) if is_listpack(map.iter()) => "listpack",
+        DataType::HashMap(_) => "hashtable",
+        DataType::ZSet(map) if is_listpack(map.iter()) => "listpack",
+        DataType::ZSet(_) => "skiplist",
+        _ => "raw",
+    }
+}
+
+/// `OBJECT ENCODING | IDLETIME | FREQ | REFCOUNT key`
+///
+/// Looking a key up with OBJECT does not count as an access. The idle time is in seconds,
+/// both it and the LFU counter are tracked whatever `maxmemory-policy` is. A missing key
+/// replies `nil`.
+pub fn object(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let subcommand = parts.next().ok_or(WRONG_ARGS)?.to_lowercase();
+    if !matches!(
+        subcommand.as_str(),
+        "encoding" | "idletime" | "freq" | "refcount"
+    ) {
+        return Err(UNKNOWN_SUBCOMMAND);
+    }
+    let key = parts.next().ok_or(WRONG_ARGS)?;
+    if parts.next().is_some() {
+        return Err(WRONG_ARGS);
+    }
+
+    let entry = match db.peek(key) {
+        Some(entry) => entry,
+        None => return Ok("nil".to_string()),
+    };
+    let now = now_millis();
+    let result = match subcommand.as_str() {
+        "encoding" => encoding(entry.value()).to_string(),
+        "idletime" => (entry.idle_time(now) / 1000).to_string(),
+        "freq" => entry.frequency(now).to_string(),
+        // values are never shared between keys
+        _ => "1".to_string(),
+    };
+    Ok(result)
+}
@@ -1,7 +1,5 @@
-use std::collections::HashMap;
-use rus_key_db::db::{DataType, Db};
-
-const EXPIRED: &str = "expired";
+use rus_key_db::db::Db;
+use rus_key_db::notify::NOTIFY_GENERIC;
 
 pub fn rename(
     old_name: Option<&str>,
@@ -27,22 +25,7 @@ pub fn rename(
         return Err("New name is exists");
     }
 
-    let mut expired_map = match db.get(EXPIRED) {
-        Some(DataType::HashMap(expired_map)) => expired_map.clone(),
-        None => HashMap::new(),
-        _ => HashMap::new(),
-    };
-
-    // if expired_map contains old_name, insert new_name
-    if expired_map.contains_key(old_name) {
-        let old_expired = match expired_map.get(old_name) {
-            Some(old_expired) => old_expired,
-            None => return Err("No such key"),
-        };
-
-        expired_map.insert(new_name.to_string(), old_expired.to_string());
-        db.set(EXPIRED.to_string(), DataType::HashMap(expired_map));
-    }
+    let old_expired = db.expire_time(old_name);
 
     let value = match db.get(old_name) {
         Some(value) => value.clone(),
@@ -50,10 +33,17 @@ pub fn rename(
     };
 
     db.set(new_name.to_string(), value);
+    // new_name takes the expiration of old_name, or none
+    match old_expired {
+        Some(old_expired) => db.expire_at(new_name, old_expired),
+        None => db.persist(new_name),
+    };
 
     if !db.delete(old_name) {
         return Err("Failed to delete old key");
     }
+    db.notify(NOTIFY_GENERIC, "rename_from", old_name);
+    db.notify(NOTIFY_GENERIC, "rename_to", new_name);
 
     Ok("1".to_string())
 }
@@ -0,0 +1,136 @@
+use std::str::SplitAsciiWhitespace;
+
+use rus_key_command_lib::glob_match;
+use rus_key_db::db::{DataType, Db};
+
+use crate::check_type::check_type;
+
+const DEFAULT_COUNT: usize = 10;
+
+const WRONG_ARGS: &str = "ERR wrong number of arguments for command";
+const SYNTAX_ERROR: &str = "ERR syntax error";
+const INVALID_CURSOR: &str = "ERR invalid cursor";
+const NOT_INTEGER: &str = "ERR value is not an integer or out of range";
+const WRONG_TYPE: &str = "WRONGTYPE Operation against a key holding the wrong kind of value";
+
+struct ScanOptions {
+    cursor: u64,
+    pattern: Option<String>,
+    count: usize,
+    key_type: Option<String>,
+    no_values: bool,
+}
+
+// Parses `cursor [MATCH pattern] [COUNT count]` plus the options specific to `command`.
+fn parse_options(
+    command: &str,
+    parts: &mut SplitAsciiWhitespace,
+) -> Result<ScanOptions, &'static str> {
+    let cursor = match parts.next() {
+        Some(cursor) => cursor.parse::<u64>().map_err(|_| INVALID_CURSOR)?,
+        None => return Err(WRONG_ARGS),
+    };
+    let mut options = ScanOptions {
+        cursor,
+        pattern: None,
+        count: DEFAULT_COUNT,
+        key_type: None,
+        no_values: false,
+    };
+    while let Some(option) = parts.next() {
+        match option.to_lowercase().as_str() {
+            "match" => options.pattern = Some(parts.next().ok_or(SYNTAX_ERROR)?.to_string()),
+            "count" => {
+                let count = parts.next().ok_or(SYNTAX_ERROR)?;
+                options.count = match count.parse::<usize>() {
+                    Ok(count) if count > 0 => count,
+                    Ok(_) => return Err(SYNTAX_ERROR),
+                    Err(_) => return Err(NOT_INTEGER),
+                };
+            }
+            "type" if command == "scan" => {
+                options.key_type = Some(parts.next().ok_or(SYNTAX_ERROR)?.to_lowercase())
+            }
+            "novalues" if command == "hscan" => options.no_values = true,
+            _ => return Err(SYNTAX_ERROR),
+        }
+    }
+    Ok(options)
+}
+
+fn matches(pattern: &Option<String>, item: &str) -> bool {
+    pattern
+        .as_ref()
+        .is_none_or(|pattern| glob_match(pattern, item))
+}
+
+/// `SCAN cursor [MATCH pattern] [COUNT count] [TYPE type]`
+///
+/// MATCH and TYPE filter the page after it is taken, so a page may be empty while the
+/// cursor is not 0. The reply is `cursor: [key ...]`.
+pub fn scan(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let options = parse_options("scan", parts)?;
+    let (cursor, page) = db.scan(options.cursor, options.count);
+
+    let mut result = Vec::new();
+    for key in page {
+        if !matches(&options.pattern, &key) {
+            continue;
+        }
+        if let Some(key_type) = &options.key_type {
+            if check_type(Some(&key), db).to_lowercase() != *key_type {
+                continue;
+            }
+        }
+        result.push(key);
+    }
+    Ok(format!("{}: [{}]", cursor, result.join(" ")))
+}
+
+/// `HSCAN key cursor [MATCH pattern] [COUNT count] [NOVALUES]` and
+/// `ZSCAN key cursor [MATCH pattern] [COUNT count]`
+///
+/// Both iterate the field-value pairs of a hash or the member-score pairs of a geo set,
+/// replying `cursor: [field: value ...]`.
+pub fn scan_map(
+    command: &str,
+    parts: &mut SplitAsciiWhitespace,
+    db: &mut Db,
+) -> Result<String, &'static str> {
+    let key = parts.next().ok_or(WRONG_ARGS)?;
+    let options = parse_options(command, parts)?;
+    match db.get(key) {
+        Some(DataType::ZSet(_)) | Some(DataType::HashMap(_)) => {}
+        Some(_) => return Err(WRONG_TYPE),
+        None => return Ok("0: []".to_string()),
+    }
+    let (cursor, page) = db.scan_fields(key, options.cursor, options.count);
+    let result: Vec<String> = page
+        .into_iter()
+        .filter(|field| matches(&options.pattern, field))
+        .map(|field| {
+            if options.no_values {
+                return field;
+            }
+            let value = match db.peek(key).map(|entry| entry.value()) {
+                Some(DataType::ZSet(map)) => map.get(&field),
+                Some(DataType::HashMap(map)) => map.get(&field),
+                _ => None,
+            };
+            format!("{}: {}", field, value.map_or("nil", |value| value))
+        })
+        .collect();
+    Ok(format!("{}: [{}]", cursor, result.join(" ")))
+}
+
+/// `SSCAN key cursor [MATCH pattern] [COUNT count]`
+///
+/// There is no set type yet, so a missing key is an empty set and any other key has the wrong type.
+pub fn sscan(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let key = parts.next().ok_or(WRONG_ARGS)?;
+    parse_options("sscan", parts)?;
+    if db.get(key).is_none() {
+        return Ok("0: []".to_string());
+    }
+    Err(WRONG_TYPE)
+}
@@ -0,0 +1,203 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::str::SplitAsciiWhitespace;
+
+use rus_key_db::db::{DataType, Db};
+use rus_key_db::notify::{NOTIFY_GENERIC, NOTIFY_LIST};
+
+const SYNTAX_ERROR: &str = "ERR syntax error";
+const WRONG_ARGS: &str = "ERR wrong number of arguments for command";
+const NOT_INTEGER: &str = "ERR value is not an integer or out of range";
+const NOT_DOUBLE: &str = "ERR One or more scores can't be converted into double";
+const WRONG_TYPE: &str = "WRONGTYPE Operation against a key holding the wrong kind of value";
+
+struct SortOptions<'a> {
+    by: Option<&'a str>,
+    offset: usize,
+    count: Option<usize>,
+    get: Vec<&'a str>,
+    desc: bool,
+    alpha: bool,
+    store: Option<&'a str>,
+}
+
+fn parse_options<'a>(
+    read_only: bool,
+    parts: &mut SplitAsciiWhitespace<'a>,
+) -> Result<SortOptions<'a>, &'static str> {
+    let mut options = SortOptions {
+        by: None,
+        offset: 0,
+        count: None,
+        get: Vec::new(),
+        desc: false,
+        alpha: false,
+        store: None,
+    };
+    while let Some(option) = parts.next() {
+        match option.to_lowercase().as_str() {
+            "asc" => options.desc = false,
+            "desc" => options.desc = true,
+            "alpha" => options.alpha = true,
+            "by" => options.by = Some(parts.next().ok_or(SYNTAX_ERROR)?),
+            "get" => options.get.push(parts.next().ok_or(SYNTAX_ERROR)?),
+            "limit" => {
+                let offset = parts.next().ok_or(SYNTAX_ERROR)?;
+                let count = parts.next().ok_or(SYNTAX_ERROR)?;
+                let offset = offset.parse::<i64>().map_err(|_| NOT_INTEGER)?;
+                let count = count.parse::<i64>().map_err(|_| NOT_INTEGER)?;
+                options.offset = offset.max(0) as usize;
+                // a negative count returns every element after the offset
+                options.count = (count >= 0).then_some(count as usize);
+            }
+            "store" if !read_only => options.store = Some(parts.next().ok_or(SYNTAX_ERROR)?),
+            _ => return Err(SYNTAX_ERROR),
+        }
+    }
+    Ok(options)
+}
+
+// Replaces the first `*` of `pattern` with `element` and reads that string key, or the hash
+// field after `->`. `#` is the element itself, a pattern without `*` matches nothing.
+fn lookup(db: &Db, pattern: &str, element: &str) -> Option<String> {
+    if pattern == "#" {
+        return Some(element.to_string());
+    }
+    let star = pattern.find('*')?;
+    let (key_pattern, field) = match pattern[star..].find("->") {
+        Some(arrow) if star + arrow + 2 < pattern.len() => {
+            (&pattern[..star + arrow], Some(&pattern[star + arrow + 2..]))
+        }
+        _ => (pattern, None),
+    };
+    let key = key_pattern.replacen('*', element, 1);
+    match (db.get(&key)?, field) {
+        (DataType::String(value), None) => Some(value.clone()),
+        (DataType::HashMap(map), Some(field)) => map.get(field).cloned(),
+        (DataType::ZSet(map), Some(field)) => map.get(field).cloned(),
+        _ => None,
+    }
+}
+
+// the members of a sorted set by score, then by member
+fn by_score(set: &BTreeMap<String, String>) -> Vec<String> {
+    let mut members: Vec<(&String, f64)> = set
+        .iter()
+        .map(|(member, score)| (member, score.parse().unwrap_or(0.0)))
+        .collect();
+    members.sort_by(|(a, a_score), (b, b_score)| {
+        a_score
+            .partial_cmp(b_score)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| a.cmp(b))
+    });
+    members
+        .into_iter()
+        .map(|(member, _)| member.clone())
+        .collect()
+}
+
+/// `SORT key [BY pattern] [LIMIT offset count] [GET pattern ...] [ASC|DESC] [ALPHA] [STORE destination]`
+///
+/// Sorts the elements of a list or the members of a sorted set, like the geo sets, numerically
+/// unless ALPHA is given. BY sorts by the string keys or hash fields (`key_*->field`) named
+/// after each element, and a BY pattern without `*` skips sorting, leaving the members of a
+/// sorted set in score order. GET replies the values looked up
+/// the same way instead of the elements, `#` being the element itself.
+///
+/// `SORT_RO` is the same command without STORE.
+///
+/// # Returns
+///
+/// * The sorted elements, `nil` for a GET lookup that does not exist, or with STORE the
+///   number of elements stored as a list in `destination`.
+pub fn sort(
+    read_only: bool,
+    parts: &mut SplitAsciiWhitespace,
+    db: &mut Db,
+) -> Result<String, &'static str> {
+    let key = parts.next().ok_or(WRONG_ARGS)?;
+    let options = parse_options(read_only, parts)?;
+    let elements: Vec<String> = match db.get(key) {
+        Some(DataType::List(list)) => list.clone(),
+        Some(DataType::ZSet(set)) => by_score(set),
+        Some(_) => return Err(WRONG_TYPE),
+        None => Vec::new(),
+    };
+
+    let sorted = options.by.is_none_or(|by| by.contains('*'));
+    let mut elements = if sorted {
+        let mut weighted = Vec::with_capacity(elements.len());
+        for element in elements {
+            let weight = match options.by {
+                Some(by) => lookup(db, by, &element),
+                None => Some(element.clone()),
+            };
+            let score = match &weight {
+                _ if options.alpha => 0.0,
+                // a missing BY key weighs 0
+                None => 0.0,
+                Some(weight) => weight.parse::<f64>().map_err(|_| NOT_DOUBLE)?,
+            };
+            weighted.push((element, weight, score));
+        }
+        weighted.sort_by(|(a, a_weight, a_score), (b, b_weight, b_score)| {
+            let ordering = if options.alpha {
+                a_weight.cmp(b_weight)
+            } else {
+                a_score.partial_cmp(b_score).unwrap_or(Ordering::Equal)
+            }
+            .then_with(|| a.cmp(b));
+            if options.desc {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+        weighted
+            .into_iter()
+            .map(|(element, _, _)| element)
+            .collect()
+    } else {
+        elements
+    };
+
+    let end = match options.count {
+        Some(count) => options.offset.saturating_add(count).min(elements.len()),
+        None => elements.len(),
+    };
+    let elements = elements.drain(options.offset.min(end)..end);
+    let result: Vec<Option<String>> = if options.get.is_empty() {
+        elements.map(Some).collect()
+    } else {
+        elements
+            .flat_map(|element| {
+                options
+                    .get
+                    .iter()
+                    .map(|pattern| lookup(db, pattern, &element))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    };
+
+    match options.store {
+        Some(destination) => {
+            let len = result.len();
+            let deleted = db.delete(destination);
+            if len > 0 {
+                let list = result.into_iter().map(Option::unwrap_or_default).collect();
+                db.set(destination.to_string(), DataType::List(list));
+                db.notify(NOTIFY_LIST, "sortstore", destination);
+            } else if deleted {
+                db.notify(NOTIFY_GENERIC, "del", destination);
+            }
+            Ok(len.to_string())
+        }
+        None => Ok(result
+            .into_iter()
+            .map(|value| value.unwrap_or_else(|| "nil".to_string()))
+            .collect::<Vec<_>>()
+            .join(" ")),
+    }
+}
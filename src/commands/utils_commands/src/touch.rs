@@ -0,0 +1,17 @@
+use std::str::SplitAsciiWhitespace;
+
+use rus_key_db::db::Db;
+
+/// `TOUCH key [key ...]`
+///
+/// Updates the access time and the LFU counter of the keys without reading them.
+///
+/// # Returns
+///
+/// * The number of keys that exist.
+pub fn touch(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> String {
+    parts
+        .filter(|key| db.get(key).is_some())
+        .count()
+        .to_string()
+}
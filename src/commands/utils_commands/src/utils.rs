@@ -1,13 +1,19 @@
 use std::str::SplitAsciiWhitespace;
 
-use rus_key_trait::command_trait::Command;
+use rus_key_trait::command_trait::{Arity, Command};
 use rus_key_db::db::Db;
 
 use crate::check_expired::check_expired;
 use crate::rename::rename;
 use crate::randomkey::randomkey;
-use crate::del_key::del_key;
+use crate::del_key::{del_key, unlink};
 use crate::check_type::check_type;
+use crate::keys::keys;
+use crate::scan::{scan, scan_map, sscan};
+use crate::object::object;
+use crate::dump::{dump, restore};
+use crate::sort::sort;
+use crate::touch::touch;
 
 pub struct UtilsCommand {
     command: String,
@@ -41,8 +47,28 @@ impl Command for UtilsCommand {
             }
             "randomkey" => Ok(randomkey(db)),
             "del" => Ok(del_key(parts, db)),
+            "unlink" => Ok(unlink(parts, db)),
+            "touch" => Ok(touch(parts, db)),
             "type" => Ok(check_type(parts.next(), db)),
+            "keys" => keys(parts.next(), db),
+            "scan" => scan(parts, db),
+            "hscan" | "zscan" => scan_map(self.command.as_str(), parts, db),
+            "sscan" => sscan(parts, db),
+            "object" => object(parts, db),
+            "dump" => dump(parts, db),
+            "restore" => restore(parts, db),
+            "sort" => sort(false, parts, db),
+            "sort_ro" => sort(true, parts, db),
             _ => Err("UtilsCommand Error: Command not found"),
         }
     }
+
+    fn arity(&self) -> Arity {
+        match self.command.as_str() {
+            "randomkey" => Arity::new(0),
+            "rename" | "renamenx" | "hscan" | "zscan" | "sscan" => Arity::new(2),
+            "restore" => Arity::with_integers(3, &[1]),
+            _ => Arity::new(1),
+        }
+    }
 }
@@ -0,0 +1,26 @@
+pub const WRONG_TYPE: &str =
+    "There is no such key, the key is expired, or the data type is incorrect";
+
+pub const WRONG_ARGS: &str = "ERR wrong number of arguments for command";
+
+pub const SYNTAX_ERROR: &str = "ERR syntax error";
+
+pub const INVALID_VECTOR: &str = "ERR invalid vector specification";
+
+pub const INVALID_FILTER: &str = "ERR syntax error in FILTER expression";
+
+pub const INVALID_ATTRIBUTES: &str = "ERR invalid JSON in attributes";
+
+pub const DIMENSION_MISMATCH: &str = "ERR Vector dimension mismatch";
+
+pub const QUANTIZATION_MISMATCH: &str = "ERR asked quantization mismatch with existing vector set";
+
+pub const ELEMENT_NOT_FOUND: &str = "ERR element not found in set";
+
+// HNSW defaults
+pub const DEFAULT_M: usize = 16;
+pub const DEFAULT_EF_CONSTRUCTION: usize = 200;
+pub const DEFAULT_COUNT: usize = 10;
+pub const DEFAULT_EF: usize = 100;
+// how many candidates a filtered search explores for each requested result
+pub const FILTER_EF_FACTOR: usize = 100;
@@ -0,0 +1,356 @@
+use crate::r#const::INVALID_FILTER;
+use serde_json::Value;
+
+/// A value produced while evaluating a filter.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operand {
+    Number(f64),
+    Str(String),
+    Bool(bool),
+    Null,
+    List(Vec<Operand>),
+}
+
+impl Operand {
+    fn from_json(value: &Value) -> Operand {
+        match value {
+            Value::Number(number) => Operand::Number(number.as_f64().unwrap_or(0.0)),
+            Value::String(string) => Operand::Str(string.clone()),
+            Value::Bool(flag) => Operand::Bool(*flag),
+            Value::Array(array) => Operand::List(array.iter().map(Operand::from_json).collect()),
+            Value::Null | Value::Object(_) => Operand::Null,
+        }
+    }
+
+    fn truthy(&self) -> bool {
+        match self {
+            Operand::Number(number) => *number != 0.0,
+            Operand::Str(string) => !string.is_empty(),
+            Operand::Bool(flag) => *flag,
+            Operand::Null => false,
+            Operand::List(list) => !list.is_empty(),
+        }
+    }
+
+    fn number(&self) -> Option<f64> {
+        match self {
+            Operand::Number(number) => Some(*number),
+            Operand::Bool(flag) => Some(if *flag { 1.0 } else { 0.0 }),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Str(String),
+    Selector(Vec<String>),
+    Word(String),
+    Op(&'static str),
+}
+
+const OPERATORS: [&str; 19] = [
+    "==", "!=", ">=", "<=", "&&", "||", ">", "<", "!", "+", "-", "*", "/", "%", "(", ")", "[", "]",
+    ",",
+];
+
+fn tokenize(expression: &str) -> Result<Vec<Token>, &'static str> {
+    let chars: Vec<char> = expression.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '"' || c == '\'' {
+            let start = i + 1;
+            i = start;
+            while i < chars.len() && chars[i] != c {
+                i += 1;
+            }
+            if i >= chars.len() {
+                return Err(INVALID_FILTER);
+            }
+            tokens.push(Token::Str(chars[start..i].iter().collect()));
+            i += 1;
+        } else if c.is_ascii_digit()
+            || (c == '.' && chars.get(i + 1).is_some_and(|n| n.is_ascii_digit()))
+        {
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_ascii_digit() || chars[i] == '.' || chars[i] == 'e')
+            {
+                i += 1;
+            }
+            let number: String = chars[start..i].iter().collect();
+            tokens.push(Token::Number(number.parse().map_err(|_| INVALID_FILTER)?));
+        } else if c == '.' {
+            let mut path = Vec::new();
+            while i < chars.len() && chars[i] == '.' {
+                let start = i + 1;
+                i = start;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                if i == start {
+                    return Err(INVALID_FILTER);
+                }
+                path.push(chars[start..i].iter().collect());
+            }
+            tokens.push(Token::Selector(path));
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            tokens.push(Token::Word(word.to_lowercase()));
+        } else {
+            let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+            let op = OPERATORS
+                .iter()
+                .find(|op| rest.starts_with(*op))
+                .ok_or(INVALID_FILTER)?;
+            tokens.push(Token::Op(op));
+            i += op.len();
+        }
+    }
+    Ok(tokens)
+}
+
+/// A parsed filter expression.
+#[derive(Debug, Clone)]
+pub enum Expr {
+    Literal(Operand),
+    Selector(Vec<String>),
+    List(Vec<Expr>),
+    Not(Box<Expr>),
+    Negate(Box<Expr>),
+    Binary(&'static str, Box<Expr>, Box<Expr>),
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn accept(&mut self, names: &[&'static str]) -> Option<&'static str> {
+        let found = match self.peek() {
+            Some(Token::Op(op)) => names.iter().find(|name| *name == op),
+            Some(Token::Word(word)) => names.iter().find(|name| *name == word),
+            _ => None,
+        };
+        let found = found.copied();
+        if found.is_some() {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn or(&mut self) -> Result<Expr, &'static str> {
+        let mut left = self.and()?;
+        while self.accept(&["or", "||"]).is_some() {
+            left = Expr::Binary("or", Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr, &'static str> {
+        let mut left = self.not()?;
+        while self.accept(&["and", "&&"]).is_some() {
+            left = Expr::Binary("and", Box::new(left), Box::new(self.not()?));
+        }
+        Ok(left)
+    }
+
+    fn not(&mut self) -> Result<Expr, &'static str> {
+        if self.accept(&["not", "!"]).is_some() {
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr, &'static str> {
+        let left = self.additive()?;
+        match self.accept(&["==", "!=", ">=", "<=", ">", "<", "in"]) {
+            Some(op) => Ok(Expr::Binary(op, Box::new(left), Box::new(self.additive()?))),
+            None => Ok(left),
+        }
+    }
+
+    fn additive(&mut self) -> Result<Expr, &'static str> {
+        let mut left = self.multiplicative()?;
+        while let Some(op) = self.accept(&["+", "-"]) {
+            left = Expr::Binary(op, Box::new(left), Box::new(self.multiplicative()?));
+        }
+        Ok(left)
+    }
+
+    fn multiplicative(&mut self) -> Result<Expr, &'static str> {
+        let mut left = self.unary()?;
+        while let Some(op) = self.accept(&["*", "/", "%"]) {
+            left = Expr::Binary(op, Box::new(left), Box::new(self.unary()?));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr, &'static str> {
+        if self.accept(&["-"]).is_some() {
+            return Ok(Expr::Negate(Box::new(self.unary()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr, &'static str> {
+        match self.next() {
+            Some(Token::Number(number)) => Ok(Expr::Literal(Operand::Number(number))),
+            Some(Token::Str(string)) => Ok(Expr::Literal(Operand::Str(string))),
+            Some(Token::Selector(path)) => Ok(Expr::Selector(path)),
+            Some(Token::Word(word)) => match word.as_str() {
+                "true" => Ok(Expr::Literal(Operand::Bool(true))),
+                "false" => Ok(Expr::Literal(Operand::Bool(false))),
+                "null" => Ok(Expr::Literal(Operand::Null)),
+                _ => Err(INVALID_FILTER),
+            },
+            Some(Token::Op("(")) => {
+                let expr = self.or()?;
+                match self.next() {
+                    Some(Token::Op(")")) => Ok(expr),
+                    _ => Err(INVALID_FILTER),
+                }
+            }
+            Some(Token::Op("[")) => {
+                let mut items = Vec::new();
+                if self.accept(&["]"]).is_some() {
+                    return Ok(Expr::List(items));
+                }
+                loop {
+                    items.push(self.or()?);
+                    match self.next() {
+                        Some(Token::Op(",")) => continue,
+                        Some(Token::Op("]")) => return Ok(Expr::List(items)),
+                        _ => return Err(INVALID_FILTER),
+                    }
+                }
+            }
+            _ => Err(INVALID_FILTER),
+        }
+    }
+}
+
+/// Parses a filter expression.
+///
+/// Selectors like `.year` read a field of the element attributes. The supported syntax is
+/// numbers, strings, `true`, `false`, `null`, lists (`[1, 2]`), arithmetic (`+ - * / %`),
+/// comparisons (`== != > >= < <=`), `in` (list membership or substring) and the logical
+/// operators `and`/`&&`, `or`/`||` and `not`/`!`.
+///
+/// # Returns
+///
+/// * The parsed expression, or an error message if it cannot be parsed.
+pub fn parse(expression: &str) -> Result<Expr, &'static str> {
+    let mut parser = Parser {
+        tokens: tokenize(expression)?,
+        position: 0,
+    };
+    let expr = parser.or()?;
+    if parser.position != parser.tokens.len() {
+        return Err(INVALID_FILTER);
+    }
+    Ok(expr)
+}
+
+fn compare(op: &str, left: &Operand, right: &Operand) -> bool {
+    let ordering = match (left, right) {
+        (Operand::Str(a), Operand::Str(b)) => Some(a.cmp(b)),
+        _ => match (left.number(), right.number()) {
+            (Some(a), Some(b)) => a.partial_cmp(&b),
+            _ => None,
+        },
+    };
+    match op {
+        "==" => ordering.map_or(left == right, |o| o.is_eq()),
+        "!=" => ordering.map_or(left != right, |o| o.is_ne()),
+        ">" => ordering.is_some_and(|o| o.is_gt()),
+        ">=" => ordering.is_some_and(|o| o.is_ge()),
+        "<" => ordering.is_some_and(|o| o.is_lt()),
+        "<=" => ordering.is_some_and(|o| o.is_le()),
+        _ => false,
+    }
+}
+
+/// Evaluates an expression against attributes.
+///
+/// # Returns
+///
+/// * The value, or `None` when a selected field does not exist.
+fn evaluate(expr: &Expr, attributes: &Value) -> Option<Operand> {
+    match expr {
+        Expr::Literal(operand) => Some(operand.clone()),
+        Expr::Selector(path) => path
+            .iter()
+            .try_fold(attributes, |value, key| value.get(key.as_str()))
+            .map(Operand::from_json),
+        Expr::List(items) => items
+            .iter()
+            .map(|item| evaluate(item, attributes))
+            .collect::<Option<Vec<Operand>>>()
+            .map(Operand::List),
+        Expr::Not(inner) => Some(Operand::Bool(!evaluate(inner, attributes)?.truthy())),
+        Expr::Negate(inner) => Some(Operand::Number(-evaluate(inner, attributes)?.number()?)),
+        Expr::Binary("and", left, right) => Some(Operand::Bool(
+            evaluate(left, attributes)?.truthy() && evaluate(right, attributes)?.truthy(),
+        )),
+        Expr::Binary("or", left, right) => {
+            let left = evaluate(left, attributes).is_some_and(|value| value.truthy());
+            let right = || evaluate(right, attributes).is_some_and(|value| value.truthy());
+            Some(Operand::Bool(left || right()))
+        }
+        Expr::Binary(op, left, right) => {
+            let (left, right) = (evaluate(left, attributes)?, evaluate(right, attributes)?);
+            match *op {
+                "in" => Some(Operand::Bool(match (&left, &right) {
+                    (Operand::Str(needle), Operand::Str(haystack)) => {
+                        haystack.contains(needle.as_str())
+                    }
+                    (_, Operand::List(items)) => {
+                        items.iter().any(|item| compare("==", &left, item))
+                    }
+                    _ => false,
+                })),
+                "+" | "-" | "*" | "/" | "%" => {
+                    let (a, b) = (left.number()?, right.number()?);
+                    Some(Operand::Number(match *op {
+                        "+" => a + b,
+                        "-" => a - b,
+                        "*" => a * b,
+                        "/" => a / b,
+                        _ => a % b,
+                    }))
+                }
+                _ => Some(Operand::Bool(compare(op, &left, &right))),
+            }
+        }
+    }
+}
+
+/// Checks whether an element with the given attributes passes the filter.
+///
+/// Elements without attributes, or missing a selected field, never match.
+pub fn matches(expr: &Expr, attributes: Option<&Value>) -> bool {
+    attributes
+        .and_then(|attributes| evaluate(expr, attributes))
+        .is_some_and(|value| value.truthy())
+}
@@ -0,0 +1,10 @@
+mod r#const;
+mod filter;
+mod utils;
+mod v_add;
+mod v_attr;
+mod v_emb;
+mod v_info;
+mod v_rem;
+mod v_sim;
+pub mod vector;
@@ -0,0 +1,75 @@
+use crate::r#const::{INVALID_ATTRIBUTES, INVALID_VECTOR, WRONG_TYPE};
+use rus_key_db::db::{DataType, Db};
+use rus_key_db::vector::VectorSet;
+use serde_json::Value;
+
+/// Returns the vector set held at `key`, removing it first if it is expired.
+pub fn get_vector_set<'a>(
+    key: &str,
+    db: &'a mut Db,
+) -> Result<Option<&'a mut VectorSet>, &'static str> {
+    match db.get_mut(key) {
+        Some(DataType::VectorSet(set)) => Ok(Some(set)),
+        Some(_) => Err(WRONG_TYPE),
+        None => Ok(None),
+    }
+}
+
+/// Parses `num value ...` following a `VALUES` keyword.
+///
+/// # Arguments
+///
+/// * `args` - The remaining arguments, starting with the number of values.
+///
+/// # Returns
+///
+/// * The vector and the number of arguments it used.
+pub fn parse_values(args: &[&str]) -> Result<(Vec<f32>, usize), &'static str> {
+    let count = args
+        .first()
+        .and_then(|count| count.parse::<usize>().ok())
+        .filter(|count| *count > 0)
+        .ok_or(INVALID_VECTOR)?;
+    if args.len() < count + 1 {
+        return Err(INVALID_VECTOR);
+    }
+    let vector = args[1..=count]
+        .iter()
+        .map(|value| value.parse::<f32>().ok().filter(|value| value.is_finite()))
+        .collect::<Option<Vec<f32>>>()
+        .ok_or(INVALID_VECTOR)?;
+    Ok((vector, count + 1))
+}
+
+/// Parses a JSON object spread over whitespace separated arguments.
+///
+/// The shortest run of arguments forming a valid JSON value is used, so the object can
+/// be followed by other options. An empty string removes the attributes.
+///
+/// # Returns
+///
+/// * The attributes and the number of arguments they used.
+pub fn parse_attributes(args: &[&str]) -> Result<(Option<Value>, usize), &'static str> {
+    match args.first() {
+        Some(&"\"\"") | Some(&"''") => return Ok((None, 1)),
+        None => return Err(INVALID_ATTRIBUTES),
+        _ => {}
+    }
+    for end in 1..=args.len() {
+        if let Ok(value) = serde_json::from_str::<Value>(&args[..end].join(" ")) {
+            if !value.is_object() {
+                return Err(INVALID_ATTRIBUTES);
+            }
+            return Ok((Some(value), end));
+        }
+    }
+    Err(INVALID_ATTRIBUTES)
+}
+
+/// Formats a float the way vector components are shown.
+pub fn format_float(value: f32) -> String {
+    format!("{:.6}", value)
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
@@ -0,0 +1,93 @@
+use crate::r#const::{
+    DEFAULT_EF_CONSTRUCTION, DEFAULT_M, DIMENSION_MISMATCH, INVALID_VECTOR, QUANTIZATION_MISMATCH,
+    SYNTAX_ERROR, WRONG_ARGS,
+};
+use crate::utils::{get_vector_set, parse_attributes, parse_values};
+use rus_key_db::db::{DataType, Db};
+use rus_key_db::notify::NOTIFY_MODULE;
+use rus_key_db::vector::{Quantization, VectorSet};
+use std::str::SplitAsciiWhitespace;
+
+/// Adds an element to a vector set, creating the set if needed.
+///
+/// Syntax: `VADD key VALUES num value [value ...] element [NOQUANT | Q8] [EF build-ef]
+/// [M numlinks] [SETATTR attributes]`
+///
+/// The first element fixes the dimension of the set. `Q8` stores vectors as signed bytes,
+/// `NOQUANT` (the default) as floats; the choice is fixed when the set is created, like
+/// `M` and `EF`. `SETATTR` takes a JSON object used by `VSIM ... FILTER`.
+///
+/// # Returns
+///
+/// * `1` if the element was added, `0` if its vector was updated.
+pub fn v_add(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let key = parts.next().ok_or(WRONG_ARGS)?;
+    let args: Vec<&str> = parts.collect();
+    match args.first() {
+        Some(arg) if arg.eq_ignore_ascii_case("values") => {}
+        Some(_) => return Err(SYNTAX_ERROR),
+        None => return Err(WRONG_ARGS),
+    }
+    let (vector, used) = parse_values(&args[1..])?;
+    let element = *args.get(used + 1).ok_or(WRONG_ARGS)?;
+
+    let mut quantization = None;
+    let mut m = DEFAULT_M;
+    let mut ef = DEFAULT_EF_CONSTRUCTION;
+    let mut attributes = None;
+    let mut i = used + 2;
+    while i < args.len() {
+        match args[i].to_lowercase().as_str() {
+            "noquant" => quantization = Some(Quantization::NoQuant),
+            "q8" => quantization = Some(Quantization::Q8),
+            "ef" | "m" => {
+                let value = args
+                    .get(i + 1)
+                    .and_then(|value| value.parse::<usize>().ok())
+                    .filter(|value| *value > 0)
+                    .ok_or(SYNTAX_ERROR)?;
+                if args[i].eq_ignore_ascii_case("ef") {
+                    ef = value;
+                } else {
+                    m = value;
+                }
+                i += 1;
+            }
+            "setattr" => {
+                let (parsed, used) = parse_attributes(&args[i + 1..])?;
+                attributes = Some(parsed);
+                i += used;
+            }
+            _ => return Err(SYNTAX_ERROR),
+        }
+        i += 1;
+    }
+
+    let set = match get_vector_set(key, db)? {
+        Some(set) => set,
+        None => {
+            db.set(
+                key.to_string(),
+                DataType::VectorSet(VectorSet::new(
+                    vector.len(),
+                    quantization.unwrap_or(Quantization::NoQuant),
+                    m,
+                    ef,
+                )),
+            );
+            get_vector_set(key, db)?.ok_or(INVALID_VECTOR)?
+        }
+    };
+    if quantization.is_some_and(|quantization| quantization != set.quantization()) {
+        return Err(QUANTIZATION_MISMATCH);
+    }
+    if vector.len() != set.dim() {
+        return Err(DIMENSION_MISMATCH);
+    }
+    let added = set.add(element, &vector, None)?;
+    if let Some(attributes) = attributes {
+        set.set_attributes(element, attributes);
+    }
+    db.notify(NOTIFY_MODULE, "vadd", key);
+    Ok(if added { "1" } else { "0" }.to_string())
+}
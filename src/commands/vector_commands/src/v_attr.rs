@@ -0,0 +1,52 @@
+use crate::r#const::{SYNTAX_ERROR, WRONG_ARGS};
+use crate::utils::{get_vector_set, parse_attributes};
+use rus_key_db::db::Db;
+use rus_key_db::notify::NOTIFY_MODULE;
+use std::str::SplitAsciiWhitespace;
+
+/// Replaces the attributes of an element.
+///
+/// Syntax: `VSETATTR key element attributes`
+///
+/// The attributes are a JSON object, `""` removes them.
+///
+/// # Returns
+///
+/// * `1` if the element exists, `0` otherwise.
+pub fn v_set_attr(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let (key, element) = match (parts.next(), parts.next()) {
+        (Some(key), Some(element)) => (key, element),
+        _ => return Err(WRONG_ARGS),
+    };
+    let args: Vec<&str> = parts.collect();
+    let (attributes, used) = parse_attributes(&args)?;
+    if used != args.len() {
+        return Err(SYNTAX_ERROR);
+    }
+    let updated =
+        get_vector_set(key, db)?.is_some_and(|set| set.set_attributes(element, attributes));
+    if updated {
+        db.notify(NOTIFY_MODULE, "vsetattr", key);
+    }
+    Ok(if updated { "1" } else { "0" }.to_string())
+}
+
+/// Returns the attributes of an element.
+///
+/// Syntax: `VGETATTR key element`
+///
+/// # Returns
+///
+/// * The attributes as JSON, or `nil` if the element does not exist or has none.
+pub fn v_get_attr(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let (key, element) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(key), Some(element), None) => (key, element),
+        _ => return Err(WRONG_ARGS),
+    };
+    let attributes = get_vector_set(key, db)?.and_then(|set| {
+        set.attributes(element)
+            .flatten()
+            .map(|value| value.to_string())
+    });
+    Ok(attributes.unwrap_or_else(|| "nil".to_string()))
+}
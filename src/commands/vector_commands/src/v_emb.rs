@@ -0,0 +1,29 @@
+use crate::r#const::WRONG_ARGS;
+use crate::utils::{format_float, get_vector_set};
+use rus_key_db::db::Db;
+use std::str::SplitAsciiWhitespace;
+
+/// Returns the vector of an element.
+///
+/// Syntax: `VEMB key element`
+///
+/// Sets using `Q8` return an approximation of the vector that was added.
+///
+/// # Returns
+///
+/// * The components separated by spaces, or `nil` if the element does not exist.
+pub fn v_emb(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let (key, element) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(key), Some(element), None) => (key, element),
+        _ => return Err(WRONG_ARGS),
+    };
+    let vector = get_vector_set(key, db)?.and_then(|set| set.embedding(element));
+    Ok(match vector {
+        Some(vector) => vector
+            .iter()
+            .map(|value| format_float(*value))
+            .collect::<Vec<String>>()
+            .join(" "),
+        None => "nil".to_string(),
+    })
+}
@@ -0,0 +1,66 @@
+use crate::r#const::WRONG_ARGS;
+use crate::utils::get_vector_set;
+use rus_key_db::db::Db;
+use std::str::SplitAsciiWhitespace;
+
+fn only_key<'a>(parts: &mut SplitAsciiWhitespace<'a>) -> Result<&'a str, &'static str> {
+    match (parts.next(), parts.next()) {
+        (Some(key), None) => Ok(key),
+        _ => Err(WRONG_ARGS),
+    }
+}
+
+/// Returns the number of elements in a vector set.
+///
+/// Syntax: `VCARD key`
+///
+/// # Returns
+///
+/// * The number of elements, 0 if the key does not exist.
+pub fn v_card(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let key = only_key(parts)?;
+    let count = get_vector_set(key, db)?.map_or(0, |set| set.len());
+    Ok(count.to_string())
+}
+
+/// Returns the dimension of the vectors in a vector set.
+///
+/// Syntax: `VDIM key`
+///
+/// # Returns
+///
+/// * The dimension, or `nil` if the key does not exist.
+pub fn v_dim(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let key = only_key(parts)?;
+    Ok(match get_vector_set(key, db)? {
+        Some(set) => set.dim().to_string(),
+        None => "nil".to_string(),
+    })
+}
+
+/// Returns information about the index of a vector set.
+///
+/// Syntax: `VINFO key`
+///
+/// # Returns
+///
+/// * All fields as `name: value` pairs, or `nil` if the key does not exist.
+pub fn v_info(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let key = only_key(parts)?;
+    let set = match get_vector_set(key, db)? {
+        Some(set) => set,
+        None => return Ok("nil".to_string()),
+    };
+    let fields = [
+        ("quant-type", set.quantization().name().to_string()),
+        ("vector-dim", set.dim().to_string()),
+        ("size", set.len().to_string()),
+        ("max-level", set.max_level().to_string()),
+        ("hnsw-m", set.m().to_string()),
+    ];
+    Ok(fields
+        .iter()
+        .map(|(title, value)| format!("{}: {}", title, value))
+        .collect::<Vec<String>>()
+        .join(" "))
+}
@@ -0,0 +1,34 @@
+use crate::r#const::WRONG_ARGS;
+use crate::utils::get_vector_set;
+use rus_key_db::db::Db;
+use rus_key_db::notify::NOTIFY_MODULE;
+use std::str::SplitAsciiWhitespace;
+
+/// Removes an element from a vector set.
+///
+/// Syntax: `VREM key element`
+///
+/// The set is deleted when its last element is removed.
+///
+/// # Returns
+///
+/// * `1` if the element was removed, `0` if it did not exist.
+pub fn v_rem(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let (key, element) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(key), Some(element), None) => (key, element),
+        _ => return Err(WRONG_ARGS),
+    };
+    let set = match get_vector_set(key, db)? {
+        Some(set) => set,
+        None => return Ok("0".to_string()),
+    };
+    if !set.contains(element) {
+        return Ok("0".to_string());
+    }
+    set.remove(element);
+    if set.is_empty() {
+        db.delete(key);
+    }
+    db.notify(NOTIFY_MODULE, "vrem", key);
+    Ok("1".to_string())
+}
@@ -0,0 +1,108 @@
+use crate::filter::{matches, parse};
+use crate::r#const::{
+    DEFAULT_COUNT, DEFAULT_EF, DIMENSION_MISMATCH, ELEMENT_NOT_FOUND, FILTER_EF_FACTOR,
+    SYNTAX_ERROR, WRONG_ARGS,
+};
+use crate::utils::{get_vector_set, parse_values};
+use rus_key_db::db::Db;
+use serde_json::Value;
+use std::str::SplitAsciiWhitespace;
+
+// keywords ending a FILTER expression
+const OPTIONS: [&str; 4] = ["withscores", "count", "ef", "filter-ef"];
+
+/// Finds the elements most similar to an element or a vector.
+///
+/// Syntax: `VSIM key (ELE element | VALUES num value [value ...]) [WITHSCORES] [COUNT num]
+/// [EF search-exploration-factor] [FILTER expression] [FILTER-EF max-filtering-effort]`
+///
+/// Similarity is the cosine similarity mapped to `0..1`, 1 meaning the same direction.
+/// `FILTER` keeps only elements whose attributes match the expression, e.g.
+/// `.year >= 1980 and .genre in ["action", "drama"]`; it runs until the next option.
+/// Filtered searches explore `FILTER-EF` candidates, `COUNT * 100` by default.
+///
+/// # Returns
+///
+/// * The elements, most similar first, or `element: score` pairs with `WITHSCORES`.
+pub fn v_sim(parts: &mut SplitAsciiWhitespace, db: &mut Db) -> Result<String, &'static str> {
+    let key = parts.next().ok_or(WRONG_ARGS)?;
+    let args: Vec<&str> = parts.collect();
+
+    let (mut i, element, query) = match args.first().map(|arg| arg.to_lowercase()).as_deref() {
+        Some("ele") => (2, Some(*args.get(1).ok_or(WRONG_ARGS)?), None),
+        Some("values") => {
+            let (vector, used) = parse_values(&args[1..])?;
+            (used + 1, None, Some(vector))
+        }
+        Some(_) => return Err(SYNTAX_ERROR),
+        None => return Err(WRONG_ARGS),
+    };
+
+    let mut with_scores = false;
+    let mut count = DEFAULT_COUNT;
+    let mut ef = DEFAULT_EF;
+    let mut filter = None;
+    let mut filter_ef = None;
+    while i < args.len() {
+        match args[i].to_lowercase().as_str() {
+            "withscores" => with_scores = true,
+            "count" | "ef" | "filter-ef" => {
+                let value = args
+                    .get(i + 1)
+                    .and_then(|value| value.parse::<usize>().ok())
+                    .filter(|value| *value > 0)
+                    .ok_or(SYNTAX_ERROR)?;
+                match args[i].to_lowercase().as_str() {
+                    "count" => count = value,
+                    "ef" => ef = value,
+                    _ => filter_ef = Some(value),
+                }
+                i += 1;
+            }
+            "filter" => {
+                let start = i + 1;
+                let mut end = start;
+                while end < args.len() && !OPTIONS.contains(&args[end].to_lowercase().as_str()) {
+                    end += 1;
+                }
+                filter = Some(parse(&args[start..end].join(" "))?);
+                i = end - 1;
+            }
+            _ => return Err(SYNTAX_ERROR),
+        }
+        i += 1;
+    }
+
+    let set = match get_vector_set(key, db)? {
+        Some(set) => set,
+        None => return Ok("".to_string()),
+    };
+    let query = match (query, element) {
+        (Some(query), _) => query,
+        (None, Some(element)) => set.embedding(element).ok_or(ELEMENT_NOT_FOUND)?,
+        (None, None) => return Err(SYNTAX_ERROR),
+    };
+    if query.len() != set.dim() {
+        return Err(DIMENSION_MISMATCH);
+    }
+
+    let results = match filter.as_ref() {
+        Some(expr) => {
+            let predicate = |attributes: Option<&Value>| matches(expr, attributes);
+            let ef = filter_ef.unwrap_or(count * FILTER_EF_FACTOR).max(ef);
+            set.search(&query, count, ef, Some(&predicate))
+        }
+        None => set.search(&query, count, ef, None),
+    };
+    Ok(results
+        .iter()
+        .map(|(element, score)| {
+            if with_scores {
+                format!("{}: {:.4}", element, score)
+            } else {
+                element.clone()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" "))
+}
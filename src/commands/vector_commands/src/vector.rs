@@ -0,0 +1,50 @@
+use crate::v_add::v_add;
+use crate::v_attr::{v_get_attr, v_set_attr};
+use crate::v_emb::v_emb;
+use crate::v_info::{v_card, v_dim, v_info};
+use crate::v_rem::v_rem;
+use crate::v_sim::v_sim;
+use rus_key_db::db::Db;
+use rus_key_trait::command_trait::{Arity, Command};
+use std::str::SplitAsciiWhitespace;
+
+pub struct VectorCommand {
+    command: String,
+}
+
+impl VectorCommand {
+    pub fn new(command: String) -> VectorCommand {
+        VectorCommand { command }
+    }
+}
+
+impl Command for VectorCommand {
+    fn execute(
+        &self,
+        parts: &mut SplitAsciiWhitespace,
+        db: &mut Db,
+    ) -> Result<String, &'static str> {
+        match self.command.as_str() {
+            "vadd" => v_add(parts, db),
+            "vsim" => v_sim(parts, db),
+            "vrem" => v_rem(parts, db),
+            "vcard" => v_card(parts, db),
+            "vdim" => v_dim(parts, db),
+            "vemb" => v_emb(parts, db),
+            "vinfo" => v_info(parts, db),
+            "vsetattr" => v_set_attr(parts, db),
+            "vgetattr" => v_get_attr(parts, db),
+            _ => Err("VectorCommand Error: Command not found"),
+        }
+    }
+
+    fn arity(&self) -> Arity {
+        match self.command.as_str() {
+            // VALUES count value element
+            "vadd" => Arity::with_integers(5, &[2]),
+            "vsim" | "vsetattr" => Arity::new(3),
+            "vrem" | "vemb" | "vgetattr" => Arity::new(2),
+            _ => Arity::new(1),
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use crate::dump::{Reader, Writer, BAD_FORMAT};
+use crate::hash::hash64;
+
+// each new sub filter gets a tighter error rate so the compound rate stays bounded
+const TIGHTENING_RATIO: f64 = 0.5;
+
+#[derive(Debug, Clone)]
+struct SubFilter {
+    bits: Vec<u64>,
+    num_bits: u64,
+    hashes: u32,
+    capacity: u64,
+    items: u64,
+}
+
+impl SubFilter {
+    fn new(capacity: u64, error_rate: f64) -> SubFilter {
+        let ln2 = std::f64::consts::LN_2;
+        let num_bits = ((-(capacity as f64) * error_rate.ln()) / (ln2 * ln2))
+            .ceil()
+            .max(64.0) as u64;
+        let hashes = (-error_rate.ln() / ln2).ceil().max(1.0) as u32;
+        SubFilter {
+            bits: vec![0; num_bits.div_ceil(64) as usize],
+            num_bits,
+            hashes,
+            capacity,
+            items: 0,
+        }
+    }
+
+    fn positions(&self, item: &str) -> impl Iterator<Item = u64> + '_ {
+        // double hashing: h1 + i * h2
+        let h1 = hash64(item.as_bytes(), 0);
+        let h2 = hash64(item.as_bytes(), 1) | 1;
+        (0..self.hashes as u64).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % self.num_bits)
+    }
+
+    fn contains(&self, item: &str) -> bool {
+        self.positions(item)
+            .all(|bit| self.bits[(bit / 64) as usize] & (1 << (bit % 64)) != 0)
+    }
+
+    fn insert(&mut self, item: &str) {
+        let positions: Vec<u64> = self.positions(item).collect();
+        for bit in positions {
+            self.bits[(bit / 64) as usize] |= 1 << (bit % 64);
+        }
+        self.items += 1;
+    }
+}
+
+/// A scalable bloom filter.
+///
+/// Items go to the newest sub filter. Once it holds `capacity` items a new sub filter
+/// `expansion` times larger is stacked on top, unless the filter is non scaling.
+#[derive(Debug, Clone)]
+pub struct BloomFilter {
+    error_rate: f64,
+    // 0 means non scaling
+    expansion: u32,
+    filters: Vec<SubFilter>,
+}
+
+impl BloomFilter {
+    /// Creates a filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `error_rate` - The desired false positive rate, between 0 and 1.
+    /// * `capacity` - The number of items the first sub filter holds.
+    /// * `expansion` - The growth factor of new sub filters, 0 for a non scaling filter.
+    pub fn new(error_rate: f64, capacity: u64, expansion: u32) -> BloomFilter {
+        BloomFilter {
+            error_rate,
+            expansion,
+            filters: vec![SubFilter::new(capacity, error_rate * TIGHTENING_RATIO)],
+        }
+    }
+
+    /// Checks whether an item may have been added.
+    pub fn exists(&self, item: &str) -> bool {
+        self.filters.iter().any(|filter| filter.contains(item))
+    }
+
+    /// Adds an item.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(true)` if the item was added, `Ok(false)` if it may already exist,
+    ///   or an error if a non scaling filter is full.
+    pub fn add(&mut self, item: &str) -> Result<bool, &'static str> {
+        if self.exists(item) {
+            return Ok(false);
+        }
+        let last = self.filters.len() - 1;
+        if self.filters[last].items >= self.filters[last].capacity {
+            if self.expansion == 0 {
+                return Err("ERR non scaling filter is full");
+            }
+            let capacity = self.filters[last].capacity * self.expansion as u64;
+            let error_rate = self.error_rate * TIGHTENING_RATIO.powi(self.filters.len() as i32 + 1);
+            self.filters.push(SubFilter::new(capacity, error_rate));
+        }
+        let last = self.filters.len() - 1;
+        self.filters[last].insert(item);
+        Ok(true)
+    }
+
+    /// Returns the number of items the filter holds before it is full or grows again.
+    pub fn capacity(&self) -> u64 {
+        self.filters.iter().map(|filter| filter.capacity).sum()
+    }
+
+    /// Returns the memory used by the bit arrays, in bytes.
+    pub fn size(&self) -> usize {
+        self.filters
+            .iter()
+            .map(|filter| filter.bits.len() * 8)
+            .sum::<usize>()
+            + std::mem::size_of::<BloomFilter>()
+    }
+
+    /// Returns the number of sub filters.
+    pub fn filters(&self) -> usize {
+        self.filters.len()
+    }
+
+    /// Returns the number of items added.
+    pub fn items(&self) -> u64 {
+        self.filters.iter().map(|filter| filter.items).sum()
+    }
+
+    /// Returns the growth factor of new sub filters, 0 for a non scaling filter.
+    pub fn expansion(&self) -> u32 {
+        self.expansion
+    }
+}
+
+impl BloomFilter {
+    pub(crate) fn dump(&self, writer: &mut Writer) {
+        writer.f64(self.error_rate);
+        writer.u32(self.expansion);
+        writer.usize(self.filters.len());
+        for filter in &self.filters {
+            writer.u64(filter.num_bits);
+            writer.u32(filter.hashes);
+            writer.u64(filter.capacity);
+            writer.u64(filter.items);
+            writer.usize(filter.bits.len());
+            for word in &filter.bits {
+                writer.u64(*word);
+            }
+        }
+    }
+
+    pub(crate) fn restore(reader: &mut Reader) -> Result<BloomFilter, &'static str> {
+        let error_rate = reader.f64()?;
+        let expansion = reader.u32()?;
+        let mut filters = Vec::new();
+        for _ in 0..reader.count(36)? {
+            let num_bits = reader.u64()?;
+            let hashes = reader.u32()?;
+            let capacity = reader.u64()?;
+            let items = reader.u64()?;
+            let bits = (0..reader.count(8)?)
+                .map(|_| reader.u64())
+                .collect::<Result<Vec<u64>, _>>()?;
+            if num_bits == 0 || hashes == 0 || bits.len() as u64 != num_bits.div_ceil(64) {
+                return Err(BAD_FORMAT);
+            }
+            filters.push(SubFilter {
+                bits,
+                num_bits,
+                hashes,
+                capacity,
+                items,
+            });
+        }
+        if filters.is_empty() {
+            return Err(BAD_FORMAT);
+        }
+        Ok(BloomFilter {
+            error_rate,
+            expansion,
+            filters,
+        })
+    }
+}
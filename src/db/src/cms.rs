@@ -0,0 +1,146 @@
+use crate::dump::{Reader, Writer, BAD_FORMAT};
+use crate::hash::hash64;
+
+/// A count-min sketch.
+///
+/// `depth` rows of `width` counters. Every row counts the item in one counter chosen by its
+/// own hash function, and the estimate is the smallest of those counters, so counts are
+/// never under-estimated.
+#[derive(Debug, Clone)]
+pub struct CountMinSketch {
+    width: usize,
+    depth: usize,
+    counters: Vec<u64>,
+    count: u64,
+}
+
+impl CountMinSketch {
+    /// Creates a sketch with the given dimensions.
+    pub fn new(width: usize, depth: usize) -> CountMinSketch {
+        CountMinSketch {
+            width,
+            depth,
+            counters: vec![0; width * depth],
+            count: 0,
+        }
+    }
+
+    /// Creates a sketch sized for an error bound.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The over-estimation as a fraction of the total count.
+    /// * `probability` - The desired probability of exceeding the error bound.
+    pub fn with_probability(error: f64, probability: f64) -> CountMinSketch {
+        let width = (2.0 / error).ceil() as usize;
+        let depth = (probability.ln() / 0.5f64.ln()).ceil().max(1.0) as usize;
+        CountMinSketch::new(width, depth)
+    }
+
+    fn index(&self, row: usize, item: &str) -> usize {
+        row * self.width + (hash64(item.as_bytes(), row as u64) % self.width as u64) as usize
+    }
+
+    /// Increments the counters of an item.
+    ///
+    /// # Returns
+    ///
+    /// * The new estimated count of the item.
+    pub fn incr_by(&mut self, item: &str, increment: u64) -> u64 {
+        let mut min = u64::MAX;
+        for row in 0..self.depth {
+            let index = self.index(row, item);
+            self.counters[index] = self.counters[index].saturating_add(increment);
+            min = min.min(self.counters[index]);
+        }
+        self.count = self.count.saturating_add(increment);
+        min
+    }
+
+    /// Returns the estimated count of an item.
+    pub fn query(&self, item: &str) -> u64 {
+        (0..self.depth)
+            .map(|row| self.counters[self.index(row, item)])
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// Replaces the counters with the weighted sum of other sketches.
+    ///
+    /// # Arguments
+    ///
+    /// * `sources` - The sketches with their weights, all with the same dimensions as `self`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())`, or an error if the dimensions differ.
+    pub fn merge(&mut self, sources: &[(&CountMinSketch, u64)]) -> Result<(), &'static str> {
+        if sources
+            .iter()
+            .any(|(sketch, _)| sketch.width != self.width || sketch.depth != self.depth)
+        {
+            return Err("ERR CMS: width/depth is not equal");
+        }
+        let mut counters = vec![0u64; self.counters.len()];
+        let mut count = 0u64;
+        for (sketch, weight) in sources {
+            for (counter, value) in counters.iter_mut().zip(sketch.counters.iter()) {
+                *counter = counter.saturating_add(value.saturating_mul(*weight));
+            }
+            count = count.saturating_add(sketch.count.saturating_mul(*weight));
+        }
+        self.counters = counters;
+        self.count = count;
+        Ok(())
+    }
+
+    /// Returns the number of counters per row.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the number of rows.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the total of all increments.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns the memory used by the counters, in bytes.
+    pub fn size(&self) -> usize {
+        self.counters.len() * std::mem::size_of::<u64>() + std::mem::size_of::<CountMinSketch>()
+    }
+}
+
+impl CountMinSketch {
+    pub(crate) fn dump(&self, writer: &mut Writer) {
+        writer.usize(self.width);
+        writer.usize(self.depth);
+        writer.u64(self.count);
+        writer.usize(self.counters.len());
+        for counter in &self.counters {
+            writer.u64(*counter);
+        }
+    }
+
+    pub(crate) fn restore(reader: &mut Reader) -> Result<CountMinSketch, &'static str> {
+        let width = reader.usize()?;
+        let depth = reader.usize()?;
+        let count = reader.u64()?;
+        let counters = (0..reader.count(8)?)
+            .map(|_| reader.u64())
+            .collect::<Result<Vec<u64>, _>>()?;
+        if width == 0 || depth == 0 || width.checked_mul(depth) != Some(counters.len()) {
+            return Err(BAD_FORMAT);
+        }
+        Ok(CountMinSketch {
+            width,
+            depth,
+            counters,
+            count,
+        })
+    }
+}
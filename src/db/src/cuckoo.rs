@@ -0,0 +1,298 @@
+use crate::dump::{Reader, Writer, BAD_FORMAT};
+use crate::hash::hash64;
+use rand::Rng;
+
+// an empty slot, fingerprints are never 0
+const EMPTY_SLOT: u8 = 0;
+
+#[derive(Debug, Clone)]
+struct SubFilter {
+    // `num_buckets * bucket_size` fingerprints
+    slots: Vec<u8>,
+    num_buckets: u64,
+}
+
+impl SubFilter {
+    fn new(capacity: u64, bucket_size: usize) -> SubFilter {
+        let num_buckets = capacity
+            .div_ceil(bucket_size as u64)
+            .max(1)
+            .next_power_of_two();
+        SubFilter {
+            slots: vec![EMPTY_SLOT; num_buckets as usize * bucket_size],
+            num_buckets,
+        }
+    }
+}
+
+/// A cuckoo filter.
+///
+/// Each item is stored as an 8 bit fingerprint in one of two candidate buckets, which makes
+/// deletion possible. When both buckets are full, fingerprints are relocated up to
+/// `max_iterations` times; if that fails a new sub filter `expansion` times larger is added.
+#[derive(Debug, Clone)]
+pub struct CuckooFilter {
+    bucket_size: usize,
+    max_iterations: u32,
+    // 0 means non scaling
+    expansion: u32,
+    filters: Vec<SubFilter>,
+    items: u64,
+    deletes: u64,
+}
+
+impl CuckooFilter {
+    /// Creates a filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The number of items the first sub filter is sized for.
+    /// * `bucket_size` - The number of fingerprints per bucket.
+    /// * `max_iterations` - The number of relocations tried before the filter is considered full.
+    /// * `expansion` - The growth factor of new sub filters, 0 for a non scaling filter.
+    pub fn new(
+        capacity: u64,
+        bucket_size: usize,
+        max_iterations: u32,
+        expansion: u32,
+    ) -> CuckooFilter {
+        CuckooFilter {
+            bucket_size,
+            max_iterations,
+            expansion,
+            filters: vec![SubFilter::new(capacity, bucket_size)],
+            items: 0,
+            deletes: 0,
+        }
+    }
+
+    fn fingerprint(item: &str) -> (u8, u64) {
+        let hash = hash64(item.as_bytes(), 0);
+        // keep the fingerprint out of the bits used for the bucket index
+        let fingerprint = ((hash >> 56) as u8).max(1);
+        (fingerprint, hash)
+    }
+
+    fn alt_index(index: u64, fingerprint: u8, num_buckets: u64) -> u64 {
+        (index ^ hash64(&[fingerprint], 1)) & (num_buckets - 1)
+    }
+
+    fn bucket(&self, index: u64) -> std::ops::Range<usize> {
+        let start = index as usize * self.bucket_size;
+        start..start + self.bucket_size
+    }
+
+    fn candidates(&self, filter: usize, item: &str) -> (u8, u64, u64) {
+        let (fingerprint, hash) = CuckooFilter::fingerprint(item);
+        let num_buckets = self.filters[filter].num_buckets;
+        let i1 = hash & (num_buckets - 1);
+        let i2 = CuckooFilter::alt_index(i1, fingerprint, num_buckets);
+        (fingerprint, i1, i2)
+    }
+
+    fn try_insert(&mut self, filter: usize, index: u64, fingerprint: u8) -> bool {
+        let range = self.bucket(index);
+        let slots = &mut self.filters[filter].slots[range];
+        match slots.iter_mut().find(|slot| **slot == EMPTY_SLOT) {
+            Some(slot) => {
+                *slot = fingerprint;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Inserts into one sub filter, relocating fingerprints if both buckets are full.
+    ///
+    /// Relocations are undone when no free slot is found, so a failed insert never loses
+    /// a fingerprint that is already stored.
+    fn insert_into(&mut self, filter: usize, item: &str) -> bool {
+        let (fingerprint, i1, i2) = self.candidates(filter, item);
+        if self.try_insert(filter, i1, fingerprint) || self.try_insert(filter, i2, fingerprint) {
+            return true;
+        }
+
+        let num_buckets = self.filters[filter].num_buckets;
+        let mut rng = rand::thread_rng();
+        let mut index = if rng.gen_bool(0.5) { i1 } else { i2 };
+        let mut fingerprint = fingerprint;
+        let mut swaps: Vec<usize> = Vec::new();
+        for _ in 0..self.max_iterations {
+            let slot = self.bucket(index).start + rng.gen_range(0..self.bucket_size);
+            std::mem::swap(&mut fingerprint, &mut self.filters[filter].slots[slot]);
+            swaps.push(slot);
+            index = CuckooFilter::alt_index(index, fingerprint, num_buckets);
+            if self.try_insert(filter, index, fingerprint) {
+                return true;
+            }
+        }
+        for slot in swaps.into_iter().rev() {
+            std::mem::swap(&mut fingerprint, &mut self.filters[filter].slots[slot]);
+        }
+        false
+    }
+
+    /// Adds an item, duplicates are allowed.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())`, or an error if the filter is full and cannot grow.
+    pub fn add(&mut self, item: &str) -> Result<(), &'static str> {
+        let last = self.filters.len() - 1;
+        if !self.insert_into(last, item) {
+            if self.expansion == 0 {
+                return Err("ERR Filter is full");
+            }
+            let capacity =
+                self.filters[last].num_buckets * self.bucket_size as u64 * self.expansion as u64;
+            self.filters
+                .push(SubFilter::new(capacity, self.bucket_size));
+            if !self.insert_into(last + 1, item) {
+                return Err("ERR Filter is full");
+            }
+        }
+        self.items += 1;
+        Ok(())
+    }
+
+    /// Returns how many times the fingerprint of an item is stored.
+    ///
+    /// Items sharing a fingerprint and buckets are counted together.
+    pub fn count(&self, item: &str) -> u64 {
+        (0..self.filters.len())
+            .map(|filter| {
+                let (fingerprint, i1, i2) = self.candidates(filter, item);
+                let slots = &self.filters[filter].slots;
+                let mut count = slots[self.bucket(i1)]
+                    .iter()
+                    .filter(|slot| **slot == fingerprint)
+                    .count();
+                if i2 != i1 {
+                    count += slots[self.bucket(i2)]
+                        .iter()
+                        .filter(|slot| **slot == fingerprint)
+                        .count();
+                }
+                count as u64
+            })
+            .sum()
+    }
+
+    /// Checks whether an item may have been added.
+    pub fn exists(&self, item: &str) -> bool {
+        self.count(item) > 0
+    }
+
+    /// Removes one occurrence of an item.
+    ///
+    /// # Returns
+    ///
+    /// * `true` if a matching fingerprint was removed.
+    pub fn delete(&mut self, item: &str) -> bool {
+        // newest filters first, like inserts
+        for filter in (0..self.filters.len()).rev() {
+            let (fingerprint, i1, i2) = self.candidates(filter, item);
+            for index in [i1, i2] {
+                let range = self.bucket(index);
+                let slots = &mut self.filters[filter].slots[range];
+                if let Some(slot) = slots.iter_mut().find(|slot| **slot == fingerprint) {
+                    *slot = EMPTY_SLOT;
+                    self.items = self.items.saturating_sub(1);
+                    self.deletes += 1;
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Returns the number of buckets over all sub filters.
+    pub fn buckets(&self) -> u64 {
+        self.filters.iter().map(|filter| filter.num_buckets).sum()
+    }
+
+    /// Returns the memory used by the buckets, in bytes.
+    pub fn size(&self) -> usize {
+        self.filters
+            .iter()
+            .map(|filter| filter.slots.len())
+            .sum::<usize>()
+            + std::mem::size_of::<CuckooFilter>()
+    }
+
+    /// Returns the number of sub filters.
+    pub fn filters(&self) -> usize {
+        self.filters.len()
+    }
+
+    /// Returns the number of items stored.
+    pub fn items(&self) -> u64 {
+        self.items
+    }
+
+    /// Returns the number of items deleted.
+    pub fn deletes(&self) -> u64 {
+        self.deletes
+    }
+
+    /// Returns the number of fingerprints per bucket.
+    pub fn bucket_size(&self) -> usize {
+        self.bucket_size
+    }
+
+    /// Returns the growth factor of new sub filters, 0 for a non scaling filter.
+    pub fn expansion(&self) -> u32 {
+        self.expansion
+    }
+
+    /// Returns the number of relocations tried before the filter is considered full.
+    pub fn max_iterations(&self) -> u32 {
+        self.max_iterations
+    }
+}
+
+impl CuckooFilter {
+    pub(crate) fn dump(&self, writer: &mut Writer) {
+        writer.usize(self.bucket_size);
+        writer.u32(self.max_iterations);
+        writer.u32(self.expansion);
+        writer.u64(self.items);
+        writer.u64(self.deletes);
+        writer.usize(self.filters.len());
+        for filter in &self.filters {
+            writer.u64(filter.num_buckets);
+            writer.bytes(&filter.slots);
+        }
+    }
+
+    pub(crate) fn restore(reader: &mut Reader) -> Result<CuckooFilter, &'static str> {
+        let bucket_size = reader.usize()?;
+        let max_iterations = reader.u32()?;
+        let expansion = reader.u32()?;
+        let items = reader.u64()?;
+        let deletes = reader.u64()?;
+        let mut filters = Vec::new();
+        for _ in 0..reader.count(16)? {
+            let num_buckets = reader.u64()?;
+            let slots = reader.bytes()?;
+            if bucket_size == 0
+                || !num_buckets.is_power_of_two()
+                || (num_buckets as usize).checked_mul(bucket_size) != Some(slots.len())
+            {
+                return Err(BAD_FORMAT);
+            }
+            filters.push(SubFilter { slots, num_buckets });
+        }
+        if filters.is_empty() {
+            return Err(BAD_FORMAT);
+        }
+        Ok(CuckooFilter {
+            bucket_size,
+            max_iterations,
+            expansion,
+            filters,
+            items,
+            deletes,
+        })
+    }
+}
@@ -0,0 +1,175 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::db::Db;
+use crate::eviction::{parse_memory, EvictionPolicy};
+use crate::server::Server;
+
+const OUT_OF_MEMORY: &str = "OOM command not allowed when used memory > 'maxmemory'.";
+const DEFAULT_MAXMEMORY_SAMPLES: usize = 5;
+
+/// The numbered logical databases of a server.
+///
+/// Connections address a database by index with `SELECT`, every database has its own keys,
+/// expirations and indexes.
+pub struct Databases {
+    dbs: Vec<Db>,
+    server: Arc<Server>,
+    // database where the next active expire cycle starts
+    expire_cursor: usize,
+}
+
+impl Databases {
+    /// Creates `count` empty databases, at least one, sharing one server state.
+    pub fn new(count: usize) -> Databases {
+        let server = Arc::new(Server::default());
+        Databases {
+            dbs: (0..count.max(1))
+                .map(|index| {
+                    let mut db = Db::with_server(Arc::clone(&server));
+                    db.index = index;
+                    db
+                })
+                .collect(),
+            server,
+            expire_cursor: 0,
+        }
+    }
+
+    pub fn server(&self) -> &Server {
+        &self.server
+    }
+
+    /// Returns the server state, for a connection to reach it without locking the databases.
+    pub fn shared_server(&self) -> Arc<Server> {
+        Arc::clone(&self.server)
+    }
+
+    /// Returns the number of databases.
+    pub fn count(&self) -> usize {
+        self.dbs.len()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Db> {
+        self.dbs.get(index)
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut Db> {
+        self.dbs.get_mut(index)
+    }
+
+    /// Borrows two different databases at once.
+    pub fn pair_mut(&mut self, first: usize, second: usize) -> Option<(&mut Db, &mut Db)> {
+        if first == second || first >= self.dbs.len() || second >= self.dbs.len() {
+            return None;
+        }
+        if first < second {
+            let (left, right) = self.dbs.split_at_mut(second);
+            Some((&mut left[first], &mut right[0]))
+        } else {
+            let (left, right) = self.dbs.split_at_mut(first);
+            Some((&mut right[0], &mut left[second]))
+        }
+    }
+
+    /// Swaps the content of two databases, connections keep their index and see the other data.
+    pub fn swap(&mut self, first: usize, second: usize) -> bool {
+        if first >= self.dbs.len() || second >= self.dbs.len() {
+            return false;
+        }
+        self.dbs.swap(first, second);
+        // the numbers stay with the positions, for the keyspace event channels
+        self.dbs[first].index = first;
+        self.dbs[second].index = second;
+        self.server.watches.touch_db(first);
+        self.server.watches.touch_db(second);
+        true
+    }
+
+    /// Runs the active expire cycle over the databases within a time budget.
+    ///
+    /// A cycle stopped by the budget resumes at the next database the next time, so every
+    /// database gets its turn.
+    ///
+    /// # Returns
+    ///
+    /// * The number of deleted keys.
+    pub fn active_expire_cycle(&mut self, budget: Duration) -> usize {
+        let deadline = Instant::now() + budget;
+        let mut expired = 0;
+        for _ in 0..self.dbs.len() {
+            let index = self.expire_cursor;
+            self.expire_cursor = (self.expire_cursor + 1) % self.dbs.len();
+            expired += self.dbs[index].active_expire_cycle(deadline);
+            if Instant::now() >= deadline {
+                break;
+            }
+        }
+        expired
+    }
+
+    /// Returns the estimated memory used by the keys of every database, in bytes.
+    pub fn used_memory(&mut self) -> usize {
+        self.dbs.iter_mut().map(|db| db.used_memory()).sum()
+    }
+
+    /// Returns the `maxmemory` limit in bytes, 0 for no limit.
+    pub fn maxmemory(&self) -> u64 {
+        self.server
+            .config_get("maxmemory")
+            .and_then(|maxmemory| parse_memory(&maxmemory))
+            .unwrap_or(0)
+    }
+
+    /// Returns the `maxmemory-policy`, `noeviction` if it is not set or unknown.
+    pub fn maxmemory_policy(&self) -> EvictionPolicy {
+        self.server
+            .config_get("maxmemory-policy")
+            .and_then(|policy| EvictionPolicy::parse(&policy))
+            .unwrap_or(EvictionPolicy::NoEviction)
+    }
+
+    /// Evicts keys until the used memory is under `maxmemory`.
+    ///
+    /// Like Redis the eviction is approximate: every round samples `maxmemory-samples` keys
+    /// of each database and evicts the best candidate for the policy.
+    ///
+    /// # Returns
+    ///
+    /// * The number of evicted keys, or an `OOM` error if the memory is still over the limit
+    ///   because the policy has nothing left to evict.
+    pub fn free_memory(&mut self) -> Result<usize, &'static str> {
+        let maxmemory = self.maxmemory() as usize;
+        if maxmemory == 0 {
+            return Ok(0);
+        }
+        let policy = self.maxmemory_policy();
+        let samples = self
+            .server
+            .config_get("maxmemory-samples")
+            .and_then(|samples| samples.parse::<usize>().ok())
+            .filter(|samples| *samples > 0)
+            .unwrap_or(DEFAULT_MAXMEMORY_SAMPLES);
+
+        let mut used = self.used_memory();
+        let mut evicted = 0;
+        while used > maxmemory {
+            let candidate = self
+                .dbs
+                .iter()
+                .enumerate()
+                .filter_map(|(index, db)| {
+                    db.eviction_candidate(policy, samples)
+                        .map(|(key, score)| (index, key, score))
+                })
+                .max_by_key(|(_, _, score)| *score);
+            let (index, key) = match candidate {
+                Some((index, key, _)) => (index, key),
+                None => return Err(OUT_OF_MEMORY),
+            };
+            used -= self.dbs[index].evict(&key);
+            evicted += 1;
+        }
+        Ok(evicted)
+    }
+}
@@ -1,53 +1,702 @@
+use crate::bloom::BloomFilter;
+use crate::cms::CountMinSketch;
+use crate::cuckoo::CuckooFilter;
+use crate::eviction::EvictionPolicy;
+use crate::keyset::KeySet;
+use crate::notify::{NOTIFY_EVICTED, NOTIFY_EXPIRED, NOTIFY_KEYEVENT, NOTIFY_KEYSPACE, NOTIFY_NEW};
+use crate::scan_order::ScanOrder;
+use crate::search::SearchIndex;
+use crate::server::Server;
+use crate::timeseries::TimeSeries;
+use crate::topk::TopK;
+use crate::vector::VectorSet;
 use rand::Rng;
-use std::collections::{BTreeMap, HashMap};
+use serde_json::Value;
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
 #[derive(Debug, Clone)]
 pub enum DataType {
     String(String),
-    // List(Vec<String>),
+    List(Vec<String>),
     // Set(HashMap<String, String>),
-    HashMap(HashMap<String, String>),
+    // the fields of a hash in field order
+    HashMap(BTreeMap<String, String>),
+    // the members of a sorted set and their scores, the geo sets
     ZSet(BTreeMap<String, String>),
+    Json(Value),
+    Bloom(BloomFilter),
+    Cuckoo(CuckooFilter),
+    CountMinSketch(CountMinSketch),
+    TopK(TopK),
+    TimeSeries(TimeSeries),
+    VectorSet(VectorSet),
 }
 
+impl DataType {
+    /// Returns an estimate of the memory used by the value, in bytes.
+    pub fn memory_usage(&self) -> usize {
+        let string = std::mem::size_of::<String>();
+        match self {
+            DataType::String(value) => value.len(),
+            DataType::List(list) => list.iter().map(|value| value.len() + string).sum(),
+            DataType::HashMap(map) => map
+                .iter()
+                .map(|(field, value)| field.len() + value.len() + string * 2)
+                .sum(),
+            DataType::ZSet(map) => map
+                .iter()
+                .map(|(member, score)| member.len() + score.len() + string * 2)
+                .sum(),
+            DataType::Json(value) => value.to_string().len(),
+            DataType::Bloom(filter) => filter.size(),
+            DataType::Cuckoo(filter) => filter.size(),
+            DataType::CountMinSketch(sketch) => sketch.size(),
+            DataType::TopK(topk) => topk.size(),
+            DataType::TimeSeries(series) => series.size(),
+            DataType::VectorSet(set) => set.size(),
+        }
+    }
+
+    /// Returns the number of allocations freed with the value, as the cost of freeing it.
+    pub fn free_effort(&self) -> usize {
+        match self {
+            DataType::List(list) => list.len(),
+            DataType::HashMap(map) => map.len(),
+            DataType::ZSet(map) => map.len(),
+            DataType::Json(value) => json_nodes(value),
+            DataType::TimeSeries(series) => series.len(),
+            DataType::VectorSet(set) => set.len(),
+            // strings and the probabilistic types are a few contiguous buffers
+            _ => 1,
+        }
+    }
+
+    /// Estimates the memory of a list, a hash or a sorted set from `samples` of its elements, like
+    /// `MEMORY USAGE`, other values and `samples` 0 are measured exactly.
+    pub fn sampled_memory_usage(&self, samples: usize) -> usize {
+        let string = std::mem::size_of::<String>();
+        let (len, sampled): (usize, usize) = match self {
+            DataType::List(list) if samples > 0 && list.len() > samples => (
+                list.len(),
+                list.iter()
+                    .take(samples)
+                    .map(|value| value.len() + string)
+                    .sum(),
+            ),
+            DataType::HashMap(map) if samples > 0 && map.len() > samples => (
+                map.len(),
+                map.iter()
+                    .take(samples)
+                    .map(|(field, value)| field.len() + value.len() + string * 2)
+                    .sum(),
+            ),
+            DataType::ZSet(map) if samples > 0 && map.len() > samples => (
+                map.len(),
+                map.iter()
+                    .take(samples)
+                    .map(|(member, score)| member.len() + score.len() + string * 2)
+                    .sum(),
+            ),
+            _ => return self.memory_usage(),
+        };
+        sampled * len / samples
+    }
+}
+
+fn json_nodes(value: &Value) -> usize {
+    match value {
+        Value::Array(array) => 1 + array.iter().map(json_nodes).sum::<usize>(),
+        Value::Object(object) => 1 + object.values().map(json_nodes).sum::<usize>(),
+        _ => 1,
+    }
+}
+
+// values cheaper to free than this are freed inline even when lazy freeing is enabled, like
+// the Redis LAZYFREE_THRESHOLD
+const LAZYFREE_THRESHOLD: usize = 64;
+// keys sampled per round of the active expire cycle
+const ACTIVE_EXPIRE_SAMPLES: usize = 20;
+// LFU counter of a new key, so it is not evicted before it had a chance to be read
+const LFU_INIT_VAL: u8 = 5;
+// the higher the factor, the more accesses it takes to increment the LFU counter
+const LFU_LOG_FACTOR: f64 = 10.0;
+// the LFU counter is decremented once per period without access
+const LFU_DECAY_MILLIS: i64 = 60 * 1000;
+
+/// Returns the current unix time in milliseconds, the clock of every expiration deadline.
+pub fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// A value with its expiration deadline and the metadata used by eviction.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    value: DataType,
+    // unix time in milliseconds, `None` for a key without TTL
+    expires_at: Option<i64>,
+    // estimated memory of the key and the value, in bytes
+    size: usize,
+    // unix time in milliseconds of the last access
+    access: Cell<i64>,
+    // logarithmic access counter, like the Redis LFU counter
+    frequency: Cell<u8>,
+}
+
+impl Entry {
+    fn new(key: &str, value: DataType, expires_at: Option<i64>) -> Entry {
+        Entry {
+            size: entry_size(key, &value),
+            value,
+            expires_at,
+            access: Cell::new(now_millis()),
+            frequency: Cell::new(LFU_INIT_VAL),
+        }
+    }
+
+    fn is_expired(&self, now: i64) -> bool {
+        self.expires_at.is_some_and(|deadline| deadline <= now)
+    }
+
+    pub fn value(&self) -> &DataType {
+        &self.value
+    }
+
+    /// Returns the milliseconds since the last access.
+    pub fn idle_time(&self, now: i64) -> i64 {
+        (now - self.access.get()).max(0)
+    }
+
+    /// Returns the LFU counter, decayed by the time since the last access.
+    pub fn frequency(&self, now: i64) -> u8 {
+        let periods = self.idle_time(now) / LFU_DECAY_MILLIS;
+        self.frequency
+            .get()
+            .saturating_sub(periods.min(u8::MAX as i64) as u8)
+    }
+
+    /// Records an access: the LFU counter grows with a probability falling as it gets higher,
+    /// so 255 stands for about a million accesses.
+    fn touch(&self, now: i64) {
+        let mut frequency = self.frequency(now);
+        if frequency < u8::MAX {
+            let base = frequency.saturating_sub(LFU_INIT_VAL) as f64;
+            if rand::thread_rng().gen::<f64>() < 1.0 / (base * LFU_LOG_FACTOR + 1.0) {
+                frequency += 1;
+            }
+        }
+        self.frequency.set(frequency);
+        self.access.set(now);
+    }
+}
+
+// the memory of the key and the bookkeeping of the entry
+fn entry_overhead(key: &str) -> usize {
+    key.len() + std::mem::size_of::<String>() + std::mem::size_of::<Entry>()
+}
+
+fn entry_size(key: &str, value: &DataType) -> usize {
+    entry_overhead(key) + value.memory_usage()
+}
+
+/// One logical database.
+///
+/// Every lookup is expiry aware: a key whose deadline has passed is invisible to `get`,
+/// `keys` and `randomkey`, and is deleted by the methods taking `&mut self`.
 pub struct Db {
-    map: HashMap<String, DataType>,
+    map: HashMap<String, Entry>,
+    // keys with a TTL, sampled by the active expire cycle and the volatile eviction policies
+    volatile: KeySet,
+    // every key, sampled by the allkeys eviction policies
+    key_set: KeySet,
+    // every key in cursor order, paged by SCAN
+    scan_order: ScanOrder,
+    // the fields of the hashes and sorted sets being paged by HSCAN or ZSCAN, ordered by the
+    // first page after the value changed
+    field_orders: HashMap<String, ScanOrder>,
+    // sum of the entry sizes
+    used_memory: usize,
+    // keys handed out by `get_mut`, their size is computed again by `used_memory`
+    resized: HashSet<String>,
+    // secondary indexes by name, kept in sync by `set` and `delete`
+    indexes: BTreeMap<String, SearchIndex>,
+    server: Arc<Server>,
+    // the number of the database in the keyspace event channels
+    pub(crate) index: usize,
     pub not_found_message: String,
 }
 
 impl Db {
     pub fn new() -> Db {
+        Db::with_server(Arc::new(Server::default()))
+    }
+
+    /// Creates a database sharing the config and stats of a server.
+    pub fn with_server(server: Arc<Server>) -> Db {
         Db {
             map: HashMap::new(),
+            volatile: KeySet::new(),
+            key_set: KeySet::new(),
+            scan_order: ScanOrder::new(),
+            field_orders: HashMap::new(),
+            used_memory: 0,
+            resized: HashSet::new(),
+            indexes: BTreeMap::new(),
+            server,
+            index: 0,
             not_found_message: "Key not found".to_string(),
         }
     }
 
+    pub fn server(&self) -> &Server {
+        &self.server
+    }
+
+    /// Publishes a keyspace event if its class is enabled by `notify-keyspace-events`.
+    ///
+    /// With `K` the event is sent to `__keyspace@<db>__:<key>` and with `E` the key is sent
+    /// to `__keyevent@<db>__:<event>`, like Redis.
+    ///
+    /// # Arguments
+    ///
+    /// * `class` - One of the `notify` classes, like `NOTIFY_STRING`.
+    /// * `event` - The event name, usually the command, like `set` or `expire`.
+    pub fn notify(&self, class: u32, event: &str, key: &str) {
+        let flags = self.server.notify_flags();
+        if flags & class == 0 {
+            return;
+        }
+        if flags & NOTIFY_KEYSPACE != 0 {
+            let channel = format!("__keyspace@{}__:{}", self.index, key);
+            self.server.publish(&channel, event);
+        }
+        if flags & NOTIFY_KEYEVENT != 0 {
+            let channel = format!("__keyevent@{}__:{}", self.index, event);
+            self.server.publish(&channel, key);
+        }
+    }
+
+    /// Sets the value of a key, a live key keeps its TTL.
     pub fn set(&mut self, key: String, value: DataType) {
-        self.map.insert(key, value);
+        self.expire_if_needed(&key);
+        self.server.watches.touch(self.index, &key);
+        self.reindex(&key, Some(&value));
+        let expires_at = match self.map.get(&key) {
+            Some(old) => {
+                self.used_memory -= old.size;
+                old.expires_at
+            }
+            None => None,
+        };
+        let entry = Entry::new(&key, value, expires_at);
+        self.used_memory += entry.size;
+        if self.key_set.insert(&key) {
+            self.scan_order.insert(&key);
+        }
+        self.field_orders.remove(&key);
+        match self.map.insert(key.clone(), entry) {
+            Some(old) => self.free(old, self.server.config_flag("lazyfree-lazy-server-del")),
+            None => self.notify(NOTIFY_NEW, "new", &key),
+        }
     }
 
     pub fn get(&self, key: &str) -> Option<&DataType> {
-        self.map.get(key)
+        let now = now_millis();
+        match self.map.get(key) {
+            Some(entry) if !entry.is_expired(now) => {
+                entry.touch(now);
+                Some(&entry.value)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the entry of a live key without counting an access, for introspection.
+    pub fn peek(&self, key: &str) -> Option<&Entry> {
+        self.map
+            .get(key)
+            .filter(|entry| !entry.is_expired(now_millis()))
+    }
+
+    /// Returns the estimated memory of a live key and its value, in bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `samples` - The number of elements of a hash or a sorted set measured, 0 for all.
+    pub fn memory_usage(&self, key: &str, samples: usize) -> Option<usize> {
+        let entry = self.peek(key)?;
+        Some(entry_overhead(key) + entry.value.sampled_memory_usage(samples))
     }
 
+    /// Returns the value of a live key for an update in place, its size is accounted again
+    /// by the next `used_memory`.
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut DataType> {
+        self.expire_if_needed(key);
+        let entry = self.map.get_mut(key)?;
+        entry.touch(now_millis());
+        // the value may change, as far as WATCH knows it does
+        self.server.watches.touch(self.index, key);
+        self.field_orders.remove(key);
+        if !self.resized.contains(key) {
+            self.resized.insert(key.to_string());
+        }
+        Some(&mut entry.value)
+    }
+
+    /// Deletes a key with its TTL.
+    ///
+    /// # Returns
+    ///
+    /// * `false` if the key does not exist or is expired.
     pub fn delete(&mut self, key: &str) -> bool {
-        if self.map.contains_key(key) {
-            self.map.remove(key);
-            return true;
+        if self.expire_if_needed(key) {
+            return false;
         }
-        false
+        self.remove_entry(key).is_some()
     }
 
-    pub fn check_expired(&mut self, key: &str) -> bool {
-        if self.map.contains_key(key) {
-            return true;
+    /// Deletes a key like `delete`, freeing a large value on a background thread.
+    pub fn unlink(&mut self, key: &str) -> bool {
+        if self.expire_if_needed(key) {
+            return false;
         }
-        false
+        match self.remove_entry(key) {
+            Some(entry) => {
+                self.free(entry, true);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // drops a removed entry, on the lazyfree thread if `lazy` and the value is large enough
+    fn free(&self, entry: Entry, lazy: bool) {
+        if lazy && entry.value.free_effort() > LAZYFREE_THRESHOLD {
+            self.server.lazy_free.free(entry);
+        }
+    }
+
+    // removes an entry with its TTL, its index documents and its memory
+    fn remove_entry(&mut self, key: &str) -> Option<Entry> {
+        let entry = self.map.remove(key)?;
+        self.server.watches.touch(self.index, key);
+        self.volatile.remove(key);
+        self.key_set.remove(key);
+        self.scan_order.remove(key);
+        self.field_orders.remove(key);
+        self.resized.remove(key);
+        self.reindex(key, None);
+        self.used_memory -= entry.size;
+        Some(entry)
+    }
+
+    /// Sets the expiration deadline of a live key.
+    ///
+    /// # Arguments
+    ///
+    /// * `deadline` - The unix time in milliseconds.
+    ///
+    /// # Returns
+    ///
+    /// * `false` if the key does not exist or is expired.
+    pub fn expire_at(&mut self, key: &str, deadline: i64) -> bool {
+        self.expire_if_needed(key);
+        match self.map.get_mut(key) {
+            Some(entry) => {
+                entry.expires_at = Some(deadline);
+                self.volatile.insert(key);
+                self.server.watches.touch(self.index, key);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes the TTL of a live key.
+    ///
+    /// # Returns
+    ///
+    /// * `true` if the key had a TTL.
+    pub fn persist(&mut self, key: &str) -> bool {
+        self.expire_if_needed(key);
+        let had_ttl = match self.map.get_mut(key) {
+            Some(entry) => entry.expires_at.take().is_some(),
+            None => false,
+        };
+        if had_ttl {
+            self.volatile.remove(key);
+            self.server.watches.touch(self.index, key);
+        }
+        had_ttl
+    }
+
+    /// Returns the expiration deadline of a live key, in unix time milliseconds.
+    pub fn expire_time(&self, key: &str) -> Option<i64> {
+        let now = now_millis();
+        match self.map.get(key) {
+            Some(entry) if !entry.is_expired(now) => entry.expires_at,
+            _ => None,
+        }
+    }
+
+    /// Deletes `key` if its deadline has passed.
+    ///
+    /// # Returns
+    ///
+    /// * `true` if the key was expired and deleted.
+    fn expire_if_needed(&mut self, key: &str) -> bool {
+        let now = now_millis();
+        if !self.map.get(key).is_some_and(|entry| entry.is_expired(now)) {
+            return false;
+        }
+        if let Some(entry) = self.remove_entry(key) {
+            self.free(entry, self.server.config_flag("lazyfree-lazy-expire"));
+        }
+        self.server.stats.add_expired_keys(1);
+        self.notify(NOTIFY_EXPIRED, "expired", key);
+        true
+    }
+
+    /// Deletes expired keys found by sampling the keys with a TTL, like the Redis `hz` cycle.
+    ///
+    /// Rounds of `ACTIVE_EXPIRE_SAMPLES` keys go on while more than a quarter of a round was
+    /// expired, so the work adapts to the share of expired keys, and stop at `deadline`.
+    ///
+    /// # Returns
+    ///
+    /// * The number of deleted keys.
+    pub fn active_expire_cycle(&mut self, deadline: Instant) -> usize {
+        let mut expired = 0;
+        loop {
+            let sample = self.volatile.sample(ACTIVE_EXPIRE_SAMPLES);
+            if sample.is_empty() {
+                break;
+            }
+            let round = sample
+                .iter()
+                .filter(|key| self.expire_if_needed(key))
+                .count();
+            expired += round;
+            if round * 4 <= sample.len() || Instant::now() >= deadline {
+                break;
+            }
+        }
+        expired
+    }
+
+    /// Returns the estimated memory used by the keys and values, in bytes.
+    pub fn used_memory(&mut self) -> usize {
+        for key in std::mem::take(&mut self.resized) {
+            if let Some(entry) = self.map.get_mut(&key) {
+                let size = entry_size(&key, &entry.value);
+                self.used_memory = self.used_memory - entry.size + size;
+                entry.size = size;
+            }
+        }
+        self.used_memory
+    }
+
+    /// Samples `samples` keys allowed by `policy` and picks the one to evict first.
+    ///
+    /// # Returns
+    ///
+    /// * The key with its score, the higher the score the better the candidate, so the
+    ///   candidates of several databases can be compared. `None` if no key can be evicted.
+    pub fn eviction_candidate(
+        &self,
+        policy: EvictionPolicy,
+        samples: usize,
+    ) -> Option<(String, u64)> {
+        let keys = match policy {
+            EvictionPolicy::NoEviction => return None,
+            _ if policy.is_volatile() => self.volatile.sample(samples),
+            _ => self.key_set.sample(samples),
+        };
+        let now = now_millis();
+        let mut rng = rand::thread_rng();
+        keys.into_iter()
+            .filter_map(|key| {
+                let entry = self.map.get(&key)?;
+                let score = match policy {
+                    EvictionPolicy::AllKeysLru | EvictionPolicy::VolatileLru => {
+                        entry.idle_time(now) as u64
+                    }
+                    EvictionPolicy::AllKeysLfu | EvictionPolicy::VolatileLfu => {
+                        (u8::MAX - entry.frequency(now)) as u64
+                    }
+                    EvictionPolicy::VolatileTtl => {
+                        u64::MAX - entry.expires_at.unwrap_or(i64::MAX).max(0) as u64
+                    }
+                    _ => rng.gen::<u64>(),
+                };
+                Some((key, score))
+            })
+            .max_by_key(|(_, score)| *score)
+    }
+
+    /// Deletes a key to free memory.
+    ///
+    /// # Returns
+    ///
+    /// * The freed memory, in bytes.
+    pub fn evict(&mut self, key: &str) -> usize {
+        match self.remove_entry(key) {
+            Some(entry) => {
+                self.server.stats.add_evicted_keys(1);
+                self.notify(NOTIFY_EVICTED, "evicted", key);
+                let size = entry.size;
+                self.free(entry, self.server.config_flag("lazyfree-lazy-eviction"));
+                size
+            }
+            None => 0,
+        }
+    }
+
+    /// Returns the number of keys in O(1), like DBSIZE in Redis: an expired key is counted
+    /// until it is deleted, by an access or the active expire cycle.
+    pub fn len(&self) -> usize {
+        self.key_set.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.key_set.is_empty()
+    }
+
+    /// Returns the number of keys with a TTL, expired ones included until they are deleted.
+    pub fn volatile_count(&self) -> usize {
+        self.volatile.len()
+    }
+
+    /// Updates the indexes matching `key` after its value changed.
+    ///
+    /// Hashes are indexed, any other value or a deleted key removes the document.
+    fn reindex(&mut self, key: &str, value: Option<&DataType>) {
+        for index in self.indexes.values_mut() {
+            if !index.matches_key(key) {
+                continue;
+            }
+            match value {
+                Some(DataType::HashMap(hash)) => index.add(key, hash),
+                _ => index.remove(key),
+            }
+        }
+    }
+
+    /// Adds a secondary index and indexes the existing keys it matches.
+    ///
+    /// # Returns
+    ///
+    /// * `false` if an index with the same name exists.
+    pub fn create_index(&mut self, mut index: SearchIndex) -> bool {
+        if self.indexes.contains_key(&index.name) {
+            return false;
+        }
+        for (key, entry) in self.map.iter() {
+            if let DataType::HashMap(hash) = &entry.value {
+                if index.matches_key(key) {
+                    index.add(key, hash);
+                }
+            }
+        }
+        self.indexes.insert(index.name.clone(), index);
+        true
+    }
+
+    pub fn drop_index(&mut self, name: &str) -> bool {
+        self.indexes.remove(name).is_some()
+    }
+
+    pub fn index(&self, name: &str) -> Option<&SearchIndex> {
+        self.indexes.get(name)
+    }
+
+    pub fn index_names(&self) -> Vec<String> {
+        self.indexes.keys().cloned().collect()
+    }
+
+    /// Removes every key, keeping the index definitions.
+    ///
+    /// # Returns
+    ///
+    /// * The removed entries, so the caller decides where they are dropped.
+    pub fn flush(&mut self) -> HashMap<String, Entry> {
+        for index in self.indexes.values_mut() {
+            *index = SearchIndex::new(
+                index.name.clone(),
+                index.prefixes.clone(),
+                index.schema.clone(),
+            );
+        }
+        self.server.watches.touch_db(self.index);
+        self.volatile.clear();
+        self.key_set.clear();
+        self.scan_order.clear();
+        self.field_orders.clear();
+        self.resized.clear();
+        self.used_memory = 0;
+        std::mem::take(&mut self.map)
+    }
+
+    /// Returns the live keys.
+    pub fn keys(&self) -> Vec<String> {
+        let now = now_millis();
+        self.map
+            .iter()
+            .filter(|(_, entry)| !entry.is_expired(now))
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+
+    /// Returns one page of the live keys, see `ScanOrder::page`.
+    ///
+    /// Expired keys not deleted yet take their place in the page, which may then hold fewer
+    /// than `count` keys.
+    pub fn scan(&self, cursor: u64, count: usize) -> (u64, Vec<String>) {
+        let now = now_millis();
+        let (next_cursor, page) = self.scan_order.page(cursor, count);
+        let keys = page
+            .into_iter()
+            .filter(|key| {
+                self.map
+                    .get(*key)
+                    .is_some_and(|entry| !entry.is_expired(now))
+            })
+            .map(String::from)
+            .collect();
+        (next_cursor, keys)
+    }
+
+    /// Returns one page of the fields of a hash or the members of a sorted set, see
+    /// `ScanOrder::page`.
+    ///
+    /// The fields are ordered by the first page after the value changed, every write replaces
+    /// the whole value anyway, and the next pages only walk `count` of them.
+    pub fn scan_fields(&mut self, key: &str, cursor: u64, count: usize) -> (u64, Vec<String>) {
+        if !self.field_orders.contains_key(key) {
+            let order = match self.peek(key).map(Entry::value) {
+                Some(DataType::HashMap(map)) => ScanOrder::build(map.keys()),
+                Some(DataType::ZSet(map)) => ScanOrder::build(map.keys()),
+                _ => return (0, Vec::new()),
+            };
+            self.field_orders.insert(key.to_string(), order);
+        }
+        let (next_cursor, page) = self.field_orders[key].page(cursor, count);
+        (next_cursor, page.into_iter().map(String::from).collect())
+    }
+
+    /// Checks whether a key exists, deleting it if it is expired.
+    pub fn check_expired(&mut self, key: &str) -> bool {
+        self.expire_if_needed(key);
+        self.map.contains_key(key)
     }
 
     pub fn randomkey(&mut self) -> Option<String> {
-        let keys: Vec<_> = self.map.keys().cloned().collect();
+        let keys = self.keys();
         if keys.is_empty() {
             None
         } else {
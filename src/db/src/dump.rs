@@ -0,0 +1,331 @@
+use crate::bloom::BloomFilter;
+use crate::cms::CountMinSketch;
+use crate::cuckoo::CuckooFilter;
+use crate::db::DataType;
+use crate::hash::crc64;
+use crate::timeseries::TimeSeries;
+use crate::topk::TopK;
+use crate::vector::VectorSet;
+use std::collections::BTreeMap;
+
+/// Version of the payload format, a payload with another version is refused.
+pub const DUMP_VERSION: u16 = 1;
+
+const INVALID_PAYLOAD: &str = "ERR DUMP payload version or checksum are wrong";
+pub(crate) const BAD_FORMAT: &str = "ERR Bad data format";
+// the version and the checksum closing every payload
+const FOOTER_LEN: usize = 2 + 8;
+
+const TYPE_STRING: u8 = 0;
+const TYPE_HASH: u8 = 1;
+const TYPE_ZSET: u8 = 2;
+const TYPE_JSON: u8 = 3;
+const TYPE_BLOOM: u8 = 4;
+const TYPE_CUCKOO: u8 = 5;
+const TYPE_CMS: u8 = 6;
+const TYPE_TOPK: u8 = 7;
+const TYPE_TIMESERIES: u8 = 8;
+const TYPE_VECTORSET: u8 = 9;
+const TYPE_LIST: u8 = 10;
+
+/// Appends little endian fields to a payload.
+#[derive(Default)]
+pub(crate) struct Writer {
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    pub(crate) fn u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    pub(crate) fn u32(&mut self, value: u32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub(crate) fn u64(&mut self, value: u64) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub(crate) fn i64(&mut self, value: i64) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub(crate) fn usize(&mut self, value: usize) {
+        self.u64(value as u64);
+    }
+
+    pub(crate) fn f32(&mut self, value: f32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub(crate) fn f64(&mut self, value: f64) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub(crate) fn bool(&mut self, value: bool) {
+        self.u8(value as u8);
+    }
+
+    /// Writes a length followed by the bytes.
+    pub(crate) fn bytes(&mut self, value: &[u8]) {
+        self.usize(value.len());
+        self.bytes.extend_from_slice(value);
+    }
+
+    pub(crate) fn str(&mut self, value: &str) {
+        self.bytes(value.as_bytes());
+    }
+}
+
+/// Reads the fields written by `Writer`, every read fails on a truncated payload.
+pub(crate) struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Reader<'a> {
+        Reader { bytes, position: 0 }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], &'static str> {
+        let end = self.position.checked_add(len).ok_or(BAD_FORMAT)?;
+        let bytes = self.bytes.get(self.position..end).ok_or(BAD_FORMAT)?;
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], &'static str> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    pub(crate) fn u8(&mut self) -> Result<u8, &'static str> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32, &'static str> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    pub(crate) fn u64(&mut self) -> Result<u64, &'static str> {
+        Ok(u64::from_le_bytes(self.array()?))
+    }
+
+    pub(crate) fn i64(&mut self) -> Result<i64, &'static str> {
+        Ok(i64::from_le_bytes(self.array()?))
+    }
+
+    pub(crate) fn usize(&mut self) -> Result<usize, &'static str> {
+        usize::try_from(self.u64()?).map_err(|_| BAD_FORMAT)
+    }
+
+    pub(crate) fn f32(&mut self) -> Result<f32, &'static str> {
+        Ok(f32::from_le_bytes(self.array()?))
+    }
+
+    pub(crate) fn f64(&mut self) -> Result<f64, &'static str> {
+        Ok(f64::from_le_bytes(self.array()?))
+    }
+
+    pub(crate) fn bool(&mut self) -> Result<bool, &'static str> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(BAD_FORMAT),
+        }
+    }
+
+    /// Reads the number of elements of a collection.
+    ///
+    /// Every element takes at least `min_size` bytes, so a corrupted count larger than the
+    /// rest of the payload fails here instead of allocating for it.
+    pub(crate) fn count(&mut self, min_size: usize) -> Result<usize, &'static str> {
+        let count = self.usize()?;
+        if count.saturating_mul(min_size.max(1)) > self.bytes.len() - self.position {
+            return Err(BAD_FORMAT);
+        }
+        Ok(count)
+    }
+
+    pub(crate) fn bytes(&mut self) -> Result<Vec<u8>, &'static str> {
+        let len = self.count(1)?;
+        Ok(self.take(len)?.to_vec())
+    }
+
+    pub(crate) fn string(&mut self) -> Result<String, &'static str> {
+        String::from_utf8(self.bytes()?).map_err(|_| BAD_FORMAT)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.position == self.bytes.len()
+    }
+}
+
+fn write_pairs<'a, I: ExactSizeIterator<Item = (&'a String, &'a String)>>(
+    writer: &mut Writer,
+    pairs: I,
+) {
+    writer.usize(pairs.len());
+    for (field, value) in pairs {
+        writer.str(field);
+        writer.str(value);
+    }
+}
+
+fn read_pairs(reader: &mut Reader) -> Result<Vec<(String, String)>, &'static str> {
+    // two lengths per pair
+    let count = reader.count(16)?;
+    (0..count)
+        .map(|_| Ok((reader.string()?, reader.string()?)))
+        .collect()
+}
+
+/// Serializes a value with its expiration deadline, like `DUMP`.
+///
+/// The payload is the type, the value and the deadline, followed by `DUMP_VERSION` and a
+/// CRC-64 of everything before it, so `restore` detects payloads from another version and
+/// corrupted ones.
+///
+/// # Arguments
+///
+/// * `value` - The value to serialize.
+/// * `expires_at` - The deadline in unix time milliseconds, `None` for a key without TTL.
+pub fn dump(value: &DataType, expires_at: Option<i64>) -> Vec<u8> {
+    let mut writer = Writer::default();
+    match value {
+        DataType::String(value) => {
+            writer.u8(TYPE_STRING);
+            writer.str(value);
+        }
+        DataType::List(list) => {
+            writer.u8(TYPE_LIST);
+            writer.usize(list.len());
+            for value in list {
+                writer.str(value);
+            }
+        }
+        DataType::HashMap(map) => {
+            writer.u8(TYPE_HASH);
+            write_pairs(&mut writer, map.iter());
+        }
+        DataType::ZSet(map) => {
+            writer.u8(TYPE_ZSET);
+            write_pairs(&mut writer, map.iter());
+        }
+        DataType::Json(value) => {
+            writer.u8(TYPE_JSON);
+            writer.str(&value.to_string());
+        }
+        DataType::Bloom(filter) => {
+            writer.u8(TYPE_BLOOM);
+            filter.dump(&mut writer);
+        }
+        DataType::Cuckoo(filter) => {
+            writer.u8(TYPE_CUCKOO);
+            filter.dump(&mut writer);
+        }
+        DataType::CountMinSketch(sketch) => {
+            writer.u8(TYPE_CMS);
+            sketch.dump(&mut writer);
+        }
+        DataType::TopK(topk) => {
+            writer.u8(TYPE_TOPK);
+            topk.dump(&mut writer);
+        }
+        DataType::TimeSeries(series) => {
+            writer.u8(TYPE_TIMESERIES);
+            series.dump(&mut writer);
+        }
+        DataType::VectorSet(set) => {
+            writer.u8(TYPE_VECTORSET);
+            set.dump(&mut writer);
+        }
+    }
+    writer.bool(expires_at.is_some());
+    writer.i64(expires_at.unwrap_or(0));
+
+    let mut bytes = writer.bytes;
+    bytes.extend_from_slice(&DUMP_VERSION.to_le_bytes());
+    let checksum = crc64(&bytes);
+    bytes.extend_from_slice(&checksum.to_le_bytes());
+    bytes
+}
+
+/// Deserializes a payload written by `dump`.
+///
+/// # Returns
+///
+/// * The value with its deadline, or an error if the version or the checksum do not match
+///   or the payload is malformed.
+pub fn restore(payload: &[u8]) -> Result<(DataType, Option<i64>), &'static str> {
+    if payload.len() < FOOTER_LEN {
+        return Err(INVALID_PAYLOAD);
+    }
+    let (body, checksum) = payload.split_at(payload.len() - 8);
+    if crc64(body).to_le_bytes() != checksum {
+        return Err(INVALID_PAYLOAD);
+    }
+    let (body, version) = body.split_at(body.len() - 2);
+    if version != DUMP_VERSION.to_le_bytes() {
+        return Err(INVALID_PAYLOAD);
+    }
+
+    let mut reader = Reader::new(body);
+    let value = match reader.u8()? {
+        TYPE_STRING => DataType::String(reader.string()?),
+        TYPE_LIST => {
+            // one length per element
+            let count = reader.count(8)?;
+            DataType::List(
+                (0..count)
+                    .map(|_| reader.string())
+                    .collect::<Result<_, _>>()?,
+            )
+        }
+        TYPE_HASH => DataType::HashMap(
+            read_pairs(&mut reader)?
+                .into_iter()
+                .collect::<BTreeMap<_, _>>(),
+        ),
+        TYPE_ZSET => DataType::ZSet(
+            read_pairs(&mut reader)?
+                .into_iter()
+                .collect::<BTreeMap<_, _>>(),
+        ),
+        TYPE_JSON => {
+            DataType::Json(serde_json::from_str(&reader.string()?).map_err(|_| BAD_FORMAT)?)
+        }
+        TYPE_BLOOM => DataType::Bloom(BloomFilter::restore(&mut reader)?),
+        TYPE_CUCKOO => DataType::Cuckoo(CuckooFilter::restore(&mut reader)?),
+        TYPE_CMS => DataType::CountMinSketch(CountMinSketch::restore(&mut reader)?),
+        TYPE_TOPK => DataType::TopK(TopK::restore(&mut reader)?),
+        TYPE_TIMESERIES => DataType::TimeSeries(TimeSeries::restore(&mut reader)?),
+        TYPE_VECTORSET => DataType::VectorSet(VectorSet::restore(&mut reader)?),
+        _ => return Err(BAD_FORMAT),
+    };
+    let has_ttl = reader.bool()?;
+    let expires_at = reader.i64()?;
+    if !reader.is_empty() {
+        return Err(BAD_FORMAT);
+    }
+    Ok((value, has_ttl.then_some(expires_at)))
+}
+
+/// Encodes a payload as lowercase hex, so it fits in the text protocol.
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Decodes a payload encoded by `to_hex`, case insensitive.
+pub fn from_hex(hex: &str) -> Result<Vec<u8>, &'static str> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return Err(INVALID_PAYLOAD);
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&hex[index..index + 2], 16).map_err(|_| INVALID_PAYLOAD))
+        .collect()
+}
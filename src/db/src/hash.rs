@@ -0,0 +1,51 @@
+/// Hashes bytes into a 64 bit value.
+///
+/// FNV-1a over the input followed by the splitmix64 finalizer, so the result is stable across
+/// processes and Rust versions and the low bits are well mixed. `seed` selects independent
+/// hash functions for structures that need several of them.
+///
+/// # Arguments
+///
+/// * `data` - The bytes to hash.
+/// * `seed` - The hash function to use.
+///
+/// # Returns
+///
+/// * The 64 bit hash.
+pub fn hash64(data: &[u8], seed: u64) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325 ^ seed.wrapping_mul(0x9e3779b97f4a7c15);
+    for byte in data {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d049bb133111eb);
+    hash ^ (hash >> 31)
+}
+
+// the Jones polynomial, reflected, used by the Redis DUMP format
+const CRC64_POLY: u64 = 0x95ac9329ac4bc9b5;
+
+/// Computes the CRC-64/Jones checksum of bytes, the checksum Redis appends to DUMP payloads.
+///
+/// # Arguments
+///
+/// * `data` - The bytes to check.
+///
+/// # Returns
+///
+/// * The 64 bit checksum, `0xe9c6d914c4b8d9ca` for `123456789`.
+pub fn crc64(data: &[u8]) -> u64 {
+    let mut crc: u64 = 0;
+    for byte in data {
+        crc ^= *byte as u64;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ CRC64_POLY
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    crc
+}
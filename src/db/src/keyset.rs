@@ -0,0 +1,69 @@
+use rand::Rng;
+use std::collections::HashMap;
+
+/// A set of keys supporting uniform random sampling.
+///
+/// Keys are kept in a vector, with their position in a map so that removal swaps the last
+/// key into the hole. Every operation is O(1).
+#[derive(Debug, Default)]
+pub struct KeySet {
+    keys: Vec<String>,
+    positions: HashMap<String, usize>,
+}
+
+impl KeySet {
+    pub fn new() -> KeySet {
+        KeySet::default()
+    }
+
+    /// Adds a key, returns `false` if it was already present.
+    pub fn insert(&mut self, key: &str) -> bool {
+        if self.positions.contains_key(key) {
+            return false;
+        }
+        self.positions.insert(key.to_string(), self.keys.len());
+        self.keys.push(key.to_string());
+        true
+    }
+
+    /// Removes a key, returns `false` if it was not present.
+    pub fn remove(&mut self, key: &str) -> bool {
+        let position = match self.positions.remove(key) {
+            Some(position) => position,
+            None => return false,
+        };
+        self.keys.swap_remove(position);
+        if let Some(moved) = self.keys.get(position) {
+            self.positions.insert(moved.clone(), position);
+        }
+        true
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.positions.contains_key(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.keys.clear();
+        self.positions.clear();
+    }
+
+    /// Picks `count` keys at random, with repetition.
+    pub fn sample(&self, count: usize) -> Vec<String> {
+        if self.keys.is_empty() {
+            return Vec::new();
+        }
+        let mut rng = rand::thread_rng();
+        (0..count)
+            .map(|_| self.keys[rng.gen_range(0..self.keys.len())].clone())
+            .collect()
+    }
+}
@@ -0,0 +1,57 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Frees values on a background thread, so dropping a large value does not hold the lock of
+/// its database.
+///
+/// The thread is started by the first value sent and frees the values in order.
+#[derive(Debug, Default)]
+pub struct LazyFree {
+    sender: Mutex<Option<Sender<Box<dyn Send>>>>,
+    pending: Arc<AtomicU64>,
+    freed: Arc<AtomicU64>,
+}
+
+impl LazyFree {
+    /// Hands a value over to the background thread.
+    pub fn free<T: Send + 'static>(&self, value: T) {
+        self.pending.fetch_add(1, Ordering::Relaxed);
+        let mut sender = self.sender.lock().unwrap();
+        let sender = sender.get_or_insert_with(|| self.spawn());
+        if let Err(error) = sender.send(Box::new(value)) {
+            // the thread is gone, free the value here rather than leak it
+            drop(error.0);
+            self.pending.fetch_sub(1, Ordering::Relaxed);
+            self.freed.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn spawn(&self) -> Sender<Box<dyn Send>> {
+        let (sender, receiver) = mpsc::channel::<Box<dyn Send>>();
+        let pending = Arc::clone(&self.pending);
+        let freed = Arc::clone(&self.freed);
+        thread::Builder::new()
+            .name("lazyfree".to_string())
+            .spawn(move || {
+                for value in receiver {
+                    drop(value);
+                    pending.fetch_sub(1, Ordering::Relaxed);
+                    freed.fetch_add(1, Ordering::Relaxed);
+                }
+            })
+            .expect("failed to spawn the lazyfree thread");
+        sender
+    }
+
+    /// Returns the number of values waiting to be freed.
+    pub fn pending_objects(&self) -> u64 {
+        self.pending.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of values freed by the background thread since the start.
+    pub fn freed_objects(&self) -> u64 {
+        self.freed.load(Ordering::Relaxed)
+    }
+}
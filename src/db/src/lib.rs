@@ -1 +1,22 @@
+pub mod bloom;
+pub mod cms;
+pub mod cuckoo;
+pub mod databases;
 pub mod db;
+pub mod dump;
+pub mod eviction;
+pub mod hash;
+pub mod keyset;
+pub mod lazyfree;
+pub mod modules;
+pub mod notify;
+pub mod output_buffer;
+pub mod pubsub;
+pub mod scan_order;
+pub mod scripts;
+pub mod search;
+pub mod server;
+pub mod timeseries;
+pub mod topk;
+pub mod vector;
+pub mod watch;
@@ -0,0 +1,31 @@
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// A loaded module, opaque here since the module runtime lives in a crate depending on this one.
+pub type LoadedModule = Arc<dyn Any + Send + Sync>;
+
+/// The modules loaded by `MODULE LOAD` in one server, by name.
+///
+/// Every connection of the server sees them, another server in the same process does not.
+#[derive(Default)]
+pub struct Modules {
+    modules: RwLock<BTreeMap<String, LoadedModule>>,
+}
+
+impl Modules {
+    pub fn read(&self) -> RwLockReadGuard<'_, BTreeMap<String, LoadedModule>> {
+        self.modules.read().unwrap()
+    }
+
+    pub fn write(&self) -> RwLockWriteGuard<'_, BTreeMap<String, LoadedModule>> {
+        self.modules.write().unwrap()
+    }
+}
+
+impl fmt::Debug for Modules {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.read().keys()).finish()
+    }
+}
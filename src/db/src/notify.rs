@@ -0,0 +1,56 @@
+//! Classes of keyspace events, selected by the `notify-keyspace-events` option.
+
+pub const NOTIFY_KEYSPACE: u32 = 1 << 0;
+pub const NOTIFY_KEYEVENT: u32 = 1 << 1;
+pub const NOTIFY_GENERIC: u32 = 1 << 2;
+pub const NOTIFY_STRING: u32 = 1 << 3;
+pub const NOTIFY_LIST: u32 = 1 << 4;
+pub const NOTIFY_SET: u32 = 1 << 5;
+pub const NOTIFY_HASH: u32 = 1 << 6;
+pub const NOTIFY_ZSET: u32 = 1 << 7;
+pub const NOTIFY_EXPIRED: u32 = 1 << 8;
+pub const NOTIFY_EVICTED: u32 = 1 << 9;
+pub const NOTIFY_STREAM: u32 = 1 << 10;
+/// Events of the module types: JSON, probabilistic, time series and vector sets.
+pub const NOTIFY_MODULE: u32 = 1 << 11;
+pub const NOTIFY_NEW: u32 = 1 << 12;
+/// Every class `A` stands for, new key events must be asked for.
+pub const NOTIFY_ALL: u32 = NOTIFY_GENERIC
+    | NOTIFY_STRING
+    | NOTIFY_LIST
+    | NOTIFY_SET
+    | NOTIFY_HASH
+    | NOTIFY_ZSET
+    | NOTIFY_EXPIRED
+    | NOTIFY_EVICTED
+    | NOTIFY_STREAM
+    | NOTIFY_MODULE;
+
+/// Parses the `notify-keyspace-events` characters, like `KEA` or `Kx`.
+///
+/// # Returns
+///
+/// * The classes, or `None` if a character is unknown. Nothing is published unless `K` or
+///   `E` is given with at least one class.
+pub fn parse_flags(value: &str) -> Option<u32> {
+    value.chars().try_fold(0, |flags, flag| {
+        let class = match flag {
+            'K' => NOTIFY_KEYSPACE,
+            'E' => NOTIFY_KEYEVENT,
+            'g' => NOTIFY_GENERIC,
+            '$' => NOTIFY_STRING,
+            'l' => NOTIFY_LIST,
+            's' => NOTIFY_SET,
+            'h' => NOTIFY_HASH,
+            'z' => NOTIFY_ZSET,
+            'x' => NOTIFY_EXPIRED,
+            'e' => NOTIFY_EVICTED,
+            't' => NOTIFY_STREAM,
+            'd' => NOTIFY_MODULE,
+            'n' => NOTIFY_NEW,
+            'A' => NOTIFY_ALL,
+            _ => return None,
+        };
+        Some(flags | class)
+    })
+}
@@ -0,0 +1,200 @@
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use tokio::sync::Notify;
+
+use crate::eviction::parse_memory;
+
+/// The classes of clients `client-output-buffer-limit` sets limits for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClientClass {
+    Normal,
+    /// Replicas of this server, there is no replication yet so the limit is only kept.
+    Replica,
+    /// Clients subscribed to a channel, a pattern or a shard channel.
+    PubSub,
+}
+
+impl ClientClass {
+    fn parse(class: &str) -> Option<ClientClass> {
+        match class.to_lowercase().as_str() {
+            "normal" => Some(ClientClass::Normal),
+            // the name of replicas before Redis 5
+            "replica" | "slave" => Some(ClientClass::Replica),
+            "pubsub" => Some(ClientClass::PubSub),
+            _ => None,
+        }
+    }
+}
+
+/// The output buffer limit of a class of clients, 0 disables a limit.
+///
+/// A client is disconnected as soon as its buffer reaches the hard limit, or when it stays
+/// at or over the soft limit for more than `soft_seconds`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct OutputBufferLimit {
+    pub hard: usize,
+    pub soft: usize,
+    pub soft_seconds: u64,
+}
+
+impl OutputBufferLimit {
+    /// Returns whether `pending` bytes go over the limit at `now`.
+    ///
+    /// `soft_since` is when the buffer reached the soft limit, it is set and cleared as the
+    /// buffer grows and shrinks.
+    pub fn exceeded(&self, pending: usize, soft_since: &mut Option<Instant>, now: Instant) -> bool {
+        if self.hard > 0 && pending >= self.hard {
+            return true;
+        }
+        if self.soft > 0 && pending >= self.soft {
+            let since = *soft_since.get_or_insert(now);
+            return now.duration_since(since).as_secs() > self.soft_seconds;
+        }
+        *soft_since = None;
+        false
+    }
+}
+
+/// The limits of every class, as set by `client-output-buffer-limit`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OutputBufferLimits {
+    pub normal: OutputBufferLimit,
+    pub replica: OutputBufferLimit,
+    pub pubsub: OutputBufferLimit,
+}
+
+impl Default for OutputBufferLimits {
+    // the defaults of Redis
+    fn default() -> Self {
+        OutputBufferLimits {
+            normal: OutputBufferLimit::default(),
+            replica: OutputBufferLimit {
+                hard: 256 * 1024 * 1024,
+                soft: 64 * 1024 * 1024,
+                soft_seconds: 60,
+            },
+            pubsub: OutputBufferLimit {
+                hard: 32 * 1024 * 1024,
+                soft: 8 * 1024 * 1024,
+                soft_seconds: 60,
+            },
+        }
+    }
+}
+
+impl OutputBufferLimits {
+    pub fn get(&self, class: ClientClass) -> OutputBufferLimit {
+        match class {
+            ClientClass::Normal => self.normal,
+            ClientClass::Replica => self.replica,
+            ClientClass::PubSub => self.pubsub,
+        }
+    }
+
+    /// Parses `client-output-buffer-limit`, groups of `class hard soft soft_seconds` like
+    /// `pubsub 32mb 8mb 60`, over the current limits: the classes not given are kept.
+    ///
+    /// # Returns
+    ///
+    /// * The new limits, or `None` if the value is invalid.
+    pub fn parse(&self, value: &str) -> Option<OutputBufferLimits> {
+        let parts: Vec<&str> = value.split_ascii_whitespace().collect();
+        if parts.is_empty() || !parts.len().is_multiple_of(4) {
+            return None;
+        }
+        let mut limits = *self;
+        for group in parts.chunks(4) {
+            let limit = OutputBufferLimit {
+                hard: parse_memory(group[1])? as usize,
+                soft: parse_memory(group[2])? as usize,
+                soft_seconds: group[3].parse().ok()?,
+            };
+            match ClientClass::parse(group[0])? {
+                ClientClass::Normal => limits.normal = limit,
+                ClientClass::Replica => limits.replica = limit,
+                ClientClass::PubSub => limits.pubsub = limit,
+            }
+        }
+        Some(limits)
+    }
+}
+
+impl fmt::Display for OutputBufferLimits {
+    // the value of `client-output-buffer-limit`, in bytes
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let classes = [
+            ("normal", self.normal),
+            ("replica", self.replica),
+            ("pubsub", self.pubsub),
+        ];
+        let groups: Vec<String> = classes
+            .iter()
+            .map(|(class, limit)| {
+                format!(
+                    "{} {} {} {}",
+                    class, limit.hard, limit.soft, limit.soft_seconds
+                )
+            })
+            .collect();
+        write!(f, "{}", groups.join(" "))
+    }
+}
+
+/// The bytes queued for a client and not written yet, shared by the server queuing them
+/// and the connection writing them.
+///
+/// Once over its limit the buffer is closed: nothing more is queued and the connection is
+/// told to disconnect the client.
+#[derive(Debug, Default)]
+pub struct OutputBuffer {
+    pending: AtomicUsize,
+    soft_since: Mutex<Option<Instant>>,
+    closed: AtomicBool,
+    close: Notify,
+}
+
+impl OutputBuffer {
+    /// Queues `bytes` unless it takes the buffer over the limit.
+    ///
+    /// # Returns
+    ///
+    /// * `false` if the buffer is closed, the bytes are not queued then.
+    pub fn queue(&self, bytes: usize, limit: &OutputBufferLimit) -> bool {
+        if self.is_closed() {
+            return false;
+        }
+        let pending = self.pending.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        let mut soft_since = self.soft_since.lock().unwrap();
+        if limit.exceeded(pending, &mut soft_since, Instant::now()) {
+            self.pending.fetch_sub(bytes, Ordering::Relaxed);
+            self.closed.store(true, Ordering::Relaxed);
+            self.close.notify_one();
+            return false;
+        }
+        true
+    }
+
+    /// Removes bytes written to the client from the buffer.
+    pub fn written(&self, bytes: usize) {
+        self.pending.fetch_sub(bytes, Ordering::Relaxed);
+    }
+
+    /// Returns the number of bytes queued and not written yet.
+    pub fn pending(&self) -> usize {
+        self.pending.load(Ordering::Relaxed)
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Relaxed)
+    }
+
+    /// Waits until the buffer is closed, for a connection to stop a write that does not end.
+    pub async fn closed(&self) {
+        if !self.is_closed() {
+            self.close.notified().await;
+        }
+    }
+}
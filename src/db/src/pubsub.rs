@@ -0,0 +1,252 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use rus_key_command_lib::glob_match;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+use crate::output_buffer::{OutputBuffer, OutputBufferLimit};
+
+/// A message delivered to a subscriber.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    /// The pattern the channel matched, `None` for a channel subscription.
+    pub pattern: Option<String>,
+    pub channel: String,
+    pub payload: String,
+    /// Whether the message was published to a shard channel with `SPUBLISH`.
+    pub sharded: bool,
+}
+
+impl Message {
+    /// Returns the kind of the message sent to the client: `message`, `pmessage` or
+    /// `smessage`.
+    pub fn kind(&self) -> &'static str {
+        match (&self.pattern, self.sharded) {
+            (_, true) => "smessage",
+            (Some(_), false) => "pmessage",
+            (None, false) => "message",
+        }
+    }
+
+    /// Returns the bytes the message takes in the output buffer of a client.
+    pub fn size(&self) -> usize {
+        self.pattern.as_ref().map_or(0, |pattern| pattern.len())
+            + self.channel.len()
+            + self.payload.len()
+    }
+}
+
+/// The queue of messages of one client.
+#[derive(Debug, Clone)]
+pub struct Subscriber {
+    sender: UnboundedSender<Message>,
+    output: Arc<OutputBuffer>,
+}
+
+impl Subscriber {
+    /// Returns a subscriber with an empty output buffer, and the queue its messages arrive on.
+    pub fn channel() -> (Subscriber, UnboundedReceiver<Message>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let subscriber = Subscriber {
+            sender,
+            output: Arc::default(),
+        };
+        (subscriber, receiver)
+    }
+
+    /// Returns the output buffer of the client, which counts the messages queued until the
+    /// client writes them.
+    pub fn output(&self) -> &Arc<OutputBuffer> {
+        &self.output
+    }
+
+    // queues the message, returns `false` if the client is gone or over its limit
+    fn send(&self, message: &Message, limit: &OutputBufferLimit) -> bool {
+        self.output.queue(message.size(), limit) && self.sender.send(message.clone()).is_ok()
+    }
+}
+
+// subscribers by channel or by pattern, then by client id
+type Subscriptions = HashMap<String, HashMap<u64, Subscriber>>;
+
+/// Routes published messages to the clients subscribed to a channel or to a pattern matching
+/// it.
+///
+/// Publishing never blocks: messages are queued for every subscriber, which reads them at its
+/// own pace, up to the output buffer limit of the pubsub clients.
+///
+/// Shard channels are a namespace of their own, with a single server every channel lives in
+/// the same shard.
+#[derive(Debug, Default)]
+pub struct PubSub {
+    channels: Mutex<Subscriptions>,
+    patterns: Mutex<Subscriptions>,
+    shard_channels: Mutex<Subscriptions>,
+    next_client_id: AtomicU64,
+}
+
+fn add(
+    subscriptions: &Mutex<Subscriptions>,
+    client: u64,
+    name: &str,
+    subscriber: &Subscriber,
+) -> bool {
+    subscriptions
+        .lock()
+        .unwrap()
+        .entry(name.to_string())
+        .or_default()
+        .insert(client, subscriber.clone())
+        .is_none()
+}
+
+fn remove(subscriptions: &Mutex<Subscriptions>, client: u64, name: &str) -> bool {
+    let mut subscriptions = subscriptions.lock().unwrap();
+    let removed = match subscriptions.get_mut(name) {
+        Some(clients) => clients.remove(&client).is_some(),
+        None => false,
+    };
+    if subscriptions
+        .get(name)
+        .is_some_and(|clients| clients.is_empty())
+    {
+        subscriptions.remove(name);
+    }
+    removed
+}
+
+// sends the message to every client of a channel or pattern, a client whose queue is closed
+// or over its output buffer limit is gone
+fn deliver(
+    clients: &mut HashMap<u64, Subscriber>,
+    message: &Message,
+    limit: &OutputBufferLimit,
+) -> usize {
+    clients.retain(|_, subscriber| subscriber.send(message, limit));
+    clients.len()
+}
+
+// the names matching the glob pattern if any, sorted
+fn names(subscriptions: &Mutex<Subscriptions>, pattern: Option<&str>) -> Vec<String> {
+    let mut names: Vec<String> = subscriptions
+        .lock()
+        .unwrap()
+        .keys()
+        .filter(|name| pattern.is_none_or(|pattern| glob_match(pattern, name)))
+        .cloned()
+        .collect();
+    names.sort();
+    names
+}
+
+fn count(subscriptions: &Mutex<Subscriptions>, name: &str) -> usize {
+    subscriptions
+        .lock()
+        .unwrap()
+        .get(name)
+        .map_or(0, |clients| clients.len())
+}
+
+impl PubSub {
+    /// Returns a new client id, unique for the server.
+    pub fn client_id(&self) -> u64 {
+        self.next_client_id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Subscribes a client to a channel, returns `false` if it already was.
+    pub fn subscribe(&self, client: u64, channel: &str, subscriber: &Subscriber) -> bool {
+        add(&self.channels, client, channel, subscriber)
+    }
+
+    /// Unsubscribes a client from a channel, returns `false` if it was not subscribed.
+    pub fn unsubscribe(&self, client: u64, channel: &str) -> bool {
+        remove(&self.channels, client, channel)
+    }
+
+    /// Subscribes a client to the channels matching a glob pattern.
+    pub fn psubscribe(&self, client: u64, pattern: &str, subscriber: &Subscriber) -> bool {
+        add(&self.patterns, client, pattern, subscriber)
+    }
+
+    /// Unsubscribes a client from a pattern.
+    pub fn punsubscribe(&self, client: u64, pattern: &str) -> bool {
+        remove(&self.patterns, client, pattern)
+    }
+
+    /// Subscribes a client to a shard channel.
+    pub fn ssubscribe(&self, client: u64, channel: &str, subscriber: &Subscriber) -> bool {
+        add(&self.shard_channels, client, channel, subscriber)
+    }
+
+    /// Unsubscribes a client from a shard channel.
+    pub fn sunsubscribe(&self, client: u64, channel: &str) -> bool {
+        remove(&self.shard_channels, client, channel)
+    }
+
+    /// Sends a message to the subscribers of the channel and of the matching patterns.
+    ///
+    /// # Returns
+    ///
+    /// * The number of subscriptions the message was delivered to, a client subscribed to
+    ///   the channel and to a pattern receives it twice.
+    pub fn publish(&self, channel: &str, payload: &str, limit: &OutputBufferLimit) -> usize {
+        let mut message = Message {
+            pattern: None,
+            channel: channel.to_string(),
+            payload: payload.to_string(),
+            sharded: false,
+        };
+        let mut receivers = 0;
+        if let Some(clients) = self.channels.lock().unwrap().get_mut(channel) {
+            receivers += deliver(clients, &message, limit);
+        }
+        for (pattern, clients) in self.patterns.lock().unwrap().iter_mut() {
+            if glob_match(pattern, channel) {
+                message.pattern = Some(pattern.clone());
+                receivers += deliver(clients, &message, limit);
+            }
+        }
+        receivers
+    }
+
+    /// Sends a message to the subscribers of a shard channel, patterns do not match shard
+    /// channels.
+    pub fn spublish(&self, channel: &str, payload: &str, limit: &OutputBufferLimit) -> usize {
+        let message = Message {
+            pattern: None,
+            channel: channel.to_string(),
+            payload: payload.to_string(),
+            sharded: true,
+        };
+        match self.shard_channels.lock().unwrap().get_mut(channel) {
+            Some(clients) => deliver(clients, &message, limit),
+            None => 0,
+        }
+    }
+
+    /// Returns the channels with at least one subscriber, matching the pattern if any.
+    pub fn channels(&self, pattern: Option<&str>) -> Vec<String> {
+        names(&self.channels, pattern)
+    }
+
+    /// Returns the number of subscribers of a channel, patterns are not counted.
+    pub fn numsub(&self, channel: &str) -> usize {
+        count(&self.channels, channel)
+    }
+
+    /// Returns the number of patterns with at least one subscriber.
+    pub fn numpat(&self) -> usize {
+        self.patterns.lock().unwrap().len()
+    }
+
+    /// Returns the shard channels with at least one subscriber, matching the pattern if any.
+    pub fn shard_channels(&self, pattern: Option<&str>) -> Vec<String> {
+        names(&self.shard_channels, pattern)
+    }
+
+    /// Returns the number of subscribers of a shard channel.
+    pub fn shard_numsub(&self, channel: &str) -> usize {
+        count(&self.shard_channels, channel)
+    }
+}
@@ -0,0 +1,88 @@
+use rus_key_command_lib::scan_hash;
+use std::collections::BTreeMap;
+
+/// A set of names kept in the order of the SCAN family of commands.
+///
+/// Names are ordered by a stable hash and a cursor is the hash a page starts at, so a page
+/// costs O(log n + count) and every name present for the whole iteration is returned at least
+/// once, however the set grows or shrinks between pages. Names added or removed meanwhile may
+/// or may not be returned.
+#[derive(Debug, Default)]
+pub struct ScanOrder {
+    // names by hash, the rare names sharing a hash are returned in the same page
+    buckets: BTreeMap<u64, Vec<String>>,
+}
+
+impl ScanOrder {
+    pub fn new() -> ScanOrder {
+        ScanOrder::default()
+    }
+
+    /// Orders the names of an existing collection.
+    pub fn build<'a, I>(names: I) -> ScanOrder
+    where
+        I: Iterator<Item = &'a String>,
+    {
+        let mut order = ScanOrder::new();
+        for name in names {
+            order.insert(name);
+        }
+        order
+    }
+
+    /// Adds a name, returns `false` if it was already present.
+    pub fn insert(&mut self, name: &str) -> bool {
+        let bucket = self.buckets.entry(scan_hash(name)).or_default();
+        if bucket.iter().any(|present| present == name) {
+            return false;
+        }
+        bucket.push(name.to_string());
+        true
+    }
+
+    /// Removes a name, returns `false` if it was not present.
+    pub fn remove(&mut self, name: &str) -> bool {
+        let hash = scan_hash(name);
+        let bucket = match self.buckets.get_mut(&hash) {
+            Some(bucket) => bucket,
+            None => return false,
+        };
+        let len = bucket.len();
+        bucket.retain(|present| present != name);
+        let removed = bucket.len() < len;
+        if bucket.is_empty() {
+            self.buckets.remove(&hash);
+        }
+        removed
+    }
+
+    pub fn clear(&mut self) {
+        self.buckets.clear();
+    }
+
+    /// Returns one page of names.
+    ///
+    /// # Arguments
+    ///
+    /// * `cursor` - The cursor returned by the previous page, 0 to start a new iteration.
+    /// * `count` - The number of names to return, at least 1, plus the ones sharing the hash
+    ///   of the last.
+    ///
+    /// # Returns
+    ///
+    /// * The cursor of the next page, 0 once the iteration is complete.
+    /// * The names of this page, in cursor order.
+    pub fn page(&self, cursor: u64, count: usize) -> (u64, Vec<&str>) {
+        let mut page = Vec::new();
+        let mut buckets = self.buckets.range(cursor..);
+        for (_, names) in buckets.by_ref() {
+            page.extend(names.iter().map(String::as_str));
+            if page.len() >= count.max(1) {
+                break;
+            }
+        }
+        // the next bucket comes after one of this page, so its hash is never 0
+        let next_cursor = buckets.next().map_or(0, |(hash, _)| *hash);
+        (next_cursor, page)
+    }
+}
@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const NOT_BUSY: &str = "NOTBUSY No scripts in execution right now.";
+const BUSY: &str = "BUSY RusKey is busy running a script. You can only call SCRIPT KILL.";
+const UNKILLABLE: &str = "UNKILLABLE Sorry the script already executed write commands against the dataset. You can either wait the script termination or kill the server in a hard way using the SHUTDOWN NOSAVE command.";
+
+/// Returns the SHA1 digest of a script, in lowercase hex, the name EVALSHA runs it by.
+pub fn sha1_hex(body: &str) -> String {
+    sha1_smol::Sha1::from(body).digest().to_string()
+}
+
+/// The state of the script being run, shared with the connections that may kill it.
+#[derive(Debug)]
+pub struct ScriptRun {
+    killed: AtomicBool,
+    wrote: AtomicBool,
+    started: Instant,
+    limit: Option<Duration>,
+}
+
+impl ScriptRun {
+    fn new(limit: Option<Duration>) -> ScriptRun {
+        ScriptRun {
+            killed: AtomicBool::new(false),
+            wrote: AtomicBool::new(false),
+            started: Instant::now(),
+            limit,
+        }
+    }
+
+    /// Returns whether SCRIPT KILL asked the script to stop.
+    pub fn is_killed(&self) -> bool {
+        self.killed.load(Ordering::Relaxed)
+    }
+
+    /// Records that the script ran a write command, it cannot be killed anymore.
+    pub fn set_wrote(&self) {
+        self.wrote.store(true, Ordering::Relaxed);
+    }
+
+    pub fn wrote(&self) -> bool {
+        self.wrote.load(Ordering::Relaxed)
+    }
+
+    /// Returns whether the script runs for longer than its time limit.
+    pub fn is_timed_out(&self) -> bool {
+        self.limit
+            .is_some_and(|limit| self.started.elapsed() > limit)
+    }
+}
+
+/// The Lua scripts of the server: the ones cached by EVAL or SCRIPT LOAD, by SHA1, and the
+/// one running, if any.
+#[derive(Debug, Default)]
+pub struct Scripts {
+    bodies: Mutex<HashMap<String, String>>,
+    running: Mutex<Option<Arc<ScriptRun>>>,
+}
+
+impl Scripts {
+    /// Caches a script, returns its SHA1.
+    pub fn load(&self, body: &str) -> String {
+        let sha = sha1_hex(body);
+        self.bodies
+            .lock()
+            .unwrap()
+            .entry(sha.clone())
+            .or_insert_with(|| body.to_string());
+        sha
+    }
+
+    pub fn get(&self, sha: &str) -> Option<String> {
+        self.bodies
+            .lock()
+            .unwrap()
+            .get(&sha.to_lowercase())
+            .cloned()
+    }
+
+    pub fn exists(&self, sha: &str) -> bool {
+        self.bodies
+            .lock()
+            .unwrap()
+            .contains_key(&sha.to_lowercase())
+    }
+
+    pub fn flush(&self) {
+        self.bodies.lock().unwrap().clear();
+    }
+
+    /// Marks a script as running until `finish`, `limit` is its `lua-time-limit`.
+    pub fn start(&self, limit: Option<Duration>) -> Arc<ScriptRun> {
+        let run = Arc::new(ScriptRun::new(limit));
+        *self.running.lock().unwrap() = Some(Arc::clone(&run));
+        run
+    }
+
+    pub fn finish(&self) {
+        *self.running.lock().unwrap() = None;
+    }
+
+    /// Asks the running script to stop, unless it already wrote: its changes cannot be undone
+    /// and stopping it halfway would break its atomicity.
+    pub fn kill(&self) -> Result<String, &'static str> {
+        match self.running.lock().unwrap().as_ref() {
+            None => Err(NOT_BUSY),
+            Some(run) if run.wrote() => Err(UNKILLABLE),
+            Some(run) => {
+                run.killed.store(true, Ordering::Relaxed);
+                Ok("OK".to_string())
+            }
+        }
+    }
+
+    /// Replies BUSY while the running script is past its time limit: it already wrote, so it
+    /// runs to its end, and the other commands would wait for it.
+    pub fn check_busy(&self) -> Result<(), &'static str> {
+        match self.running.lock().unwrap().as_ref() {
+            Some(run) if run.is_timed_out() => Err(BUSY),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,280 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// The type of an indexed hash field.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldType {
+    // tokenized into lowercase terms
+    Text,
+    // parsed as a float, queried by range
+    Numeric,
+    // comma separated, matched exactly ignoring case
+    Tag,
+}
+
+impl FieldType {
+    /// Parses a field type name, case insensitive.
+    pub fn parse(name: &str) -> Option<FieldType> {
+        match name.to_lowercase().as_str() {
+            "text" => Some(FieldType::Text),
+            "numeric" => Some(FieldType::Numeric),
+            "tag" => Some(FieldType::Tag),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FieldType::Text => "TEXT",
+            FieldType::Numeric => "NUMERIC",
+            FieldType::Tag => "TAG",
+        }
+    }
+}
+
+// a float usable as a BTreeMap key
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Number(f64);
+
+impl Eq for Number {}
+
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Number {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// Splits text into lowercase terms.
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(|term| term.to_lowercase())
+        .collect()
+}
+
+/// Splits a tag field into normalized tags.
+pub fn split_tags(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|tag| tag.trim().to_lowercase())
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+type Postings = HashMap<String, HashMap<String, BTreeSet<String>>>;
+
+/// A secondary index over the hashes whose key starts with one of its prefixes.
+///
+/// `Db` keeps every index up to date when keys are set or deleted, so documents are
+/// always the current content of their hash.
+#[derive(Debug, Clone)]
+pub struct SearchIndex {
+    pub name: String,
+    pub prefixes: Vec<String>,
+    pub schema: Vec<(String, FieldType)>,
+    // key -> indexed field values
+    docs: HashMap<String, BTreeMap<String, String>>,
+    // field -> term -> keys
+    terms: Postings,
+    // field -> tag -> keys
+    tags: Postings,
+    // field -> value -> keys
+    numbers: HashMap<String, BTreeMap<Number, BTreeSet<String>>>,
+}
+
+impl SearchIndex {
+    pub fn new(name: String, prefixes: Vec<String>, schema: Vec<(String, FieldType)>) -> Self {
+        SearchIndex {
+            name,
+            prefixes,
+            schema,
+            docs: HashMap::new(),
+            terms: HashMap::new(),
+            tags: HashMap::new(),
+            numbers: HashMap::new(),
+        }
+    }
+
+    /// Checks whether a key belongs to the index, an index without prefixes takes every key.
+    pub fn matches_key(&self, key: &str) -> bool {
+        self.prefixes.is_empty() || self.prefixes.iter().any(|prefix| key.starts_with(prefix))
+    }
+
+    /// Returns the type of a field in the schema.
+    pub fn field_type(&self, field: &str) -> Option<FieldType> {
+        self.schema
+            .iter()
+            .find(|(name, _)| name == field)
+            .map(|(_, field_type)| *field_type)
+    }
+
+    /// Indexes a hash, replacing the previous version of the document.
+    ///
+    /// Numeric fields that cannot be parsed are left out of the numeric index.
+    pub fn add(&mut self, key: &str, hash: &BTreeMap<String, String>) {
+        self.remove(key);
+        let mut doc = BTreeMap::new();
+        for (field, field_type) in self.schema.iter() {
+            let value = match hash.get(field) {
+                Some(value) => value,
+                None => continue,
+            };
+            match field_type {
+                FieldType::Text => {
+                    let postings = self.terms.entry(field.clone()).or_default();
+                    for term in tokenize(value) {
+                        postings.entry(term).or_default().insert(key.to_string());
+                    }
+                }
+                FieldType::Tag => {
+                    let postings = self.tags.entry(field.clone()).or_default();
+                    for tag in split_tags(value) {
+                        postings.entry(tag).or_default().insert(key.to_string());
+                    }
+                }
+                FieldType::Numeric => {
+                    if let Ok(number) = value.parse::<f64>() {
+                        self.numbers
+                            .entry(field.clone())
+                            .or_default()
+                            .entry(Number(number))
+                            .or_default()
+                            .insert(key.to_string());
+                    }
+                }
+            }
+            doc.insert(field.clone(), value.clone());
+        }
+        self.docs.insert(key.to_string(), doc);
+    }
+
+    /// Removes a document.
+    pub fn remove(&mut self, key: &str) {
+        let doc = match self.docs.remove(key) {
+            Some(doc) => doc,
+            None => return,
+        };
+        for (field, value) in doc {
+            match self.field_type(&field) {
+                Some(FieldType::Text) => {
+                    if let Some(postings) = self.terms.get_mut(&field) {
+                        for term in tokenize(&value) {
+                            remove_posting(postings, &term, key);
+                        }
+                    }
+                }
+                Some(FieldType::Tag) => {
+                    if let Some(postings) = self.tags.get_mut(&field) {
+                        for tag in split_tags(&value) {
+                            remove_posting(postings, &tag, key);
+                        }
+                    }
+                }
+                Some(FieldType::Numeric) => {
+                    if let (Ok(number), Some(values)) =
+                        (value.parse::<f64>(), self.numbers.get_mut(&field))
+                    {
+                        if let Some(keys) = values.get_mut(&Number(number)) {
+                            keys.remove(key);
+                            if keys.is_empty() {
+                                values.remove(&Number(number));
+                            }
+                        }
+                    }
+                }
+                None => {}
+            }
+        }
+    }
+
+    /// Returns every document key.
+    pub fn all(&self) -> BTreeSet<String> {
+        self.docs.keys().cloned().collect()
+    }
+
+    /// Returns the documents containing a term.
+    ///
+    /// # Arguments
+    ///
+    /// * `field` - The text field to search, `None` for every text field.
+    /// * `term` - The lowercase term.
+    /// * `prefix` - Matches every term starting with `term`.
+    pub fn term(&self, field: Option<&str>, term: &str, prefix: bool) -> BTreeSet<String> {
+        let mut result = BTreeSet::new();
+        for (name, postings) in self.terms.iter() {
+            if field.is_some_and(|field| field != name) {
+                continue;
+            }
+            if prefix {
+                for (candidate, keys) in postings {
+                    if candidate.starts_with(term) {
+                        result.extend(keys.iter().cloned());
+                    }
+                }
+            } else if let Some(keys) = postings.get(term) {
+                result.extend(keys.iter().cloned());
+            }
+        }
+        result
+    }
+
+    /// Returns the documents having one of the tags in a tag field.
+    pub fn tag(&self, field: &str, tags: &[String]) -> BTreeSet<String> {
+        let mut result = BTreeSet::new();
+        if let Some(postings) = self.tags.get(field) {
+            for tag in tags {
+                if let Some(keys) = postings.get(tag) {
+                    result.extend(keys.iter().cloned());
+                }
+            }
+        }
+        result
+    }
+
+    /// Returns the documents whose numeric field is between two bounds.
+    ///
+    /// # Arguments
+    ///
+    /// * `min` - The lower bound and whether it is exclusive.
+    /// * `max` - The upper bound and whether it is exclusive.
+    pub fn range(&self, field: &str, min: (f64, bool), max: (f64, bool)) -> BTreeSet<String> {
+        let mut result = BTreeSet::new();
+        if min.0 > max.0 {
+            return result;
+        }
+        if let Some(values) = self.numbers.get(field) {
+            for (number, keys) in values.range(Number(min.0)..=Number(max.0)) {
+                if (min.1 && number.0 == min.0) || (max.1 && number.0 == max.0) {
+                    continue;
+                }
+                result.extend(keys.iter().cloned());
+            }
+        }
+        result
+    }
+
+    /// Returns the indexed value of a field.
+    pub fn value(&self, key: &str, field: &str) -> Option<&str> {
+        self.docs.get(key)?.get(field).map(|value| value.as_str())
+    }
+
+    /// Returns the number of documents.
+    pub fn num_docs(&self) -> usize {
+        self.docs.len()
+    }
+}
+
+fn remove_posting(postings: &mut HashMap<String, BTreeSet<String>>, term: &str, key: &str) {
+    if let Some(keys) = postings.get_mut(term) {
+        keys.remove(key);
+        if keys.is_empty() {
+            postings.remove(term);
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use std::collections::{BTreeMap, HashSet};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::RwLock;
+
+use crate::lazyfree::LazyFree;
+use crate::modules::Modules;
+use crate::notify::parse_flags;
+use crate::output_buffer::{ClientClass, OutputBufferLimit, OutputBufferLimits};
+use crate::pubsub::PubSub;
+use crate::scripts::Scripts;
+use crate::watch::Watches;
+
+const NOTIFY_KEYSPACE_EVENTS: &str = "notify-keyspace-events";
+const CLIENT_OUTPUT_BUFFER_LIMIT: &str = "client-output-buffer-limit";
+
+/// Counters of server activity.
+#[derive(Debug, Default)]
+pub struct Stats {
+    expired_keys: AtomicU64,
+    evicted_keys: AtomicU64,
+    output_buffer_disconnections: AtomicU64,
+}
+
+impl Stats {
+    /// Counts a key deleted because its TTL elapsed.
+    pub fn add_expired_keys(&self, count: u64) {
+        self.expired_keys.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn expired_keys(&self) -> u64 {
+        self.expired_keys.load(Ordering::Relaxed)
+    }
+
+    /// Counts a key deleted to stay under `maxmemory`.
+    pub fn add_evicted_keys(&self, count: u64) {
+        self.evicted_keys.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn evicted_keys(&self) -> u64 {
+        self.evicted_keys.load(Ordering::Relaxed)
+    }
+
+    /// Counts a client disconnected for going over its output buffer limit.
+    pub fn add_output_buffer_disconnections(&self, count: u64) {
+        self.output_buffer_disconnections
+            .fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn output_buffer_disconnections(&self) -> u64 {
+        self.output_buffer_disconnections.load(Ordering::Relaxed)
+    }
+}
+
+/// Server state shared by every database, kept out of the user keyspace.
+#[derive(Debug, Default)]
+pub struct Server {
+    config: RwLock<BTreeMap<String, String>>,
+    pub stats: Stats,
+    pub lazy_free: LazyFree,
+    pub pubsub: PubSub,
+    pub watches: Watches,
+    pub scripts: Scripts,
+    pub modules: Modules,
+    // `notify-keyspace-events` parsed once, since every write reads it
+    notify_flags: AtomicU32,
+    // `client-output-buffer-limit` parsed once, since every message reads it
+    output_limits: RwLock<OutputBufferLimits>,
+    // the addresses clients reached the server at, MIGRATE refuses to send keys to them
+    addresses: RwLock<HashSet<SocketAddr>>,
+}
+
+impl Server {
+    /// Replaces the whole config, used when the config file is loaded.
+    pub fn load_config(&self, config: BTreeMap<String, String>) {
+        let flags = config
+            .get(NOTIFY_KEYSPACE_EVENTS)
+            .and_then(|value| parse_flags(value.trim_matches('"')))
+            .unwrap_or(0);
+        self.notify_flags.store(flags, Ordering::Relaxed);
+        let defaults = OutputBufferLimits::default();
+        *self.output_limits.write().unwrap() = config
+            .get(CLIENT_OUTPUT_BUFFER_LIMIT)
+            .and_then(|value| defaults.parse(value))
+            .unwrap_or(defaults);
+        *self.config.write().unwrap() = config;
+    }
+
+    pub fn config(&self) -> BTreeMap<String, String> {
+        self.config.read().unwrap().clone()
+    }
+
+    pub fn config_get(&self, name: &str) -> Option<String> {
+        self.config.read().unwrap().get(name).cloned()
+    }
+
+    /// Returns whether a `yes`/`no` option is `yes`, options not set are `no`.
+    pub fn config_flag(&self, name: &str) -> bool {
+        self.config_get(name)
+            .is_some_and(|value| value.eq_ignore_ascii_case("yes"))
+    }
+
+    /// Sets an option, `client-output-buffer-limit` only changes the classes it gives and is
+    /// then kept in bytes for every class.
+    pub fn config_set(&self, name: &str, value: &str) {
+        if name == NOTIFY_KEYSPACE_EVENTS {
+            let flags = parse_flags(value.trim_matches('"')).unwrap_or(0);
+            self.notify_flags.store(flags, Ordering::Relaxed);
+        }
+        if name == CLIENT_OUTPUT_BUFFER_LIMIT {
+            let mut limits = self.output_limits.write().unwrap();
+            if let Some(parsed) = limits.parse(value) {
+                *limits = parsed;
+            }
+            let value = limits.to_string();
+            self.config.write().unwrap().insert(name.to_string(), value);
+            return;
+        }
+        self.config
+            .write()
+            .unwrap()
+            .insert(name.to_string(), value.to_string());
+    }
+
+    /// Returns the classes of keyspace events to publish, see `Db::notify`.
+    pub fn notify_flags(&self) -> u32 {
+        self.notify_flags.load(Ordering::Relaxed)
+    }
+
+    /// Returns the output buffer limit of a class of clients.
+    pub fn output_limit(&self, class: ClientClass) -> OutputBufferLimit {
+        self.output_limits.read().unwrap().get(class)
+    }
+
+    /// Records an address a client reached the server at.
+    pub fn add_address(&self, addr: SocketAddr) {
+        if !self.addresses.read().unwrap().contains(&addr) {
+            self.addresses.write().unwrap().insert(addr);
+        }
+    }
+
+    /// Returns whether a client reached the server at `addr`.
+    pub fn has_address(&self, addr: &SocketAddr) -> bool {
+        self.addresses.read().unwrap().contains(addr)
+    }
+
+    /// Publishes a message to a channel, see `PubSub::publish`.
+    pub fn publish(&self, channel: &str, payload: &str) -> usize {
+        let limit = self.output_limit(ClientClass::PubSub);
+        self.pubsub.publish(channel, payload, &limit)
+    }
+
+    /// Publishes a message to a shard channel, see `PubSub::spublish`.
+    pub fn spublish(&self, channel: &str, payload: &str) -> usize {
+        let limit = self.output_limit(ClientClass::PubSub);
+        self.pubsub.spublish(channel, payload, &limit)
+    }
+}
@@ -0,0 +1,376 @@
+use crate::dump::{Reader, Writer, BAD_FORMAT};
+use std::collections::BTreeMap;
+
+/// How `TimeSeries::add` handles a sample whose timestamp already exists.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DuplicatePolicy {
+    Block,
+    First,
+    Last,
+    Min,
+    Max,
+    Sum,
+}
+
+impl DuplicatePolicy {
+    /// Parses a policy name, case insensitive.
+    pub fn parse(name: &str) -> Option<DuplicatePolicy> {
+        match name.to_lowercase().as_str() {
+            "block" => Some(DuplicatePolicy::Block),
+            "first" => Some(DuplicatePolicy::First),
+            "last" => Some(DuplicatePolicy::Last),
+            "min" => Some(DuplicatePolicy::Min),
+            "max" => Some(DuplicatePolicy::Max),
+            "sum" => Some(DuplicatePolicy::Sum),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DuplicatePolicy::Block => "block",
+            DuplicatePolicy::First => "first",
+            DuplicatePolicy::Last => "last",
+            DuplicatePolicy::Min => "min",
+            DuplicatePolicy::Max => "max",
+            DuplicatePolicy::Sum => "sum",
+        }
+    }
+}
+
+/// The function used to reduce the samples of a bucket to one value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Aggregation {
+    Avg,
+    Sum,
+    Min,
+    Max,
+    Count,
+    Last,
+}
+
+impl Aggregation {
+    /// Parses an aggregation name, case insensitive.
+    pub fn parse(name: &str) -> Option<Aggregation> {
+        match name.to_lowercase().as_str() {
+            "avg" => Some(Aggregation::Avg),
+            "sum" => Some(Aggregation::Sum),
+            "min" => Some(Aggregation::Min),
+            "max" => Some(Aggregation::Max),
+            "count" => Some(Aggregation::Count),
+            "last" => Some(Aggregation::Last),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Aggregation::Avg => "avg",
+            Aggregation::Sum => "sum",
+            Aggregation::Min => "min",
+            Aggregation::Max => "max",
+            Aggregation::Count => "count",
+            Aggregation::Last => "last",
+        }
+    }
+
+    /// Reduces the values of one bucket, which must not be empty.
+    pub fn apply(&self, values: &[f64]) -> f64 {
+        match self {
+            Aggregation::Avg => values.iter().sum::<f64>() / values.len() as f64,
+            Aggregation::Sum => values.iter().sum(),
+            Aggregation::Min => values.iter().cloned().fold(f64::INFINITY, f64::min),
+            Aggregation::Max => values.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+            Aggregation::Count => values.len() as f64,
+            Aggregation::Last => values.last().cloned().unwrap_or(0.0),
+        }
+    }
+}
+
+/// A compaction rule, stored in the source series.
+#[derive(Debug, Clone)]
+pub struct CompactionRule {
+    pub destination: String,
+    pub aggregation: Aggregation,
+    pub bucket_duration: u64,
+    // start of the newest bucket seen, the only one that is still open
+    latest_bucket: Option<u64>,
+}
+
+impl CompactionRule {
+    pub fn new(destination: String, aggregation: Aggregation, bucket_duration: u64) -> Self {
+        CompactionRule {
+            destination,
+            aggregation,
+            bucket_duration,
+            latest_bucket: None,
+        }
+    }
+}
+
+/// Groups samples into buckets aligned to multiples of `bucket_duration`.
+///
+/// # Returns
+///
+/// * One `(bucket start, aggregated value)` sample per non empty bucket, in time order.
+pub fn aggregate(
+    samples: &[(u64, f64)],
+    aggregation: Aggregation,
+    bucket_duration: u64,
+) -> Vec<(u64, f64)> {
+    let mut result = Vec::new();
+    let mut values = Vec::new();
+    let mut current: Option<u64> = None;
+    for (timestamp, value) in samples {
+        let bucket = timestamp - timestamp % bucket_duration;
+        if let Some(start) = current {
+            if start != bucket {
+                result.push((start, aggregation.apply(&values)));
+                values.clear();
+            }
+        }
+        current = Some(bucket);
+        values.push(*value);
+    }
+    if let Some(current) = current {
+        result.push((current, aggregation.apply(&values)));
+    }
+    result
+}
+
+/// A series of `(timestamp in ms, value)` samples.
+///
+/// Samples older than `retention` milliseconds relative to the newest sample are dropped,
+/// 0 keeps everything. A series may be the source of compaction rules or the destination
+/// of one, but not both.
+#[derive(Debug, Clone)]
+pub struct TimeSeries {
+    samples: BTreeMap<u64, f64>,
+    pub retention: u64,
+    pub duplicate_policy: DuplicatePolicy,
+    pub labels: Vec<(String, String)>,
+    pub rules: Vec<CompactionRule>,
+    pub source: Option<String>,
+}
+
+impl TimeSeries {
+    pub fn new(
+        retention: u64,
+        duplicate_policy: DuplicatePolicy,
+        labels: Vec<(String, String)>,
+    ) -> TimeSeries {
+        TimeSeries {
+            samples: BTreeMap::new(),
+            retention,
+            duplicate_policy,
+            labels,
+            rules: Vec::new(),
+            source: None,
+        }
+    }
+
+    /// Adds a sample.
+    ///
+    /// # Arguments
+    ///
+    /// * `timestamp` - The timestamp in milliseconds.
+    /// * `value` - The value.
+    /// * `policy` - Overrides the duplicate policy of the series for this sample.
+    ///
+    /// # Returns
+    ///
+    /// * The compacted samples to write to each rule destination, or an error message if the
+    ///   sample is outside the retention window or blocked as a duplicate.
+    pub fn add(
+        &mut self,
+        timestamp: u64,
+        value: f64,
+        policy: Option<DuplicatePolicy>,
+    ) -> Result<Vec<(String, u64, f64)>, &'static str> {
+        if self.retention > 0 {
+            if let Some((last, _)) = self.last() {
+                if timestamp < last.saturating_sub(self.retention) {
+                    return Err("ERR TSDB: Timestamp is older than retention");
+                }
+            }
+        }
+
+        let value = match self.samples.get(&timestamp) {
+            Some(old) => match policy.unwrap_or(self.duplicate_policy) {
+                DuplicatePolicy::Block => {
+                    return Err("ERR TSDB: Error at upsert, update is not supported when DUPLICATE_POLICY is set to BLOCK mode")
+                }
+                DuplicatePolicy::First => *old,
+                DuplicatePolicy::Last => value,
+                DuplicatePolicy::Min => old.min(value),
+                DuplicatePolicy::Max => old.max(value),
+                DuplicatePolicy::Sum => old + value,
+            },
+            None => value,
+        };
+        self.samples.insert(timestamp, value);
+        let compacted = self.compact(timestamp);
+        self.trim();
+        Ok(compacted)
+    }
+
+    /// Updates the rules after a sample was written at `timestamp`.
+    ///
+    /// A sample in a bucket before the newest one re-aggregates that closed bucket, a sample
+    /// opening a new bucket closes the previous one. The open bucket is not written yet.
+    fn compact(&mut self, timestamp: u64) -> Vec<(String, u64, f64)> {
+        let mut compacted = Vec::new();
+        for index in 0..self.rules.len() {
+            let duration = self.rules[index].bucket_duration;
+            let bucket = timestamp - timestamp % duration;
+            let closed = match self.rules[index].latest_bucket {
+                Some(latest) if bucket < latest => Some(bucket),
+                Some(latest) if bucket > latest => {
+                    self.rules[index].latest_bucket = Some(bucket);
+                    Some(latest)
+                }
+                Some(_) => None,
+                None => {
+                    self.rules[index].latest_bucket = Some(bucket);
+                    None
+                }
+            };
+            if let Some(closed) = closed {
+                let values: Vec<f64> = self
+                    .samples
+                    .range(closed..closed + duration)
+                    .map(|(_, value)| *value)
+                    .collect();
+                if !values.is_empty() {
+                    let rule = &self.rules[index];
+                    compacted.push((
+                        rule.destination.clone(),
+                        closed,
+                        rule.aggregation.apply(&values),
+                    ));
+                }
+            }
+        }
+        compacted
+    }
+
+    fn trim(&mut self) {
+        if self.retention == 0 {
+            return;
+        }
+        if let Some((last, _)) = self.last() {
+            let oldest = last.saturating_sub(self.retention);
+            self.samples = self.samples.split_off(&oldest);
+        }
+    }
+
+    /// Returns the newest sample.
+    pub fn last(&self) -> Option<(u64, f64)> {
+        self.samples
+            .iter()
+            .next_back()
+            .map(|(timestamp, value)| (*timestamp, *value))
+    }
+
+    /// Returns the samples between two timestamps, both inclusive, in time order.
+    pub fn range(&self, from: u64, to: u64) -> Vec<(u64, f64)> {
+        if from > to {
+            return Vec::new();
+        }
+        self.samples
+            .range(from..=to)
+            .map(|(timestamp, value)| (*timestamp, *value))
+            .collect()
+    }
+
+    /// Returns the number of samples.
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Returns the memory used by the samples and labels, in bytes.
+    pub fn size(&self) -> usize {
+        self.samples.len() * std::mem::size_of::<(u64, f64)>()
+            + self
+                .labels
+                .iter()
+                .map(|(name, value)| name.len() + value.len())
+                .sum::<usize>()
+            + std::mem::size_of::<TimeSeries>()
+    }
+
+    /// Returns the value of a label.
+    pub fn label(&self, name: &str) -> Option<&str> {
+        self.labels
+            .iter()
+            .find(|(label, _)| label == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+impl TimeSeries {
+    pub(crate) fn dump(&self, writer: &mut Writer) {
+        writer.u64(self.retention);
+        writer.str(self.duplicate_policy.name());
+        writer.usize(self.labels.len());
+        for (name, value) in &self.labels {
+            writer.str(name);
+            writer.str(value);
+        }
+        writer.usize(self.rules.len());
+        for rule in &self.rules {
+            writer.str(&rule.destination);
+            writer.str(rule.aggregation.name());
+            writer.u64(rule.bucket_duration);
+            writer.bool(rule.latest_bucket.is_some());
+            writer.u64(rule.latest_bucket.unwrap_or(0));
+        }
+        writer.bool(self.source.is_some());
+        writer.str(self.source.as_deref().unwrap_or(""));
+        writer.usize(self.samples.len());
+        for (timestamp, value) in &self.samples {
+            writer.u64(*timestamp);
+            writer.f64(*value);
+        }
+    }
+
+    pub(crate) fn restore(reader: &mut Reader) -> Result<TimeSeries, &'static str> {
+        let retention = reader.u64()?;
+        let duplicate_policy = DuplicatePolicy::parse(&reader.string()?).ok_or(BAD_FORMAT)?;
+        let labels = (0..reader.count(16)?)
+            .map(|_| Ok((reader.string()?, reader.string()?)))
+            .collect::<Result<Vec<(String, String)>, &'static str>>()?;
+        let rules = (0..reader.count(33)?)
+            .map(|_| {
+                let destination = reader.string()?;
+                let aggregation = Aggregation::parse(&reader.string()?).ok_or(BAD_FORMAT)?;
+                let bucket_duration = reader.u64()?;
+                let has_latest = reader.bool()?;
+                let latest_bucket = reader.u64()?;
+                Ok(CompactionRule {
+                    destination,
+                    aggregation,
+                    bucket_duration,
+                    latest_bucket: has_latest.then_some(latest_bucket),
+                })
+            })
+            .collect::<Result<Vec<CompactionRule>, &'static str>>()?;
+        let has_source = reader.bool()?;
+        let source = reader.string()?;
+        let samples = (0..reader.count(16)?)
+            .map(|_| Ok((reader.u64()?, reader.f64()?)))
+            .collect::<Result<BTreeMap<u64, f64>, &'static str>>()?;
+        Ok(TimeSeries {
+            samples,
+            retention,
+            duplicate_policy,
+            labels,
+            rules,
+            source: has_source.then_some(source),
+        })
+    }
+}
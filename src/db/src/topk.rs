@@ -0,0 +1,218 @@
+use crate::dump::{Reader, Writer, BAD_FORMAT};
+use crate::hash::hash64;
+use rand::Rng;
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Bucket {
+    fingerprint: u64,
+    count: u64,
+}
+
+/// A top-k list backed by a HeavyKeeper sketch.
+///
+/// Each of the `depth` rows maps an item to one bucket holding a fingerprint and a count.
+/// A matching fingerprint increments the count, a different one decays it with probability
+/// `decay^count` and takes the bucket over once it reaches zero, so heavy hitters keep their
+/// buckets while rare items are pushed out. The `k` largest estimates are kept in a list.
+#[derive(Debug, Clone)]
+pub struct TopK {
+    k: usize,
+    width: usize,
+    depth: usize,
+    decay: f64,
+    buckets: Vec<Bucket>,
+    // (item, count), at most `k` entries
+    heap: Vec<(String, u64)>,
+}
+
+impl TopK {
+    /// Creates an empty top-k list.
+    pub fn new(k: usize, width: usize, depth: usize, decay: f64) -> TopK {
+        TopK {
+            k,
+            width,
+            depth,
+            decay,
+            buckets: vec![Bucket::default(); width * depth],
+            heap: Vec::with_capacity(k),
+        }
+    }
+
+    fn fingerprint(item: &str) -> u64 {
+        // 0 marks an empty bucket
+        hash64(item.as_bytes(), u64::MAX).max(1)
+    }
+
+    fn index(&self, row: usize, item: &str) -> usize {
+        row * self.width + (hash64(item.as_bytes(), row as u64) % self.width as u64) as usize
+    }
+
+    fn min_position(&self) -> Option<usize> {
+        self.heap
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, (_, count))| *count)
+            .map(|(position, _)| position)
+    }
+
+    /// Increments an item.
+    ///
+    /// # Returns
+    ///
+    /// * The item that was expelled from the top-k list to make room for this one, if any.
+    pub fn incr_by(&mut self, item: &str, increment: u64) -> Option<String> {
+        if increment == 0 {
+            return None;
+        }
+        let fingerprint = TopK::fingerprint(item);
+        let mut rng = rand::thread_rng();
+        let mut max_count = 0;
+        for row in 0..self.depth {
+            let index = self.index(row, item);
+            let bucket = &mut self.buckets[index];
+            if bucket.count == 0 || bucket.fingerprint == fingerprint {
+                bucket.fingerprint = fingerprint;
+                bucket.count = bucket.count.saturating_add(increment);
+                max_count = max_count.max(bucket.count);
+                continue;
+            }
+            for used in 0..increment {
+                let decay = self.decay.powf(bucket.count as f64);
+                if rng.gen::<f64>() < decay {
+                    bucket.count -= 1;
+                    if bucket.count == 0 {
+                        bucket.fingerprint = fingerprint;
+                        bucket.count = increment - used;
+                        max_count = max_count.max(bucket.count);
+                        break;
+                    }
+                }
+            }
+        }
+
+        if let Some(entry) = self.heap.iter_mut().find(|(name, _)| name == item) {
+            entry.1 = entry.1.max(max_count);
+            return None;
+        }
+        if self.heap.len() < self.k {
+            if max_count > 0 {
+                self.heap.push((item.to_string(), max_count));
+            }
+            return None;
+        }
+        match self.min_position() {
+            Some(position) if max_count > self.heap[position].1 => {
+                let expelled =
+                    std::mem::replace(&mut self.heap[position], (item.to_string(), max_count));
+                Some(expelled.0)
+            }
+            _ => None,
+        }
+    }
+
+    /// Checks whether an item is in the top-k list.
+    pub fn query(&self, item: &str) -> bool {
+        self.heap.iter().any(|(name, _)| name == item)
+    }
+
+    /// Returns the estimated count of an item from the sketch.
+    pub fn count(&self, item: &str) -> u64 {
+        let fingerprint = TopK::fingerprint(item);
+        (0..self.depth)
+            .map(|row| self.buckets[self.index(row, item)])
+            .filter(|bucket| bucket.fingerprint == fingerprint)
+            .map(|bucket| bucket.count)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Returns the top-k items with their counts, highest count first.
+    pub fn list(&self) -> Vec<(String, u64)> {
+        let mut list = self.heap.clone();
+        list.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        list
+    }
+
+    /// Returns the number of items kept.
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// Returns the number of buckets per row.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the number of rows.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the decay base.
+    pub fn decay(&self) -> f64 {
+        self.decay
+    }
+
+    /// Returns the memory used by the buckets and the list, in bytes.
+    pub fn size(&self) -> usize {
+        self.buckets.len() * std::mem::size_of::<Bucket>()
+            + self
+                .heap
+                .iter()
+                .map(|(item, _)| item.len() + std::mem::size_of::<(String, u64)>())
+                .sum::<usize>()
+            + std::mem::size_of::<TopK>()
+    }
+}
+
+impl TopK {
+    pub(crate) fn dump(&self, writer: &mut Writer) {
+        writer.usize(self.k);
+        writer.usize(self.width);
+        writer.usize(self.depth);
+        writer.f64(self.decay);
+        writer.usize(self.buckets.len());
+        for bucket in &self.buckets {
+            writer.u64(bucket.fingerprint);
+            writer.u64(bucket.count);
+        }
+        writer.usize(self.heap.len());
+        for (item, count) in &self.heap {
+            writer.str(item);
+            writer.u64(*count);
+        }
+    }
+
+    pub(crate) fn restore(reader: &mut Reader) -> Result<TopK, &'static str> {
+        let k = reader.usize()?;
+        let width = reader.usize()?;
+        let depth = reader.usize()?;
+        let decay = reader.f64()?;
+        let buckets = (0..reader.count(16)?)
+            .map(|_| {
+                Ok(Bucket {
+                    fingerprint: reader.u64()?,
+                    count: reader.u64()?,
+                })
+            })
+            .collect::<Result<Vec<Bucket>, &'static str>>()?;
+        let heap = (0..reader.count(16)?)
+            .map(|_| Ok((reader.string()?, reader.u64()?)))
+            .collect::<Result<Vec<(String, u64)>, &'static str>>()?;
+        if width == 0
+            || depth == 0
+            || width.checked_mul(depth) != Some(buckets.len())
+            || heap.len() > k
+        {
+            return Err(BAD_FORMAT);
+        }
+        Ok(TopK {
+            k,
+            width,
+            depth,
+            decay,
+            buckets,
+            heap,
+        })
+    }
+}
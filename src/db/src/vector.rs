@@ -0,0 +1,678 @@
+use crate::dump::{Reader, Writer, BAD_FORMAT};
+use rand::Rng;
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+/// A predicate over the attributes of an element, used to filter search results.
+pub type AttributeFilter<'a> = &'a dyn Fn(Option<&Value>) -> bool;
+
+/// How vectors are stored.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Quantization {
+    // full precision floats
+    NoQuant,
+    // signed bytes with one scale per vector
+    Q8,
+}
+
+impl Quantization {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Quantization::NoQuant => "f32",
+            Quantization::Q8 => "int8",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Stored {
+    F32(Vec<f32>),
+    Q8(Vec<i8>, f32),
+}
+
+impl Stored {
+    fn new(unit: &[f32], quantization: Quantization) -> Stored {
+        match quantization {
+            Quantization::NoQuant => Stored::F32(unit.to_vec()),
+            Quantization::Q8 => {
+                let max = unit.iter().fold(0f32, |max, x| max.max(x.abs()));
+                let scale = if max > 0.0 { max / 127.0 } else { 1.0 };
+                let values = unit
+                    .iter()
+                    .map(|x| (x / scale).round().clamp(-127.0, 127.0) as i8)
+                    .collect();
+                Stored::Q8(values, scale)
+            }
+        }
+    }
+
+    fn to_f32(&self) -> Vec<f32> {
+        match self {
+            Stored::F32(values) => values.clone(),
+            Stored::Q8(values, scale) => values.iter().map(|x| *x as f32 * scale).collect(),
+        }
+    }
+
+    fn dot(&self, other: &Stored) -> f32 {
+        match (self, other) {
+            (Stored::F32(a), Stored::F32(b)) => a.iter().zip(b).map(|(x, y)| x * y).sum(),
+            (Stored::Q8(a, sa), Stored::Q8(b, sb)) => {
+                let dot: i32 = a.iter().zip(b).map(|(x, y)| *x as i32 * *y as i32).sum();
+                dot as f32 * sa * sb
+            }
+            (Stored::F32(a), Stored::Q8(b, scale)) | (Stored::Q8(b, scale), Stored::F32(a)) => {
+                let dot: f32 = a.iter().zip(b).map(|(x, y)| x * *y as f32).sum();
+                dot * scale
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    name: String,
+    vector: Stored,
+    // the length of the vector before it was normalized
+    norm: f32,
+    // neighbor ids per layer, `neighbors.len() - 1` is the level of the node
+    neighbors: Vec<Vec<usize>>,
+    attributes: Option<Value>,
+}
+
+// a node id with its distance to the query, ordered by distance
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Candidate {
+    distance: f32,
+    id: usize,
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance
+            .total_cmp(&other.distance)
+            .then_with(|| self.id.cmp(&other.id))
+    }
+}
+
+/// A set of named vectors indexed with HNSW for approximate nearest neighbour search.
+///
+/// Vectors are normalized on insert so the cosine distance `1 - a·b` is a dot product.
+/// Each node is placed on a random number of layers, the upper layers are sparse and
+/// used to get close to the query quickly, the bottom layer holds every node. Nodes are
+/// linked to at most `m` neighbors per layer, `2 * m` on the bottom one.
+#[derive(Debug, Clone)]
+pub struct VectorSet {
+    dim: usize,
+    quantization: Quantization,
+    m: usize,
+    ef_construction: usize,
+    nodes: Vec<Option<Node>>,
+    // ids of removed nodes, reused by later inserts
+    free: Vec<usize>,
+    names: HashMap<String, usize>,
+    entry: Option<usize>,
+}
+
+impl VectorSet {
+    /// Creates an empty set.
+    ///
+    /// # Arguments
+    ///
+    /// * `dim` - The number of dimensions of every vector.
+    /// * `quantization` - How vectors are stored.
+    /// * `m` - The number of links per node and layer.
+    /// * `ef_construction` - The number of candidates explored when a node is linked.
+    pub fn new(dim: usize, quantization: Quantization, m: usize, ef_construction: usize) -> Self {
+        VectorSet {
+            dim,
+            quantization,
+            m: m.max(2),
+            ef_construction: ef_construction.max(1),
+            nodes: Vec::new(),
+            free: Vec::new(),
+            names: HashMap::new(),
+            entry: None,
+        }
+    }
+
+    fn node(&self, id: usize) -> &Node {
+        self.nodes[id].as_ref().expect("linked node exists")
+    }
+
+    fn node_mut(&mut self, id: usize) -> &mut Node {
+        self.nodes[id].as_mut().expect("linked node exists")
+    }
+
+    fn level(&self, id: usize) -> usize {
+        self.node(id).neighbors.len() - 1
+    }
+
+    fn max_links(&self, layer: usize) -> usize {
+        if layer == 0 {
+            self.m * 2
+        } else {
+            self.m
+        }
+    }
+
+    fn distance(&self, query: &Stored, id: usize) -> f32 {
+        1.0 - query.dot(&self.node(id).vector)
+    }
+
+    fn random_level(&self) -> usize {
+        let ml = 1.0 / (self.m as f64).ln();
+        let r: f64 = rand::thread_rng().gen_range(f64::EPSILON..1.0);
+        ((-r.ln() * ml).floor() as usize).min(16)
+    }
+
+    /// Normalizes a vector, returning it with its original length.
+    fn normalize(vector: &[f32]) -> (Vec<f32>, f32) {
+        let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+        if norm == 0.0 {
+            return (vector.to_vec(), 0.0);
+        }
+        (vector.iter().map(|x| x / norm).collect(), norm)
+    }
+
+    /// Moves greedily towards the query on one layer.
+    fn greedy(&self, query: &Stored, mut current: Candidate, layer: usize) -> Candidate {
+        loop {
+            let mut changed = false;
+            for &neighbor in &self.node(current.id).neighbors[layer] {
+                let distance = self.distance(query, neighbor);
+                if distance < current.distance {
+                    current = Candidate {
+                        distance,
+                        id: neighbor,
+                    };
+                    changed = true;
+                }
+            }
+            if !changed {
+                return current;
+            }
+        }
+    }
+
+    /// Beam search on one layer.
+    ///
+    /// # Returns
+    ///
+    /// * Up to `ef` nodes closest to the query, closest first.
+    fn search_layer(
+        &self,
+        query: &Stored,
+        entry: Candidate,
+        ef: usize,
+        layer: usize,
+    ) -> Vec<Candidate> {
+        let mut visited = HashSet::from([entry.id]);
+        // min heap of nodes to expand
+        let mut candidates = BinaryHeap::from([std::cmp::Reverse(entry)]);
+        // max heap of the best nodes found
+        let mut found = BinaryHeap::from([entry]);
+        while let Some(std::cmp::Reverse(current)) = candidates.pop() {
+            let worst = found.peek().map_or(f32::INFINITY, |c| c.distance);
+            if current.distance > worst && found.len() >= ef {
+                break;
+            }
+            for &neighbor in &self.node(current.id).neighbors[layer] {
+                if !visited.insert(neighbor) {
+                    continue;
+                }
+                let candidate = Candidate {
+                    distance: self.distance(query, neighbor),
+                    id: neighbor,
+                };
+                let worst = found.peek().map_or(f32::INFINITY, |c| c.distance);
+                if found.len() < ef || candidate.distance < worst {
+                    candidates.push(std::cmp::Reverse(candidate));
+                    found.push(candidate);
+                    if found.len() > ef {
+                        found.pop();
+                    }
+                }
+            }
+        }
+        found.into_sorted_vec()
+    }
+
+    /// Walks from the entry point down to `layer`, keeping the closest node.
+    fn descend(&self, query: &Stored, layer: usize) -> Option<Candidate> {
+        let entry = self.entry?;
+        let mut current = Candidate {
+            distance: self.distance(query, entry),
+            id: entry,
+        };
+        for upper in (layer + 1..=self.level(entry)).rev() {
+            current = self.greedy(query, current, upper);
+        }
+        Some(current)
+    }
+
+    /// Keeps the closest `max` links of a node on one layer.
+    fn prune(&mut self, id: usize, layer: usize) {
+        let max = self.max_links(layer);
+        if self.node(id).neighbors[layer].len() <= max {
+            return;
+        }
+        let vector = self.node(id).vector.clone();
+        let mut links: Vec<Candidate> = self.node(id).neighbors[layer]
+            .iter()
+            .map(|&neighbor| Candidate {
+                distance: self.distance(&vector, neighbor),
+                id: neighbor,
+            })
+            .collect();
+        links.sort();
+        links.truncate(max);
+        self.node_mut(id).neighbors[layer] = links.iter().map(|c| c.id).collect();
+    }
+
+    /// Links a node that is already stored into the graph.
+    fn link(&mut self, id: usize) {
+        let level = self.level(id);
+        let entry = match self.entry {
+            Some(entry) => entry,
+            None => {
+                self.entry = Some(id);
+                return;
+            }
+        };
+        let query = self.node(id).vector.clone();
+        let top = self.level(entry);
+        let mut current = match self.descend(&query, level.min(top)) {
+            Some(current) => current,
+            None => return,
+        };
+        for layer in (0..=level.min(top)).rev() {
+            let found = self.search_layer(&query, current, self.ef_construction, layer);
+            current = found[0];
+            let neighbors: Vec<usize> = found
+                .iter()
+                .filter(|c| c.id != id)
+                .take(self.max_links(layer))
+                .map(|c| c.id)
+                .collect();
+            for &neighbor in &neighbors {
+                self.node_mut(neighbor).neighbors[layer].push(id);
+                self.prune(neighbor, layer);
+            }
+            self.node_mut(id).neighbors[layer] = neighbors;
+        }
+        if level > top {
+            self.entry = Some(id);
+        }
+    }
+
+    /// Adds an element or replaces the vector of an existing one.
+    ///
+    /// The attributes of an existing element are kept unless new ones are given.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(true)` if the element was added, `Ok(false)` if it was updated, or an error if
+    ///   the dimension does not match.
+    pub fn add(
+        &mut self,
+        name: &str,
+        vector: &[f32],
+        attributes: Option<Value>,
+    ) -> Result<bool, &'static str> {
+        if vector.len() != self.dim {
+            return Err("ERR Vector dimension mismatch");
+        }
+        let mut attributes = attributes;
+        let exists = self.names.contains_key(name);
+        if exists {
+            let old = self.remove(name);
+            if attributes.is_none() {
+                attributes = old;
+            }
+        }
+        let (unit, norm) = VectorSet::normalize(vector);
+        let node = Node {
+            name: name.to_string(),
+            vector: Stored::new(&unit, self.quantization),
+            norm,
+            neighbors: vec![Vec::new(); self.random_level() + 1],
+            attributes,
+        };
+        let id = match self.free.pop() {
+            Some(id) => {
+                self.nodes[id] = Some(node);
+                id
+            }
+            None => {
+                self.nodes.push(Some(node));
+                self.nodes.len() - 1
+            }
+        };
+        self.names.insert(name.to_string(), id);
+        self.link(id);
+        Ok(!exists)
+    }
+
+    /// Removes an element and repairs the links of its neighbors.
+    ///
+    /// # Returns
+    ///
+    /// * The attributes of the element if it existed, `None` otherwise or when it had none.
+    pub fn remove(&mut self, name: &str) -> Option<Value> {
+        let id = self.names.remove(name)?;
+        let node = self.nodes[id].take()?;
+        self.free.push(id);
+
+        // links are not always symmetric after pruning, so every node is checked
+        for other in self.nodes.iter_mut().flatten() {
+            for links in other.neighbors.iter_mut() {
+                links.retain(|&n| n != id);
+            }
+        }
+        // reconnect the former neighbors through each other
+        for (layer, links) in node.neighbors.iter().enumerate() {
+            for &neighbor in links {
+                if self.nodes[neighbor].is_none() {
+                    continue;
+                }
+                for &other in links {
+                    if other != neighbor
+                        && self.nodes[other].is_some()
+                        && !self.node(neighbor).neighbors[layer].contains(&other)
+                    {
+                        self.node_mut(neighbor).neighbors[layer].push(other);
+                    }
+                }
+                self.prune(neighbor, layer);
+            }
+        }
+
+        if self.entry == Some(id) {
+            self.entry = self
+                .nodes
+                .iter()
+                .enumerate()
+                .filter_map(|(id, node)| node.as_ref().map(|node| (id, node.neighbors.len())))
+                .max_by_key(|(_, levels)| *levels)
+                .map(|(id, _)| id);
+        }
+        node.attributes
+    }
+
+    /// Finds the elements closest to a vector.
+    ///
+    /// # Arguments
+    ///
+    /// * `vector` - The query, with the dimension of the set.
+    /// * `count` - The number of results.
+    /// * `ef` - The number of candidates explored on the bottom layer.
+    /// * `filter` - Keeps only the elements whose attributes it accepts.
+    ///
+    /// # Returns
+    ///
+    /// * `(element, similarity)` pairs, most similar first. The similarity goes from 0 for
+    ///   opposite vectors to 1 for vectors pointing the same way.
+    pub fn search(
+        &self,
+        vector: &[f32],
+        count: usize,
+        ef: usize,
+        filter: Option<AttributeFilter>,
+    ) -> Vec<(String, f32)> {
+        let (unit, _) = VectorSet::normalize(vector);
+        let query = Stored::new(&unit, Quantization::NoQuant);
+        let entry = match self.descend(&query, 0) {
+            Some(entry) => entry,
+            None => return Vec::new(),
+        };
+        self.search_layer(&query, entry, ef.max(count), 0)
+            .into_iter()
+            .filter(|c| filter.is_none_or(|filter| filter(self.node(c.id).attributes.as_ref())))
+            .take(count)
+            .map(|c| {
+                let similarity = (1.0 - c.distance / 2.0).clamp(0.0, 1.0);
+                (self.node(c.id).name.clone(), similarity)
+            })
+            .collect()
+    }
+
+    /// Returns the vector of an element, rescaled to its original length.
+    ///
+    /// Quantized sets return an approximation.
+    pub fn embedding(&self, name: &str) -> Option<Vec<f32>> {
+        let node = self.node(*self.names.get(name)?);
+        Some(node.vector.to_f32().iter().map(|x| x * node.norm).collect())
+    }
+
+    /// Returns the attributes of an element.
+    ///
+    /// # Returns
+    ///
+    /// * `None` if the element does not exist, `Some(None)` if it has no attributes.
+    pub fn attributes(&self, name: &str) -> Option<Option<&Value>> {
+        let node = self.node(*self.names.get(name)?);
+        Some(node.attributes.as_ref())
+    }
+
+    /// Replaces the attributes of an element.
+    ///
+    /// # Returns
+    ///
+    /// * `false` if the element does not exist.
+    pub fn set_attributes(&mut self, name: &str, attributes: Option<Value>) -> bool {
+        match self.names.get(name).copied() {
+            Some(id) => {
+                self.node_mut(id).attributes = attributes;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Checks whether an element exists.
+    pub fn contains(&self, name: &str) -> bool {
+        self.names.contains_key(name)
+    }
+
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Returns the memory used by the nodes and their links, in bytes.
+    pub fn size(&self) -> usize {
+        self.nodes
+            .iter()
+            .flatten()
+            .map(|node| {
+                let vector = match &node.vector {
+                    Stored::F32(vector) => vector.len() * std::mem::size_of::<f32>(),
+                    Stored::Q8(vector, _) => vector.len(),
+                };
+                let links: usize = node.neighbors.iter().map(|layer| layer.len()).sum();
+                node.name.len() * 2
+                    + vector
+                    + links * std::mem::size_of::<usize>()
+                    + node
+                        .attributes
+                        .as_ref()
+                        .map_or(0, |value| value.to_string().len())
+                    + std::mem::size_of::<Node>()
+            })
+            .sum::<usize>()
+            + std::mem::size_of::<VectorSet>()
+    }
+
+    /// Returns the number of dimensions of every vector.
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+
+    /// Returns how vectors are stored.
+    pub fn quantization(&self) -> Quantization {
+        self.quantization
+    }
+
+    /// Returns the number of links per node and layer.
+    pub fn m(&self) -> usize {
+        self.m
+    }
+
+    /// Returns the level of the entry point, the highest layer of the graph.
+    pub fn max_level(&self) -> usize {
+        self.entry.map_or(0, |entry| self.level(entry))
+    }
+}
+
+impl VectorSet {
+    pub(crate) fn dump(&self, writer: &mut Writer) {
+        writer.usize(self.dim);
+        writer.bool(self.quantization == Quantization::Q8);
+        writer.usize(self.m);
+        writer.usize(self.ef_construction);
+        writer.bool(self.entry.is_some());
+        writer.usize(self.entry.unwrap_or(0));
+        writer.usize(self.nodes.len());
+        for node in &self.nodes {
+            writer.bool(node.is_some());
+            let node = match node {
+                Some(node) => node,
+                None => continue,
+            };
+            writer.str(&node.name);
+            match &node.vector {
+                Stored::F32(values) => {
+                    writer.usize(values.len());
+                    for value in values {
+                        writer.f32(*value);
+                    }
+                }
+                Stored::Q8(values, scale) => {
+                    writer.f32(*scale);
+                    let bytes: Vec<u8> = values.iter().map(|value| *value as u8).collect();
+                    writer.bytes(&bytes);
+                }
+            }
+            writer.f32(node.norm);
+            writer.usize(node.neighbors.len());
+            for layer in &node.neighbors {
+                writer.usize(layer.len());
+                for id in layer {
+                    writer.usize(*id);
+                }
+            }
+            writer.bool(node.attributes.is_some());
+            let attributes = node.attributes.as_ref().map(|value| value.to_string());
+            writer.str(attributes.as_deref().unwrap_or(""));
+        }
+    }
+
+    pub(crate) fn restore(reader: &mut Reader) -> Result<VectorSet, &'static str> {
+        let dim = reader.usize()?;
+        let quantization = match reader.bool()? {
+            true => Quantization::Q8,
+            false => Quantization::NoQuant,
+        };
+        let m = reader.usize()?;
+        let ef_construction = reader.usize()?;
+        let has_entry = reader.bool()?;
+        let entry = reader.usize()?;
+        let mut nodes = Vec::new();
+        for _ in 0..reader.count(1)? {
+            if !reader.bool()? {
+                nodes.push(None);
+                continue;
+            }
+            let name = reader.string()?;
+            let vector = match quantization {
+                Quantization::NoQuant => Stored::F32(
+                    (0..reader.count(4)?)
+                        .map(|_| reader.f32())
+                        .collect::<Result<Vec<f32>, _>>()?,
+                ),
+                Quantization::Q8 => {
+                    let scale = reader.f32()?;
+                    let values = reader.bytes()?.into_iter().map(|byte| byte as i8).collect();
+                    Stored::Q8(values, scale)
+                }
+            };
+            let norm = reader.f32()?;
+            let neighbors = (0..reader.count(8)?)
+                .map(|_| {
+                    (0..reader.count(8)?)
+                        .map(|_| reader.usize())
+                        .collect::<Result<Vec<usize>, _>>()
+                })
+                .collect::<Result<Vec<Vec<usize>>, _>>()?;
+            let has_attributes = reader.bool()?;
+            let attributes = reader.string()?;
+            let attributes = match has_attributes {
+                true => Some(serde_json::from_str(&attributes).map_err(|_| BAD_FORMAT)?),
+                false => None,
+            };
+            nodes.push(Some(Node {
+                name,
+                vector,
+                norm,
+                neighbors,
+                attributes,
+            }));
+        }
+
+        // links, the entry point and names must point to live nodes
+        let live = |id: &usize| nodes.get(*id).is_some_and(|node| node.is_some());
+        let mut names = HashMap::new();
+        let mut free = Vec::new();
+        for (id, node) in nodes.iter().enumerate() {
+            let node = match node {
+                Some(node) => node,
+                None => {
+                    free.push(id);
+                    continue;
+                }
+            };
+            let len = match &node.vector {
+                Stored::F32(values) => values.len(),
+                Stored::Q8(values, _) => values.len(),
+            };
+            if len != dim
+                || node.neighbors.is_empty()
+                || !node.neighbors.iter().flatten().all(live)
+                || names.insert(node.name.clone(), id).is_some()
+            {
+                return Err(BAD_FORMAT);
+            }
+        }
+        let entry = has_entry.then_some(entry);
+        if m < 2
+            || ef_construction == 0
+            || entry.is_some_and(|entry| !live(&entry))
+            || entry.is_none() != names.is_empty()
+        {
+            return Err(BAD_FORMAT);
+        }
+        Ok(VectorSet {
+            dim,
+            quantization,
+            m,
+            ef_construction,
+            nodes,
+            free,
+            names,
+            entry,
+        })
+    }
+}
@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+// the flags of the clients watching a key, by database number and key
+type Watchers = HashMap<(usize, String), Vec<Arc<AtomicBool>>>;
+
+/// The keys watched by clients with `WATCH`, so a change aborts their next `EXEC`.
+///
+/// Every client has one dirty flag, set when any key it watches is modified, deleted,
+/// expired or evicted. The keys are by database number, like the keyspace event channels.
+#[derive(Debug, Default)]
+pub struct Watches {
+    keys: Mutex<Watchers>,
+    // the number of watched keys, so writes skip the lock while nobody watches
+    count: AtomicUsize,
+}
+
+impl Watches {
+    /// Watches a key for the client owning `dirty`.
+    pub fn watch(&self, db: usize, key: &str, dirty: &Arc<AtomicBool>) {
+        let mut keys = self.keys.lock().unwrap();
+        let watchers = keys.entry((db, key.to_string())).or_default();
+        if !watchers.iter().any(|watcher| Arc::ptr_eq(watcher, dirty)) {
+            watchers.push(Arc::clone(dirty));
+        }
+        self.count.store(keys.len(), Ordering::Relaxed);
+    }
+
+    /// Stops watching a key for the client owning `dirty`.
+    pub fn unwatch(&self, db: usize, key: &str, dirty: &Arc<AtomicBool>) {
+        let mut keys = self.keys.lock().unwrap();
+        let name = (db, key.to_string());
+        if let Some(watchers) = keys.get_mut(&name) {
+            watchers.retain(|watcher| !Arc::ptr_eq(watcher, dirty));
+            if watchers.is_empty() {
+                keys.remove(&name);
+            }
+        }
+        self.count.store(keys.len(), Ordering::Relaxed);
+    }
+
+    /// Returns whether no client watches any key.
+    pub fn is_empty(&self) -> bool {
+        self.count.load(Ordering::Relaxed) == 0
+    }
+
+    /// Marks the clients watching a key as dirty.
+    pub fn touch(&self, db: usize, key: &str) {
+        if self.is_empty() {
+            return;
+        }
+        if let Some(watchers) = self.keys.lock().unwrap().get(&(db, key.to_string())) {
+            for watcher in watchers {
+                watcher.store(true, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Marks the clients watching any key of a database as dirty, when it is flushed or
+    /// swapped.
+    pub fn touch_db(&self, db: usize) {
+        if self.is_empty() {
+            return;
+        }
+        for ((watched_db, _), watchers) in self.keys.lock().unwrap().iter() {
+            if *watched_db == db {
+                for watcher in watchers {
+                    watcher.store(true, Ordering::Relaxed);
+                }
+            }
+        }
+    }
+}
@@ -5,14 +5,34 @@ use hashmap_commands::hashmap::HashMapCommand;
 use ping_commands::ping::PingCommand;
 use string_commands::string::StringCommand;
 use utils_commands::utils::UtilsCommand;
-use crate::command_init::{EXPIRED_COMMANDS, HASHMAP_COMMANDS, STRING_COMMANDS, UTILS_COMMANDS};
-use std::collections::HashMap;
+use geo_commands::geo::GeoCommand;
+use json_commands::json::JsonCommand;
+use probabilistic_commands::probabilistic::ProbabilisticCommand;
+use timeseries_commands::timeseries::TimeSeriesCommand;
+use vector_commands::vector::VectorCommand;
+use search_commands::search::SearchCommand;
+use module_commands::module::ModuleCommand;
+use module_commands::registry;
+use rus_key_db::server::Server;
+use crate::command_init::{
+    DATABASE_COMMANDS, DENYOOM_COMMANDS, EXPIRED_COMMANDS, GEO_COMMANDS, HASHMAP_COMMANDS, JSON_COMMANDS,
+    PROBABILISTIC_COMMANDS, PUBSUB_COMMANDS, SCRIPT_COMMANDS, SEARCH_COMMANDS, STRING_COMMANDS,
+    TIMESERIES_COMMANDS, TRANSACTION_COMMANDS, UTILS_COMMANDS, VECTOR_COMMANDS,
+};
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+
+// the commands built in, including the ones handled by the server, modules cannot take them
+static BUILTIN_COMMANDS: OnceLock<HashSet<String>> = OnceLock::new();
 
 pub struct CommandFactory {
     commands: HashMap<String, Box<dyn Command>>,
+    // the commands registered by modules, they may write anything
+    module_commands: HashSet<String>,
 }
 
 impl CommandFactory {
+    /// Creates the built in commands, without the ones of modules.
     pub fn new() -> CommandFactory {
         let mut commands: HashMap<String, Box<dyn Command>> = HashMap::new();
         // Config
@@ -51,7 +71,90 @@ impl CommandFactory {
                 Box::new(UtilsCommand::new(command.to_string())),
             );
         }
-        CommandFactory { commands }
+
+        // Geo
+        for command in GEO_COMMANDS.iter() {
+            commands.insert(
+                command.to_string(),
+                Box::new(GeoCommand::new(command.to_string())),
+            );
+        }
+
+        // Json
+        for command in JSON_COMMANDS.iter() {
+            commands.insert(
+                command.to_string(),
+                Box::new(JsonCommand::new(command.to_string())),
+            );
+        }
+
+        // Probabilistic
+        for command in PROBABILISTIC_COMMANDS.iter() {
+            commands.insert(
+                command.to_string(),
+                Box::new(ProbabilisticCommand::new(command.to_string())),
+            );
+        }
+
+        // TimeSeries
+        for command in TIMESERIES_COMMANDS.iter() {
+            commands.insert(
+                command.to_string(),
+                Box::new(TimeSeriesCommand::new(command.to_string())),
+            );
+        }
+
+        // Vector
+        for command in VECTOR_COMMANDS.iter() {
+            commands.insert(
+                command.to_string(),
+                Box::new(VectorCommand::new(command.to_string())),
+            );
+        }
+
+        // Search
+        for command in SEARCH_COMMANDS.iter() {
+            commands.insert(
+                command.to_string(),
+                Box::new(SearchCommand::new(command.to_string())),
+            );
+        }
+
+        // Module
+        let builtin = BUILTIN_COMMANDS.get_or_init(|| {
+            let mut builtin: HashSet<String> = commands.keys().cloned().collect();
+            builtin.insert("module".to_string());
+            for command in DATABASE_COMMANDS
+                .iter()
+                .chain(PUBSUB_COMMANDS.iter())
+                .chain(SCRIPT_COMMANDS.iter())
+                .chain(TRANSACTION_COMMANDS.iter())
+            {
+                builtin.insert(command.to_string());
+            }
+            builtin
+        });
+        commands.insert("module".to_string(), Box::new(ModuleCommand::new(builtin)));
+        CommandFactory {
+            commands,
+            module_commands: HashSet::new(),
+        }
+    }
+
+    /// Creates the built in commands and the ones of the modules loaded in `server` so far.
+    pub fn with_modules(server: &Server) -> CommandFactory {
+        let mut factory = CommandFactory::new();
+        for (command, wasm_command) in registry::commands(server) {
+            factory.module_commands.insert(command.clone());
+            factory.commands.insert(command, Box::new(wasm_command));
+        }
+        factory
+    }
+
+    /// Returns whether a command is refused with an OOM error when eviction cannot bring the
+    /// databases under `maxmemory`, the commands of modules always are.
+    pub fn is_deny_oom(&self, cmd: &str) -> bool {
+        DENYOOM_COMMANDS.contains(&cmd) || self.module_commands.contains(cmd)
     }
 
     pub fn create(&self, cmd: &str) -> Option<&Box<dyn Command>> {
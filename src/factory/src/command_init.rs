@@ -19,10 +19,253 @@ pub const STRING_COMMANDS: [&str; 17] = [
     "lcs",
     "strlen",
 ];
-pub const HASHMAP_COMMANDS: [&str; 2] = ["hmset", "hgetall"];
-pub const EXPIRED_COMMANDS: [&str; 6] =
-    ["expired", "expireat", "pexpireat", "ttl", "pttl", "persist"];
-pub const UTILS_COMMANDS: [&str; 6] = ["rename", "renamenx", "randomkey", "del", "exists", "type"];
+pub const HASHMAP_COMMANDS: [&str; 3] = ["hmset", "hset", "hgetall"];
+pub const EXPIRED_COMMANDS: [&str; 9] = [
+    "expire",
+    "pexpire",
+    "expireat",
+    "pexpireat",
+    "ttl",
+    "pttl",
+    "expiretime",
+    "pexpiretime",
+    "persist",
+];
+pub const UTILS_COMMANDS: [&str; 18] = [
+    "rename",
+    "renamenx",
+    "randomkey",
+    "del",
+    "exists",
+    "type",
+    "keys",
+    "scan",
+    "hscan",
+    "sscan",
+    "zscan",
+    "object",
+    "dump",
+    "restore",
+    "sort",
+    "sort_ro",
+    "unlink",
+    "touch",
+];
+pub const GEO_COMMANDS: [&str; 6] = [
+    "geoadd",
+    "geopos",
+    "geodist",
+    "geohash",
+    "geosearch",
+    "geosearchstore",
+];
+pub const JSON_COMMANDS: [&str; 10] = [
+    "json.set",
+    "json.get",
+    "json.del",
+    "json.type",
+    "json.numincrby",
+    "json.strappend",
+    "json.arrappend",
+    "json.arrpop",
+    "json.arrlen",
+    "json.objkeys",
+];
+pub const PROBABILISTIC_COMMANDS: [&str; 26] = [
+    "bf.reserve",
+    "bf.add",
+    "bf.madd",
+    "bf.exists",
+    "bf.mexists",
+    "bf.info",
+    "cf.reserve",
+    "cf.add",
+    "cf.addnx",
+    "cf.exists",
+    "cf.mexists",
+    "cf.del",
+    "cf.count",
+    "cf.info",
+    "cms.initbydim",
+    "cms.initbyprob",
+    "cms.incrby",
+    "cms.query",
+    "cms.merge",
+    "cms.info",
+    "topk.reserve",
+    "topk.add",
+    "topk.incrby",
+    "topk.query",
+    "topk.list",
+    "topk.info",
+];
+pub const TIMESERIES_COMMANDS: [&str; 12] = [
+    "ts.create",
+    "ts.add",
+    "ts.madd",
+    "ts.incrby",
+    "ts.get",
+    "ts.range",
+    "ts.revrange",
+    "ts.mrange",
+    "ts.mrevrange",
+    "ts.createrule",
+    "ts.deleterule",
+    "ts.info",
+];
+pub const VECTOR_COMMANDS: [&str; 9] = [
+    "vadd",
+    "vsim",
+    "vrem",
+    "vcard",
+    "vdim",
+    "vemb",
+    "vinfo",
+    "vsetattr",
+    "vgetattr",
+];
+pub const SEARCH_COMMANDS: [&str; 5] = [
+    "ft.create",
+    "ft.search",
+    "ft.dropindex",
+    "ft.info",
+    "ft._list",
+];
+
+// commands that may use more memory, refused with an OOM error when eviction cannot free it
+pub const DENYOOM_COMMANDS: [&str; 44] = [
+    "append",
+    "decr",
+    "decrby",
+    "getset",
+    "incr",
+    "incrby",
+    "incrbyfloat",
+    "set",
+    "mset",
+    "setrange",
+    "hmset",
+    "hset",
+    "geoadd",
+    "geosearchstore",
+    "json.set",
+    "json.numincrby",
+    "json.strappend",
+    "json.arrappend",
+    "bf.reserve",
+    "bf.add",
+    "bf.madd",
+    "cf.reserve",
+    "cf.add",
+    "cf.addnx",
+    "cms.initbydim",
+    "cms.initbyprob",
+    "cms.incrby",
+    "cms.merge",
+    "topk.reserve",
+    "topk.add",
+    "topk.incrby",
+    "ts.create",
+    "ts.add",
+    "ts.madd",
+    "ts.incrby",
+    "ts.createrule",
+    "vadd",
+    "vsetattr",
+    "ft.create",
+    "restore",
+    "copy",
+    "sort",
+    "eval",
+    "evalsha",
+];
+
+// handled by the server, not by the factory, since they work on every database
+pub const DATABASE_COMMANDS: [&str; 10] = [
+    "select", "move", "swapdb", "dbsize", "flushdb", "flushall", "info", "memory", "copy",
+    "migrate",
+];
+
+// handled by the server too, subscriptions belong to the connection
+pub const PUBSUB_COMMANDS: [&str; 9] = [
+    "subscribe",
+    "unsubscribe",
+    "psubscribe",
+    "punsubscribe",
+    "ssubscribe",
+    "sunsubscribe",
+    "publish",
+    "spublish",
+    "pubsub",
+];
+
+// the commands reading keys only, the ones a read only script may call
+pub const READONLY_COMMANDS: [&str; 58] = [
+    "get",
+    "getrange",
+    "mget",
+    "lcs",
+    "strlen",
+    "hgetall",
+    "ttl",
+    "pttl",
+    "expiretime",
+    "pexpiretime",
+    "randomkey",
+    "exists",
+    "type",
+    "keys",
+    "scan",
+    "hscan",
+    "sscan",
+    "zscan",
+    "object",
+    "dump",
+    "sort_ro",
+    "touch",
+    "geopos",
+    "geodist",
+    "geohash",
+    "geosearch",
+    "json.get",
+    "json.type",
+    "json.arrlen",
+    "json.objkeys",
+    "bf.exists",
+    "bf.mexists",
+    "bf.info",
+    "cf.exists",
+    "cf.mexists",
+    "cf.count",
+    "cf.info",
+    "cms.query",
+    "cms.info",
+    "topk.query",
+    "topk.list",
+    "topk.info",
+    "ts.get",
+    "ts.range",
+    "ts.revrange",
+    "ts.mrange",
+    "ts.mrevrange",
+    "ts.info",
+    "vsim",
+    "vcard",
+    "vdim",
+    "vemb",
+    "vinfo",
+    "vgetattr",
+    "ft.search",
+    "ft.info",
+    "ft._list",
+    "ping",
+];
+
+// handled by the server, scripts run their commands through the factory
+pub const SCRIPT_COMMANDS: [&str; 5] = ["eval", "evalsha", "eval_ro", "evalsha_ro", "script"];
+
+// handled per connection, like the subscriptions
+pub const TRANSACTION_COMMANDS: [&str; 5] = ["multi", "exec", "discard", "watch", "unwatch"];
 
 pub fn init_commands() -> HashSet<String> {
     let mut commands_map: Vec<String> = Vec::new();
@@ -31,6 +274,16 @@ pub fn init_commands() -> HashSet<String> {
     commands_map.extend(HASHMAP_COMMANDS.iter().map(|&s| s.to_string()));
     commands_map.extend(EXPIRED_COMMANDS.iter().map(|&s| s.to_string()));
     commands_map.extend(UTILS_COMMANDS.iter().map(|&s| s.to_string()));
+    commands_map.extend(GEO_COMMANDS.iter().map(|&s| s.to_string()));
+    commands_map.extend(JSON_COMMANDS.iter().map(|&s| s.to_string()));
+    commands_map.extend(PROBABILISTIC_COMMANDS.iter().map(|&s| s.to_string()));
+    commands_map.extend(TIMESERIES_COMMANDS.iter().map(|&s| s.to_string()));
+    commands_map.extend(VECTOR_COMMANDS.iter().map(|&s| s.to_string()));
+    commands_map.extend(SEARCH_COMMANDS.iter().map(|&s| s.to_string()));
+    commands_map.extend(DATABASE_COMMANDS.iter().map(|&s| s.to_string()));
+    commands_map.extend(PUBSUB_COMMANDS.iter().map(|&s| s.to_string()));
+    commands_map.extend(SCRIPT_COMMANDS.iter().map(|&s| s.to_string()));
+    commands_map.extend(TRANSACTION_COMMANDS.iter().map(|&s| s.to_string()));
 
     println!("{:?}", commands_map);
 
@@ -1,11 +1,15 @@
-use rus_key_db::db::Db;
+use rus_key_db::databases::Databases;
 use std::str::SplitAsciiWhitespace;
 
+use crate::database::handle_database_command;
+use crate::pubsub::handle_pubsub_command;
+use crate::script::handle_script_command;
 use rus_key_factory::command_factory::CommandFactory;
 
 pub fn handle_command(
     parts: &mut SplitAsciiWhitespace,
-    db: &mut Db,
+    dbs: &mut Databases,
+    selected: &mut usize,
     factory: &CommandFactory,
 ) -> Result<String, &'static str> {
     let cmd = match parts.next() {
@@ -13,8 +17,41 @@ pub fn handle_command(
         None => return Err("No command"),
     };
     println!("Command: {}", cmd);
+    // evict before every command, only the ones that may use more memory are refused
+    if let Err(err) = dbs.free_memory() {
+        if factory.is_deny_oom(&cmd) {
+            return Err(err);
+        }
+    }
+    if let Some(result) = handle_database_command(&cmd, parts, dbs, selected) {
+        return result;
+    }
+    if let Some(result) = handle_pubsub_command(&cmd, parts, dbs) {
+        return result;
+    }
+    if let Some(result) = handle_script_command(&cmd, parts, dbs, *selected, factory) {
+        return result;
+    }
+    let db = match dbs.get_mut(*selected) {
+        Some(db) => db,
+        None => return Err("ERR DB index is out of range"),
+    };
     match factory.create(&cmd) {
         Some(command) => command.execute(parts, db),
         None => Err("Invalid command!"),
     }
 }
+
+/// Returns whether a command line may hold the databases for long, running a script or
+/// waiting for the target of MIGRATE, directly or queued in a transaction.
+pub fn may_block(line: &str) -> bool {
+    let cmd = line
+        .split_ascii_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    matches!(
+        cmd.as_str(),
+        "eval" | "evalsha" | "eval_ro" | "evalsha_ro" | "migrate" | "exec"
+    )
+}
@@ -0,0 +1,366 @@
+use std::str::SplitAsciiWhitespace;
+
+use rus_key_db::databases::Databases;
+use rus_key_db::eviction::EvictionPolicy;
+use rus_key_db::notify::NOTIFY_GENERIC;
+use rus_key_trait::command_trait::Arity;
+
+use crate::migrate::migrate;
+
+const WRONG_ARGS: &str = "ERR wrong number of arguments for command";
+const SYNTAX_ERROR: &str = "ERR syntax error";
+const NOT_INTEGER: &str = "ERR value is not an integer or out of range";
+const OUT_OF_RANGE: &str = "ERR DB index is out of range";
+const SAME_OBJECT: &str = "ERR source and destination objects are the same";
+const UNKNOWN_SUBCOMMAND: &str = "ERR unknown subcommand";
+// elements of a hash or a sorted set measured by MEMORY USAGE by default
+const DEFAULT_MEMORY_SAMPLES: usize = 5;
+// used memory share of maxmemory, in percent, reported by MEMORY DOCTOR
+const DOCTOR_MEMORY_PERCENT: usize = 90;
+
+/// Runs the commands working on the databases rather than on one of them.
+///
+/// # Arguments
+///
+/// * `cmd` - The lowercase command name.
+/// * `parts` - The command arguments.
+/// * `dbs` - The databases of the server.
+/// * `selected` - The database selected by the connection, updated by `SELECT`.
+///
+/// # Returns
+///
+/// * `None` if `cmd` is not a database command, the command result otherwise.
+pub fn handle_database_command(
+    cmd: &str,
+    parts: &mut SplitAsciiWhitespace,
+    dbs: &mut Databases,
+    selected: &mut usize,
+) -> Option<Result<String, &'static str>> {
+    let result = match cmd {
+        "select" => select(parts, dbs, selected),
+        "move" => move_key(parts, dbs, *selected),
+        "swapdb" => swap_db(parts, dbs),
+        "dbsize" => db_size(dbs, *selected),
+        "flushdb" => flush(parts, dbs, Some(*selected)),
+        "flushall" => flush(parts, dbs, None),
+        "info" => info(parts, dbs),
+        "memory" => memory(parts, dbs, *selected),
+        "copy" => copy(parts, dbs, *selected),
+        "migrate" => migrate(parts, dbs, *selected),
+        _ => return None,
+    };
+    Some(result)
+}
+
+/// Returns the arguments a database command takes, see `Arity`.
+///
+/// # Returns
+///
+/// * `None` if `cmd` is not a database command.
+pub fn database_arity(cmd: &str) -> Option<Arity> {
+    let arity = match cmd {
+        "select" => Arity::with_integers(1, &[0]),
+        "move" => Arity::with_integers(2, &[1]),
+        "swapdb" => Arity::with_integers(2, &[0, 1]),
+        "dbsize" | "flushdb" | "flushall" | "info" => Arity::new(0),
+        "memory" => Arity::new(1),
+        "copy" => Arity::new(2),
+        // host port key destination-db timeout
+        "migrate" => Arity::with_integers(5, &[3, 4]),
+        _ => return None,
+    };
+    Some(arity)
+}
+
+fn parse_index(index: Option<&str>, dbs: &Databases) -> Result<usize, &'static str> {
+    let index = index.ok_or(WRONG_ARGS)?;
+    match index.parse::<i64>() {
+        Ok(index) if index >= 0 && (index as usize) < dbs.count() => Ok(index as usize),
+        Ok(_) => Err(OUT_OF_RANGE),
+        Err(_) => Err(NOT_INTEGER),
+    }
+}
+
+fn select(
+    parts: &mut SplitAsciiWhitespace,
+    dbs: &Databases,
+    selected: &mut usize,
+) -> Result<String, &'static str> {
+    *selected = parse_index(parts.next(), dbs)?;
+    Ok("OK".to_string())
+}
+
+// MOVE key db, the key keeps its TTL and is not moved if it exists in the target
+fn move_key(
+    parts: &mut SplitAsciiWhitespace,
+    dbs: &mut Databases,
+    selected: usize,
+) -> Result<String, &'static str> {
+    let key = parts.next().ok_or(WRONG_ARGS)?;
+    let target = parse_index(parts.next(), dbs)?;
+    if target == selected {
+        return Err(SAME_OBJECT);
+    }
+    let (source, target) = match dbs.pair_mut(selected, target) {
+        Some(pair) => pair,
+        None => return Err(OUT_OF_RANGE),
+    };
+    if !source.check_expired(key) || target.check_expired(key) {
+        return Ok("0".to_string());
+    }
+
+    let value = match source.get(key) {
+        Some(value) => value.clone(),
+        None => return Ok("0".to_string()),
+    };
+    let expire = source.expire_time(key);
+    source.delete(key);
+    target.set(key.to_string(), value);
+    if let Some(expire) = expire {
+        target.expire_at(key, expire);
+    }
+    source.notify(NOTIFY_GENERIC, "move_from", key);
+    target.notify(NOTIFY_GENERIC, "move_to", key);
+    Ok("1".to_string())
+}
+
+// COPY source destination [DB destination-db] [REPLACE], the copy keeps the TTL
+fn copy(
+    parts: &mut SplitAsciiWhitespace,
+    dbs: &mut Databases,
+    selected: usize,
+) -> Result<String, &'static str> {
+    let source = parts.next().ok_or(WRONG_ARGS)?;
+    let destination = parts.next().ok_or(WRONG_ARGS)?;
+    let mut target = selected;
+    let mut replace = false;
+    while let Some(option) = parts.next() {
+        match option.to_lowercase().as_str() {
+            "db" => target = parse_index(parts.next(), dbs)?,
+            "replace" => replace = true,
+            _ => return Err(SYNTAX_ERROR),
+        }
+    }
+    if target == selected && source == destination {
+        return Err(SAME_OBJECT);
+    }
+
+    let source_db = dbs.get(selected).ok_or(OUT_OF_RANGE)?;
+    let value = match source_db.get(source) {
+        Some(value) => value.clone(),
+        None => return Ok("0".to_string()),
+    };
+    let expire = source_db.expire_time(source);
+    let target_db = dbs.get_mut(target).ok_or(OUT_OF_RANGE)?;
+    if target_db.check_expired(destination) {
+        if !replace {
+            return Ok("0".to_string());
+        }
+        target_db.delete(destination);
+    }
+    target_db.set(destination.to_string(), value);
+    if let Some(expire) = expire {
+        target_db.expire_at(destination, expire);
+    }
+    target_db.notify(NOTIFY_GENERIC, "copy_to", destination);
+    Ok("1".to_string())
+}
+
+fn swap_db(parts: &mut SplitAsciiWhitespace, dbs: &mut Databases) -> Result<String, &'static str> {
+    let first = parse_index(parts.next(), dbs)?;
+    let second = parse_index(parts.next(), dbs)?;
+    dbs.swap(first, second);
+    Ok("OK".to_string())
+}
+
+fn db_size(dbs: &Databases, selected: usize) -> Result<String, &'static str> {
+    let db = dbs.get(selected).ok_or(OUT_OF_RANGE)?;
+    Ok(db.len().to_string())
+}
+
+// FLUSHDB [ASYNC | SYNC] and FLUSHALL [ASYNC | SYNC], ASYNC drops the old keys on the lazyfree
+// thread
+fn flush(
+    parts: &mut SplitAsciiWhitespace,
+    dbs: &mut Databases,
+    selected: Option<usize>,
+) -> Result<String, &'static str> {
+    let lazy = match parts.next().map(|mode| mode.to_lowercase()) {
+        Some(mode) if mode == "async" => true,
+        Some(mode) if mode == "sync" => false,
+        Some(_) => return Err(SYNTAX_ERROR),
+        None => false,
+    };
+    if parts.next().is_some() {
+        return Err(SYNTAX_ERROR);
+    }
+
+    let indexes = match selected {
+        Some(selected) => vec![selected],
+        None => (0..dbs.count()).collect(),
+    };
+    let mut flushed = Vec::new();
+    for index in indexes {
+        if let Some(db) = dbs.get_mut(index) {
+            flushed.push(db.flush());
+        }
+    }
+    if lazy {
+        dbs.server().lazy_free.free(flushed);
+    }
+    Ok("OK".to_string())
+}
+
+// INFO [section], with the `memory`, `stats` and `keyspace` sections, all by default
+fn info(parts: &mut SplitAsciiWhitespace, dbs: &mut Databases) -> Result<String, &'static str> {
+    let section = parts.next().map(|section| section.to_lowercase());
+    let all = matches!(section.as_deref(), None | Some("all") | Some("everything"));
+    let mut result = Vec::new();
+    if all || section.as_deref() == Some("memory") {
+        result.push(format!("used_memory: {}", dbs.used_memory()));
+        result.push(format!("maxmemory: {}", dbs.maxmemory()));
+        result.push(format!(
+            "maxmemory_policy: {}",
+            dbs.maxmemory_policy().name()
+        ));
+        result.push(format!(
+            "lazyfree_pending_objects: {}",
+            dbs.server().lazy_free.pending_objects()
+        ));
+    }
+    if all || section.as_deref() == Some("stats") {
+        let stats = &dbs.server().stats;
+        result.push(format!("expired_keys: {}", stats.expired_keys()));
+        result.push(format!("evicted_keys: {}", stats.evicted_keys()));
+        result.push(format!(
+            "lazyfreed_objects: {}",
+            dbs.server().lazy_free.freed_objects()
+        ));
+        result.push(format!(
+            "client_output_buffer_limit_disconnections: {}",
+            stats.output_buffer_disconnections()
+        ));
+    }
+    if all || section.as_deref() == Some("keyspace") {
+        for index in 0..dbs.count() {
+            let db = match dbs.get(index) {
+                Some(db) => db,
+                None => continue,
+            };
+            let keys = db.len();
+            if keys > 0 {
+                result.push(format!(
+                    "db{}: keys={},expires={}",
+                    index,
+                    keys,
+                    db.volatile_count()
+                ));
+            }
+        }
+    }
+    Ok(result.join(" "))
+}
+
+// MEMORY USAGE key [SAMPLES count] | STATS | DOCTOR
+fn memory(
+    parts: &mut SplitAsciiWhitespace,
+    dbs: &mut Databases,
+    selected: usize,
+) -> Result<String, &'static str> {
+    let subcommand = parts.next().ok_or(WRONG_ARGS)?.to_lowercase();
+    match subcommand.as_str() {
+        "usage" => memory_usage(parts, dbs, selected),
+        "stats" => Ok(memory_stats(dbs).join(" ")),
+        "doctor" => Ok(memory_doctor(dbs)),
+        _ => Err(UNKNOWN_SUBCOMMAND),
+    }
+}
+
+fn memory_usage(
+    parts: &mut SplitAsciiWhitespace,
+    dbs: &Databases,
+    selected: usize,
+) -> Result<String, &'static str> {
+    let key = parts.next().ok_or(WRONG_ARGS)?;
+    let samples = match parts.next().map(|option| option.to_lowercase()) {
+        Some(option) if option == "samples" => {
+            let samples = parts.next().ok_or(SYNTAX_ERROR)?;
+            samples.parse::<usize>().map_err(|_| NOT_INTEGER)?
+        }
+        Some(_) => return Err(SYNTAX_ERROR),
+        None => DEFAULT_MEMORY_SAMPLES,
+    };
+    if parts.next().is_some() {
+        return Err(SYNTAX_ERROR);
+    }
+    let db = dbs.get(selected).ok_or(OUT_OF_RANGE)?;
+    Ok(db
+        .memory_usage(key, samples)
+        .map_or("nil".to_string(), |usage| usage.to_string()))
+}
+
+fn memory_stats(dbs: &mut Databases) -> Vec<String> {
+    let mut per_db = Vec::new();
+    let mut total_keys = 0;
+    for index in 0..dbs.count() {
+        let db = match dbs.get_mut(index) {
+            Some(db) => db,
+            None => continue,
+        };
+        let keys = db.len();
+        if keys > 0 {
+            per_db.push(format!(
+                "db.{}: keys={},bytes={}",
+                index,
+                keys,
+                db.used_memory()
+            ));
+        }
+        total_keys += keys;
+    }
+    let used = dbs.used_memory();
+    let mut result = vec![
+        format!("total.allocated: {}", used),
+        format!("keys.count: {}", total_keys),
+        format!(
+            "keys.bytes-per-key: {}",
+            used.checked_div(total_keys).unwrap_or(0)
+        ),
+    ];
+    result.extend(per_db);
+    result
+}
+
+fn memory_doctor(dbs: &mut Databases) -> String {
+    let used = dbs.used_memory();
+    if used == 0 {
+        return "The instance is empty, there is nothing to report.".to_string();
+    }
+    let mut issues = Vec::new();
+    let maxmemory = dbs.maxmemory() as usize;
+    if maxmemory > 0 && used * 100 > maxmemory * DOCTOR_MEMORY_PERCENT {
+        let policy = dbs.maxmemory_policy();
+        issues.push(format!(
+            "Used memory {} is over {}% of maxmemory {} with the {} policy.",
+            used,
+            DOCTOR_MEMORY_PERCENT,
+            maxmemory,
+            policy.name()
+        ));
+        if policy == EvictionPolicy::NoEviction {
+            issues
+                .push("Writes are refused with OOM errors once the limit is reached.".to_string());
+        }
+    }
+    let evicted = dbs.server().stats.evicted_keys();
+    if evicted > 0 {
+        issues.push(format!(
+            "{} keys were evicted, maxmemory may be too low for the dataset.",
+            evicted
+        ));
+    }
+    if issues.is_empty() {
+        return "No memory issues detected.".to_string();
+    }
+    issues.join(" ")
+}
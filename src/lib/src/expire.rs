@@ -0,0 +1,35 @@
+use rus_key_db::databases::Databases;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+const DEFAULT_HZ: u64 = 10;
+const MIN_HZ: u64 = 1;
+const MAX_HZ: u64 = 500;
+// share of each period the cycle may use, in percent
+const CYCLE_TIME_PERCENT: u64 = 25;
+
+/// Reads `hz` from the config, so `CONFIG SET hz` applies from the next cycle.
+fn hz(dbs: &Databases) -> u64 {
+    dbs.server()
+        .config_get("hz")
+        .and_then(|hz| hz.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_HZ)
+        .clamp(MIN_HZ, MAX_HZ)
+}
+
+/// Deletes expired keys in the background, `hz` times per second.
+///
+/// Each cycle samples keys with a TTL and may use up to 25% of its period, so keys that are
+/// never read again are reclaimed without blocking clients for long.
+pub async fn active_expire(dbs: Arc<Mutex<Databases>>) {
+    loop {
+        let period = {
+            let mut dbs = dbs.lock().await;
+            let period = Duration::from_micros(1_000_000 / hz(&dbs));
+            dbs.active_expire_cycle(period * CYCLE_TIME_PERCENT as u32 / 100);
+            period
+        };
+        tokio::time::sleep(period).await;
+    }
+}
@@ -1,6 +1,12 @@
 pub mod args;
 pub mod cmd;
+pub mod database;
+pub mod expire;
 pub mod init;
+mod migrate;
+pub mod multi;
 pub mod net;
+pub mod pubsub;
 pub mod read_line;
+pub mod script;
 mod stream;
@@ -0,0 +1,155 @@
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::str::SplitAsciiWhitespace;
+use std::time::Duration;
+
+use rus_key_db::databases::Databases;
+use rus_key_db::dump::{dump, to_hex};
+use rus_key_db::notify::NOTIFY_GENERIC;
+
+const WRONG_ARGS: &str = "ERR wrong number of arguments for command";
+const SYNTAX_ERROR: &str = "ERR syntax error";
+const NOT_INTEGER: &str = "ERR value is not an integer or out of range";
+const OUT_OF_RANGE: &str = "ERR DB index is out of range";
+const KEYS_WITH_KEY: &str =
+    "ERR When using MIGRATE KEYS option, the key argument must be set to the empty string";
+const IO_ERROR: &str = "IOERR error or timeout reading to target instance";
+const BUSY_KEY: &str = "BUSYKEY Target key name already exists.";
+const TARGET_ERROR: &str = "ERR Target instance replied with error";
+const SELF_TARGET: &str = "ERR Target instance is this instance";
+// the key argument when the keys follow KEYS
+const EMPTY_KEY: &str = "\"\"";
+const DEFAULT_TIMEOUT_MILLIS: u64 = 1000;
+
+/// A blocking connection to the target instance, one reply per command.
+struct Target {
+    stream: TcpStream,
+}
+
+impl Target {
+    fn resolve(host: &str, port: &str) -> Result<SocketAddr, &'static str> {
+        format!("{}:{}", host, port)
+            .to_socket_addrs()
+            .map_err(|_| IO_ERROR)?
+            .next()
+            .ok_or(IO_ERROR)
+    }
+
+    fn connect(addr: &SocketAddr, timeout: Duration) -> Result<Target, &'static str> {
+        let stream = TcpStream::connect_timeout(addr, timeout).map_err(|_| IO_ERROR)?;
+        stream
+            .set_read_timeout(Some(timeout))
+            .and_then(|_| stream.set_write_timeout(Some(timeout)))
+            .map_err(|_| IO_ERROR)?;
+        Ok(Target { stream })
+    }
+
+    // RusKey writes replies with no line terminator, so a reply is read until a line ends or
+    // it can only be OK or an error, which ends the migration and needs no more reading
+    fn command(&mut self, command: &str) -> Result<(), &'static str> {
+        self.stream
+            .write_all(format!("{}\r\n", command).as_bytes())
+            .map_err(|_| IO_ERROR)?;
+        let mut reply = Vec::new();
+        let mut buffer = [0; 512];
+        loop {
+            let bytes_read = self.stream.read(&mut buffer).map_err(|_| IO_ERROR)?;
+            if bytes_read == 0 {
+                return Err(IO_ERROR);
+            }
+            reply.extend_from_slice(&buffer[..bytes_read]);
+            let end = reply.iter().position(|byte| *byte == b'\n');
+            let line = String::from_utf8_lossy(&reply[..end.unwrap_or(reply.len())]);
+            match line.trim() {
+                "OK" => return Ok(()),
+                line if line.starts_with("BUSYKEY") => return Err(BUSY_KEY),
+                // the start of OK or BUSYKEY, the rest is still to come
+                line if end.is_none()
+                    && ("OK".starts_with(line) || "BUSYKEY".starts_with(line)) => {}
+                _ => return Err(TARGET_ERROR),
+            }
+        }
+    }
+}
+
+/// `MIGRATE host port key|"" destination-db timeout [COPY] [REPLACE] [KEYS key ...]`
+///
+/// Sends the keys of the selected database to another instance with `DUMP` payloads and
+/// `RESTORE`, then deletes them unless COPY is given. The payloads carry the TTL, so the
+/// clocks of both instances should agree. `timeout` is in milliseconds and bounds every
+/// network operation. The target cannot be this instance, whose databases are held meanwhile.
+///
+/// # Returns
+///
+/// * `OK`, or `NOKEY` if none of the keys exists.
+pub fn migrate(
+    parts: &mut SplitAsciiWhitespace,
+    dbs: &mut Databases,
+    selected: usize,
+) -> Result<String, &'static str> {
+    let host = parts.next().ok_or(WRONG_ARGS)?;
+    let port = parts.next().ok_or(WRONG_ARGS)?;
+    let key = parts.next().ok_or(WRONG_ARGS)?;
+    let target_db = parts.next().ok_or(WRONG_ARGS)?;
+    let timeout = parts.next().ok_or(WRONG_ARGS)?;
+    let target_db = target_db.parse::<u64>().map_err(|_| NOT_INTEGER)?;
+    let timeout = match timeout.parse::<i64>().map_err(|_| NOT_INTEGER)? {
+        timeout if timeout <= 0 => DEFAULT_TIMEOUT_MILLIS,
+        timeout => timeout as u64,
+    };
+
+    let mut copy = false;
+    let mut replace = false;
+    let mut keys = Vec::new();
+    while let Some(option) = parts.next() {
+        match option.to_lowercase().as_str() {
+            "copy" => copy = true,
+            "replace" => replace = true,
+            "keys" => {
+                if key != EMPTY_KEY {
+                    return Err(KEYS_WITH_KEY);
+                }
+                keys.extend(parts.by_ref());
+                if keys.is_empty() {
+                    return Err(SYNTAX_ERROR);
+                }
+            }
+            _ => return Err(SYNTAX_ERROR),
+        }
+    }
+    if keys.is_empty() {
+        if key == EMPTY_KEY {
+            return Err(SYNTAX_ERROR);
+        }
+        keys.push(key);
+    }
+
+    let db = dbs.get_mut(selected).ok_or(OUT_OF_RANGE)?;
+    let payloads: Vec<(&str, String)> = keys
+        .into_iter()
+        .filter_map(|key| {
+            let entry = db.peek(key)?;
+            Some((key, to_hex(&dump(entry.value(), db.expire_time(key)))))
+        })
+        .collect();
+    if payloads.is_empty() {
+        return Ok("NOKEY".to_string());
+    }
+
+    let addr = Target::resolve(host, port)?;
+    if db.server().has_address(&addr) {
+        return Err(SELF_TARGET);
+    }
+    let mut target = Target::connect(&addr, Duration::from_millis(timeout))?;
+    target.command(&format!("SELECT {}", target_db))?;
+    for (key, payload) in payloads {
+        let replace = if replace { " REPLACE" } else { "" };
+        target.command(&format!("RESTORE {} 0 {}{}", key, payload, replace))?;
+        // a key is deleted once the target has it, even if a later key fails
+        if !copy {
+            db.delete(key);
+            db.notify(NOTIFY_GENERIC, "del", key);
+        }
+    }
+    Ok("OK".to_string())
+}
@@ -0,0 +1,210 @@
+use std::str::SplitAsciiWhitespace;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use rus_key_command_lib::get_value;
+use rus_key_db::databases::Databases;
+use rus_key_factory::command_factory::CommandFactory;
+use rus_key_trait::command_trait::Arity;
+
+use crate::cmd::handle_command;
+use crate::database::database_arity;
+use crate::pubsub::{pubsub_arity, SUBSCRIBED_COMMANDS};
+use crate::script::script_arity;
+
+const WRONG_ARGS: &str = "ERR wrong number of arguments for command";
+const NESTED_MULTI: &str = "ERR MULTI calls can not be nested";
+const EXEC_WITHOUT_MULTI: &str = "ERR EXEC without MULTI";
+const DISCARD_WITHOUT_MULTI: &str = "ERR DISCARD without MULTI";
+const WATCH_INSIDE_MULTI: &str = "ERR WATCH inside MULTI is not allowed";
+const UNKNOWN_COMMAND: &str = "ERR unknown command";
+const NOT_ALLOWED: &str = "ERR Command not allowed inside a transaction";
+const EXEC_ABORT: &str = "EXECABORT Transaction discarded because of previous errors.";
+
+/// The transaction of one connection: the commands queued since `MULTI` and the keys
+/// watched since `WATCH`.
+pub struct Transaction {
+    // the command lines queued, `None` outside of MULTI
+    queued: Option<Vec<String>>,
+    // a command was refused while queuing, EXEC aborts
+    failed: bool,
+    // the database number and name of the watched keys
+    watched: Vec<(usize, String)>,
+    // set by the server when a watched key changes
+    dirty: Arc<AtomicBool>,
+}
+
+impl Default for Transaction {
+    fn default() -> Self {
+        Transaction::new()
+    }
+}
+
+impl Transaction {
+    pub fn new() -> Transaction {
+        Transaction {
+            queued: None,
+            failed: false,
+            watched: Vec::new(),
+            dirty: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Returns whether the connection is between MULTI and EXEC or DISCARD.
+    pub fn is_active(&self) -> bool {
+        self.queued.is_some()
+    }
+
+    /// Runs MULTI, EXEC, DISCARD, WATCH and UNWATCH, and queues every other command while
+    /// a transaction is open.
+    ///
+    /// EXEC runs the queued commands one after the other and replies their results, errors
+    /// included, each one ending with `\r\n` like the replies to a subscribed connection, so
+    /// replies spanning lines with `\n`, like the one of MODULE LIST, stay apart. It replies
+    /// `nil` without running them if a watched key was modified since WATCH, and fails with
+    /// EXECABORT if a command was refused while queuing: an unknown command, or one not given
+    /// the arguments of its `Arity`.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The whole command line.
+    /// * `selected` - The database selected by the connection, for WATCH and the commands.
+    ///
+    /// # Returns
+    ///
+    /// * `None` if the command is to be run now by the caller, the command result otherwise.
+    pub fn handle(
+        &mut self,
+        line: &str,
+        dbs: &mut Databases,
+        selected: &mut usize,
+        factory: &CommandFactory,
+    ) -> Option<Result<String, &'static str>> {
+        let mut parts = line.split_ascii_whitespace();
+        let cmd = parts.next()?.to_lowercase();
+        let result = match cmd.as_str() {
+            "multi" if self.is_active() => Err(NESTED_MULTI),
+            "multi" => {
+                self.queued = Some(Vec::new());
+                self.failed = false;
+                Ok("OK".to_string())
+            }
+            "exec" => self.exec(dbs, selected, factory),
+            "discard" => match self.queued.take() {
+                Some(_) => {
+                    self.unwatch(dbs);
+                    Ok("OK".to_string())
+                }
+                None => Err(DISCARD_WITHOUT_MULTI),
+            },
+            "watch" if self.is_active() => Err(WATCH_INSIDE_MULTI),
+            "watch" => {
+                let keys: Vec<&str> = parts.collect();
+                self.watch(&keys, dbs, *selected)
+            }
+            "unwatch" => {
+                self.unwatch(dbs);
+                Ok("OK".to_string())
+            }
+            _ => {
+                let queued = self.queued.as_mut()?;
+                // the subscriptions are changed outside of transactions, PING runs anywhere
+                let checked = if SUBSCRIBED_COMMANDS.contains(&cmd.as_str()) && cmd != "ping" {
+                    Err(NOT_ALLOWED)
+                } else {
+                    match arity(&cmd, factory) {
+                        Some(arity) => arity.check(&args(&mut parts)),
+                        None => Err(UNKNOWN_COMMAND),
+                    }
+                };
+                match checked {
+                    Ok(()) => {
+                        queued.push(line.to_string());
+                        Ok("QUEUED".to_string())
+                    }
+                    Err(error) => {
+                        self.failed = true;
+                        Err(error)
+                    }
+                }
+            }
+        };
+        Some(result)
+    }
+
+    fn watch(
+        &mut self,
+        keys: &[&str],
+        dbs: &Databases,
+        selected: usize,
+    ) -> Result<String, &'static str> {
+        if keys.is_empty() {
+            return Err(WRONG_ARGS);
+        }
+        for key in keys {
+            dbs.server().watches.watch(selected, key, &self.dirty);
+            self.watched.push((selected, key.to_string()));
+        }
+        Ok("OK".to_string())
+    }
+
+    fn exec(
+        &mut self,
+        dbs: &mut Databases,
+        selected: &mut usize,
+        factory: &CommandFactory,
+    ) -> Result<String, &'static str> {
+        let queued = self.queued.take().ok_or(EXEC_WITHOUT_MULTI)?;
+        // a watched key past its deadline has changed too
+        for (db, key) in &self.watched {
+            if let Some(db) = dbs.get_mut(*db) {
+                db.check_expired(key);
+            }
+        }
+        let modified = self.dirty.load(Ordering::Relaxed);
+        self.unwatch(dbs);
+        if self.failed {
+            return Err(EXEC_ABORT);
+        }
+        if modified {
+            return Ok("nil".to_string());
+        }
+        let replies = queued
+            .iter()
+            .map(|line| {
+                let mut parts = line.split_ascii_whitespace();
+                match handle_command(&mut parts, dbs, selected, factory) {
+                    Ok(reply) => format!("{}\r\n", reply),
+                    Err(error) => format!("{}\r\n", error),
+                }
+            })
+            .collect();
+        Ok(replies)
+    }
+
+    /// Stops watching every key, also when the connection is closed.
+    pub fn unwatch(&mut self, dbs: &Databases) {
+        for (db, key) in self.watched.drain(..) {
+            dbs.server().watches.unwatch(db, &key, &self.dirty);
+        }
+        self.dirty.store(false, Ordering::Relaxed);
+    }
+}
+
+// The arguments a command takes, looked for like `handle_command` does, `None` for an unknown
+// command.
+fn arity(cmd: &str, factory: &CommandFactory) -> Option<Arity> {
+    database_arity(cmd)
+        .or_else(|| pubsub_arity(cmd))
+        .or_else(|| script_arity(cmd))
+        .or_else(|| factory.create(cmd).map(|command| command.arity()))
+}
+
+// the arguments of a command line, a quoted argument being one
+fn args(parts: &mut SplitAsciiWhitespace) -> Vec<String> {
+    let mut args = Vec::new();
+    while let Some(part) = parts.next() {
+        args.push(get_value(part.to_string(), parts));
+    }
+    args
+}
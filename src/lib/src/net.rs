@@ -1,40 +1,186 @@
 use std::io::Result;
 use std::io::{Error, ErrorKind};
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::io::{AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader};
 // use std::sync::{Arc, Mutex};
 use crate::cmd;
+use crate::multi::Transaction;
+use crate::pubsub::{format_message, Subscriptions, SUBSCRIBED_COMMANDS};
+use crate::script::handle_running_script;
 use rus_key_factory::command_factory::CommandFactory;
-use rus_key_db::db::Db;
-use std::str;
+use rus_key_db::databases::Databases;
+use rus_key_db::output_buffer::{ClientClass, OutputBuffer};
+use std::sync::Arc;
 use tokio::net::TcpStream;
+use tokio::sync::Mutex;
 
-pub async fn handle_client(mut stream: TcpStream, db: &mut Db) -> Result<()> {
-    let mut buffer = [0; 512]; // read up to 512 bytes
-    loop {
-        let bytes_read = stream.read(&mut buffer).await.map_err(|e| {
-            println!("Error: {:?}", e);
-            Error::new(ErrorKind::Other, "Failed to read from socket")
-        })?;
+async fn write<W: AsyncWrite + Unpin>(writer: &mut W, response: &str) -> Result<()> {
+    writer.write_all(response.as_bytes()).await.map_err(|e| {
+        println!("Error: {:?}", e);
+        Error::new(ErrorKind::Other, "Failed to write to socket")
+    })
+}
+
+// writes unless the output buffer is closed first, returns whether it was written
+async fn write_unless_closed<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    response: &str,
+    output: &OutputBuffer,
+) -> Result<bool> {
+    tokio::select! {
+        result = write(writer, response) => result.map(|_| true),
+        _ = output.closed() => Ok(false),
+    }
+}
 
-        // if read returned 0, client has closed the connection
-        if bytes_read == 0 {
-            break;
+/// Serves one connection until the client closes it.
+///
+/// Replies are written as is, one per command. A connection subscribed with SUBSCRIBE,
+/// PSUBSCRIBE or SSUBSCRIBE switches to push mode: messages are written as they are
+/// published, with no request, so every reply and message is then a line ending with `\r\n`.
+///
+/// Replies and messages count in the output buffer of the connection until written, a client
+/// reading too slowly for `client-output-buffer-limit` is disconnected.
+pub async fn handle_client(mut stream: TcpStream, dbs: Arc<Mutex<Databases>>) -> Result<()> {
+    if let Ok(addr) = stream.local_addr() {
+        dbs.lock().await.server().add_address(addr);
+    }
+    let (reader, mut writer) = stream.split();
+    // one command per line, so long commands like RESTORE may span several reads
+    let mut lines = BufReader::new(reader).lines();
+    let mut selected = 0; // the database selected by this connection
+    let (mut subscriptions, mut messages) = Subscriptions::new(&dbs.lock().await.server().pubsub);
+    let output = Arc::clone(subscriptions.output());
+    let mut transaction = Transaction::new();
+    let server = dbs.lock().await.shared_server();
+    let result = loop {
+        tokio::select! {
+            // reading a line can be cancelled by a message without losing its start
+            line = lines.next_line() => {
+                let line = match line {
+                    Ok(Some(line)) => line,
+                    // the client has closed the connection
+                    Ok(None) => break Ok(()),
+                    Err(e) => {
+                        println!("Error: {:?}", e);
+                        break Err(Error::new(ErrorKind::Other, "Failed to read from socket"));
+                    }
+                };
+                let command = line.trim();
+                if command.is_empty() {
+                    continue;
+                }
+                // SCRIPT KILL and BUSY do not wait for the script holding the databases
+                let killed = if transaction.is_active() || subscriptions.is_subscribed() {
+                    None
+                } else {
+                    handle_running_script(command, &server)
+                };
+                let (response, limit) = if let Some(result) = killed {
+                    let response = result.unwrap_or_else(|e| e.to_string());
+                    (response, server.output_limit(ClientClass::Normal))
+                } else {
+                    // the databases are locked for one command at a time
+                    let mut dbs = dbs.lock().await;
+                    let mut run = || {
+                        execute(
+                            command,
+                            &mut dbs,
+                            &mut selected,
+                            &mut subscriptions,
+                            &mut transaction,
+                        )
+                    };
+                    // a script or MIGRATE may run for long, the other connections move to
+                    // another thread
+                    let response = if cmd::may_block(command) {
+                        tokio::task::block_in_place(run)
+                    } else {
+                        run()
+                    };
+                    let class = if subscriptions.is_subscribed() {
+                        ClientClass::PubSub
+                    } else {
+                        ClientClass::Normal
+                    };
+                    (response, dbs.server().output_limit(class))
+                };
+                if !output.queue(response.len(), &limit) {
+                    break Ok(());
+                }
+                match write_unless_closed(&mut writer, &response, &output).await {
+                    Ok(true) => output.written(response.len()),
+                    Ok(false) => break Ok(()),
+                    Err(e) => break Err(e),
+                }
+            }
+            // queued in the output buffer when published
+            Some(message) = messages.recv() => {
+                let line = format!("{}\r\n", format_message(&message));
+                match write_unless_closed(&mut writer, &line, &output).await {
+                    Ok(true) => output.written(message.size()),
+                    Ok(false) => break Ok(()),
+                    Err(e) => break Err(e),
+                }
+            }
+            _ = output.closed() => break Ok(()),
         }
-        let command = str::from_utf8(&buffer[..bytes_read]).unwrap().trim(); // convert bytes to string
+    };
+    let dbs = dbs.lock().await;
+    subscriptions.unsubscribe_all(&dbs.server().pubsub);
+    transaction.unwatch(&dbs);
+    if output.is_closed() {
+        println!("Client disconnected for going over its output buffer limit");
+        dbs.server().stats.add_output_buffer_disconnections(1);
+    }
+    result
+}
 
-        let mut parts = command.split_ascii_whitespace(); // split string into parts
-        let factory = CommandFactory::new();
-        match cmd::handle_command(&mut parts, db, &factory) {
-            Ok(response) => stream.write(response.as_bytes()).await.map_err(|e| {
-                println!("Error: {:?}", e);
-                Error::new(ErrorKind::Other, "Failed to write to socket")
-            })?,
-            Err(e) => stream.write(e.as_bytes()).await.map_err(|e| {
-                println!("Error: {:?}", e);
-                Error::new(ErrorKind::Other, "Failed to write to socket")
-            })?,
+fn execute(
+    command: &str,
+    dbs: &mut Databases,
+    selected: &mut usize,
+    subscriptions: &mut Subscriptions,
+    transaction: &mut Transaction,
+) -> String {
+    let mut parts = command.split_ascii_whitespace(); // split string into parts
+    let cmd = parts.next().unwrap_or_default().to_lowercase();
+    // inside MULTI the subscription commands are refused by the transaction
+    let handled = if transaction.is_active() {
+        None
+    } else {
+        subscriptions.handle(&cmd, &mut parts, &dbs.server().pubsub)
+    };
+    if let Some(result) = handled {
+        return match result {
+            Ok(replies) => replies
+                .iter()
+                .map(|reply| format!("{}\r\n", reply))
+                .collect(),
+            Err(e) => format!("{}\r\n", e),
         };
     }
-
-    Ok(())
+    let subscribed = subscriptions.is_subscribed();
+    if subscribed && !SUBSCRIBED_COMMANDS.contains(&cmd.as_str()) {
+        return format!(
+            "ERR Can't execute '{}': only (P|S)SUBSCRIBE / (P|S)UNSUBSCRIBE / PING are allowed in this context\r\n",
+            cmd
+        );
+    }
+    let factory = CommandFactory::with_modules(dbs.server());
+    let result = match transaction.handle(command, dbs, selected, &factory) {
+        Some(result) => result,
+        None => {
+            let mut parts = command.split_ascii_whitespace();
+            cmd::handle_command(&mut parts, dbs, selected, &factory)
+        }
+    };
+    let response = match result {
+        Ok(response) => response,
+        Err(e) => e.to_string(),
+    };
+    if subscribed {
+        format!("{}\r\n", response)
+    } else {
+        response
+    }
 }
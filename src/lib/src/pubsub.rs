@@ -0,0 +1,269 @@
+use std::collections::HashSet;
+use std::str::SplitAsciiWhitespace;
+use std::sync::Arc;
+
+use rus_key_command_lib::get_value;
+use rus_key_db::databases::Databases;
+use rus_key_db::output_buffer::OutputBuffer;
+use rus_key_db::pubsub::{Message, PubSub, Subscriber};
+use rus_key_db::server::Server;
+use rus_key_trait::command_trait::Arity;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+const WRONG_ARGS: &str = "ERR wrong number of arguments for command";
+const UNKNOWN_SUBCOMMAND: &str = "ERR unknown subcommand";
+
+/// The commands a connection may run while it is subscribed.
+pub const SUBSCRIBED_COMMANDS: [&str; 7] = [
+    "subscribe",
+    "unsubscribe",
+    "psubscribe",
+    "punsubscribe",
+    "ssubscribe",
+    "sunsubscribe",
+    "ping",
+];
+
+/// Runs the publish/subscribe commands that do not change the subscriptions of the
+/// connection, see `Subscriptions` for the others.
+///
+/// # Returns
+///
+/// * `None` if `cmd` is not one of them, the command result otherwise.
+pub fn handle_pubsub_command(
+    cmd: &str,
+    parts: &mut SplitAsciiWhitespace,
+    dbs: &Databases,
+) -> Option<Result<String, &'static str>> {
+    let server = dbs.server();
+    let result = match cmd {
+        "publish" => publish(parts, server, false),
+        "spublish" => publish(parts, server, true),
+        "pubsub" => introspect(parts, &server.pubsub),
+        _ => return None,
+    };
+    Some(result)
+}
+
+/// Returns the arguments a command of `handle_pubsub_command` takes, see `Arity`.
+///
+/// # Returns
+///
+/// * `None` if `cmd` is not one of them.
+pub fn pubsub_arity(cmd: &str) -> Option<Arity> {
+    match cmd {
+        "publish" | "spublish" => Some(Arity::new(2)),
+        "pubsub" => Some(Arity::new(1)),
+        _ => None,
+    }
+}
+
+// PUBLISH channel message, replies the number of subscriptions it was delivered to, a quoted
+// message may have spaces
+fn publish(
+    parts: &mut SplitAsciiWhitespace,
+    server: &Server,
+    sharded: bool,
+) -> Result<String, &'static str> {
+    let channel = parts.next().ok_or(WRONG_ARGS)?;
+    let message = get_value(parts.next().ok_or(WRONG_ARGS)?.to_string(), parts);
+    if parts.next().is_some() {
+        return Err(WRONG_ARGS);
+    }
+    let receivers = if sharded {
+        server.spublish(channel, &message)
+    } else {
+        server.publish(channel, &message)
+    };
+    Ok(receivers.to_string())
+}
+
+// PUBSUB CHANNELS [pattern] | NUMSUB [channel ...] | NUMPAT | SHARDCHANNELS [pattern] |
+// SHARDNUMSUB [channel ...]
+fn introspect(parts: &mut SplitAsciiWhitespace, pubsub: &PubSub) -> Result<String, &'static str> {
+    let subcommand = parts.next().ok_or(WRONG_ARGS)?.to_lowercase();
+    let result = match subcommand.as_str() {
+        "channels" => pubsub.channels(parts.next()),
+        "shardchannels" => pubsub.shard_channels(parts.next()),
+        "numsub" => parts
+            .map(|channel| format!("{} {}", channel, pubsub.numsub(channel)))
+            .collect(),
+        "shardnumsub" => parts
+            .map(|channel| format!("{} {}", channel, pubsub.shard_numsub(channel)))
+            .collect(),
+        "numpat" => return Ok(pubsub.numpat().to_string()),
+        _ => return Err(UNKNOWN_SUBCOMMAND),
+    };
+    if parts.next().is_some() {
+        return Err(WRONG_ARGS);
+    }
+    Ok(result.join(" "))
+}
+
+/// Formats a message pushed to a subscribed connection, `message channel payload`,
+/// `pmessage pattern channel payload` or `smessage channel payload`.
+pub fn format_message(message: &Message) -> String {
+    match &message.pattern {
+        Some(pattern) => format!(
+            "{} {} {} {}",
+            message.kind(),
+            pattern,
+            message.channel,
+            message.payload
+        ),
+        None => format!("{} {} {}", message.kind(), message.channel, message.payload),
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Kind {
+    Channel,
+    Pattern,
+    Shard,
+}
+
+/// The subscriptions of one connection, whose messages arrive on the receiver returned by
+/// `Subscriptions::new`.
+pub struct Subscriptions {
+    id: u64,
+    subscriber: Subscriber,
+    channels: HashSet<String>,
+    patterns: HashSet<String>,
+    shard_channels: HashSet<String>,
+}
+
+impl Subscriptions {
+    pub fn new(pubsub: &PubSub) -> (Subscriptions, UnboundedReceiver<Message>) {
+        let (subscriber, receiver) = Subscriber::channel();
+        let subscriptions = Subscriptions {
+            id: pubsub.client_id(),
+            subscriber,
+            channels: HashSet::new(),
+            patterns: HashSet::new(),
+            shard_channels: HashSet::new(),
+        };
+        (subscriptions, receiver)
+    }
+
+    /// Returns the output buffer of the connection, holding the messages not written yet.
+    pub fn output(&self) -> &Arc<OutputBuffer> {
+        self.subscriber.output()
+    }
+
+    /// Returns whether the connection is subscribed to a channel, a pattern or a shard
+    /// channel, and so only takes `SUBSCRIBED_COMMANDS`.
+    pub fn is_subscribed(&self) -> bool {
+        !self.channels.is_empty() || !self.patterns.is_empty() || !self.shard_channels.is_empty()
+    }
+
+    /// Runs SUBSCRIBE, UNSUBSCRIBE, PSUBSCRIBE, PUNSUBSCRIBE, SSUBSCRIBE and SUNSUBSCRIBE.
+    ///
+    /// An unsubscribe without arguments unsubscribes from every channel or pattern of its
+    /// kind.
+    ///
+    /// # Returns
+    ///
+    /// * `None` if `cmd` is not one of them, otherwise one reply per channel or pattern like
+    ///   `subscribe channel count`, where count is the number of channels and patterns the
+    ///   connection is still subscribed to, or of shard channels for the sharded commands.
+    pub fn handle(
+        &mut self,
+        cmd: &str,
+        parts: &mut SplitAsciiWhitespace,
+        pubsub: &PubSub,
+    ) -> Option<Result<Vec<String>, &'static str>> {
+        let result = match cmd {
+            "subscribe" => self.subscribe(Kind::Channel, cmd, parts, pubsub),
+            "psubscribe" => self.subscribe(Kind::Pattern, cmd, parts, pubsub),
+            "ssubscribe" => self.subscribe(Kind::Shard, cmd, parts, pubsub),
+            "unsubscribe" => Ok(self.unsubscribe(Kind::Channel, cmd, parts, pubsub)),
+            "punsubscribe" => Ok(self.unsubscribe(Kind::Pattern, cmd, parts, pubsub)),
+            "sunsubscribe" => Ok(self.unsubscribe(Kind::Shard, cmd, parts, pubsub)),
+            _ => return None,
+        };
+        Some(result)
+    }
+
+    /// Removes every subscription, when the connection is closed.
+    pub fn unsubscribe_all(&mut self, pubsub: &PubSub) {
+        let id = self.id;
+        for kind in [Kind::Channel, Kind::Pattern, Kind::Shard] {
+            for name in self.names(kind).drain() {
+                remove(kind, id, &name, pubsub);
+            }
+        }
+    }
+
+    fn names(&mut self, kind: Kind) -> &mut HashSet<String> {
+        match kind {
+            Kind::Channel => &mut self.channels,
+            Kind::Pattern => &mut self.patterns,
+            Kind::Shard => &mut self.shard_channels,
+        }
+    }
+
+    fn count(&self, kind: Kind) -> usize {
+        match kind {
+            Kind::Shard => self.shard_channels.len(),
+            _ => self.channels.len() + self.patterns.len(),
+        }
+    }
+
+    fn subscribe(
+        &mut self,
+        kind: Kind,
+        cmd: &str,
+        parts: &mut SplitAsciiWhitespace,
+        pubsub: &PubSub,
+    ) -> Result<Vec<String>, &'static str> {
+        let names: Vec<&str> = parts.collect();
+        if names.is_empty() {
+            return Err(WRONG_ARGS);
+        }
+        let mut replies = Vec::new();
+        for name in names {
+            if self.names(kind).insert(name.to_string()) {
+                match kind {
+                    Kind::Channel => pubsub.subscribe(self.id, name, &self.subscriber),
+                    Kind::Pattern => pubsub.psubscribe(self.id, name, &self.subscriber),
+                    Kind::Shard => pubsub.ssubscribe(self.id, name, &self.subscriber),
+                };
+            }
+            replies.push(format!("{} {} {}", cmd, name, self.count(kind)));
+        }
+        Ok(replies)
+    }
+
+    fn unsubscribe(
+        &mut self,
+        kind: Kind,
+        cmd: &str,
+        parts: &mut SplitAsciiWhitespace,
+        pubsub: &PubSub,
+    ) -> Vec<String> {
+        let mut names: Vec<String> = parts.map(|name| name.to_string()).collect();
+        if names.is_empty() {
+            names = self.names(kind).iter().cloned().collect();
+            names.sort();
+        }
+        if names.is_empty() {
+            return vec![format!("{} nil {}", cmd, self.count(kind))];
+        }
+        let mut replies = Vec::new();
+        for name in names {
+            if self.names(kind).remove(&name) {
+                remove(kind, self.id, &name, pubsub);
+            }
+            replies.push(format!("{} {} {}", cmd, name, self.count(kind)));
+        }
+        replies
+    }
+}
+
+fn remove(kind: Kind, client: u64, name: &str, pubsub: &PubSub) {
+    match kind {
+        Kind::Channel => pubsub.unsubscribe(client, name),
+        Kind::Pattern => pubsub.punsubscribe(client, name),
+        Kind::Shard => pubsub.sunsubscribe(client, name),
+    };
+}
@@ -5,8 +5,7 @@ use rustyline::error::ReadlineError;
 use rustyline::{DefaultEditor, Result};
 use std::process;
 
-async fn send_command(command: &str, state: &Store) {
-    let mut client = Client::new(&state.url).await.unwrap();
+async fn send_command(command: &str, client: &mut Client) {
     match client.send_command(command).await {
         Ok(response) => {
             println!("{:?}", response);
@@ -19,7 +18,15 @@ async fn send_command(command: &str, state: &Store) {
 
 pub async fn read_line(state: &Store) -> Result<()> {
     let mut rl = DefaultEditor::new()?;
-    let commands = init_commands();
+    let mut commands = init_commands();
+    // one connection for the whole session, so SELECT sticks
+    let mut client = match Client::new(&state.url).await {
+        Ok(client) => client,
+        Err(e) => {
+            println!("Error: {:?}", e);
+            process::exit(1);
+        }
+    };
     loop {
         match rl.readline(format!("{} RusKey >", state.url).as_str()) {
             Ok(line) => {
@@ -41,14 +48,48 @@ pub async fn read_line(state: &Store) -> Result<()> {
                     }
                     Some(&"config") => match parts.get(1) {
                         Some(&"get") | Some(&"set") => {
-                            send_command(&parts.join(" "), &state).await;
+                            // config values like the keyspace event classes are case sensitive
+                            let original: Vec<&str> = line.split_whitespace().collect();
+                            send_command(&original.join(" "), &mut client).await;
                         }
                         _ => {
                             println!("Read Config Invalid command");
                         }
                     },
+                    Some(&"eval") | Some(&"evalsha") | Some(&"eval_ro") | Some(&"evalsha_ro")
+                    | Some(&"script") => {
+                        // scripts are case sensitive
+                        let original: Vec<&str> = line.split_whitespace().collect();
+                        send_command(&original.join(" "), &mut client).await;
+                    }
+                    Some(&"module") => {
+                        // module paths are case sensitive
+                        let original: Vec<&str> = line.split_whitespace().collect();
+                        send_command(&original.join(" "), &mut client).await;
+                        // the commands of the loaded modules, one module per line after its
+                        // name and path
+                        if let Ok(modules) = client.send_command("module list").await {
+                            for module in modules.lines() {
+                                commands.extend(module.split(' ').skip(2).map(String::from));
+                            }
+                        }
+                    }
+                    Some(&"subscribe") | Some(&"psubscribe") | Some(&"ssubscribe") => {
+                        send_command(&parts.join(" "), &mut client).await;
+                        // the connection now only receives messages, until the session ends
+                        println!("Reading messages... (press Ctrl-C to quit)");
+                        loop {
+                            match client.read_message().await {
+                                Ok(messages) => print!("{}", messages),
+                                Err(e) => {
+                                    println!("Error: {:?}", e);
+                                    process::exit(1);
+                                }
+                            }
+                        }
+                    }
                     Some(command) if commands.contains(&command.to_string()) => {
-                        send_command(&parts.join(" "), &state).await;
+                        send_command(&parts.join(" "), &mut client).await;
                     }
                     _ => {
                         println!("Read Invalid command");
@@ -0,0 +1,424 @@
+use std::cell::{Cell, RefCell};
+use std::str::SplitAsciiWhitespace;
+use std::sync::Arc;
+use std::time::Duration;
+
+use mlua::{HookTriggers, Lua, LuaOptions, StdLib, Value, Variadic};
+use rus_key_command_lib::{get_value, quote_value};
+use rus_key_db::databases::Databases;
+use rus_key_db::scripts::{sha1_hex, ScriptRun};
+use rus_key_db::server::Server;
+use rus_key_factory::command_factory::CommandFactory;
+use rus_key_factory::command_init::READONLY_COMMANDS;
+use rus_key_trait::command_trait::Arity;
+
+const WRONG_ARGS: &str = "ERR wrong number of arguments for command";
+const UNKNOWN_SUBCOMMAND: &str = "ERR unknown subcommand";
+const INVALID_NUMKEYS: &str = "ERR value is not an integer or out of range";
+const NEGATIVE_NUMKEYS: &str = "ERR Number of keys can't be negative";
+const TOO_MANY_KEYS: &str = "ERR Number of keys can't be greater than number of args";
+const DB_OUT_OF_RANGE: &str = "ERR DB index is out of range";
+const NO_SCRIPT: &str = "NOSCRIPT No matching script. Please use EVAL.";
+const COMPILE_ERROR: &str = "ERR Error compiling script";
+const RUN_ERROR: &str = "ERR Error running script";
+const SCRIPT_ERROR: &str = "ERR Error returned by script";
+const KILLED: &str = "ERR Script killed by user with SCRIPT KILL...";
+const TIMED_OUT: &str = "ERR Script killed after running longer than lua-time-limit";
+const NO_ARGS: &str = "ERR Please specify at least one argument for this redis lib call";
+const INVALID_ARG: &str = "ERR Lua redis lib command arguments must be strings or integers";
+const UNKNOWN_COMMAND: &str = "ERR Unknown Redis command called from script";
+const WRITE_NOT_ALLOWED: &str = "ERR Write commands are not allowed from read-only scripts";
+
+const LUA_TIME_LIMIT: &str = "lua-time-limit";
+const DEFAULT_LUA_TIME_LIMIT: u64 = 5000;
+// the number of Lua instructions run between two checks for SCRIPT KILL and the time limit
+const HOOK_INSTRUCTIONS: u32 = 1000;
+
+// not callable from scripts, like in Redis
+const NOSCRIPT_COMMANDS: [&str; 2] = ["config", "module"];
+
+// the commands replying stored values, kept strings in Lua even when they look like numbers
+const VALUE_REPLIES: [&str; 13] = [
+    "get",
+    "getdel",
+    "getex",
+    "getset",
+    "getrange",
+    "mget",
+    "lcs",
+    "hgetall",
+    "json.get",
+    "randomkey",
+    "keys",
+    "sort",
+    "sort_ro",
+];
+
+/// Runs EVAL, EVALSHA, EVAL_RO, EVALSHA_RO and SCRIPT.
+///
+/// A script runs on the database selected by the connection, with its keys in `KEYS` and
+/// its other arguments in `ARGV`. It calls commands with `redis.call`, which raises their
+/// errors, or `redis.pcall`, which returns them as `{err = message}` tables. Integer replies
+/// are Lua integers and `nil` is `false`, other replies are strings.
+///
+/// The script returns its reply: `nil` and `false` are `nil`, `true` is 1, numbers are
+/// truncated to integers, `{err = message}` and `{ok = status}` tables are an error and a
+/// status, and the elements of other tables are joined by spaces like the replies of the
+/// commands with several values. Errors are static, so an error table replies the error of
+/// the command it came from, and any other message is logged and replied as a generic error.
+///
+/// Like the commands sent by the clients, the commands called refuse to use more memory with
+/// an OOM error once eviction cannot bring the databases under `maxmemory`.
+///
+/// A script holds the databases until it ends, so a read-only one is stopped once it runs
+/// longer than `lua-time-limit` milliseconds, 0 for no limit, and SCRIPT KILL stops it sooner.
+/// A script that already wrote is never stopped halfway, the other connections get a BUSY
+/// error once it is past the limit until it ends.
+///
+/// # Returns
+///
+/// * `None` if `cmd` is not one of them, the command result otherwise.
+pub fn handle_script_command(
+    cmd: &str,
+    parts: &mut SplitAsciiWhitespace,
+    dbs: &mut Databases,
+    selected: usize,
+    factory: &CommandFactory,
+) -> Option<Result<String, &'static str>> {
+    let result = match cmd {
+        "eval" => eval(parts, dbs, selected, factory, false, false),
+        "evalsha" => eval(parts, dbs, selected, factory, true, false),
+        "eval_ro" => eval(parts, dbs, selected, factory, false, true),
+        "evalsha_ro" => eval(parts, dbs, selected, factory, true, true),
+        "script" => script(parts, dbs.server()),
+        _ => return None,
+    };
+    Some(result)
+}
+
+/// Returns the arguments a command of `handle_script_command` takes, see `Arity`.
+///
+/// # Returns
+///
+/// * `None` if `cmd` is not one of them.
+pub fn script_arity(cmd: &str) -> Option<Arity> {
+    match cmd {
+        // the script, or its SHA1 digest, and numkeys
+        "eval" | "evalsha" | "eval_ro" | "evalsha_ro" => Some(Arity::with_integers(2, &[1])),
+        "script" => Some(Arity::new(1)),
+        _ => None,
+    }
+}
+
+/// Runs SCRIPT KILL without the databases, since the script to kill holds them, and replies
+/// BUSY to the other commands while a script past its time limit holds them. A connection
+/// takes the server once when it starts, so the kill comes from one opened before the script.
+///
+/// # Returns
+///
+/// * `None` if `line` is to be run with the databases, the command result otherwise.
+pub fn handle_running_script(line: &str, server: &Server) -> Option<Result<String, &'static str>> {
+    let parts: Vec<String> = line
+        .split_ascii_whitespace()
+        .map(|part| part.to_lowercase())
+        .collect();
+    if parts == ["script", "kill"] {
+        return Some(server.scripts.kill());
+    }
+    server.scripts.check_busy().err().map(Err)
+}
+
+// EVAL script numkeys [key ...] [arg ...], or EVALSHA sha1 numkeys ...
+fn eval(
+    parts: &mut SplitAsciiWhitespace,
+    dbs: &mut Databases,
+    selected: usize,
+    factory: &CommandFactory,
+    by_sha: bool,
+    readonly: bool,
+) -> Result<String, &'static str> {
+    let server = dbs.shared_server();
+    let first = parts.next().ok_or(WRONG_ARGS)?;
+    let body = if by_sha {
+        server.scripts.get(first).ok_or(NO_SCRIPT)?
+    } else {
+        // the script is quoted, it has spaces
+        let body = get_value(first.to_string(), parts);
+        server.scripts.load(&body);
+        body
+    };
+    let numkeys: i64 = parts
+        .next()
+        .ok_or(WRONG_ARGS)?
+        .parse()
+        .map_err(|_| INVALID_NUMKEYS)?;
+    let mut keys = Vec::new();
+    while let Some(part) = parts.next() {
+        keys.push(get_value(part.to_string(), parts));
+    }
+    if numkeys < 0 {
+        return Err(NEGATIVE_NUMKEYS);
+    }
+    if numkeys as usize > keys.len() {
+        return Err(TOO_MANY_KEYS);
+    }
+    let argv = keys.split_off(numkeys as usize);
+    let limit = server
+        .config_get(LUA_TIME_LIMIT)
+        .and_then(|limit| limit.parse().ok())
+        .unwrap_or(DEFAULT_LUA_TIME_LIMIT);
+    if dbs.get(selected).is_none() {
+        return Err(DB_OUT_OF_RANGE);
+    }
+    let script = Script {
+        factory,
+        run: server
+            .scripts
+            .start((limit > 0).then(|| Duration::from_millis(limit))),
+        readonly,
+        selected,
+    };
+    let result = script.run(&body, keys, argv, dbs);
+    server.scripts.finish();
+    result
+}
+
+// SCRIPT LOAD|EXISTS|FLUSH|KILL
+fn script(parts: &mut SplitAsciiWhitespace, server: &Server) -> Result<String, &'static str> {
+    let subcommand = parts.next().ok_or(WRONG_ARGS)?.to_lowercase();
+    match subcommand.as_str() {
+        "load" => {
+            let body = get_value(parts.next().ok_or(WRONG_ARGS)?.to_string(), parts);
+            Ok(server.scripts.load(&body))
+        }
+        "exists" => {
+            let exists: Vec<&str> = parts
+                .map(|sha| if server.scripts.exists(sha) { "1" } else { "0" })
+                .collect();
+            if exists.is_empty() {
+                return Err(WRONG_ARGS);
+            }
+            Ok(exists.join(" "))
+        }
+        // ASYNC and SYNC are accepted, the cache is small enough to drop at once
+        "flush" => {
+            if let Some(mode) = parts.next() {
+                let mode = mode.to_lowercase();
+                if mode != "async" && mode != "sync" {
+                    return Err(UNKNOWN_SUBCOMMAND);
+                }
+            }
+            server.scripts.flush();
+            Ok("OK".to_string())
+        }
+        "kill" => server.scripts.kill(),
+        _ => Err(UNKNOWN_SUBCOMMAND),
+    }
+}
+
+struct Script<'a> {
+    factory: &'a CommandFactory,
+    run: Arc<ScriptRun>,
+    readonly: bool,
+    // the database the commands called run on
+    selected: usize,
+}
+
+impl Script<'_> {
+    fn run(
+        &self,
+        body: &str,
+        keys: Vec<String>,
+        argv: Vec<String>,
+        dbs: &mut Databases,
+    ) -> Result<String, &'static str> {
+        // no io, os or package, scripts only reach the keys through redis.call
+        let libs = StdLib::TABLE | StdLib::STRING | StdLib::MATH | StdLib::UTF8;
+        let lua = Lua::new_with(libs, LuaOptions::new()).map_err(|_| RUN_ERROR)?;
+        let run = Arc::clone(&self.run);
+        lua.set_hook(
+            HookTriggers::new().every_nth_instruction(HOOK_INSTRUCTIONS),
+            move |_, _| {
+                if run.is_killed() {
+                    return Err(mlua::Error::RuntimeError(KILLED.to_string()));
+                }
+                // stopping a script that wrote would break its atomicity
+                if run.is_timed_out() && !run.wrote() {
+                    return Err(mlua::Error::RuntimeError(TIMED_OUT.to_string()));
+                }
+                Ok(())
+            },
+        );
+        let dbs = RefCell::new(dbs);
+        // the error raised by the last redis.call, to reply it if the script does not catch it
+        let raised = Cell::new(None);
+        // the errors of the commands called, the ones an error table may reply
+        let errors = RefCell::new(Vec::new());
+        let result = lua.scope(|scope| {
+            let globals = lua.globals();
+            globals.set("dofile", Value::Nil)?;
+            globals.set("loadfile", Value::Nil)?;
+            globals.set("KEYS", lua.create_sequence_from(keys)?)?;
+            globals.set("ARGV", lua.create_sequence_from(argv)?)?;
+            let redis = lua.create_table()?;
+            let call = scope.create_function(|lua, args: Variadic<Value>| {
+                match self.call(&args, &mut dbs.borrow_mut()) {
+                    Ok((cmd, reply)) => reply_to_lua(lua, &cmd, reply),
+                    Err(error) => {
+                        raised.set(Some(error));
+                        record_error(&mut errors.borrow_mut(), error);
+                        Err(mlua::Error::RuntimeError(error.to_string()))
+                    }
+                }
+            })?;
+            let pcall = scope.create_function(|lua, args: Variadic<Value>| {
+                match self.call(&args, &mut dbs.borrow_mut()) {
+                    Ok((cmd, reply)) => reply_to_lua(lua, &cmd, reply),
+                    Err(error) => {
+                        record_error(&mut errors.borrow_mut(), error);
+                        Ok(Value::Table(lua.create_table_from([("err", error)])?))
+                    }
+                }
+            })?;
+            redis.set("call", call)?;
+            redis.set("pcall", pcall)?;
+            redis.set(
+                "error_reply",
+                lua.create_function(|lua, message: String| {
+                    lua.create_table_from([("err", message)])
+                })?,
+            )?;
+            redis.set(
+                "status_reply",
+                lua.create_function(|lua, status: String| lua.create_table_from([("ok", status)]))?,
+            )?;
+            redis.set(
+                "sha1hex",
+                lua.create_function(|_, body: String| Ok(sha1_hex(&body)))?,
+            )?;
+            globals.set("redis", redis)?;
+            let function = lua.load(body).set_name("user_script").into_function()?;
+            let value: Value = function.call(())?;
+            Ok(lua_to_reply(&value, &errors.borrow()))
+        });
+        result.unwrap_or_else(|error| Err(script_error(&error, raised.get())))
+    }
+
+    // runs a command of redis.call or redis.pcall, returns its name and reply
+    fn call(&self, args: &[Value], dbs: &mut Databases) -> Result<(String, String), &'static str> {
+        let mut words = Vec::new();
+        for arg in args {
+            let word = match arg {
+                Value::String(word) => word.to_str().map_err(|_| INVALID_ARG)?.to_string(),
+                Value::Integer(word) => word.to_string(),
+                Value::Number(word) => word.to_string(),
+                _ => return Err(INVALID_ARG),
+            };
+            words.push(word);
+        }
+        let cmd = words.first().ok_or(NO_ARGS)?.to_lowercase();
+        let command = match self.factory.create(&cmd) {
+            Some(command) if !NOSCRIPT_COMMANDS.contains(&cmd.as_str()) => command,
+            _ => return Err(UNKNOWN_COMMAND),
+        };
+        if !READONLY_COMMANDS.contains(&cmd.as_str()) {
+            if self.readonly {
+                return Err(WRITE_NOT_ALLOWED);
+            }
+            // evict before every write, like `handle_command`
+            if let Err(error) = dbs.free_memory() {
+                if self.factory.is_deny_oom(&cmd) {
+                    return Err(error);
+                }
+            }
+            self.run.set_wrote();
+        }
+        // the commands read quoted values, for arguments with spaces or quotation marks
+        let line: Vec<String> = words[1..].iter().map(|word| quote_value(word)).collect();
+        let line = line.join(" ");
+        let db = dbs.get_mut(self.selected).ok_or(DB_OUT_OF_RANGE)?;
+        let reply = command.execute(&mut line.split_ascii_whitespace(), db)?;
+        Ok((cmd, reply))
+    }
+}
+
+// a command reply as a Lua value
+fn reply_to_lua<'lua>(lua: &'lua Lua, cmd: &str, reply: String) -> mlua::Result<Value<'lua>> {
+    if reply == "nil" {
+        return Ok(Value::Boolean(false));
+    }
+    if !VALUE_REPLIES.contains(&cmd) {
+        if let Ok(integer) = reply.parse::<i64>() {
+            return Ok(Value::Integer(integer));
+        }
+    }
+    Ok(Value::String(lua.create_string(&reply)?))
+}
+
+// keeps the distinct errors of the commands called, there are only so many static ones
+fn record_error(errors: &mut Vec<&'static str>, error: &'static str) {
+    if !errors.contains(&error) {
+        errors.push(error);
+    }
+}
+
+// the value returned by a script as its reply, `errors` are the ones an error table may reply
+fn lua_to_reply(value: &Value, errors: &[&'static str]) -> Result<String, &'static str> {
+    match value {
+        Value::Nil | Value::Boolean(false) => Ok("nil".to_string()),
+        Value::Boolean(true) => Ok("1".to_string()),
+        Value::Integer(integer) => Ok(integer.to_string()),
+        Value::Number(number) => Ok((*number as i64).to_string()),
+        Value::String(string) => Ok(string.to_string_lossy().to_string()),
+        Value::Table(table) => {
+            if let Ok(Value::String(error)) = table.raw_get("err") {
+                let message = error.to_string_lossy();
+                return match errors.iter().find(|error| **error == message) {
+                    Some(error) => Err(error),
+                    None => {
+                        println!("Error: {}", message);
+                        Err(SCRIPT_ERROR)
+                    }
+                };
+            }
+            if let Ok(Value::String(status)) = table.raw_get("ok") {
+                return Ok(status.to_string_lossy().to_string());
+            }
+            let elements: Vec<String> = table
+                .clone()
+                .sequence_values::<Value>()
+                .map_while(|element| element.ok())
+                .map(|element| {
+                    lua_to_reply(&element, errors).unwrap_or_else(|error| error.to_string())
+                })
+                .collect();
+            Ok(elements.join(" "))
+        }
+        _ => Ok("nil".to_string()),
+    }
+}
+
+// the reply of a failed script: the error of redis.call it did not catch, SCRIPT KILL or the
+// time limit, or the Lua error, only logged since its message is not static
+fn script_error(error: &mlua::Error, raised: Option<&'static str>) -> &'static str {
+    match error {
+        mlua::Error::CallbackError { cause, .. } => script_error(cause, raised),
+        mlua::Error::RuntimeError(message) => match raised {
+            Some(raised) if message == raised => raised,
+            _ if message == KILLED => KILLED,
+            _ if message == TIMED_OUT => TIMED_OUT,
+            _ => {
+                println!("Error: {}", message);
+                RUN_ERROR
+            }
+        },
+        mlua::Error::SyntaxError { message, .. } => {
+            println!("Error: {}", message);
+            COMPILE_ERROR
+        }
+        error => {
+            println!("Error: {}", error);
+            RUN_ERROR
+        }
+    }
+}
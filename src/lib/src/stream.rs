@@ -62,4 +62,18 @@ impl Client {
             }
         }
     }
+
+    /// Waits for data the server sends with no request, like the messages published to a
+    /// subscribed connection.
+    pub async fn read_message(&mut self) -> Result<String> {
+        let mut buf = [0; 512];
+        let n = self.stream.read(&mut buf).await?;
+        if n == 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "Connection closed by the server",
+            ));
+        }
+        Ok(String::from_utf8_lossy(&buf[..n]).to_string())
+    }
 }
@@ -1,5 +1,6 @@
 use rus_key_lib::args::Opt;
-use rus_key_db::db::{Db, DataType};
+use rus_key_db::databases::Databases;
+use rus_key_lib::expire::active_expire;
 use rus_key_lib::init::{Config, init, Store};
 use rus_key_lib::net::handle_client;
 use rus_key_lib::read_line::read_line;
@@ -8,19 +9,22 @@ use std::sync::Arc;
 use tokio::net::TcpListener;
 use tokio::sync::Mutex;
 
+const DEFAULT_DATABASES: usize = 16;
+
 #[tokio::main]
 async fn main() {
-    let db = Arc::new(Mutex::new(Db::new()));
     // init config
     let config_map = init();
     println!("config: {:?}", config_map);
-    db.lock().await.set(
-        "ruskey_config".to_string(),
-        DataType::ZSet(config_map.clone()),
-    );
     // parse args priority command line > config file
     let opt = Opt::parse();
-    let config = Config::new(opt, config_map);
+    let config = Config::new(opt, config_map.clone());
+    let databases = config
+        .get("databases")
+        .and_then(|databases| databases.parse::<usize>().ok())
+        .unwrap_or(DEFAULT_DATABASES);
+    let dbs = Arc::new(Mutex::new(Databases::new(databases)));
+    dbs.lock().await.server().load_config(config_map);
     let host = config
         .get("host")
         .unwrap_or_else(|| String::from("127.0.0.1"));
@@ -37,14 +41,15 @@ async fn main() {
         }
     });
 
+    tokio::spawn(active_expire(Arc::clone(&dbs)));
+
     loop {
         let (stream, _) = listener.accept().await.unwrap();
 
-        let db = Arc::clone(&db);
+        let dbs = Arc::clone(&dbs);
 
         tokio::spawn(async move {
-            let mut db_lock = db.lock().await;
-            if let Err(e) = handle_client(stream, &mut *db_lock).await {
+            if let Err(e) = handle_client(stream, dbs).await {
                 println!("Error: {:?}", e);
             }
         });
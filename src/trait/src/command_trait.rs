@@ -1,10 +1,49 @@
 use std::str::SplitAsciiWhitespace;
 use rus_key_db::db::Db;
 
+const WRONG_ARGS: &str = "ERR wrong number of arguments for command";
+const NOT_INTEGER: &str = "ERR value is not an integer or out of range";
+
+/// The arguments a command takes, its name not included, checked when the command is queued
+/// in a transaction so that a command bound to fail aborts EXEC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Arity {
+    // the minimum number of arguments
+    min: usize,
+    // the positions of the arguments that must be integers, counted from 0
+    integers: &'static [usize],
+}
+
+impl Arity {
+    pub const fn new(min: usize) -> Arity {
+        Arity { min, integers: &[] }
+    }
+
+    pub const fn with_integers(min: usize, integers: &'static [usize]) -> Arity {
+        Arity { min, integers }
+    }
+
+    /// Checks the arguments of a command, a quoted argument being one.
+    pub fn check(&self, args: &[String]) -> Result<(), &'static str> {
+        if args.len() < self.min {
+            return Err(WRONG_ARGS);
+        }
+        for position in self.integers {
+            if let Some(arg) = args.get(*position) {
+                arg.parse::<i64>().map_err(|_| NOT_INTEGER)?;
+            }
+        }
+        Ok(())
+    }
+}
+
 pub trait Command: Send + Sync {
     fn execute(
         &self,
         parts: &mut SplitAsciiWhitespace,
         db: &mut Db,
     ) -> Result<String, &'static str>;
+
+    /// Returns the arguments the command takes, see `Arity`.
+    fn arity(&self) -> Arity;
 }
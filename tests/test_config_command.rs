@@ -1,5 +1,5 @@
 use rus_key_trait::command_trait::Command;
-use rus_key_db::db::{DataType, Db};
+use rus_key_db::db::Db;
 use config_commands::config::ConfigCommand;
 use rus_key_lib::init;
 
@@ -7,10 +7,7 @@ use rus_key_lib::init;
 fn test_config_get_command() {
     let mut db = Db::new();
     let config_map = init::init();
-    db.set(
-        "ruskey_config".to_string(),
-        DataType::ZSet(config_map.clone()),
-    );
+    db.server().load_config(config_map.clone());
 
     let command = ConfigCommand {};
     let all_command_str = "get *";
@@ -0,0 +1,276 @@
+use rus_key_db::databases::Databases;
+use rus_key_factory::command_factory::CommandFactory;
+use rus_key_lib::cmd::handle_command;
+
+fn command(
+    dbs: &mut Databases,
+    selected: &mut usize,
+    factory: &CommandFactory,
+    line: &str,
+) -> Result<String, &'static str> {
+    let mut parts = line.split_ascii_whitespace();
+    handle_command(&mut parts, dbs, selected, factory)
+}
+
+#[test]
+fn test_select_command() {
+    let mut dbs = Databases::new(4);
+    let factory = CommandFactory::new();
+    let mut selected = 0;
+
+    let tests_case: Vec<(&str, Result<String, &'static str>)> = vec![
+        ("set key zero", Ok("OK".to_string())),
+        ("select 1", Ok("OK".to_string())),
+        ("get key", Ok("nil".to_string())),
+        ("set key one", Ok("OK".to_string())),
+        ("dbsize", Ok("1".to_string())),
+        ("select 0", Ok("OK".to_string())),
+        ("get key", Ok("zero".to_string())),
+        ("select 4", Err("ERR DB index is out of range")),
+        ("select -1", Err("ERR DB index is out of range")),
+        (
+            "select abc",
+            Err("ERR value is not an integer or out of range"),
+        ),
+        ("select", Err("ERR wrong number of arguments for command")),
+    ];
+    for (line, expected_result) in tests_case {
+        println!("line: {}", line);
+        let result = command(&mut dbs, &mut selected, &factory, line);
+        assert_eq!(result, expected_result);
+    }
+    assert_eq!(selected, 0);
+
+    // another connection keeps its own selection
+    let mut other = 1;
+    let result = command(&mut dbs, &mut other, &factory, "get key");
+    assert_eq!(result.unwrap(), "one".to_string());
+}
+
+#[test]
+fn test_move_command() {
+    let mut dbs = Databases::new(4);
+    let factory = CommandFactory::new();
+    let mut selected = 0;
+
+    let tests_case: Vec<(&str, Result<String, &'static str>)> = vec![
+        ("set key value", Ok("OK".to_string())),
+        ("set ttl_key value EX 100", Ok("OK".to_string())),
+        ("move key 2", Ok("1".to_string())),
+        ("exists key", Ok("0".to_string())),
+        ("move not_exists 2", Ok("0".to_string())),
+        ("move ttl_key 2", Ok("1".to_string())),
+        (
+            "move key 0",
+            Err("ERR source and destination objects are the same"),
+        ),
+        ("move key 9", Err("ERR DB index is out of range")),
+        // the key is not moved over an existing one
+        ("set key other", Ok("OK".to_string())),
+        ("move key 2", Ok("0".to_string())),
+        ("get key", Ok("other".to_string())),
+        ("select 2", Ok("OK".to_string())),
+        ("get key", Ok("value".to_string())),
+        ("ttl key", Ok("-1".to_string())),
+        ("dbsize", Ok("2".to_string())),
+    ];
+    for (line, expected_result) in tests_case {
+        println!("line: {}", line);
+        let result = command(&mut dbs, &mut selected, &factory, line);
+        assert_eq!(result, expected_result);
+    }
+
+    // the TTL moves with the key
+    let result = command(&mut dbs, &mut selected, &factory, "ttl ttl_key").unwrap();
+    let ttl = result.parse::<i64>().unwrap();
+    assert!(ttl > 0 && ttl <= 100);
+    let mut source = 0;
+    let result = command(&mut dbs, &mut source, &factory, "ttl ttl_key");
+    assert_eq!(result.unwrap(), "-2".to_string());
+}
+
+#[test]
+fn test_swapdb_command() {
+    let mut dbs = Databases::new(4);
+    let factory = CommandFactory::new();
+    let mut selected = 0;
+
+    let tests_case: Vec<(&str, Result<String, &'static str>)> = vec![
+        ("set key zero", Ok("OK".to_string())),
+        ("select 3", Ok("OK".to_string())),
+        ("set key three", Ok("OK".to_string())),
+        ("set other three", Ok("OK".to_string())),
+        ("swapdb 0 3", Ok("OK".to_string())),
+        // the connection keeps db 3, which now holds the data of db 0
+        ("get key", Ok("zero".to_string())),
+        ("dbsize", Ok("1".to_string())),
+        ("select 0", Ok("OK".to_string())),
+        ("get key", Ok("three".to_string())),
+        ("swapdb 0 4", Err("ERR DB index is out of range")),
+        ("swapdb 0", Err("ERR wrong number of arguments for command")),
+    ];
+    for (line, expected_result) in tests_case {
+        println!("line: {}", line);
+        let result = command(&mut dbs, &mut selected, &factory, line);
+        assert_eq!(result, expected_result);
+    }
+}
+
+#[test]
+fn test_flush_command() {
+    let mut dbs = Databases::new(4);
+    let factory = CommandFactory::new();
+    let mut selected = 0;
+
+    let tests_case: Vec<(&str, Result<String, &'static str>)> = vec![
+        ("set key zero", Ok("OK".to_string())),
+        ("select 1", Ok("OK".to_string())),
+        ("set key one", Ok("OK".to_string())),
+        ("set other one", Ok("OK".to_string())),
+        ("flushdb", Ok("OK".to_string())),
+        ("dbsize", Ok("0".to_string())),
+        ("select 0", Ok("OK".to_string())),
+        ("dbsize", Ok("1".to_string())),
+        ("flushdb fast", Err("ERR syntax error")),
+        ("select 2", Ok("OK".to_string())),
+        ("set key two", Ok("OK".to_string())),
+        ("flushall async", Ok("OK".to_string())),
+        ("dbsize", Ok("0".to_string())),
+        ("select 0", Ok("OK".to_string())),
+        ("dbsize", Ok("0".to_string())),
+    ];
+    for (line, expected_result) in tests_case {
+        println!("line: {}", line);
+        let result = command(&mut dbs, &mut selected, &factory, line);
+        assert_eq!(result, expected_result);
+    }
+}
+
+#[test]
+fn test_config_shared_by_databases() {
+    let mut dbs = Databases::new(4);
+    let factory = CommandFactory::new();
+    let mut config = std::collections::BTreeMap::new();
+    config.insert("maxclients".to_string(), "10".to_string());
+    dbs.server().load_config(config);
+
+    let mut first = 0;
+    let mut second = 3;
+    let result = command(&mut dbs, &mut first, &factory, "config set maxclients 20");
+    assert_eq!(result.unwrap(), "OK".to_string());
+    let result = command(&mut dbs, &mut second, &factory, "config get maxclients");
+    assert_eq!(result.unwrap(), "maxclients: 20".to_string());
+
+    // FLUSHALL only removes keys
+    let result = command(&mut dbs, &mut first, &factory, "flushall");
+    assert_eq!(result.unwrap(), "OK".to_string());
+    let result = command(&mut dbs, &mut first, &factory, "config get maxclients");
+    assert_eq!(result.unwrap(), "maxclients: 20".to_string());
+}
+
+#[test]
+fn test_info_command() {
+    let mut dbs = Databases::new(4);
+    let factory = CommandFactory::new();
+    let mut selected = 0;
+
+    let tests_case: Vec<(&str, Result<String, &'static str>)> = vec![
+        ("set key value", Ok("OK".to_string())),
+        ("set ttl_key value EX 100", Ok("OK".to_string())),
+        ("select 2", Ok("OK".to_string())),
+        ("set key value", Ok("OK".to_string())),
+        (
+            "info keyspace",
+            Ok("db0: keys=2,expires=1 db2: keys=1,expires=0".to_string()),
+        ),
+        (
+            "info stats",
+            Ok("expired_keys: 0 evicted_keys: 0 lazyfreed_objects: 0 client_output_buffer_limit_disconnections: 0".to_string()),
+        ),
+    ];
+    for (line, expected_result) in tests_case {
+        println!("line: {}", line);
+        let result = command(&mut dbs, &mut selected, &factory, line);
+        assert_eq!(result, expected_result);
+    }
+
+    // the background cycle deletes keys nobody reads
+    dbs.get_mut(2).unwrap().expire_at("key", 1);
+    let expired = dbs.active_expire_cycle(std::time::Duration::from_millis(25));
+    assert_eq!(expired, 1);
+    let result = command(&mut dbs, &mut selected, &factory, "info stats");
+    assert_eq!(
+        result.unwrap(),
+        "expired_keys: 1 evicted_keys: 0 lazyfreed_objects: 0 client_output_buffer_limit_disconnections: 0".to_string()
+    );
+    let result = command(&mut dbs, &mut selected, &factory, "info keyspace");
+    assert_eq!(result.unwrap(), "db0: keys=2,expires=1".to_string());
+}
+
+#[test]
+fn test_memory_command() {
+    let mut dbs = Databases::new(4);
+    let factory = CommandFactory::new();
+    let mut selected = 0;
+
+    let result = command(&mut dbs, &mut selected, &factory, "memory doctor");
+    assert_eq!(
+        result.unwrap(),
+        "The instance is empty, there is nothing to report.".to_string()
+    );
+
+    command(&mut dbs, &mut selected, &factory, "set key value").unwrap();
+    let mut fields = String::new();
+    for index in 0..20 {
+        fields.push_str(&format!(" field{} value{}", index, index));
+    }
+    let line = format!("hmset hash{}", fields);
+    command(&mut dbs, &mut selected, &factory, &line).unwrap();
+
+    let usage = command(&mut dbs, &mut selected, &factory, "memory usage key").unwrap();
+    let usage = usage.parse::<usize>().unwrap();
+    assert!(usage > "keyvalue".len());
+    let exact = command(
+        &mut dbs,
+        &mut selected,
+        &factory,
+        "memory usage hash SAMPLES 0",
+    );
+    let exact = exact.unwrap().parse::<usize>().unwrap();
+    assert_eq!(dbs.used_memory(), usage + exact);
+    let sampled = command(&mut dbs, &mut selected, &factory, "memory usage hash");
+    assert!(sampled.unwrap().parse::<usize>().unwrap() > 0);
+
+    let tests_case: Vec<(&str, Result<String, &'static str>)> = vec![
+        ("memory usage not_exists", Ok("nil".to_string())),
+        ("memory usage key samples", Err("ERR syntax error")),
+        (
+            "memory usage key samples x",
+            Err("ERR value is not an integer or out of range"),
+        ),
+        ("memory usage key count 1", Err("ERR syntax error")),
+        (
+            "memory usage",
+            Err("ERR wrong number of arguments for command"),
+        ),
+        ("memory purge", Err("ERR unknown subcommand")),
+        (
+            "memory stats",
+            Ok(format!(
+                "total.allocated: {} keys.count: 2 keys.bytes-per-key: {} db.0: keys=2,bytes={}",
+                usage + exact,
+                (usage + exact) / 2,
+                usage + exact
+            )),
+        ),
+        (
+            "memory doctor",
+            Ok("No memory issues detected.".to_string()),
+        ),
+    ];
+    for (line, expected_result) in tests_case {
+        println!("line: {}", line);
+        let result = command(&mut dbs, &mut selected, &factory, line);
+        assert_eq!(result, expected_result);
+    }
+}
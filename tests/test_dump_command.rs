@@ -0,0 +1,345 @@
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use json_commands::json::JsonCommand;
+use rus_key_db::bloom::BloomFilter;
+use rus_key_db::cms::CountMinSketch;
+use rus_key_db::cuckoo::CuckooFilter;
+use rus_key_db::databases::Databases;
+use rus_key_db::db::{DataType, Db};
+use rus_key_db::dump::{dump, from_hex, restore, to_hex};
+use rus_key_db::timeseries::{DuplicatePolicy, TimeSeries};
+use rus_key_db::topk::TopK;
+use rus_key_db::vector::{Quantization, VectorSet};
+use rus_key_factory::command_factory::CommandFactory;
+use rus_key_lib::cmd::handle_command;
+use rus_key_lib::net::handle_client;
+use rus_key_trait::command_trait::Command;
+use tokio::sync::Mutex;
+
+fn command(
+    dbs: &mut Databases,
+    selected: &mut usize,
+    factory: &CommandFactory,
+    line: &str,
+) -> Result<String, &'static str> {
+    let mut parts = line.split_ascii_whitespace();
+    handle_command(&mut parts, dbs, selected, factory)
+}
+
+fn values() -> Vec<DataType> {
+    let mut bloom = BloomFilter::new(0.01, 10, 2);
+    let mut cuckoo = CuckooFilter::new(10, 2, 20, 1);
+    let mut cms = CountMinSketch::new(10, 3);
+    let mut topk = TopK::new(3, 8, 4, 0.9);
+    for index in 0..30 {
+        let item = format!("item_{}", index % 7);
+        bloom.add(&item).unwrap();
+        cuckoo.add(&item).unwrap();
+        cms.incr_by(&item, 1);
+        topk.incr_by(&item, 1);
+    }
+    let mut series = TimeSeries::new(
+        1000,
+        DuplicatePolicy::Sum,
+        vec![("sensor".to_string(), "1".to_string())],
+    );
+    for timestamp in 0..5 {
+        series
+            .add(timestamp * 10, timestamp as f64 / 2.0, None)
+            .unwrap();
+    }
+    let mut vectors = VectorSet::new(3, Quantization::Q8, 4, 10);
+    for index in 0..6 {
+        let vector = [index as f32, 1.0, -(index as f32)];
+        vectors.add(&format!("v{}", index), &vector, None).unwrap();
+    }
+    vectors.remove("v2");
+    let mut db = Db::new();
+    let command = JsonCommand::new("json.set".to_string());
+    let mut parts = r#"doc $ {"a": [1, 2.5, {"b": null}], "c": "text"}"#.split_ascii_whitespace();
+    command.execute(&mut parts, &mut db).unwrap();
+    let json = db.peek("doc").unwrap().value().clone();
+    let mut zset = BTreeMap::new();
+    zset.insert("member".to_string(), "1.5".to_string());
+
+    vec![
+        DataType::String("value with unicode é".to_string()),
+        DataType::List(vec!["a".to_string(), "".to_string(), "a".to_string()]),
+        DataType::ZSet(zset),
+        json,
+        DataType::Bloom(bloom),
+        DataType::Cuckoo(cuckoo),
+        DataType::CountMinSketch(cms),
+        DataType::TopK(topk),
+        DataType::TimeSeries(series),
+        DataType::VectorSet(vectors),
+    ]
+}
+
+#[test]
+fn test_dump_round_trip() {
+    for value in values() {
+        let payload = dump(&value, Some(1234));
+        let (restored, expires_at) = restore(&payload).unwrap();
+        assert_eq!(expires_at, Some(1234));
+        // the restored value serializes to the same payload
+        assert_eq!(dump(&restored, Some(1234)), payload);
+        assert_eq!(from_hex(&to_hex(&payload)).unwrap(), payload);
+    }
+
+    let mut hash = BTreeMap::new();
+    hash.insert("field".to_string(), "value".to_string());
+    let payload = dump(&DataType::HashMap(hash.clone()), None);
+    match restore(&payload).unwrap() {
+        (DataType::HashMap(restored), None) => assert_eq!(restored, hash),
+        _ => panic!("hash expected"),
+    }
+}
+
+#[test]
+fn test_restore_invalid_payload() {
+    let invalid = "ERR DUMP payload version or checksum are wrong";
+    let payload = dump(&DataType::String("value".to_string()), None);
+
+    let mut corrupted = payload.clone();
+    corrupted[2] ^= 1;
+    assert_eq!(restore(&corrupted).unwrap_err(), invalid);
+    assert_eq!(restore(&payload[..4]).unwrap_err(), invalid);
+    assert_eq!(from_hex("abc").unwrap_err(), invalid);
+    assert_eq!(from_hex("zz").unwrap_err(), invalid);
+}
+
+#[test]
+fn test_dump_restore_command() {
+    let mut dbs = Databases::new(4);
+    let factory = CommandFactory::new();
+    let mut selected = 0;
+
+    command(&mut dbs, &mut selected, &factory, "set key value EX 100").unwrap();
+    command(&mut dbs, &mut selected, &factory, "set other value").unwrap();
+    let payload = command(&mut dbs, &mut selected, &factory, "dump key").unwrap();
+
+    let tests_case: Vec<(String, Result<String, &'static str>)> = vec![
+        ("dump not_exists".to_string(), Ok("nil".to_string())),
+        (
+            format!("restore other 0 {}", payload),
+            Err("BUSYKEY Target key name already exists."),
+        ),
+        (
+            format!("restore other 0 {} REPLACE", payload),
+            Ok("OK".to_string()),
+        ),
+        ("get other".to_string(), Ok("value".to_string())),
+        (format!("restore copy 0 {}", payload), Ok("OK".to_string())),
+        (
+            format!("restore persistent 5000 {}", payload),
+            Ok("OK".to_string()),
+        ),
+        // a deadline in the past creates nothing
+        (
+            format!("restore gone 1 {} ABSTTL", payload),
+            Ok("OK".to_string()),
+        ),
+        ("exists gone".to_string(), Ok("0".to_string())),
+        (
+            format!("restore new -1 {}", payload),
+            Err("ERR Invalid TTL value, must be >= 0"),
+        ),
+        (
+            "restore new 0 00ff".to_string(),
+            Err("ERR DUMP payload version or checksum are wrong"),
+        ),
+        (
+            format!("restore new 0 {} FOO", payload),
+            Err("ERR syntax error"),
+        ),
+        (
+            "restore new 0".to_string(),
+            Err("ERR wrong number of arguments for command"),
+        ),
+    ];
+    for (line, expected_result) in tests_case {
+        println!("line: {}", line);
+        let result = command(&mut dbs, &mut selected, &factory, &line);
+        assert_eq!(result, expected_result);
+    }
+
+    // the TTL comes from the payload unless one is given
+    for (key, max) in [("other", 100), ("copy", 100), ("persistent", 5)] {
+        let ttl = command(&mut dbs, &mut selected, &factory, &format!("ttl {}", key));
+        let ttl = ttl.unwrap().parse::<i64>().unwrap();
+        assert!(ttl > 0 && ttl <= max);
+    }
+}
+
+#[test]
+fn test_copy_command() {
+    let mut dbs = Databases::new(4);
+    let factory = CommandFactory::new();
+    let mut selected = 0;
+
+    let tests_case: Vec<(&str, Result<String, &'static str>)> = vec![
+        ("set key value EX 100", Ok("OK".to_string())),
+        ("set other old", Ok("OK".to_string())),
+        ("copy key new", Ok("1".to_string())),
+        ("copy key other", Ok("0".to_string())),
+        ("copy key other REPLACE", Ok("1".to_string())),
+        ("get other", Ok("value".to_string())),
+        ("copy not_exists new", Ok("0".to_string())),
+        (
+            "copy key key",
+            Err("ERR source and destination objects are the same"),
+        ),
+        ("copy key key DB 2", Ok("1".to_string())),
+        ("copy key key DB 9", Err("ERR DB index is out of range")),
+        ("copy key new2 FOO", Err("ERR syntax error")),
+        ("select 2", Ok("OK".to_string())),
+        ("get key", Ok("value".to_string())),
+    ];
+    for (line, expected_result) in tests_case {
+        println!("line: {}", line);
+        let result = command(&mut dbs, &mut selected, &factory, line);
+        assert_eq!(result, expected_result);
+    }
+    let ttl = command(&mut dbs, &mut selected, &factory, "ttl key").unwrap();
+    assert!(ttl.parse::<i64>().unwrap() > 0);
+
+    // copies are independent of the source
+    selected = 0;
+    command(&mut dbs, &mut selected, &factory, "append new _more").unwrap();
+    let result = command(&mut dbs, &mut selected, &factory, "get key");
+    assert_eq!(result.unwrap(), "value".to_string());
+}
+
+// runs a server on a random local port in the background, like a second instance
+fn start_server() -> (u16, Arc<Mutex<Databases>>) {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.set_nonblocking(true).unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let dbs = Arc::new(Mutex::new(Databases::new(4)));
+    let server_dbs = Arc::clone(&dbs);
+    thread::spawn(move || {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async move {
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                tokio::spawn(handle_client(stream, Arc::clone(&server_dbs)));
+            }
+        });
+    });
+    (port, dbs)
+}
+
+#[test]
+fn test_migrate_command() {
+    let (port, target) = start_server();
+    let mut dbs = Databases::new(4);
+    let factory = CommandFactory::new();
+    let mut selected = 0;
+
+    // large enough to span several reads on the target
+    let large = "x".repeat(4096);
+    let tests_case: Vec<(String, Result<String, &'static str>)> = vec![
+        ("set key value EX 100".to_string(), Ok("OK".to_string())),
+        (format!("set large {}", large), Ok("OK".to_string())),
+        ("set kept value".to_string(), Ok("OK".to_string())),
+        (format!("migrate 127.0.0.1 {} key 2 1000", port), Ok("OK".to_string())),
+        ("exists key".to_string(), Ok("0".to_string())),
+        (
+            format!("migrate 127.0.0.1 {} not_exists 2 1000", port),
+            Ok("NOKEY".to_string()),
+        ),
+        (
+            format!("migrate 127.0.0.1 {} \"\" 2 1000 COPY KEYS large kept", port),
+            Ok("OK".to_string()),
+        ),
+        ("exists large".to_string(), Ok("1".to_string())),
+        ("exists kept".to_string(), Ok("1".to_string())),
+        (
+            format!("migrate 127.0.0.1 {} kept 2 1000", port),
+            Err("BUSYKEY Target key name already exists."),
+        ),
+        ("exists kept".to_string(), Ok("1".to_string())),
+        (
+            format!("migrate 127.0.0.1 {} kept 2 1000 REPLACE", port),
+            Ok("OK".to_string()),
+        ),
+        ("exists kept".to_string(), Ok("0".to_string())),
+        (
+            format!("migrate 127.0.0.1 {} key 2 1000 KEYS large", port),
+            Err("ERR When using MIGRATE KEYS option, the key argument must be set to the empty string"),
+        ),
+        (
+            format!("migrate 127.0.0.1 {} large 2 abc", port),
+            Err("ERR value is not an integer or out of range"),
+        ),
+    ];
+    for (line, expected_result) in tests_case {
+        println!("line: {}", line);
+        let result = command(&mut dbs, &mut selected, &factory, &line);
+        assert_eq!(result, expected_result);
+    }
+
+    let mut target = target.blocking_lock();
+    let db = target.get_mut(2).unwrap();
+    assert_eq!(db.keys().len(), 3);
+    assert!(db.expire_time("key").is_some());
+    match db.get("large") {
+        Some(DataType::String(value)) => assert_eq!(*value, large),
+        _ => panic!("string expected"),
+    }
+}
+
+#[test]
+fn test_migrate_unreachable() {
+    let mut dbs = Databases::new(1);
+    let factory = CommandFactory::new();
+    let mut selected = 0;
+    command(&mut dbs, &mut selected, &factory, "set key value").unwrap();
+
+    // a port nobody listens on
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    drop(listener);
+    let line = format!("migrate 127.0.0.1 {} key 0 100", port);
+    let result = command(&mut dbs, &mut selected, &factory, &line);
+    assert_eq!(
+        result,
+        Err("IOERR error or timeout reading to target instance")
+    );
+    let result = command(&mut dbs, &mut selected, &factory, "exists key");
+    assert_eq!(result.unwrap(), "1".to_string());
+}
+
+#[test]
+fn test_migrate_to_itself() {
+    let (port, _dbs) = start_server();
+    let mut client = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    client
+        .set_read_timeout(Some(Duration::from_secs(10)))
+        .unwrap();
+    let mut request = |line: &str| {
+        client
+            .write_all(format!("{}\r\n", line).as_bytes())
+            .unwrap();
+        let mut buf = [0; 512];
+        let n = client.read(&mut buf).unwrap();
+        String::from_utf8_lossy(&buf[..n]).to_string()
+    };
+
+    assert_eq!(request("set key value"), "OK");
+    // the databases are held by MIGRATE, the target would wait for them until the timeout
+    let start = Instant::now();
+    assert_eq!(
+        request(&format!("migrate 127.0.0.1 {} key 1 5000", port)),
+        "ERR Target instance is this instance"
+    );
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert_eq!(request("exists key"), "1");
+}
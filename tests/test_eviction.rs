@@ -0,0 +1,249 @@
+use std::collections::BTreeMap;
+use std::thread;
+use std::time::Duration;
+
+use rus_key_db::databases::Databases;
+use rus_key_db::db::{DataType, Db};
+use rus_key_db::eviction::{parse_memory, EvictionPolicy};
+use rus_key_factory::command_factory::CommandFactory;
+use rus_key_lib::cmd::handle_command;
+
+const OOM: &str = "OOM command not allowed when used memory > 'maxmemory'.";
+
+fn command(
+    dbs: &mut Databases,
+    factory: &CommandFactory,
+    line: &str,
+) -> Result<String, &'static str> {
+    let mut selected = 0;
+    let mut parts = line.split_ascii_whitespace();
+    handle_command(&mut parts, dbs, &mut selected, factory)
+}
+
+fn limit(dbs: &mut Databases, maxmemory: usize, policy: &str) {
+    let mut config = BTreeMap::new();
+    config.insert("maxmemory".to_string(), maxmemory.to_string());
+    config.insert("maxmemory-policy".to_string(), policy.to_string());
+    config.insert("maxmemory-samples".to_string(), "50".to_string());
+    dbs.server().load_config(config);
+}
+
+#[test]
+fn test_parse_memory() {
+    assert_eq!(parse_memory("0"), Some(0));
+    assert_eq!(parse_memory("100"), Some(100));
+    assert_eq!(parse_memory("1k"), Some(1000));
+    assert_eq!(parse_memory("1kb"), Some(1024));
+    assert_eq!(parse_memory("2MB"), Some(2 * 1024 * 1024));
+    assert_eq!(parse_memory("1g"), Some(1000 * 1000 * 1000));
+    assert_eq!(parse_memory("1tb"), None);
+    assert_eq!(parse_memory("mb"), None);
+    assert_eq!(
+        EvictionPolicy::parse("ALLKEYS-LRU"),
+        Some(EvictionPolicy::AllKeysLru)
+    );
+    assert_eq!(EvictionPolicy::parse("lru"), None);
+}
+
+#[test]
+fn test_used_memory() {
+    let mut db = Db::new();
+    assert_eq!(db.used_memory(), 0);
+
+    db.set("key".to_string(), DataType::String("value".to_string()));
+    let used = db.used_memory();
+    assert!(used > "keyvalue".len());
+
+    // values updated in place are accounted again
+    if let Some(DataType::String(value)) = db.get_mut("key") {
+        value.push_str(&"x".repeat(1000));
+    }
+    assert_eq!(db.used_memory(), used + 1000);
+
+    db.set("key".to_string(), DataType::String("value".to_string()));
+    assert_eq!(db.used_memory(), used);
+    db.delete("key");
+    assert_eq!(db.used_memory(), 0);
+}
+
+#[test]
+fn test_noeviction() {
+    let mut dbs = Databases::new(2);
+    let factory = CommandFactory::new();
+    command(&mut dbs, &factory, "set key value").unwrap();
+    let used = dbs.used_memory();
+    limit(&mut dbs, used, "noeviction");
+
+    assert_eq!(
+        command(&mut dbs, &factory, "set other value"),
+        Ok("OK".to_string())
+    );
+    // over the limit, writes are refused and reads still work
+    assert_eq!(command(&mut dbs, &factory, "set third value"), Err(OOM));
+    assert_eq!(command(&mut dbs, &factory, "append key more"), Err(OOM));
+    assert_eq!(
+        command(&mut dbs, &factory, "get key"),
+        Ok("value".to_string())
+    );
+    assert_eq!(
+        command(&mut dbs, &factory, "del other"),
+        Ok("1".to_string())
+    );
+    assert_eq!(
+        command(&mut dbs, &factory, "set third value"),
+        Ok("OK".to_string())
+    );
+}
+
+#[test]
+fn test_script_noeviction() {
+    let mut dbs = Databases::new(2);
+    let factory = CommandFactory::new();
+    command(&mut dbs, &factory, "set key value").unwrap();
+    let used = dbs.used_memory();
+    limit(&mut dbs, used, "noeviction");
+
+    // the writes of a script are refused once over the limit, like the ones of the clients
+    assert_eq!(
+        command(
+            &mut dbs,
+            &factory,
+            "eval \"for i = 1, 1000 do redis.call('set', i, 'x') end\" 0"
+        ),
+        Err(OOM)
+    );
+    assert_eq!(command(&mut dbs, &factory, "dbsize"), Ok("2".to_string()));
+    assert_eq!(command(&mut dbs, &factory, "eval \"return 1\" 0"), Err(OOM));
+    assert_eq!(
+        command(
+            &mut dbs,
+            &factory,
+            "eval_ro \"return redis.call('get', 'key')\" 0"
+        ),
+        Ok("value".to_string())
+    );
+    command(&mut dbs, &factory, "del 1").unwrap();
+    assert_eq!(
+        command(
+            &mut dbs,
+            &factory,
+            "eval \"redis.call('set', 'a', 'x') return redis.pcall('set', 'b', 'x')['err']\" 0"
+        ),
+        Ok(OOM.to_string())
+    );
+}
+
+#[test]
+fn test_allkeys_lru() {
+    let mut dbs = Databases::new(2);
+    let factory = CommandFactory::new();
+    command(&mut dbs, &factory, "set hot value").unwrap();
+    for index in 0..10 {
+        command(&mut dbs, &factory, &format!("set cold_{} value", index)).unwrap();
+    }
+    thread::sleep(Duration::from_millis(5));
+    command(&mut dbs, &factory, "get hot").unwrap();
+    let used = dbs.used_memory();
+    limit(&mut dbs, used, "allkeys-lru");
+
+    for index in 0..5 {
+        command(&mut dbs, &factory, &format!("set new_{} value", index)).unwrap();
+    }
+    assert_eq!(dbs.free_memory(), Ok(1));
+    assert!(dbs.used_memory() <= used);
+    assert_eq!(
+        command(&mut dbs, &factory, "get hot"),
+        Ok("value".to_string())
+    );
+    assert_eq!(dbs.server().stats.evicted_keys(), 5);
+    assert_eq!(command(&mut dbs, &factory, "dbsize"), Ok("11".to_string()));
+}
+
+#[test]
+fn test_allkeys_lfu() {
+    let mut dbs = Databases::new(2);
+    let factory = CommandFactory::new();
+    command(&mut dbs, &factory, "set hot value").unwrap();
+    for _ in 0..100 {
+        command(&mut dbs, &factory, "get hot").unwrap();
+    }
+    for index in 0..10 {
+        command(&mut dbs, &factory, &format!("set cold_{} value", index)).unwrap();
+    }
+    let used = dbs.used_memory();
+    limit(&mut dbs, used, "allkeys-lfu");
+
+    for index in 0..5 {
+        command(&mut dbs, &factory, &format!("set new_{} value", index)).unwrap();
+    }
+    assert_eq!(
+        command(&mut dbs, &factory, "get hot"),
+        Ok("value".to_string())
+    );
+    assert_eq!(dbs.server().stats.evicted_keys(), 5);
+}
+
+#[test]
+fn test_volatile_ttl() {
+    let mut dbs = Databases::new(2);
+    let factory = CommandFactory::new();
+    command(&mut dbs, &factory, "set persistent value").unwrap();
+    command(&mut dbs, &factory, "set short value EX 100").unwrap();
+    command(&mut dbs, &factory, "set long value EX 1000").unwrap();
+    let used = dbs.used_memory();
+    limit(&mut dbs, used, "volatile-ttl");
+
+    assert_eq!(
+        command(&mut dbs, &factory, "set new value"),
+        Ok("OK".to_string())
+    );
+    assert_eq!(
+        command(&mut dbs, &factory, "get new"),
+        Ok("value".to_string())
+    );
+    assert_eq!(
+        command(&mut dbs, &factory, "exists short"),
+        Ok("0".to_string())
+    );
+    assert_eq!(
+        command(&mut dbs, &factory, "exists long"),
+        Ok("1".to_string())
+    );
+
+    assert_eq!(
+        command(&mut dbs, &factory, "set other value"),
+        Ok("OK".to_string())
+    );
+    assert_eq!(
+        command(&mut dbs, &factory, "exists long"),
+        Ok("0".to_string())
+    );
+    assert_eq!(
+        command(&mut dbs, &factory, "set last value"),
+        Ok("OK".to_string())
+    );
+    // only keys with a TTL may be evicted
+    assert_eq!(command(&mut dbs, &factory, "set final value"), Err(OOM));
+    assert_eq!(
+        command(&mut dbs, &factory, "exists persistent"),
+        Ok("1".to_string())
+    );
+}
+
+#[test]
+fn test_info_memory() {
+    let mut dbs = Databases::new(2);
+    let factory = CommandFactory::new();
+    limit(&mut dbs, 1024 * 1024, "allkeys-random");
+    command(&mut dbs, &factory, "set key value").unwrap();
+
+    let used = dbs.used_memory();
+    let result = command(&mut dbs, &factory, "info memory");
+    assert_eq!(
+        result.unwrap(),
+        format!(
+            "used_memory: {} maxmemory: 1048576 maxmemory_policy: allkeys-random lazyfree_pending_objects: 0",
+            used
+        )
+    );
+}
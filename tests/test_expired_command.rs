@@ -1,5 +1,6 @@
 use expired_commands::expired::ExpiredCommand;
 use string_commands::string::StringCommand;
+use utils_commands::utils::UtilsCommand;
 use rus_key_trait::command_trait::Command;
 use rus_key_db::db::Db;
 
@@ -29,12 +30,12 @@ fn expired_command(db: &mut Db) {
     // set string key
     set_key(db);
 
-    let command_expired = ExpiredCommand::new("expired".to_string());
+    let command_expired = ExpiredCommand::new("expire".to_string());
     let command_expired_str = "key 1000";
     let mut parts_expired = command_expired_str.split_ascii_whitespace();
     let result = command_expired.execute(&mut parts_expired, db);
 
-    assert_eq!(result.unwrap(), "OK".to_string());
+    assert_eq!(result.unwrap(), "1".to_string());
 }
 
 fn expireat_command(db: &mut Db) {
@@ -47,7 +48,7 @@ fn expireat_command(db: &mut Db) {
     let mut parts_expireat = command_expireat_str.split_ascii_whitespace();
     let result = command_expireat.execute(&mut parts_expireat, db);
 
-    assert_eq!(result.unwrap(), "OK".to_string());
+    assert_eq!(result.unwrap(), "1".to_string());
 }
 
 fn pexpire_command(db: &mut Db) {
@@ -60,7 +61,7 @@ fn pexpire_command(db: &mut Db) {
     let mut parts_pexpire = command_pexpire_str.split_ascii_whitespace();
     let result = command_pexpire.execute(&mut parts_pexpire, db);
 
-    assert_eq!(result.unwrap(), "OK".to_string());
+    assert_eq!(result.unwrap(), "1".to_string());
 }
 
 #[test]
@@ -147,3 +148,221 @@ fn test_persist_command() {
     let result = command_persist.execute(&mut parts_persist, &mut db);
     assert_eq!(result.unwrap(), "-1".to_string());
 }
+
+#[test]
+fn test_internal_key_names_are_user_keys() {
+    let mut db = Db::new();
+    expired_command(&mut db);
+
+    // keys named like the old bookkeeping keys are normal keys
+    let command_set = StringCommand::new("set".to_string());
+    for command_set_str in ["expired value", "ruskey_config value"] {
+        let mut parts_set = command_set_str.split_ascii_whitespace();
+        let result = command_set.execute(&mut parts_set, &mut db);
+        assert_eq!(result.unwrap(), "OK".to_string());
+    }
+    let command_del = UtilsCommand::new("del".to_string());
+    let mut parts_del = "expired".split_ascii_whitespace();
+    let result = command_del.execute(&mut parts_del, &mut db);
+    assert_eq!(result.unwrap(), "1".to_string());
+
+    // the TTLs are not affected
+    let ttl_result = ttl_command(&mut db, "ttl", "key");
+    assert!(0 < ttl_result && ttl_result <= 1000);
+
+    let command_type = UtilsCommand::new("type".to_string());
+    let mut parts_type = "ruskey_config".split_ascii_whitespace();
+    let result = command_type.execute(&mut parts_type, &mut db);
+    assert_eq!(result.unwrap(), "string".to_string());
+}
+
+#[test]
+fn test_expired_keys_stats() {
+    let mut db = Db::new();
+    set_key(&mut db);
+    db.expire_at("key", 1);
+
+    assert_eq!(ttl_command(&mut db, "ttl", "key"), -2);
+    assert_eq!(db.server().stats.expired_keys(), 1);
+}
+
+#[test]
+fn test_expired_keys_are_invisible() {
+    let mut db = Db::new();
+    set_key(&mut db);
+    db.expire_at("key", 1);
+
+    let tests_case = vec![("exists", "0"), ("type", "none"), ("del", "0")];
+    for (command, expected_result) in tests_case {
+        println!("command: {}", command);
+        let command = UtilsCommand::new(command.to_string());
+        let mut parts = "key".split_ascii_whitespace();
+        let result = command.execute(&mut parts, &mut db);
+        assert_eq!(result.unwrap(), expected_result.to_string());
+    }
+    assert!(db.get("key").is_none());
+    assert_eq!(db.keys(), vec!["key_not_expired".to_string()]);
+}
+
+#[test]
+fn test_del_removes_ttl() {
+    let mut db = Db::new();
+    expired_command(&mut db);
+
+    let command_del = UtilsCommand::new("del".to_string());
+    let mut parts_del = "key".split_ascii_whitespace();
+    let result = command_del.execute(&mut parts_del, &mut db);
+    assert_eq!(result.unwrap(), "1".to_string());
+
+    // a re-created key does not inherit the old TTL
+    let command_set = StringCommand::new("set".to_string());
+    let mut parts_set = "key value".split_ascii_whitespace();
+    let result = command_set.execute(&mut parts_set, &mut db);
+    assert_eq!(result.unwrap(), "OK".to_string());
+    assert_eq!(ttl_command(&mut db, "ttl", "key"), -1);
+
+    // modifying a key keeps its TTL
+    expired_command(&mut db);
+    let command_append = StringCommand::new("append".to_string());
+    let mut parts_append = "key _suffix".split_ascii_whitespace();
+    command_append.execute(&mut parts_append, &mut db).unwrap();
+    let ttl_result = ttl_command(&mut db, "ttl", "key");
+    assert!(0 < ttl_result && ttl_result <= 1000);
+}
+
+#[test]
+fn test_active_expire_cycle() {
+    let mut db = Db::new();
+    let command_set = StringCommand::new("set".to_string());
+    for i in 0..200 {
+        let command_set_str = format!("key_{} value", i);
+        let mut parts_set = command_set_str.split_ascii_whitespace();
+        command_set.execute(&mut parts_set, &mut db).unwrap();
+        // half of the keys are already expired, a quarter expire later
+        match i % 4 {
+            0 | 1 => db.expire_at(&format!("key_{}", i), 1),
+            2 => db.expire_at(&format!("key_{}", i), get_current_time() + 1000 * 1000),
+            _ => false,
+        };
+    }
+    assert_eq!(db.volatile_count(), 150);
+
+    // cycles are adaptive, each one goes on while many sampled keys are expired
+    let mut expired = 0;
+    for _ in 0..100 {
+        let deadline = std::time::Instant::now() + std::time::Duration::from_millis(25);
+        expired += db.active_expire_cycle(deadline);
+    }
+    assert_eq!(expired, 100);
+    assert_eq!(db.volatile_count(), 50);
+    assert_eq!(db.server().stats.expired_keys(), 100);
+    assert_eq!(db.keys().len(), 100);
+    assert_eq!(db.len(), 100);
+
+    // a cycle without expired keys stops after one round
+    let deadline = std::time::Instant::now() + std::time::Duration::from_millis(25);
+    assert_eq!(db.active_expire_cycle(deadline), 0);
+}
+
+fn run(db: &mut Db, command: &str, line: &str) -> Result<String, &'static str> {
+    let command = ExpiredCommand::new(command.to_string());
+    let mut parts = line.split_ascii_whitespace();
+    command.execute(&mut parts, db)
+}
+
+#[test]
+fn test_expire_flags() {
+    let mut db = Db::new();
+    set_key(&mut db);
+
+    let tests_case: Vec<(&str, &str, Result<String, &'static str>)> = vec![
+        ("expire", "key 100 XX", Ok("0".to_string())),
+        ("expire", "key 100 GT", Ok("0".to_string())),
+        ("expire", "key 100 NX", Ok("1".to_string())),
+        ("expire", "key 200 NX", Ok("0".to_string())),
+        ("expire", "key 50 GT", Ok("0".to_string())),
+        ("expire", "key 200 GT", Ok("1".to_string())),
+        ("expire", "key 300 LT", Ok("0".to_string())),
+        ("pexpire", "key 150000 LT", Ok("1".to_string())),
+        ("expire", "key 100 XX", Ok("1".to_string())),
+        // a key without TTL has an infinite one
+        ("expire", "key_not_expired 100 LT", Ok("1".to_string())),
+        ("expire", "not_exists 100", Ok("0".to_string())),
+        (
+            "expire",
+            "key 100 NX XX",
+            Err("ERR NX and XX, GT or LT options at the same time are not compatible"),
+        ),
+        (
+            "expire",
+            "key 100 GT LT",
+            Err("ERR GT and LT options at the same time are not compatible"),
+        ),
+        ("expire", "key 100 FOO", Err("ERR Unsupported option")),
+        (
+            "expire",
+            "key abc",
+            Err("ERR value is not an integer or out of range"),
+        ),
+        ("expire", "key", Err("ERR wrong number of arguments for command")),
+        (
+            "expire",
+            "key 9223372036854775807",
+            Err("ERR invalid expire time in command"),
+        ),
+    ];
+    for (command, line, expected_result) in tests_case {
+        println!("{} {}", command, line);
+        assert_eq!(run(&mut db, command, line), expected_result);
+    }
+    let ttl_result = ttl_command(&mut db, "ttl", "key");
+    assert!(90 < ttl_result && ttl_result <= 100);
+}
+
+#[test]
+fn test_expire_in_the_past_deletes_key() {
+    let mut db = Db::new();
+    for (command, line) in [
+        ("expire", "key 0"),
+        ("pexpire", "key -10"),
+        ("expireat", "key 1"),
+        ("pexpireat", "key 1000"),
+    ] {
+        set_key(&mut db);
+        assert_eq!(run(&mut db, command, line).unwrap(), "1".to_string());
+        assert_eq!(ttl_command(&mut db, "ttl", "key"), -2);
+    }
+    assert!(db.get("key").is_none());
+    // not counted as an expiration
+    assert_eq!(db.server().stats.expired_keys(), 0);
+}
+
+#[test]
+fn test_expiretime_command() {
+    let mut db = Db::new();
+    set_key(&mut db);
+
+    let deadline = get_current_time() + 100 * 1000;
+    let line = format!("key {}", deadline);
+    assert_eq!(run(&mut db, "pexpireat", &line).unwrap(), "1".to_string());
+    assert_eq!(ttl_command(&mut db, "pexpiretime", "key"), deadline);
+    assert_eq!(
+        ttl_command(&mut db, "expiretime", "key"),
+        (deadline + 500) / 1000
+    );
+    assert_eq!(ttl_command(&mut db, "expiretime", "key_not_expired"), -1);
+    assert_eq!(ttl_command(&mut db, "pexpiretime", "not_exists"), -2);
+}
+
+#[test]
+fn test_ttl_rounding() {
+    let mut db = Db::new();
+    set_key(&mut db);
+
+    assert_eq!(run(&mut db, "pexpire", "key 1900").unwrap(), "1".to_string());
+    assert_eq!(ttl_command(&mut db, "ttl", "key"), 2);
+    assert_eq!(run(&mut db, "pexpire", "key 1200").unwrap(), "1".to_string());
+    assert_eq!(ttl_command(&mut db, "ttl", "key"), 1);
+    let pttl_result = ttl_command(&mut db, "pttl", "key");
+    assert!(1100 < pttl_result && pttl_result <= 1200);
+}
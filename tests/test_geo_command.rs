@@ -0,0 +1,187 @@
+use geo_commands::geo::GeoCommand;
+use rus_key_db::db::Db;
+use rus_key_trait::command_trait::Command;
+
+fn geo_command(db: &mut Db, command: &str, args: &str) -> Result<String, &'static str> {
+    let command = GeoCommand::new(command.to_string());
+    let mut parts = args.split_ascii_whitespace();
+    command.execute(&mut parts, db)
+}
+
+fn add_sicily(db: &mut Db) {
+    let result = geo_command(
+        db,
+        "geoadd",
+        "Sicily 13.361389 38.115556 Palermo 15.087269 37.502669 Catania",
+    );
+    assert_eq!(result.unwrap(), "2".to_string());
+}
+
+#[test]
+fn test_geoadd_command() {
+    let mut db = Db::new();
+    add_sicily(&mut db);
+
+    // existing members are updated, not added
+    let result = geo_command(&mut db, "geoadd", "Sicily 13.361389 38.115556 Palermo");
+    assert_eq!(result.unwrap(), "0".to_string());
+
+    // CH counts changed members
+    let result = geo_command(&mut db, "geoadd", "Sicily CH 13.5 38.1 Palermo");
+    assert_eq!(result.unwrap(), "1".to_string());
+
+    // NX does not touch existing members
+    let result = geo_command(&mut db, "geoadd", "Sicily NX 13.361389 38.115556 Palermo");
+    assert_eq!(result.unwrap(), "0".to_string());
+
+    // XX does not add new members
+    let result = geo_command(&mut db, "geoadd", "Sicily XX 12.0 37.0 Trapani");
+    assert_eq!(result.unwrap(), "0".to_string());
+
+    let result = geo_command(&mut db, "geoadd", "Sicily 13.361389 86.0 Pole");
+    assert_eq!(result, Err("ERR invalid longitude,latitude pair"));
+
+    let result = geo_command(&mut db, "geoadd", "Sicily 13.361389 38.115556");
+    assert_eq!(result, Err("ERR syntax error"));
+}
+
+#[test]
+fn test_geopos_command() {
+    let mut db = Db::new();
+    add_sicily(&mut db);
+
+    let result = geo_command(&mut db, "geopos", "Sicily Palermo Catania NonExisting");
+    assert_eq!(
+        result.unwrap(),
+        "Palermo: 13.361389 38.115556 Catania: 15.087267 37.502668 NonExisting: nil".to_string()
+    );
+}
+
+#[test]
+fn test_geodist_command() {
+    let mut db = Db::new();
+    add_sicily(&mut db);
+
+    let result = geo_command(&mut db, "geodist", "Sicily Palermo Catania");
+    assert_eq!(result.unwrap(), "166274.1516".to_string());
+
+    let result = geo_command(&mut db, "geodist", "Sicily Palermo Catania km");
+    assert_eq!(result.unwrap(), "166.2742".to_string());
+
+    let result = geo_command(&mut db, "geodist", "Sicily Palermo Catania mi");
+    assert_eq!(result.unwrap(), "103.3182".to_string());
+
+    let result = geo_command(&mut db, "geodist", "Sicily Palermo NonExisting");
+    assert_eq!(result.unwrap(), "nil".to_string());
+
+    let result = geo_command(&mut db, "geodist", "Sicily Palermo Catania lightyear");
+    assert_eq!(
+        result,
+        Err("ERR unsupported unit provided. please use M, KM, FT, MI")
+    );
+}
+
+#[test]
+fn test_geohash_command() {
+    let mut db = Db::new();
+    add_sicily(&mut db);
+
+    let result = geo_command(&mut db, "geohash", "Sicily Palermo Catania NonExisting");
+    assert_eq!(result.unwrap(), "sqc8b49rny0 sqdtr74hyu0 nil".to_string());
+}
+
+#[test]
+fn test_geosearch_command() {
+    let mut db = Db::new();
+    add_sicily(&mut db);
+    let result = geo_command(
+        &mut db,
+        "geoadd",
+        "Sicily 12.758489 38.788135 edge1 17.241510 38.788135 edge2",
+    );
+    assert_eq!(result.unwrap(), "2".to_string());
+
+    let tests_case: Vec<(&str, Result<String, &'static str>)> = vec![
+        (
+            "Sicily FROMLONLAT 15 37 BYRADIUS 200 km ASC",
+            Ok("Catania Palermo".to_string()),
+        ),
+        (
+            "Sicily FROMLONLAT 15 37 BYRADIUS 200 km DESC",
+            Ok("Palermo Catania".to_string()),
+        ),
+        (
+            "Sicily FROMLONLAT 15 37 BYBOX 400 400 km ASC WITHCOORD WITHDIST",
+            Ok("Catania: 56.4413 15.087267 37.502668 Palermo: 190.4424 13.361389 38.115556 edge2: 279.7403 17.241510 38.788135 edge1: 279.7405 12.758488 38.788135".to_string()),
+        ),
+        (
+            "Sicily FROMMEMBER Palermo BYRADIUS 200 km COUNT 1 WITHDIST",
+            Ok("Palermo: 0.0000".to_string()),
+        ),
+        (
+            "Sicily FROMLONLAT 15 37 BYRADIUS 200 km COUNT 1",
+            Ok("Catania".to_string()),
+        ),
+        (
+            "NonExisting FROMLONLAT 15 37 BYRADIUS 200 km",
+            Ok("".to_string()),
+        ),
+        (
+            "Sicily FROMMEMBER NonExisting BYRADIUS 200 km",
+            Err("ERR could not decode requested zset member"),
+        ),
+        (
+            "Sicily BYRADIUS 200 km",
+            Err("ERR exactly one of FROMMEMBER or FROMLONLAT can be specified"),
+        ),
+        (
+            "Sicily FROMLONLAT 15 37",
+            Err("ERR exactly one of BYRADIUS and BYBOX can be specified"),
+        ),
+        (
+            "Sicily FROMLONLAT 15 37 BYRADIUS 200 km ANY",
+            Err("ERR the ANY argument requires COUNT argument"),
+        ),
+    ];
+
+    for (args, expected_result) in tests_case {
+        println!("args: {}", args);
+        let result = geo_command(&mut db, "geosearch", args);
+        assert_eq!(result, expected_result);
+    }
+}
+
+#[test]
+fn test_geosearchstore_command() {
+    let mut db = Db::new();
+    add_sicily(&mut db);
+
+    let result = geo_command(
+        &mut db,
+        "geosearchstore",
+        "Near Sicily FROMLONLAT 15 37 BYRADIUS 100 km",
+    );
+    assert_eq!(result.unwrap(), "1".to_string());
+
+    // the stored set is a geo set itself
+    let result = geo_command(&mut db, "geopos", "Near Catania Palermo");
+    assert_eq!(
+        result.unwrap(),
+        "Catania: 15.087267 37.502668 Palermo: nil".to_string()
+    );
+
+    let result = geo_command(
+        &mut db,
+        "geosearchstore",
+        "Dist Sicily FROMLONLAT 15 37 BYRADIUS 200 km ASC STOREDIST",
+    );
+    assert_eq!(result.unwrap(), "2".to_string());
+
+    // WITH* options are not allowed when storing
+    let result = geo_command(
+        &mut db,
+        "geosearchstore",
+        "Near Sicily FROMLONLAT 15 37 BYRADIUS 100 km WITHDIST",
+    );
+    assert_eq!(result, Err("ERR syntax error"));
+}
@@ -28,3 +28,26 @@ fn test_hgetall_command() {
     let hgetall_result = hgetall_command.execute(&mut hgetall_parts, &mut db);
     assert_eq!(hgetall_result, Ok("field: value".to_string()));
 }
+
+#[test]
+fn test_hset_command() {
+    let mut db = Db::new();
+    let hset_command = HashMapCommand::new("hset".to_string());
+    let mut hset_parts = "obj a 1 b 2".split_ascii_whitespace();
+    let hset_result = hset_command.execute(&mut hset_parts, &mut db);
+    assert_eq!(hset_result, Ok("2".to_string()));
+
+    // existing fields are updated and only new ones are counted
+    let mut hset_parts = "obj b 3 c 4".split_ascii_whitespace();
+    let hset_result = hset_command.execute(&mut hset_parts, &mut db);
+    assert_eq!(hset_result, Ok("1".to_string()));
+
+    let hgetall_command = HashMapCommand::new("hgetall".to_string());
+    let mut hgetall_parts = "obj".split_ascii_whitespace();
+    let hgetall_result = hgetall_command.execute(&mut hgetall_parts, &mut db);
+    assert_eq!(hgetall_result, Ok("a: 1 b: 3 c: 4".to_string()));
+
+    let mut hset_parts = "obj d".split_ascii_whitespace();
+    let hset_result = hset_command.execute(&mut hset_parts, &mut db);
+    assert_eq!(hset_result, Err("Value not specified"));
+}
@@ -0,0 +1,268 @@
+use json_commands::json::JsonCommand;
+use rus_key_db::db::Db;
+use rus_key_trait::command_trait::Command;
+use utils_commands::utils::UtilsCommand;
+
+fn json_command(db: &mut Db, command: &str, args: &str) -> Result<String, &'static str> {
+    let command = JsonCommand::new(command.to_string());
+    let mut parts = args.split_ascii_whitespace();
+    command.execute(&mut parts, db)
+}
+
+fn set_doc(db: &mut Db) {
+    let result = json_command(
+        db,
+        "json.set",
+        r#"doc $ {"name": "Leonard Cohen", "age": 82, "a": {"a": 1, "b": [1, 2, 3]}, "tags": ["poet"], "price": 1.5}"#,
+    );
+    assert_eq!(result.unwrap(), "OK".to_string());
+}
+
+fn assert_cases(db: &mut Db, tests_case: Vec<(&str, &str, Result<String, &'static str>)>) {
+    for (command, args, expected_result) in tests_case {
+        println!("command: {}, args: {}", command, args);
+        let result = json_command(db, command, args);
+        assert_eq!(result, expected_result);
+    }
+}
+
+#[test]
+fn test_json_set_get_command() {
+    let mut db = Db::new();
+    set_doc(&mut db);
+
+    assert_cases(
+        &mut db,
+        vec![
+            (
+                "json.get",
+                "doc $.name",
+                Ok(r#"["Leonard Cohen"]"#.to_string()),
+            ),
+            (
+                "json.get",
+                "doc .name",
+                Ok(r#""Leonard Cohen""#.to_string()),
+            ),
+            (
+                "json.get",
+                "doc $..a",
+                Ok(r#"[{"a":1,"b":[1,2,3]},1]"#.to_string()),
+            ),
+            ("json.get", "doc $.a.b[-1]", Ok("[3]".to_string())),
+            ("json.get", "doc $.a.b[*]", Ok("[1,2,3]".to_string())),
+            (
+                "json.get",
+                "doc $['tags'][0]",
+                Ok(r#"["poet"]"#.to_string()),
+            ),
+            ("json.get", "doc $.missing", Ok("[]".to_string())),
+            ("json.get", "doc .missing", Err("ERR path does not exist")),
+            (
+                "json.get",
+                "doc .age $.tags",
+                Ok(r#"{".age":82,"$.tags":[["poet"]]}"#.to_string()),
+            ),
+            ("json.get", "missing", Ok("nil".to_string())),
+            // new members are added to existing objects
+            ("json.set", "doc $.a.c true", Ok("OK".to_string())),
+            ("json.get", "doc $.a.c", Ok("[true]".to_string())),
+            ("json.set", "doc $.x.y 1", Ok("nil".to_string())),
+            // NX and XX
+            ("json.set", "doc $.age 1 NX", Ok("nil".to_string())),
+            ("json.set", "doc $.new 1 XX", Ok("nil".to_string())),
+            ("json.set", "doc $.age 83 XX", Ok("OK".to_string())),
+            ("json.get", "doc .age", Ok("83".to_string())),
+            // values may contain spaces
+            (
+                "json.set",
+                r#"doc $.name "Marianne Ihlen""#,
+                Ok("OK".to_string()),
+            ),
+            (
+                "json.get",
+                "doc .name",
+                Ok(r#""Marianne Ihlen""#.to_string()),
+            ),
+            (
+                "json.set",
+                "other $.a 1",
+                Err("ERR new objects must be created at the root"),
+            ),
+            ("json.set", "doc $ {bad", Err("ERR invalid JSON value")),
+            ("json.get", "doc $.a[", Err("ERR invalid path")),
+        ],
+    );
+}
+
+#[test]
+fn test_json_del_command() {
+    let mut db = Db::new();
+    set_doc(&mut db);
+
+    assert_cases(
+        &mut db,
+        vec![
+            ("json.del", "doc $.age", Ok("1".to_string())),
+            // member order is preserved
+            (
+                "json.objkeys",
+                "doc",
+                Ok(r#"["name","a","tags","price"]"#.to_string()),
+            ),
+            ("json.del", "doc $..a", Ok("2".to_string())),
+            ("json.get", "doc $.a", Ok("[]".to_string())),
+            ("json.del", "doc $.missing", Ok("0".to_string())),
+            ("json.del", "missing", Ok("0".to_string())),
+            ("json.del", "doc $", Ok("1".to_string())),
+            ("json.get", "doc", Ok("nil".to_string())),
+        ],
+    );
+}
+
+#[test]
+fn test_json_type_command() {
+    let mut db = Db::new();
+    set_doc(&mut db);
+
+    assert_cases(
+        &mut db,
+        vec![
+            ("json.type", "doc", Ok("object".to_string())),
+            ("json.type", "doc .age", Ok("integer".to_string())),
+            ("json.type", "doc $.price", Ok(r#"["number"]"#.to_string())),
+            (
+                "json.type",
+                "doc $..a",
+                Ok(r#"["object","integer"]"#.to_string()),
+            ),
+            ("json.type", "missing", Ok("nil".to_string())),
+        ],
+    );
+
+    // TYPE reports the json data type
+    let command_type = UtilsCommand::new("type".to_string());
+    let mut parts = "doc".split_ascii_whitespace();
+    let result = command_type.execute(&mut parts, &mut db);
+    assert_eq!(result.unwrap(), "ReJSON-RL".to_string());
+}
+
+#[test]
+fn test_json_numincrby_command() {
+    let mut db = Db::new();
+    set_doc(&mut db);
+
+    assert_cases(
+        &mut db,
+        vec![
+            ("json.numincrby", "doc .age 2", Ok("84".to_string())),
+            ("json.numincrby", "doc $.price 1", Ok("[2.5]".to_string())),
+            ("json.numincrby", "doc $..a 1", Ok("[null,2]".to_string())),
+            (
+                "json.numincrby",
+                "doc .name 1",
+                Err("ERR value is not a number"),
+            ),
+            (
+                "json.numincrby",
+                "doc .age abc",
+                Err("ERR invalid JSON value"),
+            ),
+            (
+                "json.numincrby",
+                "missing .age 1",
+                Err("ERR could not perform this operation on a key that doesn't exist"),
+            ),
+        ],
+    );
+}
+
+#[test]
+fn test_json_strappend_command() {
+    let mut db = Db::new();
+    set_doc(&mut db);
+
+    assert_cases(
+        &mut db,
+        vec![
+            ("json.strappend", r#"doc .name " Jr""#, Ok("16".to_string())),
+            (
+                "json.get",
+                "doc .name",
+                Ok(r#""Leonard Cohen Jr""#.to_string()),
+            ),
+            (
+                "json.strappend",
+                r#"doc $.* "!""#,
+                Ok("[17,null,null,null,null]".to_string()),
+            ),
+            (
+                "json.strappend",
+                "doc .name 1",
+                Err("ERR invalid JSON value"),
+            ),
+            (
+                "json.strappend",
+                r#"doc "x""#,
+                Err("ERR wrong type of path value"),
+            ),
+        ],
+    );
+}
+
+#[test]
+fn test_json_arr_command() {
+    let mut db = Db::new();
+    set_doc(&mut db);
+
+    assert_cases(
+        &mut db,
+        vec![
+            (
+                "json.arrappend",
+                r#"doc $.tags "singer" "song writer""#,
+                Ok("[3]".to_string()),
+            ),
+            ("json.arrlen", "doc .tags", Ok("3".to_string())),
+            ("json.arrlen", "doc $..b", Ok("[3]".to_string())),
+            ("json.arrlen", "doc $.name", Ok("[null]".to_string())),
+            ("json.arrlen", "missing", Ok("nil".to_string())),
+            (
+                "json.arrpop",
+                "doc .tags",
+                Ok(r#""song writer""#.to_string()),
+            ),
+            ("json.arrpop", "doc $.tags 0", Ok(r#"["poet"]"#.to_string())),
+            (
+                "json.arrpop",
+                "doc $.tags 10",
+                Ok(r#"["singer"]"#.to_string()),
+            ),
+            ("json.arrpop", "doc .tags", Ok("nil".to_string())),
+            (
+                "json.arrappend",
+                "doc .name 1",
+                Err("ERR wrong type of path value"),
+            ),
+        ],
+    );
+}
+
+#[test]
+fn test_json_objkeys_command() {
+    let mut db = Db::new();
+    set_doc(&mut db);
+
+    assert_cases(
+        &mut db,
+        vec![
+            ("json.objkeys", "doc .a", Ok(r#"["a","b"]"#.to_string())),
+            (
+                "json.objkeys",
+                "doc $..a",
+                Ok(r#"[["a","b"],null]"#.to_string()),
+            ),
+            ("json.objkeys", "missing", Ok("nil".to_string())),
+        ],
+    );
+}
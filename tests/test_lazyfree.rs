@@ -0,0 +1,183 @@
+use std::collections::BTreeMap;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rus_key_db::databases::Databases;
+use rus_key_db::lazyfree::LazyFree;
+use rus_key_factory::command_factory::CommandFactory;
+use rus_key_lib::cmd::handle_command;
+
+fn command(
+    dbs: &mut Databases,
+    factory: &CommandFactory,
+    line: &str,
+) -> Result<String, &'static str> {
+    let mut selected = 0;
+    let mut parts = line.split_ascii_whitespace();
+    handle_command(&mut parts, dbs, &mut selected, factory)
+}
+
+// a hash with more fields than the lazy free threshold
+fn set_large_hash(dbs: &mut Databases, factory: &CommandFactory, key: &str) {
+    let fields: String = (0..200)
+        .map(|index| format!(" field_{} value", index))
+        .collect();
+    let result = command(dbs, factory, &format!("hmset {}{}", key, fields));
+    assert_eq!(result.unwrap(), "OK".to_string());
+}
+
+fn enable(dbs: &mut Databases, option: &str) {
+    let mut config = BTreeMap::new();
+    config.insert(option.to_string(), "yes".to_string());
+    dbs.server().load_config(config);
+}
+
+// waits for the background thread to free `count` values in total
+fn wait_freed(lazy_free: &LazyFree, count: u64) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while lazy_free.freed_objects() < count || lazy_free.pending_objects() > 0 {
+        assert!(Instant::now() < deadline, "values were not freed");
+        thread::sleep(Duration::from_millis(1));
+    }
+    assert_eq!(lazy_free.freed_objects(), count);
+}
+
+#[test]
+fn test_lazy_free() {
+    let lazy_free = LazyFree::default();
+    for index in 0..10 {
+        lazy_free.free(vec![index; 1000]);
+    }
+    wait_freed(&lazy_free, 10);
+}
+
+#[test]
+fn test_unlink_command() {
+    let mut dbs = Databases::new(2);
+    let factory = CommandFactory::new();
+    set_large_hash(&mut dbs, &factory, "large");
+    command(&mut dbs, &factory, "set small value").unwrap();
+
+    let tests_case: Vec<(&str, Result<String, &'static str>)> = vec![
+        ("unlink large small not_exists", Ok("2".to_string())),
+        ("exists large", Ok("0".to_string())),
+        ("exists small", Ok("0".to_string())),
+        ("unlink large", Ok("0".to_string())),
+        ("dbsize", Ok("0".to_string())),
+    ];
+    for (line, expected_result) in tests_case {
+        println!("line: {}", line);
+        let result = command(&mut dbs, &factory, line);
+        assert_eq!(result, expected_result);
+    }
+    // the memory is accounted as freed at once, only the large value goes to the thread
+    assert_eq!(dbs.used_memory(), 0);
+    wait_freed(&dbs.server().lazy_free, 1);
+    let result = command(&mut dbs, &factory, "info stats");
+    assert_eq!(
+        result.unwrap(),
+        "expired_keys: 0 evicted_keys: 0 lazyfreed_objects: 1 client_output_buffer_limit_disconnections: 0".to_string()
+    );
+}
+
+#[test]
+fn test_flushall_async() {
+    let mut dbs = Databases::new(2);
+    let factory = CommandFactory::new();
+    set_large_hash(&mut dbs, &factory, "large");
+
+    assert_eq!(
+        command(&mut dbs, &factory, "flushall async"),
+        Ok("OK".to_string())
+    );
+    assert_eq!(command(&mut dbs, &factory, "dbsize"), Ok("0".to_string()));
+    wait_freed(&dbs.server().lazy_free, 1);
+    assert_eq!(
+        command(&mut dbs, &factory, "flushall sync"),
+        Ok("OK".to_string())
+    );
+    assert_eq!(dbs.server().lazy_free.freed_objects(), 1);
+}
+
+#[test]
+fn test_lazyfree_lazy_server_del() {
+    let mut dbs = Databases::new(2);
+    let factory = CommandFactory::new();
+    set_large_hash(&mut dbs, &factory, "large");
+
+    // overwritten values are freed inline unless enabled
+    set_large_hash(&mut dbs, &factory, "large");
+    assert_eq!(dbs.server().lazy_free.freed_objects(), 0);
+    enable(&mut dbs, "lazyfree-lazy-server-del");
+    command(&mut dbs, &factory, "set large value").unwrap();
+    wait_freed(&dbs.server().lazy_free, 1);
+
+    // DEL always frees inline
+    set_large_hash(&mut dbs, &factory, "other");
+    assert_eq!(
+        command(&mut dbs, &factory, "del other"),
+        Ok("1".to_string())
+    );
+    assert_eq!(dbs.server().lazy_free.freed_objects(), 1);
+}
+
+#[test]
+fn test_lazyfree_lazy_expire() {
+    let mut dbs = Databases::new(2);
+    let factory = CommandFactory::new();
+    enable(&mut dbs, "lazyfree-lazy-expire");
+    set_large_hash(&mut dbs, &factory, "large");
+    dbs.get_mut(0).unwrap().expire_at("large", 1);
+
+    assert_eq!(
+        command(&mut dbs, &factory, "exists large"),
+        Ok("0".to_string())
+    );
+    wait_freed(&dbs.server().lazy_free, 1);
+    assert_eq!(dbs.server().stats.expired_keys(), 1);
+}
+
+#[test]
+fn test_lazyfree_lazy_eviction() {
+    let mut dbs = Databases::new(2);
+    let factory = CommandFactory::new();
+    // the only key with a TTL is the only one volatile-lru may evict
+    set_large_hash(&mut dbs, &factory, "large");
+    command(&mut dbs, &factory, "expire large 100").unwrap();
+    let used = dbs.used_memory();
+    let mut config = BTreeMap::new();
+    config.insert("maxmemory".to_string(), used.to_string());
+    config.insert("maxmemory-policy".to_string(), "volatile-lru".to_string());
+    config.insert("lazyfree-lazy-eviction".to_string(), "yes".to_string());
+    dbs.server().load_config(config);
+
+    command(&mut dbs, &factory, "set key value").unwrap();
+    command(&mut dbs, &factory, "set other value").unwrap();
+    assert_eq!(
+        command(&mut dbs, &factory, "exists large"),
+        Ok("0".to_string())
+    );
+    wait_freed(&dbs.server().lazy_free, 1);
+    assert_eq!(dbs.server().stats.evicted_keys(), 1);
+}
+
+#[test]
+fn test_touch_command() {
+    let mut dbs = Databases::new(2);
+    let factory = CommandFactory::new();
+    command(&mut dbs, &factory, "set key value").unwrap();
+    command(&mut dbs, &factory, "set other value").unwrap();
+
+    let tests_case: Vec<(&str, Result<String, &'static str>)> = vec![
+        ("object freq key", Ok("5".to_string())),
+        ("touch key other not_exists", Ok("2".to_string())),
+        ("touch not_exists", Ok("0".to_string())),
+        ("object freq key", Ok("6".to_string())),
+        ("object idletime key", Ok("0".to_string())),
+    ];
+    for (line, expected_result) in tests_case {
+        println!("line: {}", line);
+        let result = command(&mut dbs, &factory, line);
+        assert_eq!(result, expected_result);
+    }
+}
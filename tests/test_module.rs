@@ -0,0 +1,391 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use rus_key_db::databases::Databases;
+use rus_key_factory::command_factory::CommandFactory;
+use rus_key_lib::cmd::handle_command;
+use rus_key_lib::multi::Transaction;
+
+// the factory is created per command, like the server does, so it sees the loaded modules
+fn command(dbs: &mut Databases, line: &str) -> Result<String, &'static str> {
+    let factory = CommandFactory::with_modules(dbs.server());
+    let mut selected = 0;
+    let mut parts = line.split_ascii_whitespace();
+    handle_command(&mut parts, dbs, &mut selected, &factory)
+}
+
+// every test writes its own files, the tests of the process share the temporary directory
+fn write_module(file: &str, bytes: &[u8]) -> String {
+    let path: PathBuf =
+        std::env::temp_dir().join(format!("ruskey-{}-{}.wasm", std::process::id(), file));
+    std::fs::write(&path, bytes).unwrap();
+    path.to_string_lossy().to_string()
+}
+
+// registers `<prefix>.copy src dst`, `<prefix>.del key` and `<prefix>.loop`, a prefix is 3
+// characters long
+fn commands_module(prefix: &str) -> Vec<u8> {
+    wat::parse_str(format!(
+        r#"
+        (module
+          (import "ruskey" "register" (func $register (param i32 i32)))
+          (import "ruskey" "get" (func $get (param i32 i32 i32 i32) (result i32)))
+          (import "ruskey" "set" (func $set (param i32 i32 i32 i32)))
+          (import "ruskey" "del" (func $del (param i32 i32) (result i32)))
+          (import "ruskey" "reply" (func $reply (param i32 i32)))
+          (memory (export "memory") 1)
+          (data (i32.const 0) "{prefix}.copy")
+          (data (i32.const 16) "{prefix}.del")
+          (data (i32.const 32) "{prefix}.loop")
+          (data (i32.const 48) "nil10")
+          (func (export "ruskey_init")
+            (call $register (i32.const 0) (i32.const 8))
+            (call $register (i32.const 16) (i32.const 7))
+            (call $register (i32.const 32) (i32.const 8)))
+          (func (export "ruskey_alloc") (param i32) (result i32)
+            (i32.const 1024))
+          (func $next_space (param $pos i32) (param $end i32) (result i32)
+            (block $done
+              (loop $scan
+                (br_if $done (i32.ge_u (local.get $pos) (local.get $end)))
+                (br_if $done (i32.eq (i32.load8_u (local.get $pos)) (i32.const 32)))
+                (local.set $pos (i32.add (local.get $pos) (i32.const 1)))
+                (br $scan)))
+            (local.get $pos))
+          (func (export "ruskey_command") (param $ptr i32) (param $len i32) (result i32)
+            (local $end i32) (local $a i32) (local $a_end i32) (local $b i32)
+            (local $b_end i32) (local $n i32)
+            (local.set $end (i32.add (local.get $ptr) (local.get $len)))
+            (local.set $a (i32.add (call $next_space (local.get $ptr) (local.get $end)) (i32.const 1)))
+            (local.set $a_end (call $next_space (local.get $a) (local.get $end)))
+            (local.set $b (i32.add (local.get $a_end) (i32.const 1)))
+            (local.set $b_end (call $next_space (local.get $b) (local.get $end)))
+            ;; loop
+            (if (i32.eq (i32.load8_u offset=4 (local.get $ptr)) (i32.const 108))
+              (then (loop $forever (br $forever))))
+            ;; del
+            (if (i32.eq (i32.load8_u offset=4 (local.get $ptr)) (i32.const 100))
+              (then
+                (if (call $del (local.get $a) (i32.sub (local.get $a_end) (local.get $a)))
+                  (then (call $reply (i32.const 51) (i32.const 1)))
+                  (else (call $reply (i32.const 52) (i32.const 1))))
+                (return (i32.const 0))))
+            ;; copy
+            (local.set $n
+              (call $get (local.get $a) (i32.sub (local.get $a_end) (local.get $a))
+                (i32.const 4096) (i32.const 1024)))
+            (if (i32.eq (local.get $n) (i32.const -1))
+              (then
+                (call $reply (i32.const 48) (i32.const 3))
+                (return (i32.const 0))))
+            (if (i32.lt_s (local.get $n) (i32.const 0))
+              (then (return (i32.const 1))))
+            (call $set (local.get $b) (i32.sub (local.get $b_end) (local.get $b))
+              (i32.const 4096) (local.get $n))
+            (i32.const 0)))
+        "#
+    ))
+    .unwrap()
+}
+
+// registers a single command and does nothing else
+fn register_module(name: &str) -> Vec<u8> {
+    wat::parse_str(format!(
+        r#"
+        (module
+          (import "ruskey" "register" (func $register (param i32 i32)))
+          (memory (export "memory") 1)
+          (data (i32.const 0) "{name}")
+          (func (export "ruskey_init")
+            (call $register (i32.const 0) (i32.const {len})))
+          (func (export "ruskey_alloc") (param i32) (result i32)
+            (i32.const 1024))
+          (func (export "ruskey_command") (param i32 i32) (result i32)
+            (i32.const 0)))
+        "#,
+        len = name.len()
+    ))
+    .unwrap()
+}
+
+#[test]
+fn test_module_commands() {
+    let mut dbs = Databases::new(2);
+    let path = write_module("commands", &commands_module("mda"));
+
+    let tests_case: Vec<(String, Result<String, &'static str>)> = vec![
+        (
+            "mda.copy source target".to_string(),
+            Err("Invalid command!"),
+        ),
+        (format!("module load {}", path), Ok("OK".to_string())),
+        ("set source value".to_string(), Ok("OK".to_string())),
+        ("MDA.COPY source target".to_string(), Ok("OK".to_string())),
+        ("get target".to_string(), Ok("value".to_string())),
+        ("mda.copy missing target".to_string(), Ok("nil".to_string())),
+        // a hash is not a string, the module fails
+        ("hset hash field value".to_string(), Ok("1".to_string())),
+        (
+            "mda.copy hash target".to_string(),
+            Err("ERR Module command failed"),
+        ),
+        ("mda.del target".to_string(), Ok("1".to_string())),
+        ("mda.del target".to_string(), Ok("0".to_string())),
+        ("get target".to_string(), Ok("nil".to_string())),
+        (
+            format!("module load {}", path),
+            Err("ERR Error loading the module: a module with that name is already loaded"),
+        ),
+        // scripts call module commands like the other ones
+        (
+            "eval \"return redis.call('mda.copy', KEYS[1], KEYS[2])\" 2 source copied".to_string(),
+            Ok("OK".to_string()),
+        ),
+        ("get copied".to_string(), Ok("value".to_string())),
+        (
+            "eval \"return redis.call('module', 'list')\" 0".to_string(),
+            Err("ERR Unknown Redis command called from script"),
+        ),
+    ];
+    for (line, expected_result) in tests_case {
+        println!("line: {}", line);
+        let result = command(&mut dbs, &line);
+        assert_eq!(result, expected_result);
+    }
+}
+
+#[test]
+fn test_module_fuel() {
+    let mut dbs = Databases::new(2);
+    let path = write_module("fuel", &commands_module("mdb"));
+    let mut config = BTreeMap::new();
+    config.insert("module-fuel".to_string(), "100000".to_string());
+    dbs.server().load_config(config);
+    assert_eq!(
+        command(&mut dbs, &format!("module load {}", path)),
+        Ok("OK".to_string())
+    );
+
+    let start = Instant::now();
+    assert_eq!(
+        command(&mut dbs, "mdb.loop"),
+        Err("ERR Module command ran out of fuel")
+    );
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert_eq!(command(&mut dbs, "ping"), Ok("PONG".to_string()));
+}
+
+#[test]
+fn test_module_noeviction() {
+    let mut dbs = Databases::new(2);
+    let path = write_module("noeviction", &commands_module("mdg"));
+    assert_eq!(
+        command(&mut dbs, &format!("module load {}", path)),
+        Ok("OK".to_string())
+    );
+    command(&mut dbs, "set source value").unwrap();
+    let mut config = BTreeMap::new();
+    config.insert("maxmemory".to_string(), dbs.used_memory().to_string());
+    config.insert("maxmemory-policy".to_string(), "noeviction".to_string());
+    dbs.server().load_config(config);
+
+    // a module may write anything, its commands are refused over the limit
+    assert_eq!(
+        command(&mut dbs, "mdg.copy source target"),
+        Ok("OK".to_string())
+    );
+    assert_eq!(
+        command(&mut dbs, "mdg.copy source other"),
+        Err("OOM command not allowed when used memory > 'maxmemory'.")
+    );
+    assert_eq!(
+        command(&mut dbs, "mdg.del target"),
+        Err("OOM command not allowed when used memory > 'maxmemory'.")
+    );
+    assert_eq!(command(&mut dbs, "del target"), Ok("1".to_string()));
+    assert_eq!(command(&mut dbs, "mdg.del source"), Ok("1".to_string()));
+}
+
+#[test]
+fn test_module_list_and_unload() {
+    let mut dbs = Databases::new(2);
+    let path = write_module("unload", &register_module("mdc.noop"));
+    let name = format!("ruskey-{}-unload", std::process::id());
+
+    assert_eq!(
+        command(&mut dbs, &format!("module load {}", path)),
+        Ok("OK".to_string())
+    );
+    let list = command(&mut dbs, "module list").unwrap();
+    assert!(list
+        .lines()
+        .any(|module| module == format!("{} {} mdc.noop", name, path)));
+    assert_eq!(command(&mut dbs, "mdc.noop"), Ok("OK".to_string()));
+
+    let tests_case: Vec<(String, Result<String, &'static str>)> = vec![
+        (format!("module unload {}", name), Ok("OK".to_string())),
+        (
+            format!("module unload {}", name),
+            Err("ERR Error unloading module: no such module with that name"),
+        ),
+        ("mdc.noop".to_string(), Err("Invalid command!")),
+        (
+            "module".to_string(),
+            Err("ERR wrong number of arguments for command"),
+        ),
+        ("module unknown".to_string(), Err("ERR unknown subcommand")),
+    ];
+    for (line, expected_result) in tests_case {
+        println!("line: {}", line);
+        let result = command(&mut dbs, &line);
+        assert_eq!(result, expected_result);
+    }
+    assert!(!command(&mut dbs, "module list").unwrap().contains(&name));
+}
+
+#[test]
+fn test_module_load_errors() {
+    let mut dbs = Databases::new(2);
+    let invalid = write_module("invalid", b"not wasm");
+    let builtin = write_module("builtin", &register_module("get"));
+    let empty = write_module(
+        "empty",
+        &wat::parse_str(
+            r#"
+            (module
+              (memory (export "memory") 1)
+              (func (export "ruskey_init"))
+              (func (export "ruskey_alloc") (param i32) (result i32) (i32.const 0))
+              (func (export "ruskey_command") (param i32 i32) (result i32) (i32.const 0)))
+            "#,
+        )
+        .unwrap(),
+    );
+    // no access to anything but the host API
+    let system = write_module(
+        "system",
+        &wat::parse_str(
+            r#"
+            (module
+              (import "wasi_snapshot_preview1" "fd_write"
+                (func (param i32 i32 i32 i32) (result i32)))
+              (memory (export "memory") 1)
+              (func (export "ruskey_init"))
+              (func (export "ruskey_alloc") (param i32) (result i32) (i32.const 0))
+              (func (export "ruskey_command") (param i32 i32) (result i32) (i32.const 0)))
+            "#,
+        )
+        .unwrap(),
+    );
+    let no_exports = write_module(
+        "noexports",
+        &wat::parse_str("(module (memory (export \"memory\") 1))").unwrap(),
+    );
+    let missing = std::env::temp_dir().join("ruskey-missing-module.wasm");
+
+    let tests_case: Vec<(String, Result<String, &'static str>)> = vec![
+        (
+            format!("module load {}", invalid),
+            Err("ERR Error loading the module: not a valid WebAssembly module"),
+        ),
+        (
+            format!("module load {}", builtin),
+            Err("ERR Error loading the module: command name already in use"),
+        ),
+        ("get get".to_string(), Ok("nil".to_string())),
+        (
+            format!("module load {}", empty),
+            Err("ERR Error loading the module: it registers no command"),
+        ),
+        (
+            format!("module load {}", system),
+            Err("ERR Error loading the module: it imports something the host API does not provide"),
+        ),
+        (
+            format!("module load {}", no_exports),
+            Err("ERR Error loading the module: it must export memory, ruskey_init, ruskey_alloc and ruskey_command"),
+        ),
+        (
+            format!("module load {}", missing.to_string_lossy()),
+            Err("ERR Error loading the module: cannot read the file"),
+        ),
+        (
+            "module load".to_string(),
+            Err("ERR wrong number of arguments for command"),
+        ),
+    ];
+    for (line, expected_result) in tests_case {
+        println!("line: {}", line);
+        let result = command(&mut dbs, &line);
+        assert_eq!(result, expected_result);
+    }
+}
+
+#[test]
+fn test_module_command_name_in_use() {
+    let mut dbs = Databases::new(2);
+    let first = write_module("first", &register_module("mde.cmd"));
+    let second = write_module("second", &register_module("mde.cmd"));
+
+    assert_eq!(
+        command(&mut dbs, &format!("module load {}", first)),
+        Ok("OK".to_string())
+    );
+    assert_eq!(
+        command(&mut dbs, &format!("module load {}", second)),
+        Err("ERR Error loading the module: command name already in use")
+    );
+}
+
+#[test]
+fn test_modules_per_server() {
+    let mut dbs = Databases::new(2);
+    let mut other = Databases::new(2);
+    let path = write_module("server", &register_module("mdf.noop"));
+    let name = format!("ruskey-{}-server", std::process::id());
+
+    assert_eq!(
+        command(&mut dbs, &format!("module load {}", path)),
+        Ok("OK".to_string())
+    );
+    assert_eq!(command(&mut dbs, "mdf.noop"), Ok("OK".to_string()));
+    // another server in the process has its own modules
+    assert_eq!(command(&mut other, "mdf.noop"), Err("Invalid command!"));
+    assert_eq!(command(&mut other, "module list"), Ok("".to_string()));
+    assert_eq!(
+        command(&mut other, &format!("module load {}", path)),
+        Ok("OK".to_string())
+    );
+    assert_eq!(
+        command(&mut other, &format!("module unload {}", name)),
+        Ok("OK".to_string())
+    );
+    assert_eq!(command(&mut dbs, "mdf.noop"), Ok("OK".to_string()));
+}
+
+#[test]
+fn test_module_list_in_transaction() {
+    let mut dbs = Databases::new(2);
+    for (file, name) in [("lista", "mdh.a"), ("listb", "mdh.b")] {
+        let path = write_module(file, &register_module(name));
+        command(&mut dbs, &format!("module load {}", path)).unwrap();
+    }
+    let list = command(&mut dbs, "module list").unwrap();
+    assert_eq!(list.lines().count(), 2);
+
+    // the lines of a reply stay within it
+    let factory = CommandFactory::with_modules(dbs.server());
+    let mut transaction = Transaction::new();
+    let mut selected = 0;
+    for line in ["multi", "module list", "ping"] {
+        transaction
+            .handle(line, &mut dbs, &mut selected, &factory)
+            .unwrap()
+            .unwrap();
+    }
+    assert_eq!(
+        transaction.handle("exec", &mut dbs, &mut selected, &factory),
+        Some(Ok(format!("{}\r\nPONG\r\n", list)))
+    );
+}
@@ -0,0 +1,330 @@
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use rus_key_db::databases::Databases;
+use rus_key_factory::command_factory::CommandFactory;
+use rus_key_lib::cmd::handle_command;
+use rus_key_lib::multi::Transaction;
+use rus_key_lib::net::handle_client;
+use tokio::sync::Mutex;
+
+// another client, outside of the transaction
+fn command(
+    dbs: &mut Databases,
+    factory: &CommandFactory,
+    line: &str,
+) -> Result<String, &'static str> {
+    let mut selected = 0;
+    let mut parts = line.split_ascii_whitespace();
+    handle_command(&mut parts, dbs, &mut selected, factory)
+}
+
+// the client of the transaction
+fn transaction_command(
+    transaction: &mut Transaction,
+    dbs: &mut Databases,
+    factory: &CommandFactory,
+    line: &str,
+) -> Result<String, &'static str> {
+    let mut selected = 0;
+    match transaction.handle(line, dbs, &mut selected, factory) {
+        Some(result) => result,
+        None => {
+            let mut parts = line.split_ascii_whitespace();
+            handle_command(&mut parts, dbs, &mut selected, factory)
+        }
+    }
+}
+
+#[test]
+fn test_multi_exec() {
+    let mut dbs = Databases::new(2);
+    let factory = CommandFactory::new();
+    let mut transaction = Transaction::new();
+
+    let tests_case: Vec<(&str, Result<String, &'static str>)> = vec![
+        ("exec", Err("ERR EXEC without MULTI")),
+        ("discard", Err("ERR DISCARD without MULTI")),
+        ("multi", Ok("OK".to_string())),
+        ("multi", Err("ERR MULTI calls can not be nested")),
+        ("set counter 1", Ok("QUEUED".to_string())),
+        ("incr counter", Ok("QUEUED".to_string())),
+        // errors at run time are replied in place, the other commands still run
+        ("hset counter field a", Ok("QUEUED".to_string())),
+        ("get counter", Ok("QUEUED".to_string())),
+        (
+            "exec",
+            Ok("OK\r\n2\r\n\
+                There is no such key, the key is expired, or the data type is incorrect\r\n2\r\n"
+                .to_string()),
+        ),
+        ("get counter", Ok("2".to_string())),
+        ("multi", Ok("OK".to_string())),
+        ("set counter 10", Ok("QUEUED".to_string())),
+        ("discard", Ok("OK".to_string())),
+        ("get counter", Ok("2".to_string())),
+        ("multi", Ok("OK".to_string())),
+        ("exec", Ok("".to_string())),
+    ];
+    for (line, expected_result) in tests_case {
+        println!("line: {}", line);
+        let result = transaction_command(&mut transaction, &mut dbs, &factory, line);
+        assert_eq!(result, expected_result);
+    }
+}
+
+#[test]
+fn test_exec_abort() {
+    let mut dbs = Databases::new(2);
+    let factory = CommandFactory::new();
+    let mut transaction = Transaction::new();
+
+    let tests_case: Vec<(&str, Result<String, &'static str>)> = vec![
+        ("multi", Ok("OK".to_string())),
+        ("set key value", Ok("QUEUED".to_string())),
+        ("unknown key", Err("ERR unknown command")),
+        (
+            "subscribe news",
+            Err("ERR Command not allowed inside a transaction"),
+        ),
+        (
+            "exec",
+            Err("EXECABORT Transaction discarded because of previous errors."),
+        ),
+        ("get key", Ok("nil".to_string())),
+        // the next transaction starts over
+        ("multi", Ok("OK".to_string())),
+        ("set key value", Ok("QUEUED".to_string())),
+        ("exec", Ok("OK\r\n".to_string())),
+        // missing arguments and arguments which are not integers are refused when queued
+        ("multi", Ok("OK".to_string())),
+        ("set", Err("ERR wrong number of arguments for command")),
+        (
+            "expire key notanint",
+            Err("ERR value is not an integer or out of range"),
+        ),
+        (
+            "set \"quoted key\"",
+            Err("ERR wrong number of arguments for command"),
+        ),
+        // the commands run by the server are checked too
+        (
+            "select one",
+            Err("ERR value is not an integer or out of range"),
+        ),
+        (
+            "publish news",
+            Err("ERR wrong number of arguments for command"),
+        ),
+        ("publish news \"quoted message\"", Ok("QUEUED".to_string())),
+        ("expire key 100", Ok("QUEUED".to_string())),
+        (
+            "exec",
+            Err("EXECABORT Transaction discarded because of previous errors."),
+        ),
+        ("ttl key", Ok("-1".to_string())),
+    ];
+    for (line, expected_result) in tests_case {
+        println!("line: {}", line);
+        let result = transaction_command(&mut transaction, &mut dbs, &factory, line);
+        assert_eq!(result, expected_result);
+    }
+}
+
+#[test]
+fn test_watch() {
+    let factory = CommandFactory::new();
+    // the change made by another client to the watched key, before EXEC
+    let changes = [
+        "set key other",
+        "append key more",
+        "del key",
+        "expire key 100",
+        "rename key renamed",
+        "flushdb",
+        "swapdb 0 1",
+    ];
+    for change in changes {
+        println!("change: {}", change);
+        let mut dbs = Databases::new(2);
+        let mut transaction = Transaction::new();
+        command(&mut dbs, &factory, "set key value").unwrap();
+        for line in ["watch key", "multi", "set key mine"] {
+            transaction_command(&mut transaction, &mut dbs, &factory, line).unwrap();
+        }
+        command(&mut dbs, &factory, change).unwrap();
+        assert_eq!(
+            transaction_command(&mut transaction, &mut dbs, &factory, "exec"),
+            Ok("nil".to_string())
+        );
+        assert_ne!(
+            command(&mut dbs, &factory, "get key"),
+            Ok("mine".to_string())
+        );
+    }
+
+    // reading the key or changing another one does not abort
+    let mut dbs = Databases::new(2);
+    let mut transaction = Transaction::new();
+    let tests_case: Vec<(&str, Result<String, &'static str>)> = vec![
+        ("watch", Err("ERR wrong number of arguments for command")),
+        ("watch key", Ok("OK".to_string())),
+        ("multi", Ok("OK".to_string())),
+        ("watch key", Err("ERR WATCH inside MULTI is not allowed")),
+        ("set key mine", Ok("QUEUED".to_string())),
+    ];
+    for (line, expected_result) in tests_case {
+        println!("line: {}", line);
+        let result = transaction_command(&mut transaction, &mut dbs, &factory, line);
+        assert_eq!(result, expected_result);
+    }
+    command(&mut dbs, &factory, "get key").unwrap();
+    command(&mut dbs, &factory, "set other value").unwrap();
+    assert_eq!(
+        transaction_command(&mut transaction, &mut dbs, &factory, "exec"),
+        Ok("OK\r\n".to_string())
+    );
+
+    // EXEC unwatches, a change after it does not abort the next transaction
+    command(&mut dbs, &factory, "set key other").unwrap();
+    for line in ["multi", "get key"] {
+        transaction_command(&mut transaction, &mut dbs, &factory, line).unwrap();
+    }
+    assert_eq!(
+        transaction_command(&mut transaction, &mut dbs, &factory, "exec"),
+        Ok("other\r\n".to_string())
+    );
+}
+
+#[test]
+fn test_unwatch() {
+    let mut dbs = Databases::new(2);
+    let factory = CommandFactory::new();
+    let mut transaction = Transaction::new();
+
+    let tests_case: Vec<(&str, Result<String, &'static str>)> = vec![
+        ("watch key", Ok("OK".to_string())),
+        ("unwatch", Ok("OK".to_string())),
+        ("multi", Ok("OK".to_string())),
+        ("set key mine", Ok("QUEUED".to_string())),
+    ];
+    for (line, expected_result) in tests_case {
+        println!("line: {}", line);
+        let result = transaction_command(&mut transaction, &mut dbs, &factory, line);
+        assert_eq!(result, expected_result);
+    }
+    command(&mut dbs, &factory, "set key other").unwrap();
+    assert_eq!(
+        transaction_command(&mut transaction, &mut dbs, &factory, "exec"),
+        Ok("OK\r\n".to_string())
+    );
+
+    // DISCARD unwatches too
+    for line in ["watch key", "multi", "discard"] {
+        transaction_command(&mut transaction, &mut dbs, &factory, line).unwrap();
+    }
+    command(&mut dbs, &factory, "set key other").unwrap();
+    for line in ["multi", "get key"] {
+        transaction_command(&mut transaction, &mut dbs, &factory, line).unwrap();
+    }
+    assert_eq!(
+        transaction_command(&mut transaction, &mut dbs, &factory, "exec"),
+        Ok("other\r\n".to_string())
+    );
+}
+
+#[test]
+fn test_watch_expired_key() {
+    let mut dbs = Databases::new(2);
+    let factory = CommandFactory::new();
+    let mut transaction = Transaction::new();
+
+    command(&mut dbs, &factory, "set key value").unwrap();
+    command(&mut dbs, &factory, "pexpire key 20").unwrap();
+    for line in ["watch key", "multi", "set key mine"] {
+        transaction_command(&mut transaction, &mut dbs, &factory, line).unwrap();
+    }
+    // nobody reads the key, it is found expired at EXEC
+    thread::sleep(Duration::from_millis(50));
+    assert_eq!(
+        transaction_command(&mut transaction, &mut dbs, &factory, "exec"),
+        Ok("nil".to_string())
+    );
+    assert_eq!(
+        command(&mut dbs, &factory, "get key"),
+        Ok("nil".to_string())
+    );
+}
+
+fn start_server() -> (u16, Arc<Mutex<Databases>>) {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.set_nonblocking(true).unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let dbs = Arc::new(Mutex::new(Databases::new(2)));
+    let server_dbs = Arc::clone(&dbs);
+    thread::spawn(move || {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async move {
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                tokio::spawn(handle_client(stream, Arc::clone(&server_dbs)));
+            }
+        });
+    });
+    (port, dbs)
+}
+
+fn connect(port: u16) -> TcpStream {
+    let stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    stream
+}
+
+fn request(stream: &mut TcpStream, line: &str) -> String {
+    stream
+        .write_all(format!("{}\r\n", line).as_bytes())
+        .unwrap();
+    let mut buf = [0; 512];
+    let n = stream.read(&mut buf).unwrap();
+    String::from_utf8_lossy(&buf[..n]).to_string()
+}
+
+#[test]
+fn test_transaction_per_connection() {
+    let (port, dbs) = start_server();
+    let mut client = connect(port);
+    let mut other = connect(port);
+
+    assert_eq!(request(&mut client, "watch key"), "OK");
+    assert_eq!(request(&mut client, "multi"), "OK");
+    assert_eq!(request(&mut client, "set key mine"), "QUEUED");
+    // the other connection is not in the transaction
+    assert_eq!(request(&mut other, "set key other"), "OK");
+    assert_eq!(request(&mut client, "exec"), "nil");
+
+    assert_eq!(request(&mut client, "multi"), "OK");
+    assert_eq!(request(&mut client, "incr counter"), "QUEUED");
+    assert_eq!(request(&mut client, "incr counter"), "QUEUED");
+    assert_eq!(request(&mut other, "get counter"), "nil");
+    assert_eq!(request(&mut client, "exec"), "1\r\n2\r\n");
+
+    // a closed connection leaves no watched key behind
+    assert_eq!(request(&mut other, "watch key"), "OK");
+    drop(other);
+    assert_eq!(request(&mut client, "ping"), "PONG");
+    let mut unwatched = false;
+    for _ in 0..100 {
+        if dbs.blocking_lock().server().watches.is_empty() {
+            unwatched = true;
+            break;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert!(unwatched);
+}
@@ -0,0 +1,280 @@
+use std::collections::BTreeMap;
+
+use rus_key_db::databases::Databases;
+use rus_key_db::pubsub::{Message, Subscriber};
+use rus_key_factory::command_factory::CommandFactory;
+use rus_key_lib::cmd::handle_command;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+fn command(
+    dbs: &mut Databases,
+    factory: &CommandFactory,
+    selected: &mut usize,
+    line: &str,
+) -> Result<String, &'static str> {
+    let mut parts = line.split_ascii_whitespace();
+    handle_command(&mut parts, dbs, selected, factory)
+}
+
+// sets `notify-keyspace-events` and subscribes to every keyspace and keyevent channel
+fn subscribe(dbs: &mut Databases, flags: &str) -> UnboundedReceiver<Message> {
+    let mut config = BTreeMap::new();
+    config.insert("notify-keyspace-events".to_string(), flags.to_string());
+    dbs.server().load_config(config);
+    let (subscriber, receiver) = Subscriber::channel();
+    let pubsub = &dbs.server().pubsub;
+    pubsub.psubscribe(pubsub.client_id(), "__key*__:*", &subscriber);
+    receiver
+}
+
+// the channels and payloads received since the last call
+fn received(receiver: &mut UnboundedReceiver<Message>) -> Vec<(String, String)> {
+    let mut messages = Vec::new();
+    while let Ok(message) = receiver.try_recv() {
+        assert_eq!(message.pattern, Some("__key*__:*".to_string()));
+        messages.push((message.channel, message.payload));
+    }
+    messages
+}
+
+fn events(messages: &[(&str, &str)]) -> Vec<(String, String)> {
+    messages
+        .iter()
+        .map(|(channel, payload)| (channel.to_string(), payload.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_keyspace_events() {
+    let mut dbs = Databases::new(2);
+    let factory = CommandFactory::new();
+    let mut selected = 0;
+    let mut receiver = subscribe(&mut dbs, "KEA");
+
+    let tests_case: Vec<(&str, Vec<(&str, &str)>)> = vec![
+        (
+            "set key value",
+            vec![("__keyspace@0__:key", "set"), ("__keyevent@0__:set", "key")],
+        ),
+        (
+            "expire key 100",
+            vec![
+                ("__keyspace@0__:key", "expire"),
+                ("__keyevent@0__:expire", "key"),
+            ],
+        ),
+        (
+            "rename key other",
+            vec![
+                ("__keyspace@0__:key", "rename_from"),
+                ("__keyevent@0__:rename_from", "key"),
+                ("__keyspace@0__:other", "rename_to"),
+                ("__keyevent@0__:rename_to", "other"),
+            ],
+        ),
+        (
+            "hset hash field value",
+            vec![
+                ("__keyspace@0__:hash", "hset"),
+                ("__keyevent@0__:hset", "hash"),
+            ],
+        ),
+        (
+            "del other hash not_exists",
+            vec![
+                ("__keyspace@0__:other", "del"),
+                ("__keyevent@0__:del", "other"),
+                ("__keyspace@0__:hash", "del"),
+                ("__keyevent@0__:del", "hash"),
+            ],
+        ),
+        // reads and failed writes publish nothing
+        ("get key", vec![]),
+        ("del not_exists", vec![]),
+    ];
+    for (line, expected) in tests_case {
+        println!("line: {}", line);
+        command(&mut dbs, &factory, &mut selected, line).unwrap();
+        assert_eq!(received(&mut receiver), events(&expected));
+    }
+}
+
+#[test]
+fn test_keyspace_event_classes() {
+    let mut dbs = Databases::new(2);
+    let factory = CommandFactory::new();
+    let mut selected = 0;
+    // key events of the generic class and of new keys only
+    let mut receiver = subscribe(&mut dbs, "Egn");
+
+    command(&mut dbs, &factory, &mut selected, "set key value").unwrap();
+    assert_eq!(
+        received(&mut receiver),
+        events(&[("__keyevent@0__:new", "key")])
+    );
+    command(&mut dbs, &factory, &mut selected, "set key other").unwrap();
+    assert_eq!(received(&mut receiver), events(&[]));
+    command(&mut dbs, &factory, &mut selected, "del key").unwrap();
+    assert_eq!(
+        received(&mut receiver),
+        events(&[("__keyevent@0__:del", "key")])
+    );
+
+    // K or E alone selects no class
+    let mut receiver = subscribe(&mut dbs, "KE");
+    command(&mut dbs, &factory, &mut selected, "set key value").unwrap();
+    assert_eq!(received(&mut receiver), events(&[]));
+}
+
+#[test]
+fn test_expired_and_evicted_events() {
+    let mut dbs = Databases::new(2);
+    let factory = CommandFactory::new();
+    let mut selected = 0;
+    let mut receiver = subscribe(&mut dbs, "Exe");
+
+    command(&mut dbs, &factory, &mut selected, "set key value").unwrap();
+    dbs.get_mut(0).unwrap().expire_at("key", 1);
+    assert_eq!(
+        command(&mut dbs, &factory, &mut selected, "exists key"),
+        Ok("0".to_string())
+    );
+    assert_eq!(
+        received(&mut receiver),
+        events(&[("__keyevent@0__:expired", "key")])
+    );
+
+    // the only key with a TTL is the only one volatile-lru may evict
+    command(&mut dbs, &factory, &mut selected, "set first value").unwrap();
+    command(&mut dbs, &factory, &mut selected, "expire first 100").unwrap();
+    let mut config = dbs.server().config();
+    config.insert("maxmemory".to_string(), dbs.used_memory().to_string());
+    config.insert("maxmemory-policy".to_string(), "volatile-lru".to_string());
+    dbs.server().load_config(config);
+    command(&mut dbs, &factory, &mut selected, "set second value").unwrap();
+    // eviction runs before the next command
+    command(&mut dbs, &factory, &mut selected, "dbsize").unwrap();
+    assert_eq!(
+        received(&mut receiver),
+        events(&[("__keyevent@0__:evicted", "first")])
+    );
+}
+
+#[test]
+fn test_keyspace_events_db_index() {
+    let mut dbs = Databases::new(2);
+    let factory = CommandFactory::new();
+    let mut selected = 0;
+    let mut receiver = subscribe(&mut dbs, "K$");
+
+    command(&mut dbs, &factory, &mut selected, "select 1").unwrap();
+    command(&mut dbs, &factory, &mut selected, "set key value").unwrap();
+    assert_eq!(
+        received(&mut receiver),
+        events(&[("__keyspace@1__:key", "set")])
+    );
+    // the channels follow the number of the database, not its content
+    command(&mut dbs, &factory, &mut selected, "swapdb 0 1").unwrap();
+    command(&mut dbs, &factory, &mut selected, "select 0").unwrap();
+    command(&mut dbs, &factory, &mut selected, "append key _more").unwrap();
+    assert_eq!(
+        received(&mut receiver),
+        events(&[("__keyspace@0__:key", "append")])
+    );
+}
+
+#[test]
+fn test_module_keyspace_events() {
+    let mut dbs = Databases::new(2);
+    let factory = CommandFactory::new();
+    let mut selected = 0;
+    let mut receiver = subscribe(&mut dbs, "Ed");
+
+    command(&mut dbs, &factory, &mut selected, "bf.add filter item").unwrap();
+    command(&mut dbs, &factory, &mut selected, "bf.exists filter item").unwrap();
+    command(&mut dbs, &factory, &mut selected, "ts.create first").unwrap();
+    command(&mut dbs, &factory, &mut selected, "ts.create second").unwrap();
+    command(
+        &mut dbs,
+        &factory,
+        &mut selected,
+        "ts.madd first 1 1.0 second 1 2.0",
+    )
+    .unwrap();
+    assert_eq!(
+        received(&mut receiver),
+        events(&[
+            ("__keyevent@0__:bf.add", "filter"),
+            ("__keyevent@0__:ts.create", "first"),
+            ("__keyevent@0__:ts.create", "second"),
+            ("__keyevent@0__:ts.madd", "first"),
+            ("__keyevent@0__:ts.madd", "second"),
+        ])
+    );
+}
+
+#[test]
+fn test_module_keyspace_events_without_change() {
+    let mut dbs = Databases::new(2);
+    let factory = CommandFactory::new();
+    let mut selected = 0;
+    let mut receiver = subscribe(&mut dbs, "Ed");
+
+    // only the commands which change their key are published
+    for line in [
+        "json.set doc . 1",
+        "json.set doc . 2 NX",
+        "json.del missing",
+        "json.arrappend doc . 3",
+        "bf.add filter item",
+        "bf.add filter item",
+        "cf.addnx cuckoo item",
+        "cf.addnx cuckoo item",
+        "cf.del cuckoo other",
+        "ts.create series",
+        "ts.madd series 1 1.0 series 1 2.0",
+        "vrem missing element",
+    ] {
+        command(&mut dbs, &factory, &mut selected, line).ok();
+    }
+    assert_eq!(
+        received(&mut receiver),
+        events(&[
+            ("__keyevent@0__:json.set", "doc"),
+            ("__keyevent@0__:bf.add", "filter"),
+            ("__keyevent@0__:cf.addnx", "cuckoo"),
+            ("__keyevent@0__:ts.create", "series"),
+            ("__keyevent@0__:ts.madd", "series"),
+        ])
+    );
+}
+
+#[test]
+fn test_config_set_notify_keyspace_events() {
+    let mut dbs = Databases::new(2);
+    let factory = CommandFactory::new();
+    let mut selected = 0;
+    let mut receiver = subscribe(&mut dbs, "");
+
+    let tests_case: Vec<(&str, Result<String, &'static str>)> = vec![
+        (
+            "config set notify-keyspace-events Kq",
+            Err("ERR Invalid event class character. Use 'Ag$lshzxeKEtdn'."),
+        ),
+        ("config set notify-keyspace-events K$", Ok("OK".to_string())),
+        (
+            "config get notify-keyspace-events",
+            Ok("notify-keyspace-events: K$".to_string()),
+        ),
+        ("set key value", Ok("OK".to_string())),
+    ];
+    for (line, expected_result) in tests_case {
+        println!("line: {}", line);
+        let result = command(&mut dbs, &factory, &mut selected, line);
+        assert_eq!(result, expected_result);
+    }
+    assert_eq!(
+        received(&mut receiver),
+        events(&[("__keyspace@0__:key", "set")])
+    );
+}
@@ -0,0 +1,281 @@
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rus_key_db::databases::Databases;
+use rus_key_db::output_buffer::{OutputBuffer, OutputBufferLimit, OutputBufferLimits};
+use rus_key_db::pubsub::Subscriber;
+use rus_key_factory::command_factory::CommandFactory;
+use rus_key_lib::cmd::handle_command;
+use rus_key_lib::net::handle_client;
+use tokio::sync::Mutex;
+
+const DEFAULT_LIMITS: &str = "normal 0 0 0 replica 256mb 64mb 60 pubsub 32mb 8mb 60";
+
+fn command(
+    dbs: &mut Databases,
+    factory: &CommandFactory,
+    line: &str,
+) -> Result<String, &'static str> {
+    let mut selected = 0;
+    let mut parts = line.split_ascii_whitespace();
+    handle_command(&mut parts, dbs, &mut selected, factory)
+}
+
+fn load_limits(dbs: &Databases, value: &str) {
+    let mut config = BTreeMap::new();
+    config.insert("client-output-buffer-limit".to_string(), value.to_string());
+    dbs.server().load_config(config);
+}
+
+#[test]
+fn test_output_buffer_limit() {
+    let limit = OutputBufferLimit {
+        hard: 100,
+        soft: 50,
+        soft_seconds: 10,
+    };
+    let start = Instant::now();
+    let mut soft_since = None;
+
+    assert!(!limit.exceeded(10, &mut soft_since, start));
+    assert_eq!(soft_since, None);
+    // over the soft limit for up to soft_seconds is allowed
+    assert!(!limit.exceeded(60, &mut soft_since, start));
+    assert_eq!(soft_since, Some(start));
+    assert!(!limit.exceeded(60, &mut soft_since, start + Duration::from_secs(10)));
+    assert!(limit.exceeded(60, &mut soft_since, start + Duration::from_secs(11)));
+    // going under the soft limit starts over
+    assert!(!limit.exceeded(10, &mut soft_since, start + Duration::from_secs(11)));
+    assert_eq!(soft_since, None);
+    assert!(!limit.exceeded(60, &mut soft_since, start + Duration::from_secs(12)));
+    // the hard limit is at once
+    assert!(limit.exceeded(100, &mut soft_since, start + Duration::from_secs(12)));
+
+    let unlimited = OutputBufferLimit::default();
+    assert!(!unlimited.exceeded(usize::MAX, &mut None, start));
+}
+
+#[test]
+fn test_parse_output_buffer_limits() {
+    let defaults = OutputBufferLimits::default();
+    assert_eq!(
+        defaults.to_string(),
+        "normal 0 0 0 replica 268435456 67108864 60 pubsub 33554432 8388608 60"
+    );
+    assert_eq!(defaults.parse(DEFAULT_LIMITS), Some(defaults));
+
+    // the classes not given are kept, slave is the former name of replica
+    let limits = defaults.parse("pubsub 1kb 512 5 slave 1mb 0 0").unwrap();
+    assert_eq!(
+        limits.to_string(),
+        "normal 0 0 0 replica 1048576 0 0 pubsub 1024 512 5"
+    );
+
+    for invalid in [
+        "",
+        "pubsub 1kb 512",
+        "client 0 0 0",
+        "pubsub 1xb 0 0",
+        "pubsub 0 0 -1",
+    ] {
+        println!("value: {}", invalid);
+        assert_eq!(defaults.parse(invalid), None);
+    }
+}
+
+#[test]
+fn test_output_buffer() {
+    let limit = OutputBufferLimit {
+        hard: 100,
+        soft: 0,
+        soft_seconds: 0,
+    };
+    let output = OutputBuffer::default();
+    assert!(output.queue(60, &limit));
+    output.written(60);
+    assert!(output.queue(60, &limit));
+    assert_eq!(output.pending(), 60);
+    assert!(!output.is_closed());
+    // reaching the hard limit closes the buffer for good
+    assert!(!output.queue(40, &limit));
+    assert!(output.is_closed());
+    assert_eq!(output.pending(), 60);
+    output.written(60);
+    assert!(!output.queue(1, &limit));
+}
+
+#[test]
+fn test_slow_subscriber() {
+    let mut dbs = Databases::new(2);
+    let factory = CommandFactory::new();
+    load_limits(&dbs, "pubsub 20 0 0");
+    let pubsub = &dbs.server().pubsub;
+    let (slow, mut slow_messages) = Subscriber::channel();
+    let (fast, mut fast_messages) = Subscriber::channel();
+    pubsub.subscribe(pubsub.client_id(), "news", &slow);
+    pubsub.subscribe(pubsub.client_id(), "news", &fast);
+
+    let tests_case: Vec<(&str, Result<String, &'static str>)> = vec![
+        // 9 bytes each, a channel of 4 and a payload of 5
+        ("publish news first", Ok("2".to_string())),
+        ("publish news again", Ok("2".to_string())),
+    ];
+    for (line, expected_result) in tests_case {
+        println!("line: {}", line);
+        let result = command(&mut dbs, &factory, line);
+        assert_eq!(result, expected_result);
+        // only the fast subscriber writes its messages
+        let message = fast_messages.try_recv().unwrap();
+        fast.output().written(message.size());
+    }
+    assert_eq!(slow.output().pending(), 18);
+
+    // the third message would take the slow subscriber to its hard limit
+    assert_eq!(
+        command(&mut dbs, &factory, "publish news third"),
+        Ok("1".to_string())
+    );
+    assert!(slow.output().is_closed());
+    assert!(!fast.output().is_closed());
+    assert_eq!(
+        command(&mut dbs, &factory, "pubsub numsub news"),
+        Ok("news 1".to_string())
+    );
+    // the messages queued before stay for the connection to drop
+    assert_eq!(slow_messages.try_recv().unwrap().payload, "first");
+    assert_eq!(slow_messages.try_recv().unwrap().payload, "again");
+    assert!(slow_messages.try_recv().is_err());
+}
+
+#[test]
+fn test_config_set_output_buffer_limit() {
+    let mut dbs = Databases::new(2);
+    let factory = CommandFactory::new();
+    load_limits(&dbs, DEFAULT_LIMITS);
+
+    let tests_case: Vec<(&str, Result<String, &'static str>)> = vec![
+        (
+            "config get client-output-buffer-limit",
+            Ok(format!("client-output-buffer-limit: {}", DEFAULT_LIMITS)),
+        ),
+        (
+            "config set client-output-buffer-limit pubsub 1MB 1kb 10",
+            Ok("OK".to_string()),
+        ),
+        (
+            "config get client-output-buffer-limit",
+            Ok("client-output-buffer-limit: normal 0 0 0 replica 268435456 67108864 60 pubsub 1048576 1024 10".to_string()),
+        ),
+        (
+            "config set client-output-buffer-limit pubsub 1mb",
+            Err("ERR Invalid client-output-buffer-limit, use class hard soft soft_seconds with class normal, replica or pubsub"),
+        ),
+    ];
+    for (line, expected_result) in tests_case {
+        println!("line: {}", line);
+        let result = command(&mut dbs, &factory, line);
+        assert_eq!(result, expected_result);
+    }
+}
+
+fn start_server() -> (u16, Arc<Mutex<Databases>>) {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.set_nonblocking(true).unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let dbs = Arc::new(Mutex::new(Databases::new(2)));
+    let server_dbs = Arc::clone(&dbs);
+    thread::spawn(move || {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async move {
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                tokio::spawn(handle_client(stream, Arc::clone(&server_dbs)));
+            }
+        });
+    });
+    (port, dbs)
+}
+
+fn connect(port: u16) -> TcpStream {
+    let stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    stream
+}
+
+fn request(stream: &mut TcpStream, line: &str) -> String {
+    stream
+        .write_all(format!("{}\r\n", line).as_bytes())
+        .unwrap();
+    let mut buf = [0; 512];
+    let n = stream.read(&mut buf).unwrap();
+    String::from_utf8_lossy(&buf[..n]).to_string()
+}
+
+#[test]
+fn test_disconnect_slow_subscriber() {
+    let (port, dbs) = start_server();
+    load_limits(&dbs.blocking_lock(), "pubsub 1mb 0 0");
+    let mut subscriber = BufReader::new(connect(port));
+    let mut publisher = connect(port);
+
+    subscriber
+        .get_mut()
+        .write_all(b"subscribe news\r\n")
+        .unwrap();
+    let mut line = String::new();
+    subscriber.read_line(&mut line).unwrap();
+    assert_eq!(line, "subscribe news 1\r\n");
+
+    // the subscriber reads nothing, once the socket buffers are full its messages pile up
+    let payload = "x".repeat(64 * 1024);
+    let mut receivers = String::new();
+    for _ in 0..1000 {
+        receivers = request(&mut publisher, &format!("publish news {}", payload));
+        if receivers == "0" {
+            break;
+        }
+    }
+    assert_eq!(receivers, "0");
+
+    // the connection is closed once the messages written before are read
+    let mut data = Vec::new();
+    subscriber.read_to_end(&mut data).unwrap();
+    let mut stats = String::new();
+    for _ in 0..100 {
+        stats = request(&mut publisher, "info stats");
+        if stats.ends_with("client_output_buffer_limit_disconnections: 1") {
+            break;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert!(stats.ends_with("client_output_buffer_limit_disconnections: 1"));
+    assert_eq!(request(&mut publisher, "pubsub numsub news"), "news 0");
+}
+
+#[test]
+fn test_disconnect_normal_client() {
+    let (port, dbs) = start_server();
+    load_limits(&dbs.blocking_lock(), "normal 1kb 0 0");
+    let mut client = connect(port);
+
+    let large = "x".repeat(2048);
+    assert_eq!(request(&mut client, &format!("set large {}", large)), "OK");
+    assert_eq!(request(&mut client, "strlen large"), "2048");
+    // a reply over the hard limit is not written, the client is disconnected
+    client.write_all(b"get large\r\n").unwrap();
+    let mut data = Vec::new();
+    client.read_to_end(&mut data).unwrap();
+    assert!(data.is_empty());
+
+    let mut other = connect(port);
+    assert!(
+        request(&mut other, "info stats").ends_with("client_output_buffer_limit_disconnections: 1")
+    );
+}
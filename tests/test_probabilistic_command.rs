@@ -0,0 +1,301 @@
+use probabilistic_commands::probabilistic::ProbabilisticCommand;
+use rus_key_db::db::Db;
+use rus_key_trait::command_trait::Command;
+use utils_commands::utils::UtilsCommand;
+
+fn probabilistic_command(db: &mut Db, command: &str, args: &str) -> Result<String, &'static str> {
+    let command = ProbabilisticCommand::new(command.to_string());
+    let mut parts = args.split_ascii_whitespace();
+    command.execute(&mut parts, db)
+}
+
+fn type_command(db: &mut Db, key: &str) -> String {
+    let command = UtilsCommand::new("type".to_string());
+    let mut parts = key.split_ascii_whitespace();
+    command.execute(&mut parts, db).unwrap()
+}
+
+fn assert_cases(db: &mut Db, tests_case: Vec<(&str, &str, Result<String, &'static str>)>) {
+    for (command, args, expected_result) in tests_case {
+        println!("command: {}, args: {}", command, args);
+        let result = probabilistic_command(db, command, args);
+        assert_eq!(result, expected_result);
+    }
+}
+
+#[test]
+fn test_bf_command() {
+    let mut db = Db::new();
+    assert_cases(
+        &mut db,
+        vec![
+            ("bf.reserve", "bf 0.001 1000", Ok("OK".to_string())),
+            ("bf.reserve", "bf 0.001 1000", Err("ERR item exists")),
+            (
+                "bf.reserve",
+                "bad 1 1000",
+                Err("ERR (0 < error rate range < 1)"),
+            ),
+            ("bf.reserve", "bad 0.01 0", Err("ERR bad capacity")),
+            ("bf.add", "bf apple", Ok("1".to_string())),
+            ("bf.add", "bf apple", Ok("0".to_string())),
+            ("bf.madd", "bf banana apple cherry", Ok("1 0 1".to_string())),
+            ("bf.exists", "bf banana", Ok("1".to_string())),
+            ("bf.exists", "missing banana", Ok("0".to_string())),
+            (
+                "bf.mexists",
+                "bf apple durian cherry",
+                Ok("1 0 1".to_string()),
+            ),
+            ("bf.info", "bf items", Ok("3".to_string())),
+            ("bf.info", "bf capacity", Ok("1000".to_string())),
+            ("bf.info", "missing", Err("ERR not found")),
+            // BF.ADD creates a filter with the default settings
+            ("bf.add", "auto item", Ok("1".to_string())),
+            ("bf.info", "auto expansion", Ok("2".to_string())),
+        ],
+    );
+    assert_eq!(type_command(&mut db, "bf"), "MBbloom--".to_string());
+}
+
+#[test]
+fn test_bf_scaling() {
+    let mut db = Db::new();
+    assert_cases(
+        &mut db,
+        vec![
+            (
+                "bf.reserve",
+                "scaling 0.01 100 EXPANSION 2",
+                Ok("OK".to_string()),
+            ),
+            (
+                "bf.reserve",
+                "fixed 0.01 10 NONSCALING",
+                Ok("OK".to_string()),
+            ),
+        ],
+    );
+
+    let mut added = 0;
+    for i in 0..1000 {
+        let result = probabilistic_command(&mut db, "bf.add", &format!("scaling url_{}", i));
+        if result.unwrap() == "1" {
+            added += 1;
+        }
+    }
+    // a few false positives are allowed
+    assert!(added > 980);
+    let filters = probabilistic_command(&mut db, "bf.info", "scaling filters").unwrap();
+    assert_eq!(filters, "4".to_string());
+    let capacity = probabilistic_command(&mut db, "bf.info", "scaling capacity").unwrap();
+    assert_eq!(capacity, "1500".to_string());
+
+    // every added item is still found
+    for i in 0..1000 {
+        let result = probabilistic_command(&mut db, "bf.exists", &format!("scaling url_{}", i));
+        assert_eq!(result.unwrap(), "1".to_string());
+    }
+
+    // a non scaling filter rejects items once full
+    let mut result = Ok("".to_string());
+    for i in 0..20 {
+        result = probabilistic_command(&mut db, "bf.add", &format!("fixed url_{}", i));
+        if result.is_err() {
+            break;
+        }
+    }
+    assert_eq!(result, Err("ERR non scaling filter is full"));
+}
+
+#[test]
+fn test_cf_command() {
+    let mut db = Db::new();
+    assert_cases(
+        &mut db,
+        vec![
+            ("cf.reserve", "cf 1000", Ok("OK".to_string())),
+            ("cf.reserve", "cf 1000", Err("ERR item exists")),
+            ("cf.reserve", "bad 0", Err("ERR bad capacity")),
+            ("cf.add", "cf apple", Ok("1".to_string())),
+            ("cf.add", "cf apple", Ok("1".to_string())),
+            ("cf.addnx", "cf apple", Ok("0".to_string())),
+            ("cf.addnx", "cf banana", Ok("1".to_string())),
+            ("cf.count", "cf apple", Ok("2".to_string())),
+            ("cf.exists", "cf banana", Ok("1".to_string())),
+            (
+                "cf.mexists",
+                "cf apple cherry banana",
+                Ok("1 0 1".to_string()),
+            ),
+            ("cf.del", "cf apple", Ok("1".to_string())),
+            ("cf.count", "cf apple", Ok("1".to_string())),
+            ("cf.del", "cf apple", Ok("1".to_string())),
+            ("cf.exists", "cf apple", Ok("0".to_string())),
+            ("cf.del", "cf apple", Ok("0".to_string())),
+            ("cf.del", "missing apple", Err("ERR not found")),
+            ("cf.count", "missing apple", Ok("0".to_string())),
+            (
+                "cf.add",
+                "cf",
+                Err("ERR wrong number of arguments for command"),
+            ),
+        ],
+    );
+    assert_eq!(type_command(&mut db, "cf"), "MBbloomCF".to_string());
+
+    // a bloom command on a cuckoo filter is a type error
+    let result = probabilistic_command(&mut db, "bf.add", "cf apple");
+    assert_eq!(
+        result,
+        Err("There is no such key, the key is expired, or the data type is incorrect")
+    );
+}
+
+#[test]
+fn test_cf_expansion() {
+    let mut db = Db::new();
+    assert_cases(
+        &mut db,
+        vec![
+            (
+                "cf.reserve",
+                "scaling 64 BUCKETSIZE 2 EXPANSION 2",
+                Ok("OK".to_string()),
+            ),
+            (
+                "cf.reserve",
+                "fixed 8 BUCKETSIZE 1 MAXITERATIONS 5 EXPANSION 0",
+                Ok("OK".to_string()),
+            ),
+        ],
+    );
+
+    for i in 0..500 {
+        let result = probabilistic_command(&mut db, "cf.add", &format!("scaling url_{}", i));
+        assert_eq!(result.unwrap(), "1".to_string());
+    }
+    for i in 0..500 {
+        let result = probabilistic_command(&mut db, "cf.exists", &format!("scaling url_{}", i));
+        assert_eq!(result.unwrap(), "1".to_string());
+    }
+    let info = probabilistic_command(&mut db, "cf.info", "scaling").unwrap();
+    assert!(info.contains("Number of items inserted: 500"));
+    assert!(!info.contains("Number of filters: 1 "));
+
+    // deleted items are gone while the others remain
+    for i in 0..250 {
+        let result = probabilistic_command(&mut db, "cf.del", &format!("scaling url_{}", i));
+        assert_eq!(result.unwrap(), "1".to_string());
+    }
+    for i in 250..500 {
+        let result = probabilistic_command(&mut db, "cf.exists", &format!("scaling url_{}", i));
+        assert_eq!(result.unwrap(), "1".to_string());
+    }
+
+    // a non scaling filter reports when it is full and keeps what it stored
+    let mut stored = Vec::new();
+    for i in 0..20 {
+        let item = format!("fixed url_{}", i);
+        match probabilistic_command(&mut db, "cf.add", &item) {
+            Ok(_) => stored.push(item),
+            Err(e) => {
+                assert_eq!(e, "ERR Filter is full");
+                break;
+            }
+        }
+    }
+    assert!(stored.len() < 20);
+    for item in stored {
+        let result = probabilistic_command(&mut db, "cf.exists", &item);
+        assert_eq!(result.unwrap(), "1".to_string());
+    }
+}
+
+#[test]
+fn test_cms_command() {
+    let mut db = Db::new();
+
+    let result = probabilistic_command(&mut db, "cms.initbydim", "dim 2000 5");
+    assert_eq!(result.unwrap(), "OK".to_string());
+    assert_eq!(type_command(&mut db, "dim"), "CMSk-TYPE".to_string());
+    let result = probabilistic_command(&mut db, "cms.initbydim", "dim 2000 5");
+    assert_eq!(result, Err("ERR CMS: key already exists"));
+
+    // 2 / 0.001 counters per row, ln(0.01) / ln(0.5) rows
+    let result = probabilistic_command(&mut db, "cms.initbyprob", "prob 0.001 0.01");
+    assert_eq!(result.unwrap(), "OK".to_string());
+    let result = probabilistic_command(&mut db, "cms.info", "prob");
+    assert_eq!(result.unwrap(), "width: 2000 depth: 7 count: 0".to_string());
+    let result = probabilistic_command(&mut db, "cms.initbyprob", "bad 1.5 0.01");
+    assert_eq!(result, Err("ERR CMS: invalid overestimation value"));
+
+    let result = probabilistic_command(&mut db, "cms.incrby", "dim foo 10 bar 42 foo 5");
+    assert_eq!(result.unwrap(), "10 42 15".to_string());
+    let result = probabilistic_command(&mut db, "cms.query", "dim foo bar baz");
+    assert_eq!(result.unwrap(), "15 42 0".to_string());
+    let result = probabilistic_command(&mut db, "cms.incrby", "dim foo");
+    assert_eq!(result, Err("ERR CMS: Cannot parse number"));
+    let result = probabilistic_command(&mut db, "cms.query", "missing foo");
+    assert_eq!(result, Err("ERR CMS: key does not exist"));
+
+    let result = probabilistic_command(&mut db, "cms.initbydim", "other 2000 5");
+    assert_eq!(result.unwrap(), "OK".to_string());
+    let result = probabilistic_command(&mut db, "cms.incrby", "other foo 1 baz 3");
+    assert_eq!(result.unwrap(), "1 3".to_string());
+    let result = probabilistic_command(&mut db, "cms.initbydim", "merged 2000 5");
+    assert_eq!(result.unwrap(), "OK".to_string());
+    let result = probabilistic_command(&mut db, "cms.merge", "merged 2 dim other WEIGHTS 1 2");
+    assert_eq!(result.unwrap(), "OK".to_string());
+    let result = probabilistic_command(&mut db, "cms.query", "merged foo bar baz");
+    assert_eq!(result.unwrap(), "17 42 6".to_string());
+    let result = probabilistic_command(&mut db, "cms.info", "merged");
+    assert_eq!(result.unwrap(), "width: 2000 depth: 5 count: 65".to_string());
+
+    let result = probabilistic_command(&mut db, "cms.merge", "merged 1 prob");
+    assert_eq!(result, Err("ERR CMS: width/depth is not equal"));
+    let result = probabilistic_command(&mut db, "cms.merge", "missing 1 dim");
+    assert_eq!(result, Err("ERR CMS: key does not exist"));
+}
+
+#[test]
+fn test_topk_command() {
+    let mut db = Db::new();
+
+    let result = probabilistic_command(&mut db, "topk.reserve", "top 2 50 4 0.9");
+    assert_eq!(result.unwrap(), "OK".to_string());
+    assert_eq!(type_command(&mut db, "top"), "TopK-TYPE".to_string());
+    let result = probabilistic_command(&mut db, "topk.reserve", "top 2");
+    assert_eq!(result, Err("ERR TopK: key already exists"));
+    let result = probabilistic_command(&mut db, "topk.reserve", "bad 2 50 4 1.5");
+    assert_eq!(
+        result,
+        Err("ERR TopK: invalid decay value. must be '<= 1' & '> 0'")
+    );
+
+    let result = probabilistic_command(&mut db, "topk.add", "top a b a");
+    assert_eq!(result.unwrap(), "nil nil nil".to_string());
+    let result = probabilistic_command(&mut db, "topk.list", "top WITHCOUNT");
+    assert_eq!(result.unwrap(), "a: 2 b: 1".to_string());
+
+    // a heavier item expels the lightest one
+    let result = probabilistic_command(&mut db, "topk.incrby", "top c 10");
+    assert_eq!(result.unwrap(), "b".to_string());
+    let result = probabilistic_command(&mut db, "topk.list", "top");
+    assert_eq!(result.unwrap(), "c a".to_string());
+    let result = probabilistic_command(&mut db, "topk.query", "top a b c");
+    assert_eq!(result.unwrap(), "1 0 1".to_string());
+
+    // heavy hitters stay in the list while many rare items pass through
+    let result = probabilistic_command(&mut db, "topk.reserve", "traffic 3");
+    assert_eq!(result.unwrap(), "OK".to_string());
+    for i in 0..200 {
+        let args = format!("traffic heavy1 heavy2 heavy3 rare_{}", i);
+        probabilistic_command(&mut db, "topk.add", &args).unwrap();
+    }
+    let result = probabilistic_command(&mut db, "topk.query", "traffic heavy1 heavy2 heavy3");
+    assert_eq!(result.unwrap(), "1 1 1".to_string());
+
+    let result = probabilistic_command(&mut db, "topk.add", "missing a");
+    assert_eq!(result, Err("ERR TopK: key does not exist"));
+}
@@ -0,0 +1,268 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use rus_key_db::databases::Databases;
+use rus_key_factory::command_factory::CommandFactory;
+use rus_key_lib::cmd::handle_command;
+use rus_key_lib::net::handle_client;
+use rus_key_lib::pubsub::{format_message, Subscriptions};
+use tokio::sync::Mutex;
+
+fn command(
+    dbs: &mut Databases,
+    factory: &CommandFactory,
+    line: &str,
+) -> Result<String, &'static str> {
+    let mut selected = 0;
+    let mut parts = line.split_ascii_whitespace();
+    handle_command(&mut parts, dbs, &mut selected, factory)
+}
+
+fn subscription(
+    subscriptions: &mut Subscriptions,
+    dbs: &Databases,
+    line: &str,
+) -> Result<Vec<String>, &'static str> {
+    let mut parts = line.split_ascii_whitespace();
+    let cmd = parts.next().unwrap();
+    subscriptions
+        .handle(cmd, &mut parts, &dbs.server().pubsub)
+        .unwrap()
+}
+
+#[test]
+fn test_subscribe_and_publish() {
+    let mut dbs = Databases::new(2);
+    let factory = CommandFactory::new();
+    let (mut subscriptions, mut messages) = Subscriptions::new(&dbs.server().pubsub);
+
+    let tests_case: Vec<(&str, Result<Vec<&str>, &'static str>)> = vec![
+        (
+            "subscribe news sport",
+            Ok(vec!["subscribe news 1", "subscribe sport 2"]),
+        ),
+        ("subscribe news", Ok(vec!["subscribe news 2"])),
+        ("psubscribe n*", Ok(vec!["psubscribe n* 3"])),
+        ("ssubscribe orders", Ok(vec!["ssubscribe orders 1"])),
+        (
+            "subscribe",
+            Err("ERR wrong number of arguments for command"),
+        ),
+    ];
+    for (line, expected_result) in tests_case {
+        println!("line: {}", line);
+        let result = subscription(&mut subscriptions, &dbs, line);
+        let expected_result =
+            expected_result.map(|replies| replies.iter().map(|r| r.to_string()).collect());
+        assert_eq!(result, expected_result);
+    }
+    assert!(subscriptions.is_subscribed());
+
+    let tests_case: Vec<(&str, Result<String, &'static str>)> = vec![
+        // the channel and the pattern subscription both receive it
+        ("publish news hello", Ok("2".to_string())),
+        ("publish sport goal", Ok("1".to_string())),
+        ("publish weather rain", Ok("0".to_string())),
+        ("publish sport \"match point\"", Ok("1".to_string())),
+        (
+            "publish sport match point",
+            Err("ERR wrong number of arguments for command"),
+        ),
+        // shard channels are not matched by patterns, and the reverse
+        ("spublish orders new", Ok("1".to_string())),
+        ("spublish news hello", Ok("0".to_string())),
+        (
+            "publish news",
+            Err("ERR wrong number of arguments for command"),
+        ),
+        ("pubsub channels", Ok("news sport".to_string())),
+        ("pubsub channels s*", Ok("sport".to_string())),
+        (
+            "pubsub numsub news weather",
+            Ok("news 1 weather 0".to_string()),
+        ),
+        ("pubsub numpat", Ok("1".to_string())),
+        ("pubsub shardchannels", Ok("orders".to_string())),
+        ("pubsub shardnumsub orders", Ok("orders 1".to_string())),
+        ("pubsub unknown", Err("ERR unknown subcommand")),
+    ];
+    for (line, expected_result) in tests_case {
+        println!("line: {}", line);
+        let result = command(&mut dbs, &factory, line);
+        assert_eq!(result, expected_result);
+    }
+
+    let mut received = Vec::new();
+    while let Ok(message) = messages.try_recv() {
+        received.push(format_message(&message));
+    }
+    assert_eq!(
+        received,
+        vec![
+            "message news hello",
+            "pmessage n* news hello",
+            "message sport goal",
+            "message sport match point",
+            "smessage orders new",
+        ]
+    );
+}
+
+#[test]
+fn test_unsubscribe() {
+    let mut dbs = Databases::new(2);
+    let factory = CommandFactory::new();
+    let (mut subscriptions, _messages) = Subscriptions::new(&dbs.server().pubsub);
+    subscription(&mut subscriptions, &dbs, "subscribe news sport").unwrap();
+    subscription(&mut subscriptions, &dbs, "psubscribe n*").unwrap();
+
+    let tests_case: Vec<(&str, Vec<&str>)> = vec![
+        (
+            "unsubscribe news weather",
+            vec!["unsubscribe news 2", "unsubscribe weather 2"],
+        ),
+        // without arguments from every channel of its kind
+        ("punsubscribe", vec!["punsubscribe n* 1"]),
+        ("punsubscribe", vec!["punsubscribe nil 1"]),
+        ("unsubscribe", vec!["unsubscribe sport 0"]),
+        ("sunsubscribe", vec!["sunsubscribe nil 0"]),
+    ];
+    for (line, expected) in tests_case {
+        println!("line: {}", line);
+        let result = subscription(&mut subscriptions, &dbs, line).unwrap();
+        assert_eq!(result, expected);
+    }
+    assert!(!subscriptions.is_subscribed());
+    assert_eq!(
+        command(&mut dbs, &factory, "pubsub channels"),
+        Ok("".to_string())
+    );
+    assert_eq!(
+        command(&mut dbs, &factory, "publish news hello"),
+        Ok("0".to_string())
+    );
+
+    // a closed connection leaves no subscription behind
+    subscription(&mut subscriptions, &dbs, "subscribe news").unwrap();
+    subscription(&mut subscriptions, &dbs, "ssubscribe orders").unwrap();
+    subscriptions.unsubscribe_all(&dbs.server().pubsub);
+    assert_eq!(
+        command(&mut dbs, &factory, "pubsub numsub news"),
+        Ok("news 0".to_string())
+    );
+    assert_eq!(
+        command(&mut dbs, &factory, "pubsub shardchannels"),
+        Ok("".to_string())
+    );
+}
+
+fn start_server() -> (u16, Arc<Mutex<Databases>>) {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.set_nonblocking(true).unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let dbs = Arc::new(Mutex::new(Databases::new(2)));
+    let server_dbs = Arc::clone(&dbs);
+    thread::spawn(move || {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async move {
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                tokio::spawn(handle_client(stream, Arc::clone(&server_dbs)));
+            }
+        });
+    });
+    (port, dbs)
+}
+
+fn connect(port: u16) -> TcpStream {
+    let stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    stream
+}
+
+// a command outside of push mode, whose reply has no line ending
+fn request(stream: &mut TcpStream, line: &str) -> String {
+    stream
+        .write_all(format!("{}\r\n", line).as_bytes())
+        .unwrap();
+    let mut buf = [0; 512];
+    let n = stream.read(&mut buf).unwrap();
+    String::from_utf8_lossy(&buf[..n]).to_string()
+}
+
+fn read_line(reader: &mut BufReader<TcpStream>) -> String {
+    let mut line = String::new();
+    reader.read_line(&mut line).unwrap();
+    line.trim_end().to_string()
+}
+
+#[test]
+fn test_push_mode() {
+    let (port, _dbs) = start_server();
+    let mut subscriber = BufReader::new(connect(port));
+    let mut publisher = connect(port);
+
+    assert_eq!(request(&mut publisher, "set key value"), "OK");
+    subscriber
+        .get_mut()
+        .write_all(b"subscribe news\r\npsubscribe __keyspace*\r\n")
+        .unwrap();
+    assert_eq!(read_line(&mut subscriber), "subscribe news 1");
+    assert_eq!(read_line(&mut subscriber), "psubscribe __keyspace* 2");
+
+    // messages arrive without a request
+    assert_eq!(request(&mut publisher, "publish news hello"), "1");
+    assert_eq!(read_line(&mut subscriber), "message news hello");
+    assert_eq!(request(&mut publisher, "publish news again"), "1");
+    assert_eq!(read_line(&mut subscriber), "message news again");
+
+    // only the subscription commands and PING run in push mode
+    subscriber.get_mut().write_all(b"get key\r\n").unwrap();
+    assert_eq!(
+        read_line(&mut subscriber),
+        "ERR Can't execute 'get': only (P|S)SUBSCRIBE / (P|S)UNSUBSCRIBE / PING are allowed in this context"
+    );
+    subscriber.get_mut().write_all(b"ping\r\n").unwrap();
+    assert_eq!(read_line(&mut subscriber), "PONG");
+
+    // back to requests and replies once unsubscribed from everything
+    subscriber
+        .get_mut()
+        .write_all(b"unsubscribe\r\npunsubscribe\r\n")
+        .unwrap();
+    assert_eq!(read_line(&mut subscriber), "unsubscribe news 1");
+    assert_eq!(read_line(&mut subscriber), "punsubscribe __keyspace* 0");
+    assert_eq!(request(&mut publisher, "publish news hello"), "0");
+    assert_eq!(request(subscriber.get_mut(), "get key"), "value");
+}
+
+#[test]
+fn test_closed_subscriber() {
+    let (port, _dbs) = start_server();
+    let mut subscriber = BufReader::new(connect(port));
+    let mut publisher = connect(port);
+
+    subscriber
+        .get_mut()
+        .write_all(b"subscribe news\r\n")
+        .unwrap();
+    assert_eq!(read_line(&mut subscriber), "subscribe news 1");
+    assert_eq!(request(&mut publisher, "pubsub numsub news"), "news 1");
+    drop(subscriber);
+    // the server notices the closed connection on its next read
+    let mut numsub = String::new();
+    for _ in 0..100 {
+        numsub = request(&mut publisher, "pubsub numsub news");
+        if numsub == "news 0" {
+            break;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(numsub, "news 0");
+}
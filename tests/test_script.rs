@@ -0,0 +1,376 @@
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rus_key_db::databases::Databases;
+use rus_key_db::scripts::sha1_hex;
+use rus_key_factory::command_factory::CommandFactory;
+use rus_key_lib::cmd::handle_command;
+use rus_key_lib::net::handle_client;
+use tokio::sync::Mutex;
+
+fn command(
+    dbs: &mut Databases,
+    factory: &CommandFactory,
+    line: &str,
+) -> Result<String, &'static str> {
+    let mut selected = 0;
+    let mut parts = line.split_ascii_whitespace();
+    handle_command(&mut parts, dbs, &mut selected, factory)
+}
+
+fn set_time_limit(dbs: &Databases, limit: &str) {
+    let mut config = BTreeMap::new();
+    config.insert("lua-time-limit".to_string(), limit.to_string());
+    dbs.server().load_config(config);
+}
+
+#[test]
+fn test_eval_command() {
+    let mut dbs = Databases::new(2);
+    let factory = CommandFactory::new();
+
+    let tests_case: Vec<(&str, Result<String, &'static str>)> = vec![
+        ("eval \"return 1\" 0", Ok("1".to_string())),
+        (
+            "eval \"return {KEYS[1], KEYS[2], ARGV[1]}\" 2 first second third",
+            Ok("first second third".to_string()),
+        ),
+        (
+            "eval \"return redis.call('set', KEYS[1], ARGV[1])\" 1 key \"a value\"",
+            Ok("OK".to_string()),
+        ),
+        (
+            "eval \"return redis.call('get', KEYS[1])\" 1 key",
+            Ok("a value".to_string()),
+        ),
+        // arguments with quotation marks and spaces reach the command as they are
+        (
+            "eval \"local q = string.char(34) redis.call('set', KEYS[1], q .. 'a b' .. q .. ' c' .. string.char(92)) return redis.call('get', KEYS[1])\" 1 quoted",
+            Ok("\"a b\" c\\".to_string()),
+        ),
+        (
+            "eval \"redis.call('set', KEYS[1], string.char(34) .. 'x') return redis.call('get', KEYS[1])\" 1 quoted",
+            Ok("\"x".to_string()),
+        ),
+        // integer replies are Lua integers, nil is false
+        (
+            "eval \"local n = redis.call('incr', KEYS[1]) return n + 1\" 1 counter",
+            Ok("2".to_string()),
+        ),
+        (
+            "eval \"return redis.call('get', KEYS[1]) == false\" 1 missing",
+            Ok("1".to_string()),
+        ),
+        // values stay strings, even numbers
+        (
+            "eval \"return type(redis.call('get', KEYS[1]))\" 1 counter",
+            Ok("string".to_string()),
+        ),
+        ("eval \"return 3.9\" 0", Ok("3".to_string())),
+        ("eval \"return nil\" 0", Ok("nil".to_string())),
+        (
+            "eval \"return redis.status_reply('DONE')\" 0",
+            Ok("DONE".to_string()),
+        ),
+        (
+            "eval \"return redis.sha1hex('')\" 0",
+            Ok("da39a3ee5e6b4b0d3255bfef95601890afd80709".to_string()),
+        ),
+        (
+            "eval \"return 1\" 2 key",
+            Err("ERR Number of keys can't be greater than number of args"),
+        ),
+        (
+            "eval \"return 1\" -1",
+            Err("ERR Number of keys can't be negative"),
+        ),
+        (
+            "eval \"return 1\"",
+            Err("ERR wrong number of arguments for command"),
+        ),
+    ];
+    for (line, expected_result) in tests_case {
+        println!("line: {}", line);
+        let result = command(&mut dbs, &factory, line);
+        assert_eq!(result, expected_result);
+    }
+}
+
+#[test]
+fn test_eval_errors() {
+    let mut dbs = Databases::new(2);
+    let factory = CommandFactory::new();
+
+    let tests_case: Vec<(&str, Result<String, &'static str>)> = vec![
+        // redis.call raises the error of the command, redis.pcall returns it
+        (
+            "eval \"return redis.call('rename', KEYS[1], KEYS[2])\" 2 missing other",
+            Err("No such key"),
+        ),
+        (
+            "eval \"return redis.pcall('rename', KEYS[1], KEYS[2])['err']\" 2 missing other",
+            Ok("No such key".to_string()),
+        ),
+        (
+            "eval \"return redis.call('unknown')\" 0",
+            Err("ERR Unknown Redis command called from script"),
+        ),
+        (
+            "eval \"return redis.call('config', 'get', 'maxmemory')\" 0",
+            Err("ERR Unknown Redis command called from script"),
+        ),
+        (
+            "eval \"return redis.call()\" 0",
+            Err("ERR Please specify at least one argument for this redis lib call"),
+        ),
+        // an error table replies the error of the command it came from
+        (
+            "eval \"return redis.pcall('rename', KEYS[1], KEYS[2])\" 2 missing other",
+            Err("No such key"),
+        ),
+        // other messages are not kept, the reply is generic
+        (
+            "eval \"return redis.error_reply('ERR limited')\" 0",
+            Err("ERR Error returned by script"),
+        ),
+        (
+            "eval \"return {err = ARGV[1]}\" 0 \"ERR any message\"",
+            Err("ERR Error returned by script"),
+        ),
+        ("eval \"return (\" 0", Err("ERR Error compiling script")),
+        ("eval \"error('boom')\" 0", Err("ERR Error running script")),
+        // no access to files or the system
+        (
+            "eval \"return os.time()\" 0",
+            Err("ERR Error running script"),
+        ),
+        (
+            "eval \"return io.open('/etc/passwd')\" 0",
+            Err("ERR Error running script"),
+        ),
+        (
+            "eval \"return dofile('/etc/passwd')\" 0",
+            Err("ERR Error running script"),
+        ),
+    ];
+    for (line, expected_result) in tests_case {
+        println!("line: {}", line);
+        let result = command(&mut dbs, &factory, line);
+        assert_eq!(result, expected_result);
+    }
+}
+
+#[test]
+fn test_eval_ro_command() {
+    let mut dbs = Databases::new(2);
+    let factory = CommandFactory::new();
+    command(&mut dbs, &factory, "set key value").unwrap();
+
+    let tests_case: Vec<(&str, Result<String, &'static str>)> = vec![
+        (
+            "eval_ro \"return redis.call('get', KEYS[1])\" 1 key",
+            Ok("value".to_string()),
+        ),
+        (
+            "eval_ro \"return redis.call('set', KEYS[1], 'other')\" 1 key",
+            Err("ERR Write commands are not allowed from read-only scripts"),
+        ),
+        ("get key", Ok("value".to_string())),
+    ];
+    for (line, expected_result) in tests_case {
+        println!("line: {}", line);
+        let result = command(&mut dbs, &factory, line);
+        assert_eq!(result, expected_result);
+    }
+}
+
+#[test]
+fn test_evalsha_and_script_command() {
+    let mut dbs = Databases::new(2);
+    let factory = CommandFactory::new();
+    let script = "return redis.call('incrby', KEYS[1], ARGV[1])";
+    let sha = sha1_hex(script);
+    let evaluated = sha1_hex("return 1");
+    command(&mut dbs, &factory, "set counter 0").unwrap();
+
+    let tests_case: Vec<(String, Result<String, &'static str>)> = vec![
+        (
+            format!("evalsha {} 1 counter 5", sha),
+            Err("NOSCRIPT No matching script. Please use EVAL."),
+        ),
+        (format!("script load \"{}\"", script), Ok(sha.clone())),
+        (format!("evalsha {} 1 counter 5", sha), Ok("5".to_string())),
+        (
+            format!("evalsha {} 1 counter 5", sha.to_uppercase()),
+            Ok("10".to_string()),
+        ),
+        (
+            format!("evalsha_ro {} 1 counter 5", sha),
+            Err("ERR Write commands are not allowed from read-only scripts"),
+        ),
+        // EVAL caches its script too
+        ("eval \"return 1\" 0".to_string(), Ok("1".to_string())),
+        (
+            format!("script exists {} {} {}", sha, evaluated, "0".repeat(40)),
+            Ok("1 1 0".to_string()),
+        ),
+        ("script flush".to_string(), Ok("OK".to_string())),
+        (format!("script exists {}", sha), Ok("0".to_string())),
+        ("script flush async".to_string(), Ok("OK".to_string())),
+        (
+            "script kill".to_string(),
+            Err("NOTBUSY No scripts in execution right now."),
+        ),
+        ("script unknown".to_string(), Err("ERR unknown subcommand")),
+    ];
+    for (line, expected_result) in tests_case {
+        println!("line: {}", line);
+        let result = command(&mut dbs, &factory, &line);
+        assert_eq!(result, expected_result);
+    }
+}
+
+#[test]
+fn test_script_time_limit() {
+    let mut dbs = Databases::new(2);
+    let factory = CommandFactory::new();
+    set_time_limit(&dbs, "50");
+
+    let start = Instant::now();
+    assert_eq!(
+        command(&mut dbs, &factory, "eval \"while true do end\" 0"),
+        Err("ERR Script killed after running longer than lua-time-limit")
+    );
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert_eq!(
+        command(&mut dbs, &factory, "script kill"),
+        Err("NOTBUSY No scripts in execution right now.")
+    );
+
+    // a script which wrote runs to its end
+    set_time_limit(&dbs, "1");
+    assert_eq!(
+        command(
+            &mut dbs,
+            &factory,
+            "eval \"redis.call('set', KEYS[1], 'before') local n = 0 while n < 1e6 do n = n + 1 end redis.call('set', KEYS[1], 'after') return n\" 1 key"
+        ),
+        Ok("1000000".to_string())
+    );
+    assert_eq!(
+        command(&mut dbs, &factory, "get key"),
+        Ok("after".to_string())
+    );
+}
+
+fn start_server() -> (u16, Arc<Mutex<Databases>>) {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.set_nonblocking(true).unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let dbs = Arc::new(Mutex::new(Databases::new(2)));
+    let server_dbs = Arc::clone(&dbs);
+    thread::spawn(move || {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async move {
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                tokio::spawn(handle_client(stream, Arc::clone(&server_dbs)));
+            }
+        });
+    });
+    (port, dbs)
+}
+
+fn connect(port: u16) -> TcpStream {
+    let stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(10)))
+        .unwrap();
+    stream
+}
+
+fn request(stream: &mut TcpStream, line: &str) -> String {
+    stream
+        .write_all(format!("{}\r\n", line).as_bytes())
+        .unwrap();
+    let mut buf = [0; 512];
+    let n = stream.read(&mut buf).unwrap();
+    String::from_utf8_lossy(&buf[..n]).to_string()
+}
+
+// sends SCRIPT KILL until a script runs, returns its reply
+fn kill_script(other: &mut TcpStream) -> String {
+    let mut reply = String::new();
+    for _ in 0..200 {
+        reply = request(other, "script kill");
+        if !reply.starts_with("NOTBUSY") {
+            break;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    reply
+}
+
+#[test]
+fn test_script_kill() {
+    let (port, dbs) = start_server();
+    set_time_limit(&dbs.blocking_lock(), "0");
+    let mut client = connect(port);
+    let mut other = connect(port);
+    // a connection starts by locking the databases once, so it is opened before the script
+    assert_eq!(request(&mut other, "ping"), "PONG");
+
+    client
+        .write_all(b"eval \"while true do end\" 0\r\n")
+        .unwrap();
+    assert_eq!(kill_script(&mut other), "OK");
+    let mut buf = [0; 512];
+    let n = client.read(&mut buf).unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&buf[..n]),
+        "ERR Script killed by user with SCRIPT KILL..."
+    );
+    assert_eq!(request(&mut other, "ping"), "PONG");
+
+    // a script which wrote is not killed and outlives the time limit, the other
+    // connections are told it is busy
+    set_time_limit(&dbs.blocking_lock(), "100");
+    client
+        .write_all(b"eval \"redis.call('set', KEYS[1], 'value') local n = 0 while n < 3e7 do n = n + 1 end return n\" 1 key\r\n")
+        .unwrap();
+    // killed before its first command, it would stop
+    thread::sleep(Duration::from_millis(50));
+    assert!(kill_script(&mut other).starts_with("UNKILLABLE"));
+    // a command sent before the limit would wait for the script
+    thread::sleep(Duration::from_millis(300));
+    assert_eq!(
+        request(&mut other, "get key"),
+        "BUSY RusKey is busy running a script. You can only call SCRIPT KILL."
+    );
+    assert!(request(&mut other, "script kill").starts_with("UNKILLABLE"));
+    let n = client.read(&mut buf).unwrap();
+    assert_eq!(String::from_utf8_lossy(&buf[..n]), "30000000");
+    assert_eq!(request(&mut other, "get key"), "value");
+}
+
+#[test]
+fn test_script_in_transaction() {
+    let (port, _dbs) = start_server();
+    let mut client = connect(port);
+
+    assert_eq!(request(&mut client, "multi"), "OK");
+    assert_eq!(
+        request(
+            &mut client,
+            "eval \"return redis.call('incr', KEYS[1])\" 1 counter"
+        ),
+        "QUEUED"
+    );
+    assert_eq!(request(&mut client, "incr counter"), "QUEUED");
+    assert_eq!(request(&mut client, "exec"), "1\r\n2\r\n");
+}
@@ -0,0 +1,177 @@
+use expired_commands::expired::ExpiredCommand;
+use hashmap_commands::hashmap::HashMapCommand;
+use rus_key_db::db::Db;
+use rus_key_trait::command_trait::Command;
+use search_commands::search::SearchCommand;
+use utils_commands::utils::UtilsCommand;
+
+fn search_command(db: &mut Db, command: &str, args: &str) -> Result<String, &'static str> {
+    let command = SearchCommand::new(command.to_string());
+    let mut parts = args.split_ascii_whitespace();
+    command.execute(&mut parts, db)
+}
+
+fn hset(db: &mut Db, args: &str) {
+    let command = HashMapCommand::new("hset".to_string());
+    let mut parts = args.split_ascii_whitespace();
+    command.execute(&mut parts, db).unwrap();
+}
+
+fn add_users(db: &mut Db) {
+    hset(
+        db,
+        "user:1 name Alice age 31 city Paris,Lyon bio loves_rust_and_coffee",
+    );
+    hset(db, "user:2 name Bob age 25 city Berlin bio coffee_addict");
+    hset(db, "user:3 name Carol age 42 city Paris bio tea_drinker");
+    hset(db, "order:1 name Alice age 99");
+    let result = search_command(
+        db,
+        "ft.create",
+        "users ON HASH PREFIX 1 user: SCHEMA name TEXT SORTABLE age NUMERIC city TAG bio TEXT",
+    );
+    assert_eq!(result.unwrap(), "OK".to_string());
+}
+
+#[test]
+fn test_ft_create_command() {
+    let mut db = Db::new();
+    add_users(&mut db);
+
+    // existing hashes are indexed when the index is created
+    let result = search_command(&mut db, "ft.info", "users");
+    assert_eq!(
+        result.unwrap(),
+        "index_name: users prefixes: user: attributes: name TEXT,age NUMERIC,city TAG,bio TEXT num_docs: 3"
+            .to_string()
+    );
+
+    let result = search_command(&mut db, "ft.create", "users SCHEMA name TEXT");
+    assert_eq!(result, Err("ERR Index already exists"));
+    let result = search_command(&mut db, "ft.create", "bad SCHEMA name VECTOR");
+    assert_eq!(result, Err("ERR Invalid field type"));
+    let result = search_command(&mut db, "ft.create", "bad PREFIX 1 user:");
+    assert_eq!(result, Err("ERR Fields arguments are missing"));
+
+    let result = search_command(&mut db, "ft._list", "");
+    assert_eq!(result.unwrap(), "users".to_string());
+    let result = search_command(&mut db, "ft.dropindex", "users");
+    assert_eq!(result.unwrap(), "OK".to_string());
+    let result = search_command(&mut db, "ft.search", "users *");
+    assert_eq!(result, Err("ERR Unknown Index name"));
+}
+
+#[test]
+fn test_ft_search_command() {
+    let mut db = Db::new();
+    add_users(&mut db);
+
+    let tests_case: Vec<(&str, Result<String, &'static str>)> = vec![
+        (
+            "users * NOCONTENT",
+            Ok("3 user:1 user:2 user:3".to_string()),
+        ),
+        (
+            "users @age:[30 50] NOCONTENT",
+            Ok("2 user:1 user:3".to_string()),
+        ),
+        (
+            "users @age:[(31 +inf] NOCONTENT",
+            Ok("1 user:3".to_string()),
+        ),
+        (
+            "users @city:{paris} NOCONTENT",
+            Ok("2 user:1 user:3".to_string()),
+        ),
+        (
+            "users @city:{Lyon|Berlin} NOCONTENT",
+            Ok("2 user:1 user:2".to_string()),
+        ),
+        ("users coffee NOCONTENT", Ok("2 user:1 user:2".to_string())),
+        ("users @name:alice NOCONTENT", Ok("1 user:1".to_string())),
+        ("users @name:coffee NOCONTENT", Ok("0".to_string())),
+        ("users @name:car* NOCONTENT", Ok("1 user:3".to_string())),
+        (
+            "users coffee -@city:{berlin} NOCONTENT",
+            Ok("1 user:1".to_string()),
+        ),
+        (
+            "users @city:{paris} @age:[-inf 40]",
+            Ok(
+                "1 user:1: [age: 31 bio: loves_rust_and_coffee city: Paris,Lyon name: Alice]"
+                    .to_string(),
+            ),
+        ),
+        (
+            "users * SORTBY age DESC NOCONTENT",
+            Ok("3 user:3 user:1 user:2".to_string()),
+        ),
+        (
+            "users * SORTBY name LIMIT 1 1 NOCONTENT",
+            Ok("3 user:2".to_string()),
+        ),
+        ("users @missing:foo", Err("ERR Unknown field")),
+        ("users @age:foo", Err("ERR Syntax error in query")),
+        (
+            "users @age:[1 x]",
+            Err("ERR Bad lower range or upper range"),
+        ),
+        (
+            "users * LIMIT 1",
+            Err("ERR LIMIT requires two non negative integers"),
+        ),
+    ];
+
+    for (args, expected_result) in tests_case {
+        println!("args: {}", args);
+        let result = search_command(&mut db, "ft.search", args);
+        assert_eq!(result, expected_result);
+    }
+}
+
+#[test]
+fn test_ft_index_maintenance() {
+    let mut db = Db::new();
+    add_users(&mut db);
+
+    // HSET updates the document
+    hset(&mut db, "user:2 city Paris");
+    let result = search_command(&mut db, "ft.search", "users @city:{paris} NOCONTENT");
+    assert_eq!(result.unwrap(), "3 user:1 user:2 user:3".to_string());
+    let result = search_command(&mut db, "ft.search", "users @city:{berlin} NOCONTENT");
+    assert_eq!(result.unwrap(), "0".to_string());
+
+    // HMSET replaces it
+    let command = HashMapCommand::new("hmset".to_string());
+    let mut parts = "user:3 name Carol age 18".split_ascii_whitespace();
+    command.execute(&mut parts, &mut db).unwrap();
+    let result = search_command(&mut db, "ft.search", "users @age:[0 20] NOCONTENT");
+    assert_eq!(result.unwrap(), "1 user:3".to_string());
+    let result = search_command(&mut db, "ft.search", "users tea NOCONTENT");
+    assert_eq!(result.unwrap(), "0".to_string());
+
+    // new keys are indexed, keys outside the prefix are not
+    hset(&mut db, "user:4 name Dave age 20");
+    hset(&mut db, "admin:1 name Dave age 20");
+    let result = search_command(&mut db, "ft.search", "users dave NOCONTENT");
+    assert_eq!(result.unwrap(), "1 user:4".to_string());
+
+    // DEL removes the document
+    let command = UtilsCommand::new("del".to_string());
+    let mut parts = "user:4".split_ascii_whitespace();
+    command.execute(&mut parts, &mut db).unwrap();
+    let result = search_command(&mut db, "ft.search", "users dave NOCONTENT");
+    assert_eq!(result.unwrap(), "0".to_string());
+
+    // so does expiry
+    let expire_at = chrono::Utc::now().timestamp_millis() + 50;
+    let command = ExpiredCommand::new("pexpireat".to_string());
+    let args = format!("user:1 {}", expire_at);
+    let mut parts = args.split_ascii_whitespace();
+    command.execute(&mut parts, &mut db).unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    let result = search_command(&mut db, "ft.search", "users * NOCONTENT");
+    assert_eq!(result.unwrap(), "2 user:2 user:3".to_string());
+    let result = search_command(&mut db, "ft.info", "users");
+    assert!(result.unwrap().ends_with("num_docs: 2"));
+}
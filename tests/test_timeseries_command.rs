@@ -0,0 +1,283 @@
+use rus_key_db::db::Db;
+use rus_key_trait::command_trait::Command;
+use timeseries_commands::timeseries::TimeSeriesCommand;
+
+fn ts_command(db: &mut Db, command: &str, args: &str) -> Result<String, &'static str> {
+    let command = TimeSeriesCommand::new(command.to_string());
+    let mut parts = args.split_ascii_whitespace();
+    command.execute(&mut parts, db)
+}
+
+#[test]
+fn test_ts_create_and_add_command() {
+    let mut db = Db::new();
+
+    let result = ts_command(
+        &mut db,
+        "ts.create",
+        "temp RETENTION 100 LABELS sensor 1 room kitchen",
+    );
+    assert_eq!(result.unwrap(), "OK".to_string());
+    let result = ts_command(&mut db, "ts.create", "temp");
+    assert_eq!(result, Err("ERR TSDB: key already exists"));
+    let result = ts_command(&mut db, "ts.create", "bad DUPLICATE_POLICY often");
+    assert_eq!(result, Err("ERR TSDB: Unknown DUPLICATE_POLICY"));
+
+    let result = ts_command(&mut db, "ts.add", "temp 1000 20.5");
+    assert_eq!(result.unwrap(), "1000".to_string());
+    let result = ts_command(&mut db, "ts.add", "temp 1000 21");
+    assert_eq!(
+        result,
+        Err("ERR TSDB: Error at upsert, update is not supported when DUPLICATE_POLICY is set to BLOCK mode")
+    );
+    let result = ts_command(&mut db, "ts.add", "temp 1000 21 ON_DUPLICATE max");
+    assert_eq!(result.unwrap(), "1000".to_string());
+    let result = ts_command(&mut db, "ts.get", "temp");
+    assert_eq!(result.unwrap(), "1000: 21".to_string());
+
+    // samples older than the retention window are dropped or rejected
+    let result = ts_command(&mut db, "ts.add", "temp 1150 22");
+    assert_eq!(result.unwrap(), "1150".to_string());
+    let result = ts_command(&mut db, "ts.range", "temp - +");
+    assert_eq!(result.unwrap(), "1150: 22".to_string());
+    let result = ts_command(&mut db, "ts.add", "temp 1000 20");
+    assert_eq!(result, Err("ERR TSDB: Timestamp is older than retention"));
+
+    // TS.ADD creates missing series
+    let result = ts_command(
+        &mut db,
+        "ts.add",
+        "hum 10 40 DUPLICATE_POLICY sum LABELS room kitchen",
+    );
+    assert_eq!(result.unwrap(), "10".to_string());
+    let result = ts_command(&mut db, "ts.add", "hum 10 2");
+    assert_eq!(result.unwrap(), "10".to_string());
+    let result = ts_command(&mut db, "ts.get", "hum");
+    assert_eq!(result.unwrap(), "10: 42".to_string());
+
+    let result = ts_command(
+        &mut db,
+        "ts.madd",
+        "hum 20 1 temp 1200 23 missing 10 1 hum abc 1",
+    );
+    assert_eq!(
+        result.unwrap(),
+        "20 1200 ERR TSDB: the key does not exist ERR TSDB: invalid timestamp".to_string()
+    );
+
+    let result = ts_command(&mut db, "ts.info", "temp");
+    assert_eq!(
+        result.unwrap(),
+        "totalSamples: 2 firstTimestamp: 1150 lastTimestamp: 1200 retentionTime: 100 duplicatePolicy: block labels: sensor=1,room=kitchen sourceKey: nil rules: ".to_string()
+    );
+}
+
+#[test]
+fn test_ts_incrby_command() {
+    let mut db = Db::new();
+
+    let result = ts_command(&mut db, "ts.incrby", "counter 5 TIMESTAMP 100");
+    assert_eq!(result.unwrap(), "100".to_string());
+    let result = ts_command(&mut db, "ts.incrby", "counter 2.5 TIMESTAMP 100");
+    assert_eq!(result.unwrap(), "100".to_string());
+    let result = ts_command(&mut db, "ts.incrby", "counter 1 TIMESTAMP 200");
+    assert_eq!(result.unwrap(), "200".to_string());
+    let result = ts_command(&mut db, "ts.range", "counter - +");
+    assert_eq!(result.unwrap(), "100: 7.5 200: 8.5".to_string());
+
+    let result = ts_command(&mut db, "ts.incrby", "counter 1 TIMESTAMP 50");
+    assert_eq!(
+        result,
+        Err("ERR TSDB: timestamp must be equal to or higher than the maximum existing timestamp")
+    );
+    let result = ts_command(&mut db, "ts.incrby", "counter abc");
+    assert_eq!(result, Err("ERR TSDB: invalid value"));
+}
+
+#[test]
+fn test_ts_range_command() {
+    let mut db = Db::new();
+    for (timestamp, value) in [(0, 1), (5, 3), (10, 5), (15, 2), (20, 8), (31, 4)] {
+        let args = format!("cpu {} {}", timestamp, value);
+        ts_command(&mut db, "ts.add", &args).unwrap();
+    }
+
+    let tests_case: Vec<(&str, &str, Result<String, &'static str>)> = vec![
+        (
+            "ts.range",
+            "cpu 5 20",
+            Ok("5: 3 10: 5 15: 2 20: 8".to_string()),
+        ),
+        ("ts.range", "cpu - + COUNT 2", Ok("0: 1 5: 3".to_string())),
+        (
+            "ts.revrange",
+            "cpu - + COUNT 2",
+            Ok("31: 4 20: 8".to_string()),
+        ),
+        (
+            "ts.range",
+            "cpu - + AGGREGATION avg 10",
+            Ok("0: 2 10: 3.5 20: 8 30: 4".to_string()),
+        ),
+        (
+            "ts.range",
+            "cpu - + AGGREGATION sum 10",
+            Ok("0: 4 10: 7 20: 8 30: 4".to_string()),
+        ),
+        (
+            "ts.range",
+            "cpu - + AGGREGATION min 20",
+            Ok("0: 1 20: 4".to_string()),
+        ),
+        (
+            "ts.range",
+            "cpu - + AGGREGATION max 20",
+            Ok("0: 5 20: 8".to_string()),
+        ),
+        (
+            "ts.range",
+            "cpu - + AGGREGATION count 20",
+            Ok("0: 4 20: 2".to_string()),
+        ),
+        (
+            "ts.revrange",
+            "cpu - + AGGREGATION last 20 COUNT 1",
+            Ok("20: 4".to_string()),
+        ),
+        ("ts.range", "cpu 50 +", Ok("".to_string())),
+        (
+            "ts.range",
+            "cpu - + AGGREGATION median 10",
+            Err("ERR TSDB: Unknown aggregation type"),
+        ),
+        (
+            "ts.range",
+            "cpu - + AGGREGATION avg 0",
+            Err("ERR TSDB: bucketDuration must be greater than zero"),
+        ),
+        (
+            "ts.range",
+            "missing - +",
+            Err("ERR TSDB: the key does not exist"),
+        ),
+    ];
+
+    for (command, args, expected_result) in tests_case {
+        println!("{} {}", command, args);
+        let result = ts_command(&mut db, command, args);
+        assert_eq!(result, expected_result);
+    }
+}
+
+#[test]
+fn test_ts_mrange_command() {
+    let mut db = Db::new();
+    ts_command(&mut db, "ts.create", "a LABELS host web1 role web").unwrap();
+    ts_command(&mut db, "ts.create", "b LABELS host web2 role web").unwrap();
+    ts_command(&mut db, "ts.create", "c LABELS host db1").unwrap();
+    ts_command(&mut db, "ts.madd", "a 1 10 a 2 20 b 1 30 c 1 40").unwrap();
+
+    let tests_case: Vec<(&str, Result<String, &'static str>)> = vec![
+        (
+            "- + FILTER role=web",
+            Ok("a: [1: 10 2: 20] b: [1: 30]".to_string()),
+        ),
+        (
+            "- + FILTER role=web host!=web1",
+            Ok("b: [1: 30]".to_string()),
+        ),
+        (
+            "- + FILTER host=(web1,db1)",
+            Ok("a: [1: 10 2: 20] c: [1: 40]".to_string()),
+        ),
+        ("- + FILTER role=", Ok("c: [1: 40]".to_string())),
+        (
+            "- + FILTER role!=",
+            Ok("a: [1: 10 2: 20] b: [1: 30]".to_string()),
+        ),
+        (
+            "- + AGGREGATION sum 10 FILTER role=web",
+            Ok("a: [0: 30] b: [0: 30]".to_string()),
+        ),
+        ("- + FILTER host=none", Ok("".to_string())),
+        ("- +", Err("ERR TSDB: failed parsing labels")),
+        ("- + FILTER role", Err("ERR TSDB: failed parsing labels")),
+    ];
+
+    for (args, expected_result) in tests_case {
+        println!("args: {}", args);
+        let result = ts_command(&mut db, "ts.mrange", args);
+        assert_eq!(result, expected_result);
+    }
+
+    let result = ts_command(&mut db, "ts.mrevrange", "- + COUNT 1 FILTER host=web1");
+    assert_eq!(result.unwrap(), "a: [2: 20]".to_string());
+}
+
+#[test]
+fn test_ts_createrule_command() {
+    let mut db = Db::new();
+    ts_command(&mut db, "ts.create", "raw").unwrap();
+    ts_command(&mut db, "ts.create", "avg").unwrap();
+    ts_command(&mut db, "ts.create", "max").unwrap();
+
+    let result = ts_command(&mut db, "ts.createrule", "raw avg AGGREGATION avg 10");
+    assert_eq!(result.unwrap(), "OK".to_string());
+    let result = ts_command(&mut db, "ts.createrule", "raw max AGGREGATION max 20");
+    assert_eq!(result.unwrap(), "OK".to_string());
+
+    let tests_case = [
+        (
+            "raw raw AGGREGATION avg 10",
+            "ERR TSDB: the source key and destination key should be different",
+        ),
+        (
+            "max avg AGGREGATION avg 10",
+            "ERR TSDB: the destination key already has a src rule",
+        ),
+        (
+            "avg raw AGGREGATION avg 10",
+            "ERR TSDB: the destination key already has a dst rule",
+        ),
+        (
+            "raw missing AGGREGATION avg 10",
+            "ERR TSDB: the key does not exist",
+        ),
+    ];
+    for (args, expected_error) in tests_case {
+        let result = ts_command(&mut db, "ts.createrule", args);
+        assert_eq!(result, Err(expected_error));
+    }
+
+    for (timestamp, value) in [(1, 1), (5, 3), (12, 10), (25, 7)] {
+        let args = format!("raw {} {}", timestamp, value);
+        ts_command(&mut db, "ts.add", &args).unwrap();
+    }
+    // the open buckets are not written yet
+    let result = ts_command(&mut db, "ts.range", "avg - +");
+    assert_eq!(result.unwrap(), "0: 2 10: 10".to_string());
+    let result = ts_command(&mut db, "ts.range", "max - +");
+    assert_eq!(result.unwrap(), "0: 10".to_string());
+
+    // a late sample rewrites its closed bucket
+    ts_command(&mut db, "ts.add", "raw 7 8").unwrap();
+    let result = ts_command(&mut db, "ts.range", "avg - +");
+    assert_eq!(result.unwrap(), "0: 4 10: 10".to_string());
+
+    let result = ts_command(&mut db, "ts.info", "raw");
+    assert!(result
+        .unwrap()
+        .ends_with("sourceKey: nil rules: avg:avg:10,max:max:20"));
+    let result = ts_command(&mut db, "ts.info", "avg");
+    assert!(result.unwrap().ends_with("sourceKey: raw rules: "));
+
+    let result = ts_command(&mut db, "ts.deleterule", "raw avg");
+    assert_eq!(result.unwrap(), "OK".to_string());
+    let result = ts_command(&mut db, "ts.deleterule", "raw avg");
+    assert_eq!(result, Err("ERR TSDB: compaction rule does not exist"));
+    ts_command(&mut db, "ts.add", "raw 40 1").unwrap();
+    let result = ts_command(&mut db, "ts.range", "avg - +");
+    assert_eq!(result.unwrap(), "0: 4 10: 10".to_string());
+    let result = ts_command(&mut db, "ts.range", "max - +");
+    assert_eq!(result.unwrap(), "0: 10 20: 7".to_string());
+}
@@ -1,5 +1,7 @@
 use rus_key_trait::command_trait::Command;
+use rus_key_command_lib::glob_match;
 use rus_key_db::db::Db;
+use geo_commands::geo::GeoCommand;
 use hashmap_commands::hashmap::HashMapCommand;
 use string_commands::string::StringCommand;
 use utils_commands::utils::UtilsCommand;
@@ -182,7 +184,394 @@ fn test_type_command() {
     let command_type_str = "hash_key";
     let mut parts_type = command_type_str.split_ascii_whitespace();
     let result = command_type.execute(&mut parts_type, &mut db);
-    assert_eq!(result.unwrap(), "zset".to_string());
+    assert_eq!(result.unwrap(), "hash".to_string());
 
     // TODO list set hash type
 }
+
+#[test]
+fn test_glob_match() {
+    let tests_case = vec![
+        ("*", "", true),
+        ("*", "anything", true),
+        ("h?llo", "hello", true),
+        ("h?llo", "hllo", false),
+        ("h*llo", "hllo", true),
+        ("h*llo", "heeeello", true),
+        ("h*llo", "hello world", false),
+        ("h[ae]llo", "hallo", true),
+        ("h[ae]llo", "hillo", false),
+        ("h[^e]llo", "hallo", true),
+        ("h[^e]llo", "hello", false),
+        ("h[a-b]llo", "hbllo", true),
+        ("h[b-a]llo", "hallo", true),
+        ("h[a-b]llo", "hcllo", false),
+        ("h\\*llo", "h*llo", true),
+        ("h\\*llo", "hello", false),
+        ("h[\\]]llo", "h]llo", true),
+        ("a*b*c", "aXXbYYc", true),
+        ("a*b*c", "aXXbYY", false),
+        ("user:[0-9]*", "user:42:name", true),
+        ("h[abc", "hb", true),
+        ("end\\", "end\\", true),
+    ];
+
+    for (pattern, str, expected) in tests_case {
+        println!("pattern: {} str: {}", pattern, str);
+        assert_eq!(glob_match(pattern, str), expected);
+    }
+}
+
+#[test]
+fn test_keys_command() {
+    let mut db = Db::new();
+    set_key(&mut db);
+
+    let command_keys = UtilsCommand::new("keys".to_string());
+    let tests_case: Vec<(&str, Result<String, &'static str>)> = vec![
+        ("*", Ok("hash_key key other_key".to_string())),
+        ("*key", Ok("hash_key key other_key".to_string())),
+        ("k?y", Ok("key".to_string())),
+        ("[ho]*", Ok("hash_key other_key".to_string())),
+        ("[^ho]*", Ok("key".to_string())),
+        ("nothing*", Ok("".to_string())),
+        ("", Err("ERR wrong number of arguments for command")),
+    ];
+
+    for (args, expected_result) in tests_case {
+        println!("args: {}", args);
+        let mut parts_keys = args.split_ascii_whitespace();
+        let result = command_keys.execute(&mut parts_keys, &mut db);
+        assert_eq!(result, expected_result);
+    }
+
+    // expired keys are skipped
+    db.expire_at("key", 1);
+    let mut parts_keys = "*".split_ascii_whitespace();
+    let result = command_keys.execute(&mut parts_keys, &mut db);
+    assert_eq!(result.unwrap(), "hash_key other_key".to_string());
+    assert!(db.get("key").is_none());
+}
+
+// splits a `cursor: [item ...]` scan reply
+fn parse_scan(reply: &str) -> (String, Vec<String>) {
+    let (cursor, items) = reply.split_once(": ").unwrap();
+    let items = items.trim_start_matches('[').trim_end_matches(']');
+    (
+        cursor.to_string(),
+        items.split_ascii_whitespace().map(|s| s.to_string()).collect(),
+    )
+}
+
+#[test]
+fn test_scan_command() {
+    let mut db = Db::new();
+    let key_arr = random_generate_key(&mut db);
+
+    // keys present for the whole iteration are returned while the keyspace grows and shrinks
+    let command_scan = UtilsCommand::new("scan".to_string());
+    let command_set = StringCommand::new("set".to_string());
+    let command_del = UtilsCommand::new("del".to_string());
+    let mut seen = std::collections::HashSet::new();
+    let mut cursor = "0".to_string();
+    let mut round = 0;
+    loop {
+        let command_scan_str = format!("{} COUNT 7", cursor);
+        let mut parts_scan = command_scan_str.split_ascii_whitespace();
+        let result = command_scan.execute(&mut parts_scan, &mut db).unwrap();
+        let (next, keys) = parse_scan(&result);
+        seen.extend(keys);
+
+        for i in 0..20 {
+            let command_set_str = format!("grow_{}_{} value", round, i);
+            let mut parts_set = command_set_str.split_ascii_whitespace();
+            command_set.execute(&mut parts_set, &mut db).unwrap();
+        }
+        if round > 0 {
+            let command_del_str = format!("grow_{}_0 grow_{}_1", round - 1, round - 1);
+            let mut parts_del = command_del_str.split_ascii_whitespace();
+            command_del.execute(&mut parts_del, &mut db).unwrap();
+        }
+        round += 1;
+
+        cursor = next;
+        if cursor == "0" {
+            break;
+        }
+    }
+    for key in key_arr {
+        assert!(seen.contains(&key), "{} not returned", key);
+    }
+
+    // MATCH and TYPE filter the keys
+    set_key(&mut db);
+    let mut matched = Vec::new();
+    let mut cursor = "0".to_string();
+    loop {
+        let command_scan_str = format!("{} MATCH *key COUNT 50 TYPE hash", cursor);
+        let mut parts_scan = command_scan_str.split_ascii_whitespace();
+        let result = command_scan.execute(&mut parts_scan, &mut db).unwrap();
+        let (next, keys) = parse_scan(&result);
+        matched.extend(keys);
+        cursor = next;
+        if cursor == "0" {
+            break;
+        }
+    }
+    assert_eq!(matched, vec!["hash_key".to_string()]);
+
+    let tests_case: Vec<(&str, Result<String, &'static str>)> = vec![
+        ("", Err("ERR wrong number of arguments for command")),
+        ("abc", Err("ERR invalid cursor")),
+        ("0 COUNT 0", Err("ERR syntax error")),
+        ("0 COUNT abc", Err("ERR value is not an integer or out of range")),
+        ("0 NOVALUES", Err("ERR syntax error")),
+    ];
+    for (args, expected_result) in tests_case {
+        println!("args: {}", args);
+        let mut parts_scan = args.split_ascii_whitespace();
+        let result = command_scan.execute(&mut parts_scan, &mut db);
+        assert_eq!(result, expected_result);
+    }
+}
+
+#[test]
+fn test_hscan_command() {
+    let mut db = Db::new();
+    set_key(&mut db);
+
+    let command_hscan = UtilsCommand::new("hscan".to_string());
+    let tests_case: Vec<(&str, Result<String, &'static str>)> = vec![
+        // pages are in cursor order, not field order
+        (
+            "hash_key 0",
+            Ok("0: [field2: value2 field1: value1]".to_string()),
+        ),
+        ("hash_key 0 MATCH *2", Ok("0: [field2: value2]".to_string())),
+        ("hash_key 0 NOVALUES", Ok("0: [field2 field1]".to_string())),
+        ("not_exists 0", Ok("0: []".to_string())),
+        (
+            "key 0",
+            Err("WRONGTYPE Operation against a key holding the wrong kind of value"),
+        ),
+        ("hash_key 0 TYPE string", Err("ERR syntax error")),
+    ];
+    for (args, expected_result) in tests_case {
+        println!("args: {}", args);
+        let mut parts_hscan = args.split_ascii_whitespace();
+        let result = command_hscan.execute(&mut parts_hscan, &mut db);
+        assert_eq!(result, expected_result);
+    }
+
+    // every field is returned once over a paged iteration
+    let command_hset = HashMapCommand::new("hset".to_string());
+    let fields: Vec<String> = (0..50).map(|i| format!("f{} {}", i, i)).collect();
+    let command_hset_str = format!("big {}", fields.join(" "));
+    let mut parts_hset = command_hset_str.split_ascii_whitespace();
+    command_hset.execute(&mut parts_hset, &mut db).unwrap();
+    let mut seen = Vec::new();
+    let mut cursor = "0".to_string();
+    loop {
+        let command_hscan_str = format!("big {} COUNT 9 NOVALUES", cursor);
+        let mut parts_hscan = command_hscan_str.split_ascii_whitespace();
+        let result = command_hscan.execute(&mut parts_hscan, &mut db).unwrap();
+        let (next, items) = parse_scan(&result);
+        seen.extend(items);
+        cursor = next;
+        if cursor == "0" {
+            break;
+        }
+    }
+    seen.sort();
+    let mut expected: Vec<String> = (0..50).map(|i| format!("f{}", i)).collect();
+    expected.sort();
+    assert_eq!(seen, expected);
+
+    // fields present for the whole iteration are returned while the hash grows
+    let mut seen = std::collections::HashSet::new();
+    let mut cursor = "0".to_string();
+    let mut round = 0;
+    loop {
+        let command_hscan_str = format!("big {} COUNT 9 NOVALUES", cursor);
+        let mut parts_hscan = command_hscan_str.split_ascii_whitespace();
+        let result = command_hscan.execute(&mut parts_hscan, &mut db).unwrap();
+        let (next, items) = parse_scan(&result);
+        seen.extend(items);
+        let command_hset_str = format!("big grow_{} value", round);
+        let mut parts_hset = command_hset_str.split_ascii_whitespace();
+        command_hset.execute(&mut parts_hset, &mut db).unwrap();
+        round += 1;
+        cursor = next;
+        if cursor == "0" {
+            break;
+        }
+    }
+    for field in expected {
+        assert!(seen.contains(&field), "{} not returned", field);
+    }
+}
+
+#[test]
+fn test_sscan_zscan_command() {
+    let mut db = Db::new();
+    set_key(&mut db);
+
+    let command_zscan = UtilsCommand::new("zscan".to_string());
+    let mut parts_zscan = "hash_key 0 MATCH field1".split_ascii_whitespace();
+    let result = command_zscan.execute(&mut parts_zscan, &mut db);
+    assert_eq!(result.unwrap(), "0: [field1: value1]".to_string());
+
+    let command_sscan = UtilsCommand::new("sscan".to_string());
+    let mut parts_sscan = "not_exists 0".split_ascii_whitespace();
+    let result = command_sscan.execute(&mut parts_sscan, &mut db);
+    assert_eq!(result.unwrap(), "0: []".to_string());
+
+    let mut parts_sscan = "key 0".split_ascii_whitespace();
+    let result = command_sscan.execute(&mut parts_sscan, &mut db);
+    assert_eq!(
+        result,
+        Err("WRONGTYPE Operation against a key holding the wrong kind of value")
+    );
+}
+
+#[test]
+fn test_object_command() {
+    let mut db = Db::new();
+    set_key(&mut db);
+    let command_set = StringCommand::new("set".to_string());
+    let long_value = format!("long_key {}", "x".repeat(50));
+    for line in ["int_key 12345", long_value.as_str()] {
+        let mut parts_set = line.split_ascii_whitespace();
+        command_set.execute(&mut parts_set, &mut db).unwrap();
+    }
+
+    let tests_case: Vec<(&str, Result<String, &'static str>)> = vec![
+        ("encoding key", Ok("embstr".to_string())),
+        ("ENCODING int_key", Ok("int".to_string())),
+        ("encoding long_key", Ok("raw".to_string())),
+        ("encoding hash_key", Ok("listpack".to_string())),
+        ("encoding not_exists", Ok("nil".to_string())),
+        ("refcount key", Ok("1".to_string())),
+        ("idletime key", Ok("0".to_string())),
+        // a new key starts with the LFU counter at 5
+        ("freq other_key", Ok("5".to_string())),
+        ("freq", Err("ERR wrong number of arguments for command")),
+        ("help key", Err("ERR unknown subcommand")),
+    ];
+    let command_object = UtilsCommand::new("object".to_string());
+    for (line, expected_result) in tests_case {
+        println!("line: {}", line);
+        let mut parts = line.split_ascii_whitespace();
+        let result = command_object.execute(&mut parts, &mut db);
+        assert_eq!(result, expected_result);
+    }
+
+    // reads count as accesses, OBJECT does not
+    let command_get = StringCommand::new("get".to_string());
+    let mut parts_get = "other_key".split_ascii_whitespace();
+    command_get.execute(&mut parts_get, &mut db).unwrap();
+    let mut parts = "freq other_key".split_ascii_whitespace();
+    let result = command_object.execute(&mut parts, &mut db);
+    assert_eq!(result.unwrap(), "6".to_string());
+}
+
+#[test]
+fn test_sort_command() {
+    let mut db = Db::new();
+    set_key(&mut db);
+    let command_set = StringCommand::new("set".to_string());
+    for line in ["weight_1 30", "weight_2 10", "weight_3 20", "weight_10 5"] {
+        let mut parts_set = line.split_ascii_whitespace();
+        command_set.execute(&mut parts_set, &mut db).unwrap();
+    }
+    // geo sets are sorted sets, their members are in score order from west to east here
+    let command_geoadd = GeoCommand::new("geoadd".to_string());
+    for line in [
+        "ids 10 0 3 11 0 1 12 0 10 13 0 2",
+        "words 10 0 banana 11 0 apple",
+    ] {
+        let mut parts_geoadd = line.split_ascii_whitespace();
+        command_geoadd.execute(&mut parts_geoadd, &mut db).unwrap();
+    }
+    let command_hmset = HashMapCommand::new("hmset".to_string());
+    for line in [
+        "user_1 name carol",
+        "user_2 name alice",
+        "user_3 name bob",
+        "user_10 name dave",
+    ] {
+        let mut parts_hmset = line.split_ascii_whitespace();
+        command_hmset.execute(&mut parts_hmset, &mut db).unwrap();
+    }
+
+    let tests_case: Vec<(&str, &str, Result<String, &'static str>)> = vec![
+        ("sort", "ids", Ok("1 2 3 10".to_string())),
+        ("sort", "ids DESC", Ok("10 3 2 1".to_string())),
+        ("sort", "ids ALPHA", Ok("1 10 2 3".to_string())),
+        ("sort", "ids LIMIT 1 2", Ok("2 3".to_string())),
+        ("sort", "ids LIMIT 2 -1", Ok("3 10".to_string())),
+        ("sort", "ids LIMIT 10 2", Ok("".to_string())),
+        ("sort", "ids BY nosort", Ok("3 1 10 2".to_string())),
+        ("sort", "ids BY weight_*", Ok("10 2 3 1".to_string())),
+        (
+            "sort",
+            "ids BY user_*->name ALPHA",
+            Ok("2 3 1 10".to_string()),
+        ),
+        // missing weights are 0, ties are ordered by the elements
+        ("sort", "ids BY missing_*", Ok("1 10 2 3".to_string())),
+        (
+            "sort",
+            "ids BY nosort GET # GET user_*->name",
+            Ok("3 bob 1 carol 10 dave 2 alice".to_string()),
+        ),
+        (
+            "sort",
+            "ids GET weight_* GET missing_*",
+            Ok("30 nil 10 nil 20 nil 5 nil".to_string()),
+        ),
+        ("sort", "words ALPHA", Ok("apple banana".to_string())),
+        ("sort", "words ALPHA DESC", Ok("banana apple".to_string())),
+        (
+            "sort",
+            "words",
+            Err("ERR One or more scores can't be converted into double"),
+        ),
+        (
+            "sort",
+            "key",
+            Err("WRONGTYPE Operation against a key holding the wrong kind of value"),
+        ),
+        // hashes are not sorted sets
+        (
+            "sort",
+            "user_1",
+            Err("WRONGTYPE Operation against a key holding the wrong kind of value"),
+        ),
+        ("sort", "not_exists", Ok("".to_string())),
+        ("sort", "ids LIMIT 1", Err("ERR syntax error")),
+        (
+            "sort",
+            "ids LIMIT a 1",
+            Err("ERR value is not an integer or out of range"),
+        ),
+        ("sort", "ids FOO", Err("ERR syntax error")),
+        ("sort", "ids BY weight_* STORE sorted", Ok("4".to_string())),
+        ("type", "sorted", Ok("list".to_string())),
+        ("sort", "sorted DESC", Ok("10 3 2 1".to_string())),
+        ("sort", "sorted BY nosort", Ok("10 2 3 1".to_string())),
+        ("sort", "ids GET missing_* STORE empty", Ok("4".to_string())),
+        ("sort", "empty ALPHA", Ok("   ".to_string())),
+        ("sort_ro", "ids DESC LIMIT 0 1", Ok("10".to_string())),
+        ("sort_ro", "ids STORE sorted", Err("ERR syntax error")),
+        ("sort", "not_exists STORE sorted", Ok("0".to_string())),
+        ("type", "sorted", Ok("none".to_string())),
+    ];
+    for (command, line, expected_result) in tests_case {
+        println!("command: {}, line: {}", command, line);
+        let command = UtilsCommand::new(command.to_string());
+        let mut parts = line.split_ascii_whitespace();
+        let result = command.execute(&mut parts, &mut db);
+        assert_eq!(result, expected_result);
+    }
+}
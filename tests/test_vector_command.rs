@@ -0,0 +1,251 @@
+use rand::Rng;
+use rus_key_db::db::Db;
+use rus_key_trait::command_trait::Command;
+use vector_commands::vector::VectorCommand;
+
+fn vector_command(db: &mut Db, command: &str, args: &str) -> Result<String, &'static str> {
+    let command = VectorCommand::new(command.to_string());
+    let mut parts = args.split_ascii_whitespace();
+    command.execute(&mut parts, db)
+}
+
+fn random_vector(dim: usize) -> Vec<f32> {
+    let mut rng = rand::thread_rng();
+    (0..dim).map(|_| rng.gen_range(-1.0..1.0)).collect()
+}
+
+fn values_args(vector: &[f32]) -> String {
+    let values: Vec<String> = vector.iter().map(|value| value.to_string()).collect();
+    format!("VALUES {} {}", vector.len(), values.join(" "))
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    dot / (norm_a * norm_b)
+}
+
+/// The share of the exact nearest neighbours found by `VSIM`.
+fn recall(db: &mut Db, key: &str, vectors: &[(String, Vec<f32>)], queries: usize) -> f32 {
+    let mut found = 0;
+    for _ in 0..queries {
+        let query = random_vector(vectors[0].1.len());
+        let mut exact: Vec<(f32, &str)> = vectors
+            .iter()
+            .map(|(name, vector)| (cosine(&query, vector), name.as_str()))
+            .collect();
+        exact.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let args = format!("{} {} COUNT 10", key, values_args(&query));
+        let result = vector_command(db, "vsim", &args).unwrap();
+        let result: Vec<&str> = result.split(' ').collect();
+        found += exact[..10]
+            .iter()
+            .filter(|(_, name)| result.contains(name))
+            .count();
+    }
+    found as f32 / (queries * 10) as f32
+}
+
+#[test]
+fn test_vadd_command() {
+    let mut db = Db::new();
+
+    let result = vector_command(&mut db, "vadd", "points VALUES 3 1 0 0 a");
+    assert_eq!(result.unwrap(), "1".to_string());
+    let result = vector_command(&mut db, "vadd", "points VALUES 3 0 2 0 b");
+    assert_eq!(result.unwrap(), "1".to_string());
+    // an existing element is updated
+    let result = vector_command(&mut db, "vadd", "points VALUES 3 0 3 4 b");
+    assert_eq!(result.unwrap(), "0".to_string());
+
+    let result = vector_command(&mut db, "vcard", "points");
+    assert_eq!(result.unwrap(), "2".to_string());
+    let result = vector_command(&mut db, "vdim", "points");
+    assert_eq!(result.unwrap(), "3".to_string());
+    let result = vector_command(&mut db, "vemb", "points b");
+    assert_eq!(result.unwrap(), "0 3 4".to_string());
+    let result = vector_command(&mut db, "vemb", "points c");
+    assert_eq!(result.unwrap(), "nil".to_string());
+    let result = vector_command(&mut db, "vcard", "missing");
+    assert_eq!(result.unwrap(), "0".to_string());
+    let result = vector_command(&mut db, "vdim", "missing");
+    assert_eq!(result.unwrap(), "nil".to_string());
+
+    let result = vector_command(&mut db, "vadd", "points VALUES 2 1 0 c");
+    assert_eq!(result, Err("ERR Vector dimension mismatch"));
+    let result = vector_command(&mut db, "vadd", "points VALUES 3 1 0 0 c Q8");
+    assert_eq!(
+        result,
+        Err("ERR asked quantization mismatch with existing vector set")
+    );
+    let result = vector_command(&mut db, "vadd", "points VALUES 3 1 0 0");
+    assert_eq!(result, Err("ERR wrong number of arguments for command"));
+    let result = vector_command(&mut db, "vadd", "points VALUES 3 1 0 x c");
+    assert_eq!(result, Err("ERR invalid vector specification"));
+
+    let result = vector_command(&mut db, "vinfo", "points");
+    assert_eq!(
+        result.unwrap().split(" max-level").next().unwrap(),
+        "quant-type: f32 vector-dim: 3 size: 2"
+    );
+}
+
+#[test]
+fn test_vsim_command() {
+    let mut db = Db::new();
+    for (name, vector) in [
+        ("east", "1 0"),
+        ("north_east", "1 1"),
+        ("north", "0 1"),
+        ("west", "-1 0"),
+    ] {
+        let args = format!("dirs VALUES 2 {} {}", vector, name);
+        vector_command(&mut db, "vadd", &args).unwrap();
+    }
+
+    let result = vector_command(&mut db, "vsim", "dirs VALUES 2 2 0.1");
+    assert_eq!(result.unwrap(), "east north_east north west".to_string());
+    let result = vector_command(&mut db, "vsim", "dirs ELE north COUNT 2 WITHSCORES");
+    assert_eq!(
+        result.unwrap(),
+        "north: 1.0000 north_east: 0.8536".to_string()
+    );
+    let result = vector_command(&mut db, "vsim", "dirs ELE south");
+    assert_eq!(result, Err("ERR element not found in set"));
+    let result = vector_command(&mut db, "vsim", "dirs VALUES 3 1 0 0");
+    assert_eq!(result, Err("ERR Vector dimension mismatch"));
+    let result = vector_command(&mut db, "vsim", "missing VALUES 2 1 0");
+    assert_eq!(result.unwrap(), "".to_string());
+
+    let result = vector_command(&mut db, "vrem", "dirs north_east");
+    assert_eq!(result.unwrap(), "1".to_string());
+    let result = vector_command(&mut db, "vrem", "dirs north_east");
+    assert_eq!(result.unwrap(), "0".to_string());
+    let result = vector_command(&mut db, "vsim", "dirs VALUES 2 1 1");
+    assert_eq!(result.unwrap(), "east north west".to_string());
+}
+
+#[test]
+fn test_vsim_filter() {
+    let mut db = Db::new();
+    let movies = [
+        (
+            "alien",
+            "1 0.1",
+            r#"{"year": 1979, "genre": "horror", "rating": 8.5}"#,
+        ),
+        (
+            "aliens",
+            "1 0.2",
+            r#"{"year": 1986, "genre": "action", "rating": 8.4}"#,
+        ),
+        (
+            "heat",
+            "1 0.3",
+            r#"{"year": 1995, "genre": "action", "rating": 8.3}"#,
+        ),
+        (
+            "amelie",
+            "0.1 1",
+            r#"{"year": 2001, "genre": "comedy", "tags": ["paris"]}"#,
+        ),
+        ("untagged", "1 0", ""),
+    ];
+    for (name, vector, attributes) in movies {
+        let mut args = format!("movies VALUES 2 {} {}", vector, name);
+        if !attributes.is_empty() {
+            args.push_str(&format!(" SETATTR {}", attributes));
+        }
+        vector_command(&mut db, "vadd", &args).unwrap();
+    }
+
+    let tests_case: Vec<(&str, Result<String, &'static str>)> = vec![
+        ("FILTER .year > 1980", Ok("aliens heat amelie".to_string())),
+        (
+            "FILTER .genre == \"action\" and .year < 1990",
+            Ok("aliens".to_string()),
+        ),
+        (
+            "FILTER .genre in [\"horror\", \"comedy\"] COUNT 1",
+            Ok("alien".to_string()),
+        ),
+        ("FILTER \"paris\" in .tags", Ok("amelie".to_string())),
+        ("FILTER not (.rating >= 8.4)", Ok("heat".to_string())),
+        (
+            "FILTER (.year - 1900) % 10 == 5 || .genre == 'horror'",
+            Ok("alien heat".to_string()),
+        ),
+        (
+            "FILTER .year >",
+            Err("ERR syntax error in FILTER expression"),
+        ),
+    ];
+    for (filter, expected_result) in tests_case {
+        println!("filter: {}", filter);
+        let args = format!("movies VALUES 2 1 0 {}", filter);
+        let result = vector_command(&mut db, "vsim", &args);
+        assert_eq!(result, expected_result);
+    }
+
+    let result = vector_command(&mut db, "vgetattr", "movies heat");
+    assert_eq!(
+        result.unwrap(),
+        r#"{"year":1995,"genre":"action","rating":8.3}"#.to_string()
+    );
+    let result = vector_command(&mut db, "vsetattr", "movies heat {\"year\": 2024}");
+    assert_eq!(result.unwrap(), "1".to_string());
+    let result = vector_command(&mut db, "vsim", "movies VALUES 2 1 0 FILTER .year > 2010");
+    assert_eq!(result.unwrap(), "heat".to_string());
+    let result = vector_command(&mut db, "vsetattr", "movies heat \"\"");
+    assert_eq!(result.unwrap(), "1".to_string());
+    let result = vector_command(&mut db, "vgetattr", "movies heat");
+    assert_eq!(result.unwrap(), "nil".to_string());
+}
+
+#[test]
+fn test_vsim_recall() {
+    let mut db = Db::new();
+    let mut vectors = Vec::new();
+    for i in 0..500 {
+        let vector = random_vector(16);
+        let args = format!("embeddings {} item_{}", values_args(&vector), i);
+        vector_command(&mut db, "vadd", &args).unwrap();
+        vectors.push((format!("item_{}", i), vector));
+    }
+    assert!(recall(&mut db, "embeddings", &vectors, 20) >= 0.9);
+
+    // the graph stays searchable after removing half of the elements
+    for (name, _) in vectors.drain(..250) {
+        let args = format!("embeddings {}", name);
+        assert_eq!(vector_command(&mut db, "vrem", &args).unwrap(), "1");
+    }
+    let result = vector_command(&mut db, "vcard", "embeddings");
+    assert_eq!(result.unwrap(), "250".to_string());
+    assert!(recall(&mut db, "embeddings", &vectors, 20) >= 0.9);
+}
+
+#[test]
+fn test_vector_q8() {
+    let mut db = Db::new();
+    let mut vectors = Vec::new();
+    for i in 0..300 {
+        let vector = random_vector(32);
+        let args = format!("quantized {} item_{} Q8", values_args(&vector), i);
+        vector_command(&mut db, "vadd", &args).unwrap();
+        vectors.push((format!("item_{}", i), vector));
+    }
+
+    let result = vector_command(&mut db, "vinfo", "quantized");
+    assert!(result.unwrap().starts_with("quant-type: int8"));
+    // the stored vector is close to the original one
+    let result = vector_command(&mut db, "vemb", "quantized item_0").unwrap();
+    let embedding: Vec<f32> = result.split(' ').map(|v| v.parse().unwrap()).collect();
+    assert!(cosine(&embedding, &vectors[0].1) > 0.999);
+    for (original, stored) in vectors[0].1.iter().zip(&embedding) {
+        assert!((original - stored).abs() < 0.02);
+    }
+
+    assert!(recall(&mut db, "quantized", &vectors, 20) >= 0.85);
+}